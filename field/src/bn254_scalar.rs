@@ -0,0 +1,436 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Display, Formatter};
+use core::hash::{Hash, Hasher};
+use core::iter::{Product, Sum};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use itertools::Itertools;
+use num::bigint::BigUint;
+use num::{Integer, One};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::types::{Field, PrimeField, Sample};
+
+/// The scalar field of the BN254 elliptic curve.
+///
+/// Its order is
+/// ```ignore
+/// P = 0x30644E72 E131A029 B85045B6 8181585D 2833E848 79B97091 43E1F593 F0000001
+///   = 21888242871839275222246405745257275088548364400416034343698204186575808495617
+/// ```
+///
+/// Elements are stored in Montgomery form, i.e. `x` is represented by `x * 2^256 mod P`. They are
+/// serialized as their canonical little-endian limbs, and deserialization rejects limbs which are
+/// not below `P`.
+#[derive(Copy, Clone)]
+pub struct Bn254Scalar(pub [u64; 4]);
+
+const MODULUS: [u64; 4] = [
+    0x43e1f593f0000001,
+    0x2833e84879b97091,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+];
+
+/// `-P^-1 mod 2^64`.
+const INV: u64 = 0xc2e1f593efffffff;
+
+/// `2^512 mod P`, used to move values into Montgomery form.
+const R2: [u64; 4] = [
+    0x1bb8e645ae216da7,
+    0x53fe3ab1e35c59e3,
+    0x8c49833d53bb8085,
+    0x0216d0b17f4e44a5,
+];
+
+#[inline(always)]
+const fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = (a as u128) + (b as u128) + (carry as u128);
+    (t as u64, (t >> 64) as u64)
+}
+
+#[inline(always)]
+const fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub((b as u128) + (borrow as u128));
+    (t as u64, (t >> 127) as u64)
+}
+
+#[inline(always)]
+const fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = (a as u128) + (b as u128) * (c as u128) + (carry as u128);
+    (t as u64, (t >> 64) as u64)
+}
+
+/// Returns whether `a < P`.
+#[inline(always)]
+const fn is_reduced(a: &[u64; 4]) -> bool {
+    let (_, b) = sbb(a[0], MODULUS[0], 0);
+    let (_, b) = sbb(a[1], MODULUS[1], b);
+    let (_, b) = sbb(a[2], MODULUS[2], b);
+    let (_, b) = sbb(a[3], MODULUS[3], b);
+    b != 0
+}
+
+/// Subtracts `P` from `a` if `a >= P`. Requires `a < 2P`.
+#[inline(always)]
+const fn reduce_once(a: [u64; 4]) -> [u64; 4] {
+    let (r0, b) = sbb(a[0], MODULUS[0], 0);
+    let (r1, b) = sbb(a[1], MODULUS[1], b);
+    let (r2, b) = sbb(a[2], MODULUS[2], b);
+    let (r3, b) = sbb(a[3], MODULUS[3], b);
+    if b == 0 {
+        [r0, r1, r2, r3]
+    } else {
+        a
+    }
+}
+
+/// Montgomery reduction of a 512-bit value `t < P * 2^256`, returning `t * 2^-256 mod P`.
+const fn montgomery_reduce(mut t: [u64; 8]) -> [u64; 4] {
+    let mut carry2 = 0;
+    let mut i = 0;
+    while i < 4 {
+        let k = t[i].wrapping_mul(INV);
+        let mut carry = 0;
+        let mut j = 0;
+        while j < 4 {
+            let (lo, hi) = mac(t[i + j], k, MODULUS[j], carry);
+            t[i + j] = lo;
+            carry = hi;
+            j += 1;
+        }
+        let (lo, hi) = adc(t[i + 4], carry, carry2);
+        t[i + 4] = lo;
+        carry2 = hi;
+        i += 1;
+    }
+    // Since `P < 2^254`, the result is below `2P` and `carry2` is always zero.
+    reduce_once([t[4], t[5], t[6], t[7]])
+}
+
+const fn montgomery_mul(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mut t = [0u64; 8];
+    let mut i = 0;
+    while i < 4 {
+        let mut carry = 0;
+        let mut j = 0;
+        while j < 4 {
+            let (lo, hi) = mac(t[i + j], a[i], b[j], carry);
+            t[i + j] = lo;
+            carry = hi;
+            j += 1;
+        }
+        t[i + 4] = carry;
+        i += 1;
+    }
+    montgomery_reduce(t)
+}
+
+fn biguint_from_array(arr: [u64; 4]) -> BigUint {
+    BigUint::from_slice(&[
+        arr[0] as u32,
+        (arr[0] >> 32) as u32,
+        arr[1] as u32,
+        (arr[1] >> 32) as u32,
+        arr[2] as u32,
+        (arr[2] >> 32) as u32,
+        arr[3] as u32,
+        (arr[3] >> 32) as u32,
+    ])
+}
+
+impl Bn254Scalar {
+    /// Builds a field element from its canonical little-endian limbs, which must be below the
+    /// field order.
+    pub const fn from_canonical_limbs(limbs: [u64; 4]) -> Self {
+        Self(montgomery_mul(&limbs, &R2))
+    }
+
+    /// Returns the canonical little-endian limbs of this field element.
+    pub const fn to_canonical_limbs(&self) -> [u64; 4] {
+        let a = self.0;
+        montgomery_reduce([a[0], a[1], a[2], a[3], 0, 0, 0, 0])
+    }
+}
+
+impl Serialize for Bn254Scalar {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct("Bn254Scalar", &self.to_canonical_limbs())
+    }
+}
+
+impl<'de> Deserialize<'de> for Bn254Scalar {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        /// Same serialized form as `Bn254Scalar`, without validation.
+        #[derive(Deserialize)]
+        #[serde(rename = "Bn254Scalar")]
+        struct CanonicalLimbs([u64; 4]);
+
+        let CanonicalLimbs(limbs) = CanonicalLimbs::deserialize(deserializer)?;
+        if is_reduced(&limbs) {
+            Ok(Self::from_canonical_limbs(limbs))
+        } else {
+            Err(D::Error::custom(
+                "Bn254Scalar limbs are not below the field order",
+            ))
+        }
+    }
+}
+
+impl Default for Bn254Scalar {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl PartialEq for Bn254Scalar {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Bn254Scalar {}
+
+impl Hash for Bn254Scalar {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl Display for Bn254Scalar {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.to_canonical_biguint(), f)
+    }
+}
+
+impl Debug for Bn254Scalar {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.to_canonical_biguint(), f)
+    }
+}
+
+impl Sample for Bn254Scalar {
+    #[inline]
+    fn sample<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + ?Sized,
+    {
+        use num::bigint::RandBigInt;
+        Self::from_noncanonical_biguint(rng.gen_biguint_below(&Self::order()))
+    }
+}
+
+impl Field for Bn254Scalar {
+    const ZERO: Self = Self([0; 4]);
+    const ONE: Self = Self::from_canonical_limbs([1, 0, 0, 0]);
+    const TWO: Self = Self::from_canonical_limbs([2, 0, 0, 0]);
+    const NEG_ONE: Self = Self::from_canonical_limbs([
+        0x43e1f593f0000000,
+        0x2833e84879b97091,
+        0xb85045b68181585d,
+        0x30644e72e131a029,
+    ]);
+
+    const TWO_ADICITY: usize = 28;
+    const CHARACTERISTIC_TWO_ADICITY: usize = Self::TWO_ADICITY;
+
+    // Sage: `g = GF(p).multiplicative_generator()`
+    const MULTIPLICATIVE_GROUP_GENERATOR: Self = Self::from_canonical_limbs([5, 0, 0, 0]);
+
+    // Sage: `g_2 = power_mod(g, (p - 1) // 2^28), p)`
+    // 19103219067921713944291392827692070036145651957329286315305642004821462161904
+    const POWER_OF_TWO_GENERATOR: Self = Self::from_canonical_limbs([
+        0x9bd61b6e725b19f0,
+        0x402d111e41112ed4,
+        0x00e0a7eb8ef62abc,
+        0x2a3c09f0a58a7e85,
+    ]);
+
+    const BITS: usize = 254;
+
+    fn order() -> BigUint {
+        biguint_from_array(MODULUS)
+    }
+    fn characteristic() -> BigUint {
+        Self::order()
+    }
+
+    fn try_inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+
+        // Fermat's Little Theorem
+        Some(self.exp_biguint(&(Self::order() - BigUint::one() - BigUint::one())))
+    }
+
+    fn from_noncanonical_biguint(val: BigUint) -> Self {
+        Self::from_canonical_limbs(
+            val.mod_floor(&Self::order())
+                .to_u64_digits()
+                .into_iter()
+                .pad_using(4, |_| 0)
+                .collect::<Vec<_>>()[..]
+                .try_into()
+                .expect("error converting to u64 array"),
+        )
+    }
+
+    #[inline]
+    fn from_canonical_u64(n: u64) -> Self {
+        Self::from_canonical_limbs([n, 0, 0, 0])
+    }
+
+    #[inline]
+    fn from_noncanonical_u128(n: u128) -> Self {
+        Self::from_canonical_limbs([n as u64, (n >> 64) as u64, 0, 0])
+    }
+
+    #[inline]
+    fn from_noncanonical_u96(n: (u64, u32)) -> Self {
+        Self::from_canonical_limbs([n.0, n.1 as u64, 0, 0])
+    }
+
+    fn from_noncanonical_i64(n: i64) -> Self {
+        let f = Self::from_canonical_u64(n.unsigned_abs());
+        if n < 0 {
+            -f
+        } else {
+            f
+        }
+    }
+
+    fn from_noncanonical_u64(n: u64) -> Self {
+        Self::from_canonical_u64(n)
+    }
+}
+
+impl PrimeField for Bn254Scalar {
+    fn to_canonical_biguint(&self) -> BigUint {
+        biguint_from_array(self.to_canonical_limbs())
+    }
+}
+
+impl Neg for Bn254Scalar {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        if self.is_zero() {
+            Self::ZERO
+        } else {
+            let (r0, b) = sbb(MODULUS[0], self.0[0], 0);
+            let (r1, b) = sbb(MODULUS[1], self.0[1], b);
+            let (r2, b) = sbb(MODULUS[2], self.0[2], b);
+            let (r3, _) = sbb(MODULUS[3], self.0[3], b);
+            Self([r0, r1, r2, r3])
+        }
+    }
+}
+
+impl Add for Bn254Scalar {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        // Both operands are below `P < 2^254`, so the sum cannot overflow 256 bits.
+        let (r0, c) = adc(self.0[0], rhs.0[0], 0);
+        let (r1, c) = adc(self.0[1], rhs.0[1], c);
+        let (r2, c) = adc(self.0[2], rhs.0[2], c);
+        let (r3, _) = adc(self.0[3], rhs.0[3], c);
+        Self(reduce_once([r0, r1, r2, r3]))
+    }
+}
+
+impl AddAssign for Bn254Scalar {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sum for Bn254Scalar {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl Sub for Bn254Scalar {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        let (r0, b) = sbb(self.0[0], rhs.0[0], 0);
+        let (r1, b) = sbb(self.0[1], rhs.0[1], b);
+        let (r2, b) = sbb(self.0[2], rhs.0[2], b);
+        let (r3, b) = sbb(self.0[3], rhs.0[3], b);
+        if b == 0 {
+            Self([r0, r1, r2, r3])
+        } else {
+            let (r0, c) = adc(r0, MODULUS[0], 0);
+            let (r1, c) = adc(r1, MODULUS[1], c);
+            let (r2, c) = adc(r2, MODULUS[2], c);
+            let (r3, _) = adc(r3, MODULUS[3], c);
+            Self([r0, r1, r2, r3])
+        }
+    }
+}
+
+impl SubAssign for Bn254Scalar {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for Bn254Scalar {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self(montgomery_mul(&self.0, &rhs.0))
+    }
+}
+
+impl MulAssign for Bn254Scalar {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Product for Bn254Scalar {
+    #[inline]
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(|acc, x| acc * x).unwrap_or(Self::ONE)
+    }
+}
+
+impl Div for Bn254Scalar {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.inverse()
+    }
+}
+
+impl DivAssign for Bn254Scalar {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_field_arithmetic;
+
+    test_field_arithmetic!(crate::bn254_scalar::Bn254Scalar);
+}
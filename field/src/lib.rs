@@ -11,6 +11,7 @@ extern crate alloc;
 pub(crate) mod arch;
 
 pub mod batch_util;
+pub mod bn254_scalar;
 pub mod cosets;
 pub mod extension;
 pub mod fft;
@@ -2,10 +2,13 @@ mod allocator;
 
 use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
 use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::packable::Packable;
+use plonky2::field::packed::PackedField;
 use plonky2::field::types::Sample;
 use plonky2::hash::hash_types::{BytesHash, RichField};
 use plonky2::hash::keccak::KeccakHash;
 use plonky2::hash::poseidon::{Poseidon, SPONGE_WIDTH};
+use plonky2::hash::poseidon2::Poseidon2;
 use plonky2::plonk::config::Hasher;
 use tynm::type_name;
 
@@ -32,8 +35,41 @@ pub(crate) fn bench_poseidon<F: Poseidon>(c: &mut Criterion) {
     );
 }
 
+pub(crate) fn bench_poseidon_packed<F: Poseidon>(c: &mut Criterion) {
+    type P<F> = <F as Packable>::Packing;
+    c.bench_function(
+        &format!(
+            "poseidon_packed<{}, {SPONGE_WIDTH}, {} lanes>",
+            type_name::<F>(),
+            P::<F>::WIDTH
+        ),
+        |b| {
+            b.iter_batched(
+                || F::rand_array::<SPONGE_WIDTH>().map(P::<F>::from),
+                |state| F::poseidon_packed(state),
+                BatchSize::SmallInput,
+            )
+        },
+    );
+}
+
+pub(crate) fn bench_poseidon2<F: Poseidon2>(c: &mut Criterion) {
+    c.bench_function(
+        &format!("poseidon2<{}, {SPONGE_WIDTH}>", type_name::<F>()),
+        |b| {
+            b.iter_batched(
+                || F::rand_array::<SPONGE_WIDTH>(),
+                |state| F::poseidon2(state),
+                BatchSize::SmallInput,
+            )
+        },
+    );
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     bench_poseidon::<GoldilocksField>(c);
+    bench_poseidon_packed::<GoldilocksField>(c);
+    bench_poseidon2::<GoldilocksField>(c);
     bench_keccak::<GoldilocksField>(c);
 }
 
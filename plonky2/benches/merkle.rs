@@ -28,9 +28,37 @@ pub(crate) fn bench_merkle_tree<F: RichField, H: Hasher<F>>(c: &mut Criterion) {
     }
 }
 
+/// Compares hashing leaves one at a time with `Hasher::hash_or_noop_batch`, which may hash several
+/// leaves at once.
+pub(crate) fn bench_leaf_hashing<F: RichField, H: Hasher<F>>(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!(
+        "merkle-leaf-hashing<{}, {}>",
+        type_name::<F>(),
+        type_name::<H>()
+    ));
+    group.sample_size(10);
+
+    let size = 1 << 13;
+    let leaves = (0..size)
+        .map(|_| F::rand_vec(ELEMS_PER_LEAF))
+        .collect::<Vec<_>>();
+    group.bench_function("hash_or_noop", |b| {
+        b.iter(|| {
+            leaves
+                .iter()
+                .map(|leaf| H::hash_or_noop(leaf))
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("hash_or_noop_batch", |b| {
+        b.iter(|| H::hash_or_noop_batch(&leaves))
+    });
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     bench_merkle_tree::<GoldilocksField, PoseidonHash>(c);
     bench_merkle_tree::<GoldilocksField, KeccakHash<25>>(c);
+    bench_leaf_hashing::<GoldilocksField, PoseidonHash>(c);
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,83 @@
+mod allocator;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::hash::sha256::SHA256_BLOCK_BYTES;
+use plonky2::iop::target::ByteTarget;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::CircuitConfig;
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use rand::rngs::OsRng;
+use rand::Rng;
+use tynm::type_name;
+
+/// Builds a circuit hashing a message which is padded to `num_blocks` blocks.
+fn sha256_circuit<F: RichField + Extendable<D>, const D: usize>(
+    num_blocks: usize,
+) -> (CircuitBuilder<F, D>, Vec<ByteTarget>) {
+    let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+    // Inputs are not range-checked, so that the circuit only contains the hash.
+    let len = num_blocks * SHA256_BLOCK_BYTES - 9;
+    let input = (0..len)
+        .map(|_| ByteTarget::new_unsafe(builder.add_virtual_target()))
+        .collect::<Vec<_>>();
+    builder.sha256(&input);
+    (builder, input)
+}
+
+/// Returns the number of rows of a circuit hashing `num_blocks` blocks, including lookups.
+fn num_rows<F: RichField + Extendable<D>, const D: usize>(num_blocks: usize) -> usize {
+    let (mut builder, _) = sha256_circuit::<F, D>(num_blocks);
+    builder.add_all_lookups();
+    builder.num_gates()
+}
+
+pub(crate) fn bench_sha256<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    c: &mut Criterion,
+) {
+    // The lookup tables are shared, so the difference between circuits with three and two blocks
+    // is the cost of a single compression of message bytes.
+    let rows_one_block = num_rows::<F, D>(1);
+    let rows_per_compression = num_rows::<F, D>(3) - num_rows::<F, D>(2);
+    println!(
+        "sha256<{}>: {rows_per_compression} rows per compression, {rows_one_block} rows for a single block",
+        type_name::<F>()
+    );
+
+    let mut group = c.benchmark_group(format!("sha256<{}>", type_name::<F>()));
+    group.sample_size(10);
+
+    for num_blocks in [1, 2] {
+        let (builder, input) = sha256_circuit::<F, D>(num_blocks);
+        let data = builder.build::<C>();
+        group.bench_with_input(
+            BenchmarkId::new("prove", num_blocks),
+            &num_blocks,
+            |b, _| {
+                b.iter(|| {
+                    let mut pw = PartialWitness::new();
+                    for &t in &input {
+                        pw.set_byte_target(t, OsRng.gen());
+                    }
+                    data.prove(pw).unwrap()
+                });
+            },
+        );
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    bench_sha256::<F, C, D>(c);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
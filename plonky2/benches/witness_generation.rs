@@ -0,0 +1,60 @@
+mod allocator;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use plonky2::field::types::Sample;
+use plonky2::hash::poseidon::PoseidonHash;
+use plonky2::iop::generator::{generate_partial_witness_parallel, generate_partial_witness_serial};
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::{CircuitConfig, MockCircuitData};
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+type F = <C as GenericConfig<D>>::F;
+
+/// Builds a circuit hashing `num_hashes` times, either `num_hashes` independent inputs, which
+/// the parallel scheduler can run in a single wave, or a chain where each hash takes the previous
+/// digest as input, which needs one wave per hash.
+fn hash_circuit(num_hashes: usize, chain: bool) -> (MockCircuitData<F, C, D>, PartialWitness<F>) {
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+    let mut pw = PartialWitness::new();
+
+    let mut input: Vec<Target> = builder.add_virtual_targets(4);
+    pw.set_target_arr(&input, &F::rand_vec(4));
+    for _ in 0..num_hashes {
+        let digest = builder.hash_n_to_hash_no_pad::<PoseidonHash>(input.clone());
+        input = if chain {
+            digest.elements.to_vec()
+        } else {
+            let next = builder.add_virtual_targets(4);
+            pw.set_target_arr(&next, &F::rand_vec(4));
+            next
+        };
+    }
+
+    (builder.mock_build::<C>(), pw)
+}
+
+fn bench_witness_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("witness-generation");
+    group.sample_size(10);
+
+    let num_hashes = 1 << 12;
+    for (name, chain) in [("independent-hashes", false), ("hash-chain", true)] {
+        let (data, pw) = hash_circuit(num_hashes, chain);
+        group.bench_with_input(BenchmarkId::new("serial", name), &pw, |b, pw| {
+            b.iter(|| generate_partial_witness_serial(pw.clone(), &data.prover_only, &data.common))
+        });
+        group.bench_with_input(BenchmarkId::new("parallel", name), &pw, |b, pw| {
+            b.iter(|| {
+                generate_partial_witness_parallel(pw.clone(), &data.prover_only, &data.common)
+            })
+        });
+    }
+}
+
+criterion_group!(benches, bench_witness_generation);
+criterion_main!(benches);
@@ -0,0 +1,89 @@
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use crate::curve::curve_multiplication::to_digits;
+use crate::curve::curve_types::{Curve, ProjectivePoint};
+use crate::field::types::Field;
+use crate::util::log2_ceil;
+
+/// Picks a bucket window size for an MSM of `n` terms, roughly minimizing the number of group
+/// additions.
+pub fn msm_window_bits(n: usize) -> usize {
+    (log2_ceil(n).saturating_sub(2)).clamp(1, 16)
+}
+
+/// Computes `sum_i scalars[i] * points[i]` with Pippenger's bucket method, processing
+/// `window_bits` bits of every scalar at a time.
+pub fn msm<C: Curve>(
+    scalars: &[C::ScalarField],
+    points: &[ProjectivePoint<C>],
+    window_bits: usize,
+) -> ProjectivePoint<C> {
+    assert_eq!(
+        scalars.len(),
+        points.len(),
+        "Number of scalars and points must match"
+    );
+    assert!(window_bits > 0, "Window size must be positive");
+
+    let digits = scalars
+        .iter()
+        .map(|x| to_digits::<C>(x, window_bits))
+        .collect::<Vec<_>>();
+    let num_windows = C::ScalarField::BITS.div_ceil(window_bits);
+
+    let mut result = ProjectivePoint::ZERO;
+    for i in (0..num_windows).rev() {
+        for _ in 0..window_bits {
+            result = result.double();
+        }
+
+        // buckets[j] holds the sum of the points whose current digit is `j + 1`.
+        let mut buckets = vec![ProjectivePoint::<C>::ZERO; (1 << window_bits) - 1];
+        for (point, point_digits) in points.iter().zip(&digits) {
+            let digit = point_digits[i];
+            if digit != 0 {
+                buckets[digit - 1] = buckets[digit - 1] + *point;
+            }
+        }
+
+        // Compute sum_j (j + 1) * buckets[j] with running sums.
+        let mut running_sum = ProjectivePoint::ZERO;
+        let mut window_sum = ProjectivePoint::ZERO;
+        for bucket in buckets.into_iter().rev() {
+            running_sum = running_sum + bucket;
+            window_sum = window_sum + running_sum;
+        }
+        result = result + window_sum;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::curve_types::CurveScalar;
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::Sample;
+
+    #[test]
+    fn test_msm() {
+        let n = 10;
+        let scalars = Secp256K1Scalar::rand_vec(n);
+        let points = (0..n)
+            .map(|_| CurveScalar(Secp256K1Scalar::rand()) * Secp256K1::GENERATOR_PROJECTIVE)
+            .collect::<Vec<_>>();
+
+        let expected = scalars
+            .iter()
+            .zip(&points)
+            .fold(ProjectivePoint::ZERO, |acc, (&x, &p)| {
+                acc + CurveScalar(x) * p
+            });
+
+        for window_bits in [1, 3, msm_window_bits(n)] {
+            assert_eq!(msm(&scalars, &points, window_bits), expected);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::ops::Mul;
+
+use crate::curve::curve_types::{Curve, CurveScalar, ProjectivePoint};
+use crate::field::types::{Field, PrimeField};
+
+const WINDOW_BITS: usize = 4;
+const BASE: usize = 1 << WINDOW_BITS;
+
+/// Splits a scalar into little-endian digits of `window_bits` bits each.
+pub(crate) fn to_digits<C: Curve>(x: &C::ScalarField, window_bits: usize) -> Vec<usize> {
+    debug_assert!(window_bits > 0 && window_bits < usize::BITS as usize);
+    let limbs = x.to_canonical_biguint().to_u64_digits();
+    let num_digits = C::ScalarField::BITS.div_ceil(window_bits);
+
+    (0..num_digits)
+        .map(|i| {
+            (0..window_bits).fold(0, |digit, j| {
+                let bit = i * window_bits + j;
+                let limb = limbs.get(bit / 64).copied().unwrap_or(0);
+                digit | ((((limb >> (bit % 64)) & 1) as usize) << j)
+            })
+        })
+        .collect()
+}
+
+impl<C: Curve> ProjectivePoint<C> {
+    /// Returns `[0, p, 2p, ..., (n - 1)p]`.
+    pub(crate) fn small_multiples(&self, n: usize) -> Vec<Self> {
+        let mut multiples = Vec::with_capacity(n);
+        let mut current = Self::ZERO;
+        for _ in 0..n {
+            multiples.push(current);
+            current = current + *self;
+        }
+        multiples
+    }
+}
+
+impl<C: Curve> Mul<ProjectivePoint<C>> for CurveScalar<C> {
+    type Output = ProjectivePoint<C>;
+
+    /// Fixed-window scalar multiplication, processing `WINDOW_BITS` bits of the scalar at a time.
+    fn mul(self, rhs: ProjectivePoint<C>) -> Self::Output {
+        if self.0 == C::ScalarField::ZERO || rhs.is_zero() {
+            return ProjectivePoint::ZERO;
+        }
+
+        let multiples = rhs.small_multiples(BASE);
+        let mut y = ProjectivePoint::ZERO;
+        for &digit in to_digits::<C>(&self.0, WINDOW_BITS).iter().rev() {
+            for _ in 0..WINDOW_BITS {
+                y = y.double();
+            }
+            y = y + multiples[digit];
+        }
+        y
+    }
+}
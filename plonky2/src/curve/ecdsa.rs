@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+use crate::curve::curve_msm::msm;
+use crate::curve::curve_types::{base_to_scalar, AffinePoint, Curve, CurveScalar};
+use crate::field::types::{Field, Sample};
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(bound = "")]
+pub struct ECDSASignature<C: Curve> {
+    pub r: C::ScalarField,
+    pub s: C::ScalarField,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(bound = "")]
+pub struct ECDSASecretKey<C: Curve>(pub C::ScalarField);
+
+impl<C: Curve> ECDSASecretKey<C> {
+    pub fn to_public(&self) -> ECDSAPublicKey<C> {
+        ECDSAPublicKey((CurveScalar(self.0) * C::GENERATOR_PROJECTIVE).to_affine())
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(bound = "")]
+pub struct ECDSAPublicKey<C: Curve>(pub AffinePoint<C>);
+
+/// Signs the message hash `msg` with a random nonce.
+pub fn sign_message<C: Curve>(msg: C::ScalarField, sk: ECDSASecretKey<C>) -> ECDSASignature<C> {
+    loop {
+        let k = C::ScalarField::rand();
+        let rr = (CurveScalar(k) * C::GENERATOR_PROJECTIVE).to_affine();
+        let r = base_to_scalar::<C>(rr.x);
+        if r.is_zero() {
+            continue;
+        }
+
+        let s = k.inverse() * (msg + r * sk.0);
+        if s.is_nonzero() {
+            return ECDSASignature { r, s };
+        }
+    }
+}
+
+/// Checks that `sig` is a valid signature of the message hash `msg` under `pk`.
+pub fn verify_message<C: Curve>(
+    msg: C::ScalarField,
+    sig: ECDSASignature<C>,
+    pk: ECDSAPublicKey<C>,
+) -> bool {
+    let ECDSASignature { r, s } = sig;
+    if r.is_zero() || s.is_zero() || pk.0.zero || !pk.0.is_valid() {
+        return false;
+    }
+
+    let c = s.inverse();
+    let u1 = msg * c;
+    let u2 = r * c;
+
+    let point = msm(
+        &[u1, u2],
+        &[C::GENERATOR_PROJECTIVE, pk.0.to_projective()],
+        4,
+    );
+    if point.is_zero() {
+        return false;
+    }
+
+    let x = base_to_scalar::<C>(point.to_affine().x);
+    r == x
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::ecdsa::{sign_message, verify_message, ECDSASecretKey, ECDSASignature};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, Sample};
+
+    #[test]
+    fn test_ecdsa_native() {
+        type C = Secp256K1;
+
+        let msg = Secp256K1Scalar::rand();
+        let sk = ECDSASecretKey::<C>(Secp256K1Scalar::rand());
+        let pk = sk.to_public();
+
+        let sig = sign_message(msg, sk);
+        assert!(verify_message(msg, sig, pk));
+
+        assert!(!verify_message(msg + Secp256K1Scalar::ONE, sig, pk));
+        let other_pk = ECDSASecretKey::<C>(Secp256K1Scalar::rand()).to_public();
+        assert!(!verify_message(msg, sig, other_pk));
+        let bad_sig = ECDSASignature {
+            r: sig.r,
+            s: sig.s + Secp256K1Scalar::ONE,
+        };
+        assert!(!verify_message(msg, bad_sig, pk));
+    }
+}
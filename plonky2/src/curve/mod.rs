@@ -0,0 +1,11 @@
+//! Native elliptic curve arithmetic over short Weierstrass curves, and ECDSA on top of it.
+//!
+//! This is the out-of-circuit reference used to generate witnesses and test in-circuit curve
+//! gadgets.
+
+pub mod curve_adds;
+pub mod curve_msm;
+pub mod curve_multiplication;
+pub mod curve_types;
+pub mod ecdsa;
+pub mod secp256k1;
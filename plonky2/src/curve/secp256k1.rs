@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+use crate::curve::curve_types::{AffinePoint, Curve};
+use crate::field::secp256k1_base::Secp256K1Base;
+use crate::field::secp256k1_scalar::Secp256K1Scalar;
+use crate::field::types::Field;
+
+/// The secp256k1 curve `y^2 = x^3 + 7`.
+#[derive(Debug, Copy, Clone, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Secp256K1;
+
+impl Curve for Secp256K1 {
+    type BaseField = Secp256K1Base;
+    type ScalarField = Secp256K1Scalar;
+
+    const A: Secp256K1Base = Secp256K1Base::ZERO;
+    const B: Secp256K1Base = Secp256K1Base([7, 0, 0, 0]);
+    const GENERATOR_AFFINE: AffinePoint<Self> = AffinePoint {
+        x: SECP256K1_GENERATOR_X,
+        y: SECP256K1_GENERATOR_Y,
+        zero: false,
+    };
+}
+
+/// 55066263022277343669578718895168534326250603453777594175500187360389116729240
+const SECP256K1_GENERATOR_X: Secp256K1Base = Secp256K1Base([
+    0x59F2815B16F81798,
+    0x029BFCDB2DCE28D9,
+    0x55A06295CE870B07,
+    0x79BE667EF9DCBBAC,
+]);
+
+/// 32670510020758816978083085130507043184471273380659243275938904335757337482424
+const SECP256K1_GENERATOR_Y: Secp256K1Base = Secp256K1Base([
+    0x9C47D08FFB10D4B8,
+    0xFD17B448A6855419,
+    0x5DA4FBFC0E1108A8,
+    0x483ADA7726A3C465,
+]);
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+
+    use crate::curve::curve_types::{AffinePoint, Curve, CurveScalar, ProjectivePoint};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, PrimeField, Sample};
+
+    #[test]
+    fn test_generator() {
+        let g = Secp256K1::GENERATOR_AFFINE;
+        assert!(g.is_valid());
+        assert!(Secp256K1::is_safe_curve());
+
+        let neg_g = AffinePoint::<Secp256K1> {
+            x: g.x,
+            y: -g.y,
+            zero: g.zero,
+        };
+        assert!(neg_g.is_valid());
+    }
+
+    #[test]
+    fn test_naive_multiplication() {
+        let g = Secp256K1::GENERATOR_PROJECTIVE;
+        let ten = Secp256K1Scalar::from_canonical_u64(10);
+        let product = mul_naive(ten, g);
+        let sum = g + g + g + g + g + g + g + g + g + g;
+        assert_eq!(product, sum);
+    }
+
+    #[test]
+    fn test_g1_multiplication() {
+        let lhs = Secp256K1Scalar::from_noncanonical_biguint(BigUint::from_slice(&[
+            1111, 2222, 3333, 4444, 5555, 6666, 7777, 8888,
+        ]));
+        assert_eq!(
+            Secp256K1::convert(lhs) * Secp256K1::GENERATOR_PROJECTIVE,
+            mul_naive(lhs, Secp256K1::GENERATOR_PROJECTIVE)
+        );
+    }
+
+    #[test]
+    fn test_group_order() {
+        let g = Secp256K1::GENERATOR_PROJECTIVE;
+        assert!((CurveScalar(Secp256K1Scalar::NEG_ONE) * g + g).is_zero());
+        assert_eq!(CurveScalar(Secp256K1Scalar::NEG_ONE) * g, -g);
+    }
+
+    #[test]
+    fn test_mixed_addition() {
+        let p = CurveScalar(Secp256K1Scalar::rand()) * Secp256K1::GENERATOR_PROJECTIVE;
+        let q = CurveScalar(Secp256K1Scalar::rand()) * Secp256K1::GENERATOR_PROJECTIVE;
+        let (p_affine, q_affine) = (p.to_affine(), q.to_affine());
+
+        assert_eq!(p + q_affine, p + q);
+        assert_eq!(p_affine + q_affine, p + q);
+        assert_eq!(p_affine + p_affine, p.double());
+        assert!((p + (-p_affine)).is_zero());
+        assert_eq!(p_affine.double(), p.double().to_affine());
+    }
+
+    #[test]
+    fn test_compression() {
+        // The SEC1 compressed encoding of the generator.
+        let expected = [
+            0x02, 0x79, 0xBE, 0x66, 0x7E, 0xF9, 0xDC, 0xBB, 0xAC, 0x55, 0xA0, 0x62, 0x95, 0xCE,
+            0x87, 0x0B, 0x07, 0x02, 0x9B, 0xFC, 0xDB, 0x2D, 0xCE, 0x28, 0xD9, 0x59, 0xF2, 0x81,
+            0x5B, 0x16, 0xF8, 0x17, 0x98,
+        ];
+        let g = Secp256K1::GENERATOR_AFFINE;
+        assert_eq!(g.to_compressed_bytes(), expected);
+        assert_eq!(
+            AffinePoint::<Secp256K1>::from_compressed_bytes(&expected).unwrap(),
+            g
+        );
+
+        for _ in 0..5 {
+            let p = (CurveScalar(Secp256K1Scalar::rand()) * Secp256K1::GENERATOR_PROJECTIVE)
+                .to_affine();
+            let bytes = p.to_compressed_bytes();
+            assert_eq!(bytes.len(), AffinePoint::<Secp256K1>::compressed_len());
+            assert_eq!(AffinePoint::from_compressed_bytes(&bytes).unwrap(), p);
+            assert_eq!(
+                AffinePoint::from_compressed_bytes(&(-p).to_compressed_bytes()).unwrap(),
+                -p
+            );
+        }
+
+        let zero = AffinePoint::<Secp256K1>::ZERO;
+        assert_eq!(
+            AffinePoint::from_compressed_bytes(&zero.to_compressed_bytes()).unwrap(),
+            zero
+        );
+
+        let mut invalid_prefix = expected;
+        invalid_prefix[0] = 0x04;
+        assert!(AffinePoint::<Secp256K1>::from_compressed_bytes(&invalid_prefix).is_err());
+        assert!(AffinePoint::<Secp256K1>::from_compressed_bytes(&expected[..32]).is_err());
+    }
+
+    /// A simple, somewhat inefficient implementation of multiplication which is used as a reference
+    /// for correctness.
+    fn mul_naive(
+        lhs: Secp256K1Scalar,
+        rhs: ProjectivePoint<Secp256K1>,
+    ) -> ProjectivePoint<Secp256K1> {
+        let mut g = rhs;
+        let mut sum = ProjectivePoint::ZERO;
+        for limb in lhs.to_canonical_biguint().to_u64_digits().iter() {
+            for j in 0..64 {
+                if (limb >> j & 1u64) != 0u64 {
+                    sum = sum + g;
+                }
+                g = g.double();
+            }
+        }
+        sum
+    }
+}
@@ -1,12 +1,15 @@
 #[cfg(not(feature = "std"))]
 use alloc::{vec, vec::Vec};
 
-use hashbrown::HashMap;
+use anyhow::{anyhow, ensure};
+use hashbrown::{HashMap, HashSet};
 use itertools::izip;
 use serde::{Deserialize, Serialize};
 
 use crate::field::extension::{flatten, unflatten, Extendable};
 use crate::field::polynomial::PolynomialCoeffs;
+use crate::fri::structure::{FriInstanceInfo, FriOpenings};
+use crate::fri::verifier::{compute_evaluation, fri_combine_initial, PrecomputedReducedOpenings};
 use crate::fri::FriParams;
 use crate::gadgets::polynomial::PolynomialCoeffsExtTarget;
 use crate::hash::hash_types::{MerkleCapTarget, RichField};
@@ -15,9 +18,10 @@ use crate::hash::merkle_tree::MerkleCap;
 use crate::hash::path_compression::{compress_merkle_proofs, decompress_merkle_proofs};
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::Target;
-use crate::plonk::config::Hasher;
+use crate::plonk::config::{GenericConfig, Hasher};
 use crate::plonk::plonk_common::salt_size;
-use crate::plonk::proof::{FriInferredElements, ProofChallenges};
+use crate::plonk::proof::FriInferredElements;
+use crate::util::reverse_bits;
 
 /// Evaluations and Merkle proof produced by the prover in a FRI query step.
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
@@ -236,13 +240,142 @@ impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> FriProof<F, H,
 }
 
 impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> CompressedFriProof<F, H, D> {
+    /// Decompresses the proof of the FRI `instance` whose openings are `openings`, inferring the
+    /// coset elements which were removed by the compression.
+    ///
+    /// Returns an error if the proof doesn't have the shape given by `instance` and `params`.
+    pub fn decompress_for_instance<C: GenericConfig<D, F = F, Hasher = H>>(
+        self,
+        challenges: &FriChallenges<F, D>,
+        instance: &FriInstanceInfo<F, D>,
+        openings: &FriOpenings<F, D>,
+        params: &FriParams,
+    ) -> anyhow::Result<FriProof<F, H, D>> {
+        self.check_shape(&challenges.fri_query_indices, instance, params)?;
+        let fri_inferred_elements =
+            self.get_inferred_elements::<C>(challenges, instance, openings, params);
+        self.decompress(challenges, fri_inferred_elements, params)
+    }
+
+    /// Checks that the query rounds at `indices` are all present, with the numbers of trees,
+    /// evaluations and reduction steps given by `instance` and `params`.
+    pub(crate) fn check_shape(
+        &self,
+        indices: &[usize],
+        instance: &FriInstanceInfo<F, D>,
+        params: &FriParams,
+    ) -> anyhow::Result<()> {
+        let CompressedFriQueryRounds {
+            initial_trees_proofs,
+            steps,
+            ..
+        } = &self.query_round_proofs;
+        ensure!(
+            steps.len() == params.reduction_arity_bits.len(),
+            "Wrong number of FRI reduction steps in compressed proof."
+        );
+
+        for &(mut index) in indices {
+            let initial_trees_proof = initial_trees_proofs
+                .get(&index)
+                .ok_or_else(|| anyhow!("Missing FRI query round in compressed proof."))?;
+            ensure!(
+                initial_trees_proof.evals_proofs.len() == instance.oracles.len(),
+                "Wrong number of initial trees in compressed proof."
+            );
+            for ((evals, _), oracle) in initial_trees_proof
+                .evals_proofs
+                .iter()
+                .zip(&instance.oracles)
+            {
+                let salted = params.hiding && oracle.blinding;
+                ensure!(
+                    evals.len() == oracle.num_polys + salt_size(salted),
+                    "Wrong number of initial evaluations in compressed proof."
+                );
+            }
+
+            for (step, &arity_bits) in steps.iter().zip(&params.reduction_arity_bits) {
+                index >>= arity_bits;
+                let step = step
+                    .get(&index)
+                    .ok_or_else(|| anyhow!("Missing FRI query step in compressed proof."))?;
+                ensure!(
+                    step.evals.len() == (1 << arity_bits) - 1,
+                    "Wrong number of FRI step evaluations in compressed proof."
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Computes all coset elements that can be inferred in the FRI reduction steps. The proof must
+    /// have passed `check_shape`.
+    pub(crate) fn get_inferred_elements<C: GenericConfig<D, F = F, Hasher = H>>(
+        &self,
+        challenges: &FriChallenges<F, D>,
+        instance: &FriInstanceInfo<F, D>,
+        openings: &FriOpenings<F, D>,
+        params: &FriParams,
+    ) -> FriInferredElements<F, D> {
+        let FriChallenges {
+            fri_alpha,
+            fri_betas,
+            fri_query_indices,
+            ..
+        } = challenges;
+        let mut fri_inferred_elements = Vec::new();
+        // Holds the indices that have already been seen at each reduction depth.
+        let mut seen_indices_by_depth = vec![HashSet::new(); params.reduction_arity_bits.len()];
+        let precomputed_reduced_evals =
+            PrecomputedReducedOpenings::from_os_and_alpha(openings, *fri_alpha);
+        let log_n = params.lde_bits();
+        // Simulate the proof verification and collect the inferred elements.
+        // The content of the loop is basically the same as the `fri_verifier_query_round` function.
+        for &(mut x_index) in fri_query_indices {
+            let mut subgroup_x = F::MULTIPLICATIVE_GROUP_GENERATOR
+                * F::primitive_root_of_unity(log_n).exp_u64(reverse_bits(x_index, log_n) as u64);
+            let mut old_eval = fri_combine_initial::<F, C, D>(
+                instance,
+                &self.query_round_proofs.initial_trees_proofs[&x_index],
+                *fri_alpha,
+                subgroup_x,
+                &precomputed_reduced_evals,
+                params,
+            );
+            for (i, &arity_bits) in params.reduction_arity_bits.iter().enumerate() {
+                let coset_index = x_index >> arity_bits;
+                if !seen_indices_by_depth[i].insert(coset_index) {
+                    // If this index has already been seen, we can skip the rest of the reductions.
+                    break;
+                }
+                fri_inferred_elements.push(old_eval);
+                let arity = 1 << arity_bits;
+                let mut evals = self.query_round_proofs.steps[i][&coset_index].evals.clone();
+                let x_index_within_coset = x_index & (arity - 1);
+                evals.insert(x_index_within_coset, old_eval);
+                old_eval = compute_evaluation(
+                    subgroup_x,
+                    x_index_within_coset,
+                    arity_bits,
+                    &evals,
+                    fri_betas[i],
+                );
+                subgroup_x = subgroup_x.exp_power_of_2(arity_bits);
+                x_index = coset_index;
+            }
+        }
+        FriInferredElements(fri_inferred_elements)
+    }
+
     /// Decompress all the Merkle paths in the FRI proof and reinsert duplicate indices.
     pub(crate) fn decompress(
         self,
-        challenges: &ProofChallenges<F, D>,
+        challenges: &FriChallenges<F, D>,
         fri_inferred_elements: FriInferredElements<F, D>,
         params: &FriParams,
-    ) -> FriProof<F, H, D> {
+    ) -> anyhow::Result<FriProof<F, H, D>> {
         let CompressedFriProof {
             commit_phase_merkle_caps,
             query_round_proofs,
@@ -253,7 +386,7 @@ impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> CompressedFriPr
         let FriChallenges {
             fri_query_indices: indices,
             ..
-        } = &challenges.fri_challenges;
+        } = challenges;
         let mut fri_inferred_elements = fri_inferred_elements.0.into_iter();
         let cap_height = params.config.cap_height;
         let reduction_arity_bits = &params.reduction_arity_bits;
@@ -322,10 +455,10 @@ impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> CompressedFriPr
             initial_trees_proofs
         )
         .map(|(ls, is, ps)| decompress_merkle_proofs(ls, is, &ps, height, cap_height))
-        .collect::<Vec<_>>();
+        .collect::<anyhow::Result<Vec<_>>>()?;
         let steps_proofs = izip!(&steps_evals, &steps_indices, steps_proofs, heights)
             .map(|(ls, is, ps, h)| decompress_merkle_proofs(ls, is, &ps, h, cap_height))
-            .collect::<Vec<_>>();
+            .collect::<anyhow::Result<Vec<_>>>()?;
 
         let mut decompressed_query_proofs = Vec::with_capacity(num_reductions);
         for i in 0..indices.len() {
@@ -351,12 +484,12 @@ impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> CompressedFriPr
             })
         }
 
-        FriProof {
+        Ok(FriProof {
             commit_phase_merkle_caps,
             query_round_proofs: decompressed_query_proofs,
             final_poly,
             pow_witness,
-        }
+        })
     }
 }
 
@@ -17,7 +17,7 @@ use crate::hash::hash_types::{MerkleCapTarget, RichField};
 use crate::iop::ext_target::{flatten_target, ExtensionTarget};
 use crate::iop::target::{BoolTarget, Target};
 use crate::plonk::circuit_builder::CircuitBuilder;
-use crate::plonk::config::{AlgebraicHasher, GenericConfig};
+use crate::plonk::config::{CircuitHasher, GenericConfig};
 use crate::util::reducing::ReducingFactorTarget;
 use crate::util::{log2_strict, reverse_index_bits_in_place};
 use crate::with_context;
@@ -107,7 +107,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         proof: &FriProofTarget<D>,
         params: &FriParams,
     ) where
-        C::Hasher: AlgebraicHasher<F>,
+        C::Hasher: CircuitHasher<F>,
     {
         if let Some(max_arity_bits) = params.max_arity_bits() {
             self.check_recursion_config(max_arity_bits);
@@ -175,7 +175,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         }
     }
 
-    fn fri_verify_initial_proof<H: AlgebraicHasher<F>>(
+    fn fri_verify_initial_proof<H: CircuitHasher<F>>(
         &mut self,
         x_index_bits: &[BoolTarget],
         proof: &FriInitialTreeProofTarget,
@@ -258,7 +258,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         round_proof: &FriQueryRoundTarget<D>,
         params: &FriParams,
     ) where
-        C::Hasher: AlgebraicHasher<F>,
+        C::Hasher: CircuitHasher<F>,
     {
         let n_log = log2_strict(n);
 
@@ -8,7 +8,8 @@ use crate::hash::hash_types::RichField;
 use crate::plonk::config::GenericConfig;
 use crate::plonk::plonk_common::salt_size;
 
-pub(crate) fn validate_fri_proof_shape<F, C, const D: usize>(
+/// Checks that a [`FriProof`] has the shape expected for the given instance and parameters.
+pub fn validate_fri_proof_shape<F, C, const D: usize>(
     proof: &FriProof<F, C::Hasher, D>,
     instance: &FriInstanceInfo<F, D>,
     params: &FriParams,
@@ -4,7 +4,7 @@ use crate::field::extension::Extendable;
 use crate::fri::proof::{FriProof, FriProofTarget};
 use crate::hash::hash_types::RichField;
 use crate::iop::witness::WitnessWrite;
-use crate::plonk::config::AlgebraicHasher;
+use crate::plonk::config::{CircuitHasher, GenericHashOut};
 
 /// Set the targets in a `FriProofTarget` to their corresponding values in a `FriProof`.
 pub fn set_fri_proof_target<F, W, H, const D: usize>(
@@ -14,7 +14,7 @@ pub fn set_fri_proof_target<F, W, H, const D: usize>(
 ) where
     F: RichField + Extendable<D>,
     W: WitnessWrite<F> + ?Sized,
-    H: AlgebraicHasher<F>,
+    H: CircuitHasher<F>,
 {
     witness.set_target(fri_proof_target.pow_witness, fri_proof.pow_witness);
 
@@ -50,7 +50,7 @@ pub fn set_fri_proof_target<F, W, H, const D: usize>(
                 witness.set_target(t, x);
             }
             for (&t, &x) in at.1.siblings.iter().zip_eq(&a.1.siblings) {
-                witness.set_hash_target(t, x);
+                witness.set_target_arr(&t.elements, &x.to_vec());
             }
         }
 
@@ -64,7 +64,7 @@ pub fn set_fri_proof_target<F, W, H, const D: usize>(
                 .iter()
                 .zip_eq(&s.merkle_proof.siblings)
             {
-                witness.set_hash_target(t, x);
+                witness.set_target_arr(&t.elements, &x.to_vec());
             }
         }
     }
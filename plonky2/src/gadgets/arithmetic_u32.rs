@@ -0,0 +1,461 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::field::extension::Extendable;
+use crate::gates::comparison::ComparisonGate;
+use crate::gates::u32_arithmetic::U32ArithmeticGate;
+use crate::gates::u32_subtraction::U32SubtractionGate;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// A target holding a value less than `2^32`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct U32Target(pub Target);
+
+/// Number of chunks used by `ComparisonGate`s comparing 32-bit values, so that chunks are
+/// checked with degree 4 constraints.
+const U32_COMPARISON_CHUNKS: usize = 16;
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Adds a virtual target for a 32-bit value. It is not range-checked: see `range_check_u32`.
+    pub fn add_virtual_u32_target(&mut self) -> U32Target {
+        U32Target(self.add_virtual_target())
+    }
+
+    pub fn add_virtual_u32_targets(&mut self, n: usize) -> Vec<U32Target> {
+        self.add_virtual_targets(n)
+            .into_iter()
+            .map(U32Target)
+            .collect()
+    }
+
+    /// Returns a target for the constant `c`.
+    pub fn constant_u32(&mut self, c: u32) -> U32Target {
+        U32Target(self.constant(F::from_canonical_u32(c)))
+    }
+
+    pub fn zero_u32(&mut self) -> U32Target {
+        U32Target(self.zero())
+    }
+
+    pub fn one_u32(&mut self) -> U32Target {
+        U32Target(self.one())
+    }
+
+    pub fn connect_u32(&mut self, x: U32Target, y: U32Target) {
+        self.connect(x.0, y.0)
+    }
+
+    pub fn assert_zero_u32(&mut self, x: U32Target) {
+        self.assert_zero(x.0)
+    }
+
+    /// Checks that each of the given targets holds a value less than `2^32`.
+    pub fn range_check_u32(&mut self, vals: &[U32Target]) {
+        for &x in vals {
+            self.range_check(x.0, 32);
+        }
+    }
+
+    /// Returns `(low, high)` such that `x * y + z = high * 2^32 + low`.
+    pub fn mul_add_u32(
+        &mut self,
+        x: U32Target,
+        y: U32Target,
+        z: U32Target,
+    ) -> (U32Target, U32Target) {
+        let gate = U32ArithmeticGate::<F, D>::new_from_config(&self.config);
+        let (row, copy) = self.find_slot(gate, &[], &[]);
+
+        self.connect(
+            Target::wire(
+                row,
+                U32ArithmeticGate::<F, D>::wire_ith_multiplicand_0(copy),
+            ),
+            x.0,
+        );
+        self.connect(
+            Target::wire(
+                row,
+                U32ArithmeticGate::<F, D>::wire_ith_multiplicand_1(copy),
+            ),
+            y.0,
+        );
+        self.connect(
+            Target::wire(row, U32ArithmeticGate::<F, D>::wire_ith_addend(copy)),
+            z.0,
+        );
+
+        let output_low = U32Target(Target::wire(
+            row,
+            U32ArithmeticGate::<F, D>::wire_ith_output_low_half(copy),
+        ));
+        let output_high = U32Target(Target::wire(
+            row,
+            U32ArithmeticGate::<F, D>::wire_ith_output_high_half(copy),
+        ));
+
+        (output_low, output_high)
+    }
+
+    /// Returns `(sum, carry)` such that `x + y = carry * 2^32 + sum`.
+    pub fn add_u32(&mut self, x: U32Target, y: U32Target) -> (U32Target, U32Target) {
+        let one = self.one_u32();
+        self.mul_add_u32(x, one, y)
+    }
+
+    /// Returns `(sum, carry)` such that `x + y + carry_in = carry * 2^32 + sum`. `carry_in` must
+    /// be at most one.
+    pub fn add_u32_with_carry(
+        &mut self,
+        x: U32Target,
+        y: U32Target,
+        carry_in: BoolTarget,
+    ) -> (U32Target, U32Target) {
+        // The addend may be equal to `2^32`, which `U32ArithmeticGate` supports as the result
+        // still has at most 33 bits.
+        let addend = U32Target(self.add(y.0, carry_in.target));
+        let one = self.one_u32();
+        self.mul_add_u32(x, one, addend)
+    }
+
+    /// Adds all the given values along with `carry`, returning the low 32 bits of the sum and
+    /// the carry.
+    pub fn add_u32s_with_carry(
+        &mut self,
+        to_add: &[U32Target],
+        carry: U32Target,
+    ) -> (U32Target, U32Target) {
+        let mut sum = carry;
+        let mut high = self.zero_u32();
+        for &x in to_add {
+            let (new_sum, carry) = self.add_u32(sum, x);
+            sum = new_sum;
+            (high, _) = self.add_u32(high, carry);
+        }
+        (sum, high)
+    }
+
+    /// Returns `(low, high)` such that `x * y = high * 2^32 + low`.
+    pub fn mul_u32(&mut self, x: U32Target, y: U32Target) -> (U32Target, U32Target) {
+        let zero = self.zero_u32();
+        self.mul_add_u32(x, y, zero)
+    }
+
+    /// Returns `(result, borrow)` such that `x - y - borrow_in = result - borrow * 2^32`.
+    /// `borrow_in` must be at most one.
+    pub fn sub_u32(
+        &mut self,
+        x: U32Target,
+        y: U32Target,
+        borrow_in: U32Target,
+    ) -> (U32Target, U32Target) {
+        let gate = U32SubtractionGate::<F, D>::new_from_config(&self.config);
+        let (row, copy) = self.find_slot(gate, &[], &[]);
+
+        self.connect(
+            Target::wire(row, U32SubtractionGate::<F, D>::wire_ith_input_x(copy)),
+            x.0,
+        );
+        self.connect(
+            Target::wire(row, U32SubtractionGate::<F, D>::wire_ith_input_y(copy)),
+            y.0,
+        );
+        self.connect(
+            Target::wire(row, U32SubtractionGate::<F, D>::wire_ith_input_borrow(copy)),
+            borrow_in.0,
+        );
+
+        let output_result = U32Target(Target::wire(
+            row,
+            U32SubtractionGate::<F, D>::wire_ith_output_result(copy),
+        ));
+        let output_borrow = U32Target(Target::wire(
+            row,
+            U32SubtractionGate::<F, D>::wire_ith_output_borrow(copy),
+        ));
+
+        (output_result, output_borrow)
+    }
+
+    /// Returns whether `x <= y`. This also checks that both inputs fit in 32 bits.
+    pub fn le_u32(&mut self, x: U32Target, y: U32Target) -> BoolTarget {
+        let gate = ComparisonGate::<F, D>::new(32, U32_COMPARISON_CHUNKS);
+        let row = self.add_gate(gate, vec![]);
+
+        self.connect(
+            Target::wire(row, ComparisonGate::<F, D>::wire_first_input()),
+            x.0,
+        );
+        self.connect(
+            Target::wire(row, ComparisonGate::<F, D>::wire_second_input()),
+            y.0,
+        );
+
+        BoolTarget::new_unsafe(Target::wire(
+            row,
+            ComparisonGate::<F, D>::wire_result_bool(),
+        ))
+    }
+
+    /// Returns whether `x < y`. This also checks that both inputs fit in 32 bits.
+    pub fn lt_u32(&mut self, x: U32Target, y: U32Target) -> BoolTarget {
+        let y_le_x = self.le_u32(y, x);
+        self.not(y_le_x)
+    }
+
+    /// Returns `(quotient, remainder)` of the division of `x` by `y`, which must be nonzero.
+    pub fn div_rem_u32(&mut self, x: U32Target, y: U32Target) -> (U32Target, U32Target) {
+        let quotient = self.add_virtual_u32_target();
+        let remainder = self.add_virtual_u32_target();
+
+        self.add_simple_generator(U32DivRemGenerator {
+            dividend: x,
+            divisor: y,
+            quotient,
+            remainder,
+        });
+
+        self.range_check_u32(&[quotient]);
+        // This also range-checks `remainder` and `y`.
+        let remainder_lt_divisor = self.lt_u32(remainder, y);
+        self.assert_one(remainder_lt_divisor.target);
+
+        let (low, high) = self.mul_add_u32(quotient, y, remainder);
+        self.connect_u32(low, x);
+        self.assert_zero_u32(high);
+
+        (quotient, remainder)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct U32DivRemGenerator {
+    dividend: U32Target,
+    divisor: U32Target,
+    quotient: U32Target,
+    remainder: U32Target,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for U32DivRemGenerator {
+    fn id(&self) -> String {
+        "U32DivRemGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.dividend.0, self.divisor.0]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let dividend = witness.get_target(self.dividend.0).to_canonical_u64();
+        let divisor = witness.get_target(self.divisor.0).to_canonical_u64();
+        assert_ne!(divisor, 0, "Division by zero");
+
+        out_buffer.set_target(self.quotient.0, F::from_canonical_u64(dividend / divisor));
+        out_buffer.set_target(self.remainder.0, F::from_canonical_u64(dividend % divisor));
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target(self.dividend.0)?;
+        dst.write_target(self.divisor.0)?;
+        dst.write_target(self.quotient.0)?;
+        dst.write_target(self.remainder.0)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let dividend = U32Target(src.read_target()?);
+        let divisor = U32Target(src.read_target()?);
+        let quotient = U32Target(src.read_target()?);
+        let remainder = U32Target(src.read_target()?);
+        Ok(Self {
+            dividend,
+            divisor,
+            quotient,
+            remainder,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::field::types::Field;
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn prove_and_verify(builder: CircuitBuilder<F, D>, pw: PartialWitness<F>) -> Result<()> {
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_mul_add_u32() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut rng = OsRng;
+        for _ in 0..5 {
+            let (x, y, z) = (rng.gen::<u32>(), rng.gen::<u32>(), rng.gen::<u32>());
+            let expected = x as u64 * y as u64 + z as u64;
+
+            let xs = builder.add_virtual_u32_targets(3);
+            let (low, high) = builder.mul_add_u32(xs[0], xs[1], xs[2]);
+            let expected_low = builder.constant_u32(expected as u32);
+            let expected_high = builder.constant_u32((expected >> 32) as u32);
+            builder.connect_u32(low, expected_low);
+            builder.connect_u32(high, expected_high);
+
+            pw.set_u32_target(xs[0], x);
+            pw.set_u32_target(xs[1], y);
+            pw.set_u32_target(xs[2], z);
+        }
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_add_sub_u32() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut rng = OsRng;
+        let (x, y) = (rng.gen::<u32>(), rng.gen::<u32>());
+        let carry_in = rng.gen::<bool>();
+        let borrow_in = rng.gen::<bool>();
+
+        let x_target = builder.add_virtual_u32_target();
+        let y_target = builder.add_virtual_u32_target();
+        let carry_in_target = builder.constant_bool(carry_in);
+        let borrow_in_target = builder.constant_u32(borrow_in as u32);
+
+        let (sum, carry) = builder.add_u32_with_carry(x_target, y_target, carry_in_target);
+        let sum_expected = x as u64 + y as u64 + carry_in as u64;
+        let sum_expected_low = builder.constant_u32(sum_expected as u32);
+        let sum_expected_high = builder.constant_u32((sum_expected >> 32) as u32);
+        builder.connect_u32(sum, sum_expected_low);
+        builder.connect_u32(carry, sum_expected_high);
+
+        let (diff, borrow) = builder.sub_u32(x_target, y_target, borrow_in_target);
+        let (diff_expected, borrow_1) = x.overflowing_sub(y);
+        let (diff_expected, borrow_2) = diff_expected.overflowing_sub(borrow_in as u32);
+        let diff_expected = builder.constant_u32(diff_expected);
+        let borrow_expected = builder.constant_u32((borrow_1 || borrow_2) as u32);
+        builder.connect_u32(diff, diff_expected);
+        builder.connect_u32(borrow, borrow_expected);
+
+        let many = builder.add_virtual_u32_targets(3);
+        let values = [u32::MAX, u32::MAX, rng.gen::<u32>()];
+        let (many_sum, many_carry) = builder.add_u32s_with_carry(&many, y_target);
+        let many_expected = values.iter().map(|&v| v as u64).sum::<u64>() + y as u64;
+        let many_expected_low = builder.constant_u32(many_expected as u32);
+        let many_expected_high = builder.constant_u32((many_expected >> 32) as u32);
+        builder.connect_u32(many_sum, many_expected_low);
+        builder.connect_u32(many_carry, many_expected_high);
+
+        pw.set_u32_target(x_target, x);
+        pw.set_u32_target(y_target, y);
+        for (&t, &v) in many.iter().zip(&values) {
+            pw.set_u32_target(t, v);
+        }
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_comparison_u32() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut rng = OsRng;
+        let x = rng.gen::<u32>();
+        for y in [
+            0,
+            x.saturating_sub(1),
+            x,
+            x.saturating_add(1),
+            u32::MAX,
+            rng.gen(),
+        ] {
+            let x_target = builder.add_virtual_u32_target();
+            let y_target = builder.add_virtual_u32_target();
+            let le = builder.le_u32(x_target, y_target);
+            let lt = builder.lt_u32(x_target, y_target);
+            let le_expected = builder.constant_bool(x <= y);
+            let lt_expected = builder.constant_bool(x < y);
+            builder.connect(le.target, le_expected.target);
+            builder.connect(lt.target, lt_expected.target);
+
+            pw.set_u32_target(x_target, x);
+            pw.set_u32_target(y_target, y);
+        }
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_comparison_u32_out_of_range() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x_target = builder.add_virtual_u32_target();
+        let y_target = builder.add_virtual_u32_target();
+        builder.le_u32(x_target, y_target);
+
+        pw.set_target(x_target.0, F::from_canonical_u64(1 << 32));
+        pw.set_target(y_target.0, F::ZERO);
+
+        prove_and_verify(builder, pw).unwrap();
+    }
+
+    #[test]
+    fn test_div_rem_u32() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut rng = OsRng;
+        for (x, y) in [
+            (rng.gen::<u32>(), rng.gen_range(1..u32::MAX)),
+            (u32::MAX, 1),
+            (7, 7),
+        ] {
+            let x_target = builder.add_virtual_u32_target();
+            let y_target = builder.add_virtual_u32_target();
+            let (quotient, remainder) = builder.div_rem_u32(x_target, y_target);
+            let quotient_expected = builder.constant_u32(x / y);
+            let remainder_expected = builder.constant_u32(x % y);
+            builder.connect_u32(quotient, quotient_expected);
+            builder.connect_u32(remainder, remainder_expected);
+
+            pw.set_u32_target(x_target, x);
+            pw.set_u32_target(y_target, y);
+        }
+
+        prove_and_verify(builder, pw)
+    }
+}
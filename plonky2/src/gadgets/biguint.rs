@@ -0,0 +1,651 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::cmp::{max, min};
+
+use num::{BigUint, Integer, Zero};
+
+use crate::field::extension::Extendable;
+use crate::gadgets::range_check::RangeCheckMode;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+use crate::util::{ceil_div_usize, log2_ceil};
+
+/// Number of bits in each limb of a [`BigUintTarget`]. Products of two limbs, and sums of a few
+/// such products, stay far below the Goldilocks order so that limb arithmetic never wraps around.
+pub const BIGUINT_LIMB_BITS: usize = 16;
+
+/// An arbitrary-size unsigned integer, as little-endian limbs of [`BIGUINT_LIMB_BITS`] bits.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BigUintTarget {
+    pub limbs: Vec<Target>,
+}
+
+impl BigUintTarget {
+    pub fn num_limbs(&self) -> usize {
+        self.limbs.len()
+    }
+
+    pub fn get_limb(&self, i: usize) -> Target {
+        self.limbs[i]
+    }
+}
+
+/// Splits `x` into little-endian limbs of [`BIGUINT_LIMB_BITS`] bits, without leading zero limbs.
+pub(crate) fn biguint_to_limbs(x: &BigUint) -> Vec<u64> {
+    let mut limbs = x
+        .to_u32_digits()
+        .into_iter()
+        .flat_map(|d| [d as u64 & 0xFFFF, d as u64 >> BIGUINT_LIMB_BITS])
+        .collect::<Vec<_>>();
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    limbs
+}
+
+/// Recombines little-endian limbs of [`BIGUINT_LIMB_BITS`] bits.
+pub(crate) fn biguint_from_limbs(limbs: impl DoubleEndedIterator<Item = u64>) -> BigUint {
+    limbs.rev().fold(BigUint::zero(), |acc, limb| {
+        (acc << BIGUINT_LIMB_BITS) + BigUint::from(limb)
+    })
+}
+
+/// Number of bits needed to bound the absolute value of a column holding `num_terms` terms, each
+/// either a limb or a product of two limbs.
+pub(crate) fn max_column_bits(num_terms: usize) -> usize {
+    2 * BIGUINT_LIMB_BITS + log2_ceil(num_terms.max(1))
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn constant_biguint(&mut self, value: &BigUint) -> BigUintTarget {
+        let limbs = biguint_to_limbs(value)
+            .into_iter()
+            .map(|l| self.constant(F::from_canonical_u64(l)))
+            .collect();
+        BigUintTarget { limbs }
+    }
+
+    pub fn zero_biguint(&mut self) -> BigUintTarget {
+        BigUintTarget {
+            limbs: vec![self.zero()],
+        }
+    }
+
+    /// Adds a virtual `BigUintTarget` with `num_limbs` limbs, each range-checked to
+    /// [`BIGUINT_LIMB_BITS`] bits.
+    pub fn add_virtual_biguint_target(&mut self, num_limbs: usize) -> BigUintTarget {
+        let limbs = self.add_virtual_targets(num_limbs);
+        for &limb in &limbs {
+            self.range_check(limb, BIGUINT_LIMB_BITS);
+        }
+        BigUintTarget { limbs }
+    }
+
+    /// Asserts that `lhs` and `rhs` represent the same integer. Limbs present in only one of them
+    /// must be zero.
+    pub fn connect_biguint(&mut self, lhs: &BigUintTarget, rhs: &BigUintTarget) {
+        let min_limbs = min(lhs.num_limbs(), rhs.num_limbs());
+        for i in 0..min_limbs {
+            self.connect(lhs.get_limb(i), rhs.get_limb(i));
+        }
+
+        for i in min_limbs..lhs.num_limbs() {
+            self.assert_zero(lhs.get_limb(i));
+        }
+        for i in min_limbs..rhs.num_limbs() {
+            self.assert_zero(rhs.get_limb(i));
+        }
+    }
+
+    /// Pads the shorter of `a` and `b` with zero limbs so that both have the same length.
+    pub fn pad_biguints(
+        &mut self,
+        a: &BigUintTarget,
+        b: &BigUintTarget,
+    ) -> (BigUintTarget, BigUintTarget) {
+        let num_limbs = max(a.num_limbs(), b.num_limbs());
+        let mut padded_a = a.clone();
+        let mut padded_b = b.clone();
+        padded_a.limbs.resize(num_limbs, self.zero());
+        padded_b.limbs.resize(num_limbs, self.zero());
+        (padded_a, padded_b)
+    }
+
+    /// Returns whether `a <= b`.
+    pub fn cmp_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BoolTarget {
+        let no_borrow = self._false();
+        let (_, borrow) = self.sub_biguint_with_borrow(b, a, no_borrow);
+        self.not(borrow)
+    }
+
+    /// Asserts that `a < b`.
+    pub fn assert_lt_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) {
+        // `a < b` iff `b - a - 1` does not underflow.
+        let borrow_in = self._true();
+        let (_, borrow) = self.sub_biguint_with_borrow(b, a, borrow_in);
+        self.assert_zero(borrow.target);
+    }
+
+    pub fn add_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        let (a, b) = self.pad_biguints(a, b);
+
+        let mut carry = self.zero();
+        let mut limbs = Vec::with_capacity(a.num_limbs() + 1);
+        for (&a_i, &b_i) in a.limbs.iter().zip(&b.limbs) {
+            let sum = self.add_many([a_i, b_i, carry]);
+            let (low, high) = self.split_biguint_column(sum, BIGUINT_LIMB_BITS + 1);
+            limbs.push(low);
+            carry = high;
+        }
+        limbs.push(carry);
+
+        BigUintTarget { limbs }
+    }
+
+    /// Computes `a - b - borrow_in` modulo `2^(16 n)`, where `n` is the larger number of limbs,
+    /// and returns it along with the outgoing borrow.
+    fn sub_biguint_with_borrow(
+        &mut self,
+        a: &BigUintTarget,
+        b: &BigUintTarget,
+        borrow_in: BoolTarget,
+    ) -> (BigUintTarget, BoolTarget) {
+        let (a, b) = self.pad_biguints(a, b);
+        let base = F::from_canonical_u64(1 << BIGUINT_LIMB_BITS);
+        let one = self.one();
+
+        let mut borrow = borrow_in.target;
+        let mut limbs = Vec::with_capacity(a.num_limbs());
+        for (&a_i, &b_i) in a.limbs.iter().zip(&b.limbs) {
+            // `a_i + 2^16 - b_i - borrow` lies in `[0, 2^17)`; its high bit is set iff no borrow
+            // is needed.
+            let diff = self.add_const(a_i, base);
+            let diff = self.sub(diff, b_i);
+            let diff = self.sub(diff, borrow);
+            let (low, high) = self.split_biguint_column(diff, BIGUINT_LIMB_BITS + 1);
+            limbs.push(low);
+            borrow = self.sub(one, high);
+        }
+
+        (BigUintTarget { limbs }, BoolTarget::new_unsafe(borrow))
+    }
+
+    /// Computes `a - b`, asserting that `a >= b`.
+    pub fn sub_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        let no_borrow = self._false();
+        let (diff, borrow) = self.sub_biguint_with_borrow(a, b, no_borrow);
+        self.assert_zero(borrow.target);
+        diff
+    }
+
+    pub fn mul_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        if a.num_limbs() == 0 || b.num_limbs() == 0 {
+            return self.zero_biguint();
+        }
+
+        let mut columns = Vec::new();
+        self.accumulate_product_columns(&mut columns, a, b, false);
+        let column_bits = max_column_bits(min(a.num_limbs(), b.num_limbs()) + 1);
+
+        let mut carry = self.zero();
+        let mut limbs = Vec::with_capacity(columns.len() + 1);
+        for column in columns {
+            let sum = self.add(column, carry);
+            let (low, high) = self.split_biguint_column(sum, column_bits);
+            limbs.push(low);
+            carry = high;
+        }
+        limbs.push(carry);
+
+        BigUintTarget { limbs }
+    }
+
+    pub fn mul_biguint_by_bool(&mut self, a: &BigUintTarget, b: BoolTarget) -> BigUintTarget {
+        let limbs = a.limbs.iter().map(|&l| self.mul(l, b.target)).collect();
+        BigUintTarget { limbs }
+    }
+
+    /// Computes `x * y + z`.
+    pub fn mul_add_biguint(
+        &mut self,
+        x: &BigUintTarget,
+        y: &BigUintTarget,
+        z: &BigUintTarget,
+    ) -> BigUintTarget {
+        let prod = self.mul_biguint(x, y);
+        self.add_biguint(&prod, z)
+    }
+
+    /// Returns `(a / b, a % b)`. `b` must be nonzero.
+    pub fn div_rem_biguint(
+        &mut self,
+        a: &BigUintTarget,
+        b: &BigUintTarget,
+    ) -> (BigUintTarget, BigUintTarget) {
+        let div = self.add_virtual_biguint_target(a.num_limbs());
+        let rem = self.add_virtual_biguint_target(b.num_limbs());
+
+        self.add_simple_generator(BigUintDivRemGenerator {
+            a: a.clone(),
+            b: b.clone(),
+            div: div.clone(),
+            rem: rem.clone(),
+        });
+
+        // a - div * b - rem == 0
+        let mut columns = Vec::new();
+        self.accumulate_limb_columns(&mut columns, a, false);
+        self.accumulate_product_columns(&mut columns, &div, b, true);
+        self.accumulate_limb_columns(&mut columns, &rem, true);
+        let num_terms = min(div.num_limbs(), b.num_limbs()) + 2;
+        self.assert_zero_columns(&columns, max_column_bits(num_terms));
+
+        self.assert_lt_biguint(&rem, b);
+
+        (div, rem)
+    }
+
+    pub fn div_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        let (div, _rem) = self.div_rem_biguint(a, b);
+        div
+    }
+
+    pub fn rem_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        let (_div, rem) = self.div_rem_biguint(a, b);
+        rem
+    }
+
+    /// Splits `x`, which must fit in `num_bits > BIGUINT_LIMB_BITS` bits, into a limb holding its low
+    /// [`BIGUINT_LIMB_BITS`] bits and a carry holding the rest.
+    ///
+    /// With lookup range checks, the limb and carry are witnessed and range-checked with lookups.
+    /// Otherwise, `x` is decomposed into base-4 digits with `split_le_base`, which also range-checks
+    /// them, and the limb and carry are recombined from the digits.
+    fn split_biguint_column(&mut self, x: Target, num_bits: usize) -> (Target, Target) {
+        debug_assert!(num_bits > BIGUINT_LIMB_BITS);
+        if let RangeCheckMode::Lookup { .. } = self.range_check_mode() {
+            return self.split_low_high(x, BIGUINT_LIMB_BITS, num_bits);
+        }
+
+        let digits = self.split_le_base::<4>(x, ceil_div_usize(num_bits, 2));
+        if num_bits % 2 == 1 {
+            // The top digit holds a single bit.
+            let top = *digits.last().unwrap();
+            self.assert_bool(BoolTarget::new_unsafe(top));
+        }
+
+        let four = F::from_canonical_usize(4);
+        let mut from_digits = |digits: &[Target]| {
+            digits
+                .iter()
+                .rev()
+                .fold(self.zero(), |acc, &d| self.mul_const_add(four, acc, d))
+        };
+        let (low_digits, high_digits) = digits.split_at(BIGUINT_LIMB_BITS / 2);
+        (from_digits(low_digits), from_digits(high_digits))
+    }
+
+    /// Adds (or subtracts, if `negate`) the limbs of `x` to `columns`, extending it as needed.
+    pub(crate) fn accumulate_limb_columns(
+        &mut self,
+        columns: &mut Vec<Target>,
+        x: &BigUintTarget,
+        negate: bool,
+    ) {
+        if columns.len() < x.num_limbs() {
+            columns.resize(x.num_limbs(), self.zero());
+        }
+        for (column, &limb) in columns.iter_mut().zip(&x.limbs) {
+            *column = if negate {
+                self.sub(*column, limb)
+            } else {
+                self.add(*column, limb)
+            };
+        }
+    }
+
+    /// Adds (or subtracts, if `negate`) the column sums `sum_{i + j = k} x_i y_j` of the product
+    /// `x * y` to `columns`, extending it as needed.
+    pub(crate) fn accumulate_product_columns(
+        &mut self,
+        columns: &mut Vec<Target>,
+        x: &BigUintTarget,
+        y: &BigUintTarget,
+        negate: bool,
+    ) {
+        if x.num_limbs() == 0 || y.num_limbs() == 0 {
+            return;
+        }
+
+        let num_columns = x.num_limbs() + y.num_limbs() - 1;
+        if columns.len() < num_columns {
+            columns.resize(num_columns, self.zero());
+        }
+        let sign = if negate { F::NEG_ONE } else { F::ONE };
+        for (i, &x_i) in x.limbs.iter().enumerate() {
+            for (j, &y_j) in y.limbs.iter().enumerate() {
+                columns[i + j] = self.arithmetic(sign, F::ONE, x_i, y_j, columns[i + j]);
+            }
+        }
+    }
+
+    /// Asserts that `sum_k columns[k] * 2^(16 k) == 0`, where each column holds a signed integer
+    /// whose absolute value is below `2^max_column_bits`.
+    ///
+    /// The carries between columns are witnessed, offset to be nonnegative, and range-checked.
+    pub(crate) fn assert_zero_columns(&mut self, columns: &[Target], max_column_bits: usize) {
+        if columns.is_empty() {
+            return;
+        }
+
+        // |carry_{k+1}| <= (|column_k| + |carry_k|) / 2^16 < 2^carry_bits.
+        let carry_bits = max_column_bits - BIGUINT_LIMB_BITS + 1;
+        let offset = F::from_canonical_u64(1 << carry_bits);
+        let neg_base = -F::from_canonical_u64(1 << BIGUINT_LIMB_BITS);
+
+        let shifted_carries = self.add_virtual_targets(columns.len() - 1);
+        for &carry in &shifted_carries {
+            self.range_check(carry, carry_bits + 1);
+        }
+        self.add_simple_generator(ColumnCarryGenerator {
+            columns: columns.to_vec(),
+            shifted_carries: shifted_carries.clone(),
+            carry_bits,
+        });
+
+        let mut carry_in = self.zero();
+        for (k, &column) in columns.iter().enumerate() {
+            let sum = self.add(column, carry_in);
+            if k + 1 < columns.len() {
+                let carry_out = self.add_const(shifted_carries[k], -offset);
+                let rest = self.mul_const_add(neg_base, carry_out, sum);
+                self.assert_zero(rest);
+                carry_in = carry_out;
+            } else {
+                self.assert_zero(sum);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BigUintDivRemGenerator {
+    a: BigUintTarget,
+    b: BigUintTarget,
+    div: BigUintTarget,
+    rem: BigUintTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for BigUintDivRemGenerator
+{
+    fn id(&self) -> String {
+        "BigUintDivRemGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.a.limbs.iter().chain(&self.b.limbs).copied().collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let a = witness.get_biguint_target(&self.a);
+        let b = witness.get_biguint_target(&self.b);
+        let (div, rem) = a.div_rem(&b);
+
+        out_buffer.set_biguint_target(&self.div, &div);
+        out_buffer.set_biguint_target(&self.rem, &rem);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.a.limbs)?;
+        dst.write_target_vec(&self.b.limbs)?;
+        dst.write_target_vec(&self.div.limbs)?;
+        dst.write_target_vec(&self.rem.limbs)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = BigUintTarget {
+            limbs: src.read_target_vec()?,
+        };
+        let b = BigUintTarget {
+            limbs: src.read_target_vec()?,
+        };
+        let div = BigUintTarget {
+            limbs: src.read_target_vec()?,
+        };
+        let rem = BigUintTarget {
+            limbs: src.read_target_vec()?,
+        };
+        Ok(Self { a, b, div, rem })
+    }
+}
+
+/// Generates the carries of [`CircuitBuilder::assert_zero_columns`].
+#[derive(Debug, Default)]
+pub struct ColumnCarryGenerator {
+    columns: Vec<Target>,
+    shifted_carries: Vec<Target>,
+    carry_bits: usize,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for ColumnCarryGenerator {
+    fn id(&self) -> String {
+        "ColumnCarryGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.columns.clone()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let offset = 1i128 << self.carry_bits;
+        let mut carry = 0i128;
+        for (&column, &shifted_carry) in self.columns.iter().zip(&self.shifted_carries) {
+            // Columns hold small signed integers; interpret the upper half of the field as negative.
+            let column = witness.get_target(column).to_canonical_u64();
+            let column = if column > F::ORDER / 2 {
+                column as i128 - F::ORDER as i128
+            } else {
+                column as i128
+            };
+            carry = (column + carry) >> BIGUINT_LIMB_BITS;
+            out_buffer.set_target(
+                shifted_carry,
+                F::from_noncanonical_i64((carry + offset) as i64),
+            );
+        }
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.columns)?;
+        dst.write_target_vec(&self.shifted_carries)?;
+        dst.write_usize(self.carry_bits)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let columns = src.read_target_vec()?;
+        let shifted_carries = src.read_target_vec()?;
+        let carry_bits = src.read_usize()?;
+        Ok(Self {
+            columns,
+            shifted_carries,
+            carry_bits,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use num::bigint::RandBigInt;
+    use num::{BigUint, FromPrimitive, Integer};
+    use rand::rngs::OsRng;
+
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn random_biguint(num_u64s: usize) -> BigUint {
+        OsRng.gen_biguint(64 * num_u64s as u64)
+    }
+
+    fn prove_and_verify(builder: CircuitBuilder<F, D>, pw: PartialWitness<F>) -> Result<()> {
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_biguint_add() -> Result<()> {
+        let x_value = random_biguint(2);
+        let y_value = random_biguint(3);
+        let expected_z_value = &x_value + &y_value;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_biguint_target(8);
+        let y = builder.add_virtual_biguint_target(12);
+        let z = builder.add_biguint(&x, &y);
+        let expected_z = builder.add_virtual_biguint_target(z.num_limbs());
+        builder.connect_biguint(&z, &expected_z);
+
+        pw.set_biguint_target(&x, &x_value);
+        pw.set_biguint_target(&y, &y_value);
+        pw.set_biguint_target(&expected_z, &expected_z_value);
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_biguint_sub() -> Result<()> {
+        let mut x_value = random_biguint(3);
+        let mut y_value = random_biguint(3);
+        if y_value > x_value {
+            (x_value, y_value) = (y_value, x_value);
+        }
+        let expected_z_value = &x_value - &y_value;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.constant_biguint(&x_value);
+        let y = builder.constant_biguint(&y_value);
+        let z = builder.sub_biguint(&x, &y);
+        let expected_z = builder.constant_biguint(&expected_z_value);
+        builder.connect_biguint(&z, &expected_z);
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_biguint_sub_underflow() {
+        let x_value = BigUint::from_u64(5).unwrap();
+        let y_value = BigUint::from_u64(7).unwrap();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_biguint_target(1);
+        let y = builder.add_virtual_biguint_target(1);
+        builder.sub_biguint(&x, &y);
+        pw.set_biguint_target(&x, &x_value);
+        pw.set_biguint_target(&y, &y_value);
+
+        let data = builder.build::<C>();
+        data.prove(pw).unwrap();
+    }
+
+    #[test]
+    fn test_biguint_mul() -> Result<()> {
+        let x_value = random_biguint(2);
+        let y_value = random_biguint(4);
+        let expected_z_value = &x_value * &y_value;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_biguint_target(8);
+        let y = builder.add_virtual_biguint_target(16);
+        let z = builder.mul_biguint(&x, &y);
+        let expected_z = builder.add_virtual_biguint_target(z.num_limbs());
+        builder.connect_biguint(&z, &expected_z);
+
+        pw.set_biguint_target(&x, &x_value);
+        pw.set_biguint_target(&y, &y_value);
+        pw.set_biguint_target(&expected_z, &expected_z_value);
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_biguint_cmp() -> Result<()> {
+        let x_value = random_biguint(2);
+        let y_value = random_biguint(2);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.constant_biguint(&x_value);
+        let y = builder.constant_biguint(&y_value);
+        let cmp = builder.cmp_biguint(&x, &y);
+        let expected_cmp = builder.constant_bool(x_value <= y_value);
+        builder.connect(cmp.target, expected_cmp.target);
+
+        let cmp_self = builder.cmp_biguint(&x, &x);
+        builder.assert_one(cmp_self.target);
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_biguint_div_rem() -> Result<()> {
+        let mut x_value = random_biguint(4);
+        let mut y_value = random_biguint(2);
+        if y_value > x_value {
+            (x_value, y_value) = (y_value, x_value);
+        }
+        let (expected_div_value, expected_rem_value) = x_value.div_rem(&y_value);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_biguint_target(16);
+        let y = builder.add_virtual_biguint_target(8);
+        let (div, rem) = builder.div_rem_biguint(&x, &y);
+
+        let expected_div = builder.add_virtual_biguint_target(div.num_limbs());
+        let expected_rem = builder.add_virtual_biguint_target(rem.num_limbs());
+        builder.connect_biguint(&div, &expected_div);
+        builder.connect_biguint(&rem, &expected_rem);
+
+        pw.set_biguint_target(&x, &x_value);
+        pw.set_biguint_target(&y, &y_value);
+        pw.set_biguint_target(&expected_div, &expected_div_value);
+        pw.set_biguint_target(&expected_rem, &expected_rem_value);
+
+        prove_and_verify(builder, pw)
+    }
+}
@@ -0,0 +1,560 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, ByteTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// Tables used to compute bitwise operations one byte at a time. The two-byte tables take
+/// `256 * a + b` as input.
+#[derive(Copy, Clone, Debug)]
+enum ByteTable {
+    /// The identity on bytes, used for range checks.
+    Range,
+    /// `a ^ b`.
+    Xor,
+    /// `!a & b`.
+    AndNot,
+    /// `x >> s` for a single byte `x` and `1 <= s < 8`.
+    Shr(usize),
+}
+
+const NUM_BYTE_TABLES: usize = 10;
+
+fn identity(x: u16) -> u16 {
+    x
+}
+
+fn xor_bytes(x: u16) -> u16 {
+    (x >> 8) ^ (x & 0xff)
+}
+
+fn and_not_bytes(x: u16) -> u16 {
+    !(x >> 8) & x & 0xff
+}
+
+fn shr<const N: u16>(x: u16) -> u16 {
+    x >> N
+}
+
+const SHR_FNS: [fn(u16) -> u16; 7] = [
+    shr::<1>, shr::<2>, shr::<3>, shr::<4>, shr::<5>, shr::<6>, shr::<7>,
+];
+
+impl ByteTable {
+    const fn index(self) -> usize {
+        match self {
+            Self::Range => 0,
+            Self::Xor => 1,
+            Self::AndNot => 2,
+            Self::Shr(s) => 2 + s,
+        }
+    }
+
+    fn eval(self) -> fn(u16) -> u16 {
+        match self {
+            Self::Range => identity,
+            Self::Xor => xor_bytes,
+            Self::AndNot => and_not_bytes,
+            Self::Shr(s) => SHR_FNS[s - 1],
+        }
+    }
+
+    fn inputs(self) -> Vec<u16> {
+        match self {
+            Self::Xor | Self::AndNot => (0..=u16::MAX).collect(),
+            Self::Range | Self::Shr(_) => (0..256).collect(),
+        }
+    }
+}
+
+/// Computes bitwise operations on bytes and little-endian words of bytes with lookups.
+///
+/// Tables are only added to the builder once used, since the builder rejects unused tables. The
+/// xor and and-not tables have `2^16` entries each, i.e. around 2500 rows with the standard
+/// configuration, so the same instance should be used for a whole hash computation.
+#[derive(Debug, Default)]
+pub(crate) struct ByteLookups {
+    luts: [Option<usize>; NUM_BYTE_TABLES],
+}
+
+impl ByteLookups {
+    /// Returns the output of `table` on `input`, which is computed natively if `input` is a
+    /// constant.
+    fn lookup<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        table: ByteTable,
+        input: Target,
+    ) -> ByteTarget {
+        if let Some(c) = builder.target_as_constant(input) {
+            let output = table.eval()(c.to_canonical_u64() as u16);
+            return builder.constant_byte(output as u8);
+        }
+
+        let lut = *self.luts[table.index()]
+            .get_or_insert_with(|| builder.add_lookup_table_from_fn(table.eval(), &table.inputs()));
+        // Table outputs are all bytes.
+        ByteTarget::new_unsafe(builder.add_lookup_from_index(input, lut))
+    }
+
+    fn pair_lookup<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        table: ByteTable,
+        a: ByteTarget,
+        b: ByteTarget,
+    ) -> ByteTarget {
+        let input = builder.mul_const_add(F::from_canonical_u16(256), a.target, b.target);
+        self.lookup(builder, table, input)
+    }
+
+    /// Checks that `x` holds a byte.
+    pub(crate) fn range_check<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        x: Target,
+    ) -> ByteTarget {
+        let byte = self.lookup(builder, ByteTable::Range, x);
+        builder.connect(byte.target, x);
+        byte
+    }
+
+    pub(crate) fn xor<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        a: ByteTarget,
+        b: ByteTarget,
+    ) -> ByteTarget {
+        if builder.target_as_constant(a.target) == Some(F::ZERO) {
+            return b;
+        }
+        if builder.target_as_constant(b.target) == Some(F::ZERO) {
+            return a;
+        }
+        self.pair_lookup(builder, ByteTable::Xor, a, b)
+    }
+
+    /// Returns `!a & b`.
+    pub(crate) fn and_not<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        a: ByteTarget,
+        b: ByteTarget,
+    ) -> ByteTarget {
+        self.pair_lookup(builder, ByteTable::AndNot, a, b)
+    }
+
+    /// Returns `!a`, which needs no lookup.
+    pub(crate) fn not<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        a: ByteTarget,
+    ) -> ByteTarget {
+        let all_ones = builder.constant(F::from_canonical_u8(u8::MAX));
+        ByteTarget::new_unsafe(builder.sub(all_ones, a.target))
+    }
+
+    /// Returns `a & b`, computed as `!(!a) & b` to share the and-not table.
+    pub(crate) fn and<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        a: ByteTarget,
+        b: ByteTarget,
+    ) -> ByteTarget {
+        let not_a = self.not(builder, a);
+        self.and_not(builder, not_a, b)
+    }
+
+    pub(crate) fn xor_words<F: RichField + Extendable<D>, const D: usize, const N: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        a: &[ByteTarget; N],
+        b: &[ByteTarget; N],
+    ) -> [ByteTarget; N] {
+        core::array::from_fn(|i| self.xor(builder, a[i], b[i]))
+    }
+
+    /// Returns `!a & b`.
+    pub(crate) fn and_not_words<F: RichField + Extendable<D>, const D: usize, const N: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        a: &[ByteTarget; N],
+        b: &[ByteTarget; N],
+    ) -> [ByteTarget; N] {
+        core::array::from_fn(|i| self.and_not(builder, a[i], b[i]))
+    }
+
+    pub(crate) fn and_words<F: RichField + Extendable<D>, const D: usize, const N: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        a: &[ByteTarget; N],
+        b: &[ByteTarget; N],
+    ) -> [ByteTarget; N] {
+        core::array::from_fn(|i| self.and(builder, a[i], b[i]))
+    }
+
+    /// Rotates the little-endian word `x` right by `n` bits.
+    pub(crate) fn rotate_right<F: RichField + Extendable<D>, const D: usize, const N: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        x: &[ByteTarget; N],
+        n: usize,
+    ) -> [ByteTarget; N] {
+        self.shift_bytes_right(builder, x, n, true)
+    }
+
+    /// Rotates the little-endian word `x` left by `n` bits.
+    pub(crate) fn rotate_left<F: RichField + Extendable<D>, const D: usize, const N: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        x: &[ByteTarget; N],
+        n: usize,
+    ) -> [ByteTarget; N] {
+        self.rotate_right(builder, x, 8 * N - n % (8 * N))
+    }
+
+    /// Shifts the little-endian word `x` right by `n` bits.
+    pub(crate) fn shift_right<F: RichField + Extendable<D>, const D: usize, const N: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        x: &[ByteTarget; N],
+        n: usize,
+    ) -> [ByteTarget; N] {
+        self.shift_bytes_right(builder, x, n, false)
+    }
+
+    /// Shifts or rotates `x` right by `n` bits. Whole bytes are moved for free, and each byte of
+    /// the result combines the high bits of a byte with the low bits of the next one, which are
+    /// obtained from the `x >> s` table.
+    fn shift_bytes_right<F: RichField + Extendable<D>, const D: usize, const N: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        x: &[ByteTarget; N],
+        n: usize,
+        rotate: bool,
+    ) -> [ByteTarget; N] {
+        let n = n % (8 * N);
+        let (q, s) = (n / 8, n % 8);
+        let source = |j: usize| match (rotate, j < N) {
+            (true, _) => Some(x[j % N]),
+            (false, true) => Some(x[j]),
+            (false, false) => None,
+        };
+        if s == 0 {
+            return core::array::from_fn(|j| {
+                source(j + q).unwrap_or_else(|| builder.constant_byte(0))
+            });
+        }
+
+        // Without rotation, the bytes below `q` are shifted out entirely.
+        let highs: [Option<ByteTarget>; N] = core::array::from_fn(|j| {
+            source(j)
+                .filter(|_| rotate || j >= q)
+                .map(|byte| self.lookup(builder, ByteTable::Shr(s), byte.target))
+        });
+        let high = |j: usize| {
+            if rotate {
+                highs[j % N]
+            } else {
+                highs.get(j).copied().flatten()
+            }
+        };
+
+        core::array::from_fn(|j| {
+            // `(y << (8 - s)) & 0xff = y * 2^(8 - s) - (y >> s) * 2^8`.
+            let mut acc = high(j + q).map_or_else(|| builder.zero(), |h| h.target);
+            if let (Some(next), Some(next_high)) = (source(j + q + 1), high(j + q + 1)) {
+                acc = builder.mul_const_add(F::from_canonical_u64(1 << (8 - s)), next.target, acc);
+                acc = builder.mul_const_add(-F::from_canonical_u16(256), next_high.target, acc);
+            }
+            ByteTarget::new_unsafe(acc)
+        })
+    }
+
+    /// Returns the little-endian bytes of `x`, checking that `x < 2^(8 * num_bytes)`.
+    pub(crate) fn split_le<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        x: Target,
+        num_bytes: usize,
+    ) -> Vec<ByteTarget> {
+        assert!(num_bytes < 8, "Field elements have at most 8 bytes");
+        let bytes = self.add_split_bytes(builder, x, num_bytes);
+        let sum = pack_le(builder, &bytes);
+        builder.connect(sum, x);
+        bytes
+    }
+
+    /// Returns the eight little-endian bytes of the canonical representation of `x`.
+    pub(crate) fn split_canonical_le<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        x: Target,
+    ) -> [ByteTarget; 8] {
+        let bytes = self.add_split_bytes(builder, x, 8).try_into().unwrap();
+        let sum = pack_canonical_le(builder, &bytes);
+        builder.connect(sum, x);
+        bytes
+    }
+
+    /// Adds range-checked bytes which are generated from the little-endian representation of `x`.
+    fn add_split_bytes<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        x: Target,
+        num_bytes: usize,
+    ) -> Vec<ByteTarget> {
+        let bytes = builder.add_virtual_targets(num_bytes);
+        builder.add_simple_generator(ByteSplitGenerator {
+            integer: x,
+            bytes: bytes.clone(),
+        });
+
+        bytes
+            .into_iter()
+            .map(|byte| self.range_check(builder, byte))
+            .collect()
+    }
+}
+
+/// Returns the value of the little-endian bytes `bytes`.
+pub(crate) fn pack_le<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    bytes: &[ByteTarget],
+) -> Target {
+    let base = F::from_canonical_u16(256);
+    bytes.iter().rev().fold(builder.zero(), |acc, byte| {
+        builder.mul_const_add(base, acc, byte.target)
+    })
+}
+
+/// Returns the value of the eight little-endian bytes `bytes`, checking that it is less than the
+/// Goldilocks order `2^64 - 2^32 + 1`, i.e. that the low half is zero if the high half is
+/// `2^32 - 1`.
+pub(crate) fn pack_canonical_le<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    bytes: &[ByteTarget; 8],
+) -> Target {
+    assert_eq!(
+        F::ORDER,
+        0xFFFF_FFFF_0000_0001,
+        "Only Goldilocks is supported"
+    );
+    let low = pack_le(builder, &bytes[..4]);
+    let high = pack_le(builder, &bytes[4..]);
+
+    let max_high = builder.constant(F::from_canonical_u32(u32::MAX));
+    let high_is_max = builder.is_equal(high, max_high);
+    let low_if_high_is_max = builder.mul(high_is_max.target, low);
+    builder.assert_zero(low_if_high_is_max);
+
+    builder.mul_const_add(F::from_canonical_u64(1 << 32), high, low)
+}
+
+/// Returns the value of the eight little-endian bytes `bytes` modulo the Goldilocks order
+/// `2^64 - 2^32 + 1`, along with a borrow bit which is set if the value is at least the order,
+/// i.e. if the high half is `2^32 - 1` and the low half is nonzero.
+pub(crate) fn pack_le_with_borrow<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    bytes: &[ByteTarget; 8],
+) -> (Target, BoolTarget) {
+    assert_eq!(
+        F::ORDER,
+        0xFFFF_FFFF_0000_0001,
+        "Only Goldilocks is supported"
+    );
+    let low = pack_le(builder, &bytes[..4]);
+    let high = pack_le(builder, &bytes[4..]);
+
+    let max_high = builder.constant(F::from_canonical_u32(u32::MAX));
+    let high_is_max = builder.is_equal(high, max_high);
+    let zero = builder.zero();
+    let low_is_zero = builder.is_equal(low, zero);
+    let low_is_nonzero = builder.not(low_is_zero);
+    let borrow = builder.and(high_is_max, low_is_nonzero);
+
+    // The field arithmetic reduces the value modulo the order.
+    let value = builder.mul_const_add(F::from_canonical_u64(1 << 32), high, low);
+    (value, borrow)
+}
+
+#[derive(Debug, Default)]
+pub struct ByteSplitGenerator {
+    integer: Target,
+    bytes: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for ByteSplitGenerator {
+    fn id(&self) -> String {
+        "ByteSplitGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.integer]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let mut integer_value = witness.get_target(self.integer).to_canonical_u64();
+
+        for &byte in &self.bytes {
+            out_buffer.set_target(byte, F::from_canonical_u64(integer_value & 0xff));
+            integer_value >>= 8;
+        }
+
+        debug_assert_eq!(
+            integer_value, 0,
+            "Integer too large to fit in given number of bytes"
+        );
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target(self.integer)?;
+        dst.write_target_vec(&self.bytes)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let integer = src.read_target()?;
+        let bytes = src.read_target_vec()?;
+        Ok(Self { integer, bytes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::field::types::{Field, Field64, PrimeField64, Sample};
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_shifts_and_rotations() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut lookups = ByteLookups::default();
+
+        let x = OsRng.gen::<u32>();
+        let x_target = builder.add_virtual_byte_targets(4);
+        let x_target: [ByteTarget; 4] = core::array::from_fn(|i| x_target[i]);
+        for (i, &t) in x_target.iter().enumerate() {
+            pw.set_byte_target(t, x.to_le_bytes()[i]);
+        }
+
+        for n in [0, 1, 7, 8, 13, 24, 31] {
+            let rotated = lookups.rotate_right(&mut builder, &x_target, n);
+            let shifted = lookups.shift_right(&mut builder, &x_target, n);
+            for (targets, expected) in [(rotated, x.rotate_right(n as u32)), (shifted, x >> n)] {
+                for (&t, byte) in targets.iter().zip(expected.to_le_bytes()) {
+                    let byte = builder.constant_byte(byte);
+                    builder.connect(t.target, byte.target);
+                }
+            }
+        }
+
+        let packed = pack_le(&mut builder, &x_target);
+        let split = lookups.split_le(&mut builder, packed, 5);
+        for (&t, byte) in split.iter().zip(x.to_le_bytes()) {
+            let byte = builder.constant_byte(byte);
+            builder.connect(t.target, byte.target);
+        }
+        builder.assert_zero(split[4].target);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_split_canonical_le() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut lookups = ByteLookups::default();
+
+        for x in [
+            F::ZERO,
+            F::NEG_ONE,
+            F::from_canonical_u64(u32::MAX as u64),
+            F::rand(),
+        ] {
+            let x_target = builder.add_virtual_target();
+            pw.set_target(x_target, x);
+            let split = lookups.split_canonical_le(&mut builder, x_target);
+            for (&t, byte) in split.iter().zip(x.to_canonical_u64().to_le_bytes()) {
+                let byte = builder.constant_byte(byte);
+                builder.connect(t.target, byte.target);
+            }
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_pack_le_with_borrow() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        for x in [
+            0,
+            F::ORDER - 1,
+            F::ORDER,
+            F::ORDER + 1,
+            u64::MAX,
+            0xFFFF_FFFE_FFFF_FFFF,
+            OsRng.gen::<u64>(),
+        ] {
+            let x_target = builder.add_virtual_byte_targets(8);
+            for (&t, byte) in x_target.iter().zip(x.to_le_bytes()) {
+                pw.set_byte_target(t, byte);
+            }
+            let (value, borrow) = pack_le_with_borrow(&mut builder, &x_target.try_into().unwrap());
+            let expected_value = builder.constant(F::from_noncanonical_u64(x));
+            let expected_borrow = builder.constant_bool(x >= F::ORDER);
+            builder.connect(value, expected_value);
+            builder.connect(borrow.target, expected_borrow.target);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_le_out_of_range() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut lookups = ByteLookups::default();
+
+        let x = builder.add_virtual_target();
+        lookups.split_le(&mut builder, x, 2);
+        pw.set_target(x, F::from_canonical_u32(1 << 16));
+
+        let data = builder.build::<C>();
+        data.prove(pw).unwrap();
+    }
+}
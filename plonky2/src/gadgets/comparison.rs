@@ -0,0 +1,303 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+
+use crate::field::extension::Extendable;
+use crate::gates::comparison::ComparisonGate;
+use crate::gates::gate::Gate;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// Largest bit width supported by the comparison gadgets. Differences of inputs, shifted by
+/// `2^num_bits`, must fit in the field without wrapping around.
+pub const MAX_COMPARISON_BITS: usize = 62;
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Returns whether `x` is zero.
+    pub fn is_zero(&mut self, x: Target) -> BoolTarget {
+        let zero = self.zero();
+        self.is_equal(x, zero)
+    }
+
+    /// Returns whether `x <= y`. This also checks that both inputs are less than `2^num_bits`.
+    ///
+    /// It uses a single `ComparisonGate` if one with chunks of one or two bits covering exactly
+    /// `num_bits` bits fits in a row, and a bit decomposition of `y - x + 2^num_bits` otherwise.
+    pub fn less_than_or_equal(&mut self, x: Target, y: Target, num_bits: usize) -> BoolTarget {
+        assert_comparison_bits(num_bits);
+
+        // The gate range-checks its inputs on `num_chunks * chunk_bits` bits, so the chunks must
+        // cover exactly `num_bits` bits.
+        let chunk_bits = if num_bits % 2 == 0 { 2 } else { 1 };
+        let num_chunks = num_bits / chunk_bits;
+        let gate = ComparisonGate::<F, D>::new(num_bits, num_chunks.max(1));
+        if num_bits > 0 && gate.num_wires() <= self.config.num_wires {
+            let row = self.add_gate(gate, vec![]);
+            self.connect(
+                Target::wire(row, ComparisonGate::<F, D>::wire_first_input()),
+                x,
+            );
+            self.connect(
+                Target::wire(row, ComparisonGate::<F, D>::wire_second_input()),
+                y,
+            );
+            return BoolTarget::new_unsafe(Target::wire(
+                row,
+                ComparisonGate::<F, D>::wire_result_bool(),
+            ));
+        }
+
+        self.range_check(x, num_bits);
+        self.range_check(y, num_bits);
+        // `y - x + 2^num_bits` is in `[1, 2^(num_bits + 1))`, and its top bit is set iff `x <= y`.
+        let diff = self.sub(y, x);
+        let shifted_diff = self.add_const(diff, F::from_canonical_u64(1 << num_bits));
+        let bits = self.split_le(shifted_diff, num_bits + 1);
+        bits[num_bits]
+    }
+
+    /// Returns whether `x < y`. This also checks that both inputs are less than `2^num_bits`.
+    pub fn less_than(&mut self, x: Target, y: Target, num_bits: usize) -> BoolTarget {
+        let y_le_x = self.less_than_or_equal(y, x, num_bits);
+        self.not(y_le_x)
+    }
+
+    /// Returns the smaller of `x` and `y`, checking that both are less than `2^num_bits`.
+    pub fn min(&mut self, x: Target, y: Target, num_bits: usize) -> Target {
+        let x_le_y = self.less_than_or_equal(x, y, num_bits);
+        self.select(x_le_y, x, y)
+    }
+
+    /// Returns the larger of `x` and `y`, checking that both are less than `2^num_bits`.
+    pub fn max(&mut self, x: Target, y: Target, num_bits: usize) -> Target {
+        let x_le_y = self.less_than_or_equal(x, y, num_bits);
+        self.select(x_le_y, y, x)
+    }
+
+    /// Checks that `x <= y`, and that both are less than `2^num_bits`.
+    pub fn assert_le(&mut self, x: Target, y: Target, num_bits: usize) {
+        assert_comparison_bits(num_bits);
+        self.range_check(x, num_bits);
+        self.range_check(y, num_bits);
+        // If `y < x`, then `y - x` wraps around to at least `p - 2^num_bits`, which is out of range.
+        let diff = self.sub(y, x);
+        self.range_check(diff, num_bits);
+    }
+
+    /// Checks that `x < y`, and that both are less than `2^num_bits`.
+    pub fn assert_lt(&mut self, x: Target, y: Target, num_bits: usize) {
+        assert_comparison_bits(num_bits);
+        self.range_check(x, num_bits);
+        self.range_check(y, num_bits);
+        let diff = self.sub(y, x);
+        let diff_minus_one = self.add_const(diff, F::NEG_ONE);
+        self.range_check(diff_minus_one, num_bits);
+    }
+
+    /// Checks that `lo <= x <= hi`, and that all three are less than `2^num_bits`.
+    pub fn assert_in_range(&mut self, x: Target, lo: Target, hi: Target, num_bits: usize) {
+        assert_comparison_bits(num_bits);
+        self.range_check(x, num_bits);
+        self.range_check(lo, num_bits);
+        self.range_check(hi, num_bits);
+        let above_lo = self.sub(x, lo);
+        self.range_check(above_lo, num_bits);
+        let below_hi = self.sub(hi, x);
+        self.range_check(below_hi, num_bits);
+    }
+}
+
+fn assert_comparison_bits(num_bits: usize) {
+    assert!(
+        num_bits <= MAX_COMPARISON_BITS,
+        "Comparisons support at most {MAX_COMPARISON_BITS} bits, got {num_bits}"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::Field;
+    use crate::gadgets::range_check::RangeCheckMode;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn prove_and_verify(builder: CircuitBuilder<F, D>, pw: PartialWitness<F>) -> Result<()> {
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    /// Values at the boundaries of the range of `num_bits` bits.
+    fn boundary_values(num_bits: usize) -> Vec<u64> {
+        let max = (1 << num_bits) - 1;
+        let mut values = vec![0, 1, max / 2, max / 2 + 1, max - 1, max];
+        values.retain(|&v| v <= max);
+        values.sort();
+        values.dedup();
+        values
+    }
+
+    /// Checks every comparison gadget on all pairs of `values`.
+    fn check_comparisons(mode: RangeCheckMode, num_bits: usize, values: &[u64]) -> Result<()> {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        builder.set_range_check_mode(mode);
+        let mut pw = PartialWitness::new();
+
+        for &a in values {
+            for &b in values {
+                let x = builder.constant(F::from_canonical_u64(a));
+                let y = builder.add_virtual_target();
+                pw.set_target(y, F::from_canonical_u64(b));
+
+                let le = builder.less_than_or_equal(x, y, num_bits);
+                let lt = builder.less_than(x, y, num_bits);
+                let min = builder.min(x, y, num_bits);
+                let max = builder.max(x, y, num_bits);
+                let expected_le = builder.constant_bool(a <= b);
+                let expected_lt = builder.constant_bool(a < b);
+                let expected_min = builder.constant(F::from_canonical_u64(a.min(b)));
+                let expected_max = builder.constant(F::from_canonical_u64(a.max(b)));
+                builder.connect(le.target, expected_le.target);
+                builder.connect(lt.target, expected_lt.target);
+                builder.connect(min, expected_min);
+                builder.connect(max, expected_max);
+
+                if a <= b {
+                    builder.assert_le(x, y, num_bits);
+                    builder.assert_in_range(y, x, y, num_bits);
+                }
+                if a < b {
+                    builder.assert_lt(x, y, num_bits);
+                }
+            }
+        }
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_comparisons_exhaustive() -> Result<()> {
+        for num_bits in 1..=3 {
+            let values = (0..1 << num_bits).collect::<Vec<_>>();
+            check_comparisons(RangeCheckMode::Bits, num_bits, &values)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_comparisons_boundaries() -> Result<()> {
+        // Covers gates with one and two-bit chunks, and bit decompositions for the widths which
+        // don't fit in a `ComparisonGate`.
+        for num_bits in [8, 31, 32, 49, 50, 61, MAX_COMPARISON_BITS] {
+            check_comparisons(RangeCheckMode::Bits, num_bits, &boundary_values(num_bits))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_comparisons_lookup_range_checks() -> Result<()> {
+        check_comparisons(
+            RangeCheckMode::Lookup { limb_bits: 8 },
+            61,
+            &boundary_values(61),
+        )
+    }
+
+    #[test]
+    fn test_is_zero() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        for (value, expected) in [(F::ZERO, true), (F::ONE, false), (F::NEG_ONE, false)] {
+            let x = builder.add_virtual_target();
+            pw.set_target(x, value);
+            let is_zero = builder.is_zero(x);
+            let expected = builder.constant_bool(expected);
+            builder.connect(is_zero.target, expected.target);
+        }
+
+        prove_and_verify(builder, pw)
+    }
+
+    /// Checks `x <= y` with `less_than_or_equal`, for the given values of `x` and `y`.
+    fn less_than_or_equal_circuit(num_bits: usize, a: u64, b: u64) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        builder.less_than_or_equal(x, y, num_bits);
+        pw.set_target(x, F::from_canonical_u64(a));
+        pw.set_target(y, F::from_canonical_u64(b));
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_less_than_or_equal_gate_out_of_range() {
+        less_than_or_equal_circuit(8, 1 << 8, 0).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_less_than_or_equal_odd_bits_out_of_range() {
+        less_than_or_equal_circuit(7, 0, 1 << 7).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_less_than_or_equal_bits_out_of_range() {
+        less_than_or_equal_circuit(60, 1 << 60, 1).unwrap();
+    }
+
+    /// Checks `lo <= x <= hi` with `assert_in_range`.
+    fn assert_in_range_circuit(x: u64, lo: u64, hi: u64) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+        let x_target = builder.add_virtual_target();
+        let lo = builder.constant(F::from_canonical_u64(lo));
+        let hi = builder.constant(F::from_canonical_u64(hi));
+        builder.assert_in_range(x_target, lo, hi, 16);
+        pw.set_target(x_target, F::from_canonical_u64(x));
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_assert_in_range_bounds() -> Result<()> {
+        assert_in_range_circuit(100, 100, 200)?;
+        assert_in_range_circuit(200, 100, 200)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_in_range_below() {
+        assert_in_range_circuit(99, 100, 200).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_in_range_above() {
+        assert_in_range_circuit(201, 100, 200).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_lt_equal() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.constant(F::from_canonical_u64(5));
+        builder.assert_lt(x, x, 8);
+        prove_and_verify(builder, PartialWitness::new()).unwrap();
+    }
+}
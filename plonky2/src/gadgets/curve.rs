@@ -0,0 +1,249 @@
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use crate::curve::curve_types::{AffinePoint, Curve};
+use crate::field::extension::Extendable;
+use crate::field::types::Field;
+use crate::gadgets::nonnative::NonNativeTarget;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::BoolTarget;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// A point on the curve `C`, in affine coordinates. The point at infinity is not representable,
+/// so the arithmetic below is incomplete, and relies on the exceptional cases of the addition
+/// law being reached with negligible probability.
+#[derive(Clone, Debug, Default)]
+pub struct AffinePointTarget<C: Curve> {
+    pub x: NonNativeTarget<C::BaseField>,
+    pub y: NonNativeTarget<C::BaseField>,
+}
+
+impl<C: Curve> AffinePointTarget<C> {
+    pub fn to_vec(&self) -> Vec<NonNativeTarget<C::BaseField>> {
+        vec![self.x.clone(), self.y.clone()]
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn constant_affine_point<C: Curve>(
+        &mut self,
+        point: AffinePoint<C>,
+    ) -> AffinePointTarget<C> {
+        debug_assert!(!point.zero);
+        AffinePointTarget {
+            x: self.constant_nonnative(point.x),
+            y: self.constant_nonnative(point.y),
+        }
+    }
+
+    pub fn connect_affine_point<C: Curve>(
+        &mut self,
+        lhs: &AffinePointTarget<C>,
+        rhs: &AffinePointTarget<C>,
+    ) {
+        self.connect_nonnative(&lhs.x, &rhs.x);
+        self.connect_nonnative(&lhs.y, &rhs.y);
+    }
+
+    /// Adds a virtual point target. The caller is responsible for checking that it lies on the
+    /// curve, with `curve_assert_valid`, if it is not otherwise constrained.
+    pub fn add_virtual_affine_point_target<C: Curve>(&mut self) -> AffinePointTarget<C> {
+        let x = self.add_virtual_nonnative_target();
+        let y = self.add_virtual_nonnative_target();
+
+        AffinePointTarget { x, y }
+    }
+
+    /// Asserts that `p` satisfies the curve equation `y^2 = x^3 + a x + b`.
+    pub fn curve_assert_valid<C: Curve>(&mut self, p: &AffinePointTarget<C>) {
+        let a = self.constant_nonnative(C::A);
+        let b = self.constant_nonnative(C::B);
+
+        let y_squared = self.mul_nonnative(&p.y, &p.y);
+        let x_squared = self.mul_nonnative(&p.x, &p.x);
+        let x_cubed = self.mul_nonnative(&x_squared, &p.x);
+        let a_x = self.mul_nonnative(&a, &p.x);
+        let a_x_plus_b = self.add_nonnative(&a_x, &b);
+        let rhs = self.add_nonnative(&x_cubed, &a_x_plus_b);
+
+        self.connect_nonnative(&y_squared, &rhs);
+    }
+
+    pub fn curve_neg<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C> {
+        let neg_y = self.neg_nonnative(&p.y);
+        AffinePointTarget {
+            x: p.x.clone(),
+            y: neg_y,
+        }
+    }
+
+    /// Returns `-p` if `b` is true, and `p` otherwise.
+    pub fn curve_conditional_neg<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        b: BoolTarget,
+    ) -> AffinePointTarget<C> {
+        let not_b = self.not(b);
+        let neg = self.curve_neg(p);
+        let y_if_true = self.mul_nonnative_by_bool(&neg.y, b);
+        let y_if_false = self.mul_nonnative_by_bool(&p.y, not_b);
+        let y = self.add_nonnative(&y_if_true, &y_if_false);
+
+        AffinePointTarget { x: p.x.clone(), y }
+    }
+
+    /// Doubles `p`, which must not be a point of order two.
+    pub fn curve_double<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C> {
+        let AffinePointTarget { x, y } = p;
+
+        // lambda = (3 x^2 + a) / (2 y)
+        let x_squared = self.mul_nonnative(x, x);
+        let two_x_squared = self.add_nonnative(&x_squared, &x_squared);
+        let mut numerator = self.add_nonnative(&two_x_squared, &x_squared);
+        if C::A.is_nonzero() {
+            let a = self.constant_nonnative(C::A);
+            numerator = self.add_nonnative(&numerator, &a);
+        }
+        let two_y = self.add_nonnative(y, y);
+        let two_y_inv = self.inv_nonnative(&two_y);
+        let lambda = self.mul_nonnative(&numerator, &two_y_inv);
+
+        self.curve_add_with_slope(p, x, &lambda)
+    }
+
+    /// Doubles `p` `n` times.
+    pub fn curve_repeated_double<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        n: usize,
+    ) -> AffinePointTarget<C> {
+        let mut result = p.clone();
+        for _ in 0..n {
+            result = self.curve_double(&result);
+        }
+        result
+    }
+
+    /// Adds `p1` and `p2`, which must satisfy `p1 != p2` and `p1 != -p2`.
+    pub fn curve_add<C: Curve>(
+        &mut self,
+        p1: &AffinePointTarget<C>,
+        p2: &AffinePointTarget<C>,
+    ) -> AffinePointTarget<C> {
+        // lambda = (y2 - y1) / (x2 - x1)
+        let dy = self.sub_nonnative(&p2.y, &p1.y);
+        let dx = self.sub_nonnative(&p2.x, &p1.x);
+        let dx_inv = self.inv_nonnative(&dx);
+        let lambda = self.mul_nonnative(&dy, &dx_inv);
+
+        self.curve_add_with_slope(p1, &p2.x, &lambda)
+    }
+
+    /// Returns the third intersection of the line of slope `lambda` through `p` with the curve,
+    /// negated, given the `x` coordinate `x2` of the second intersection.
+    fn curve_add_with_slope<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        x2: &NonNativeTarget<C::BaseField>,
+        lambda: &NonNativeTarget<C::BaseField>,
+    ) -> AffinePointTarget<C> {
+        // x3 = lambda^2 - x1 - x2, y3 = lambda (x1 - x3) - y1
+        let lambda_squared = self.mul_nonnative(lambda, lambda);
+        let x1_plus_x2 = self.add_nonnative(&p.x, x2);
+        let x3 = self.sub_nonnative(&lambda_squared, &x1_plus_x2);
+        let x1_minus_x3 = self.sub_nonnative(&p.x, &x3);
+        let lambda_dx = self.mul_nonnative(lambda, &x1_minus_x3);
+        let y3 = self.sub_nonnative(&lambda_dx, &p.y);
+
+        AffinePointTarget { x: x3, y: y3 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::curve::curve_types::{AffinePoint, Curve, CurveScalar};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_base::Secp256K1Base;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, Sample};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn random_point() -> AffinePoint<Secp256K1> {
+        (CurveScalar(Secp256K1Scalar::rand()) * Secp256K1::GENERATOR_PROJECTIVE).to_affine()
+    }
+
+    #[test]
+    fn test_curve_point_is_valid() -> Result<()> {
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p = builder.add_virtual_affine_point_target::<Secp256K1>();
+        builder.curve_assert_valid(&p);
+        let neg_p = builder.curve_neg(&p);
+        builder.curve_assert_valid(&neg_p);
+
+        pw.set_affine_point_target(&p, &random_point());
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_curve_point_is_not_valid() {
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p = builder.add_virtual_affine_point_target::<Secp256K1>();
+        builder.curve_assert_valid(&p);
+
+        let mut point = random_point();
+        point.y += Secp256K1Base::ONE;
+        pw.set_affine_point_target(&p, &point);
+
+        let data = builder.build::<C>();
+        data.prove(pw).unwrap();
+    }
+
+    #[test]
+    fn test_curve_add_double() -> Result<()> {
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p1 = random_point();
+        let p2 = random_point();
+        let sum_expected = (p1.to_projective() + p2).to_affine();
+        let double_expected = p1.double();
+
+        let p1_target = builder.add_virtual_affine_point_target::<Secp256K1>();
+        let p2_target = builder.add_virtual_affine_point_target::<Secp256K1>();
+        let sum = builder.curve_add(&p1_target, &p2_target);
+        let double = builder.curve_double(&p1_target);
+
+        let sum_expected = builder.constant_affine_point(sum_expected);
+        let double_expected = builder.constant_affine_point(double_expected);
+        builder.connect_affine_point(&sum, &sum_expected);
+        builder.connect_affine_point(&double, &double_expected);
+
+        pw.set_affine_point_target(&p1_target, &p1);
+        pw.set_affine_point_target(&p2_target, &p2);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
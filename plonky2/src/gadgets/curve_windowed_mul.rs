@@ -0,0 +1,323 @@
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+use crate::curve::curve_types::{AffinePoint, Curve, CurveScalar};
+use crate::field::extension::Extendable;
+use crate::field::types::{Field, PrimeField};
+use crate::gadgets::biguint::{BigUintTarget, BIGUINT_LIMB_BITS};
+use crate::gadgets::curve::AffinePointTarget;
+use crate::gadgets::nonnative::NonNativeTarget;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::Target;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+const WINDOW_BITS: usize = 4;
+const WINDOW_SIZE: usize = 1 << WINDOW_BITS;
+
+/// A point with no known discrete logarithm, used to offset the accumulator of windowed
+/// multiplications so that it never reaches the point at infinity. It is the point with the
+/// smallest positive `x` coordinate on the curve.
+fn starting_point<C: Curve>() -> AffinePoint<C> {
+    let mut x = C::BaseField::ONE;
+    loop {
+        let y_squared = x.cube() + C::A * x + C::B;
+        if let Some(y) = y_squared.sqrt() {
+            return AffinePoint::nonzero(x, y);
+        }
+        x += C::BaseField::ONE;
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Returns `[start + i p for i in 0..16]`.
+    fn precompute_window<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        start: AffinePoint<C>,
+    ) -> Vec<AffinePointTarget<C>> {
+        let mut multiples = vec![self.constant_affine_point(start)];
+        for i in 1..WINDOW_SIZE {
+            let next = self.curve_add(&multiples[i - 1], p);
+            multiples.push(next);
+        }
+        multiples
+    }
+
+    /// Same as `precompute_window`, for a point known at circuit building time.
+    fn precompute_window_const<C: Curve>(
+        &mut self,
+        p: AffinePoint<C>,
+        start: AffinePoint<C>,
+    ) -> Vec<AffinePointTarget<C>> {
+        let p = p.to_projective();
+        let mut multiple = start.to_projective();
+        let mut multiples = Vec::with_capacity(WINDOW_SIZE);
+        for _ in 0..WINDOW_SIZE {
+            multiples.push(self.constant_affine_point(multiple.to_affine()));
+            multiple = multiple + p;
+        }
+        multiples
+    }
+
+    /// Returns `points[access_index]`. All the points must have canonical coordinates.
+    pub fn random_access_curve_points<C: Curve>(
+        &mut self,
+        access_index: Target,
+        points: &[AffinePointTarget<C>],
+    ) -> AffinePointTarget<C> {
+        let xs = points.iter().map(|p| &p.x).collect::<Vec<_>>();
+        let ys = points.iter().map(|p| &p.y).collect::<Vec<_>>();
+        let x = self.random_access_nonnative(access_index, &xs);
+        let y = self.random_access_nonnative(access_index, &ys);
+
+        AffinePointTarget { x, y }
+    }
+
+    fn random_access_nonnative<FF: Field>(
+        &mut self,
+        access_index: Target,
+        values: &[&NonNativeTarget<FF>],
+    ) -> NonNativeTarget<FF> {
+        let zero = self.zero();
+        let limbs = (0..Self::num_nonnative_limbs::<FF>())
+            .map(|i| {
+                let limb_values = values
+                    .iter()
+                    .map(|v| v.value.limbs.get(i).copied().unwrap_or(zero))
+                    .collect();
+                self.random_access(access_index, limb_values)
+            })
+            .collect();
+
+        NonNativeTarget {
+            value: BigUintTarget { limbs },
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Splits `n` into little-endian windows of `WINDOW_BITS` bits. The limbs are first split
+    /// into base 4 digits, as a base 16 `BaseSumGate` would exceed the maximum constraint degree.
+    fn split_nonnative_to_windows<FF: Field>(&mut self, n: &NonNativeTarget<FF>) -> Vec<Target> {
+        let four = F::from_canonical_usize(4);
+        n.value
+            .limbs
+            .iter()
+            .flat_map(|&limb| self.split_le_base::<4>(limb, BIGUINT_LIMB_BITS / 2))
+            .collect::<Vec<_>>()
+            .chunks(WINDOW_BITS / 2)
+            .map(|digits| self.mul_const_add(four, digits[1], digits[0]))
+            .collect()
+    }
+
+    /// Computes `n * p` with a window of `WINDOW_BITS` bits. `n` must be nonzero.
+    pub fn curve_scalar_mul_windowed<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        n: &NonNativeTarget<C::ScalarField>,
+    ) -> AffinePointTarget<C> {
+        let multiples = self.precompute_window(p, starting_point::<C>());
+        self.curve_msm_with_windows(&[&multiples], &[n])
+    }
+
+    /// Computes `n * base` for a point `base` known at circuit building time. `n` must be nonzero.
+    pub fn fixed_base_curve_mul<C: Curve>(
+        &mut self,
+        base: AffinePoint<C>,
+        n: &NonNativeTarget<C::ScalarField>,
+    ) -> AffinePointTarget<C> {
+        let multiples = self.precompute_window_const(base, starting_point::<C>());
+        self.curve_msm_with_windows(&[&multiples], &[n])
+    }
+
+    /// Computes `n * base + m * p` for a point `base` known at circuit building time, sharing
+    /// the doublings of both multiplications. `n` and `m` must be nonzero.
+    pub fn fixed_base_curve_msm<C: Curve>(
+        &mut self,
+        base: AffinePoint<C>,
+        n: &NonNativeTarget<C::ScalarField>,
+        p: &AffinePointTarget<C>,
+        m: &NonNativeTarget<C::ScalarField>,
+    ) -> AffinePointTarget<C> {
+        // The windows of `p` are offset by `2 s` rather than `s`, so that the windows added
+        // together never collide, even if `p` is `base`.
+        let start = starting_point::<C>();
+        let base_multiples = self.precompute_window_const(base, start);
+        let p_multiples = self.precompute_window(p, start.double());
+        self.curve_msm_with_windows(&[&base_multiples, &p_multiples], &[n, m])
+    }
+
+    /// Computes `sum_i n_i p_i`, given the multiples `s_i + j p_i` of each point, where `s_i` is
+    /// `i + 1` times the starting point `s`. All the scalars must be nonzero.
+    fn curve_msm_with_windows<C: Curve>(
+        &mut self,
+        multiples: &[&[AffinePointTarget<C>]],
+        scalars: &[&NonNativeTarget<C::ScalarField>],
+    ) -> AffinePointTarget<C> {
+        let windows = scalars
+            .iter()
+            .map(|n| self.split_nonnative_to_windows(n))
+            .collect::<Vec<_>>();
+        let num_windows = windows[0].len();
+
+        // Each window adds a copy of every `s_i`, so the accumulator ends up being
+        // `sum_i n_i p_i + k (sum_i s_i)`, with `k = sum_j 16^j` over all windows.
+        let mut result: Option<AffinePointTarget<C>> = None;
+        let mut k = C::ScalarField::ZERO;
+        let window_size = C::ScalarField::from_canonical_usize(WINDOW_SIZE);
+        for j in (0..num_windows).rev() {
+            if let Some(acc) = &result {
+                result = Some(self.curve_repeated_double(acc, WINDOW_BITS));
+            }
+            for (point_multiples, point_windows) in multiples.iter().zip(&windows) {
+                let to_add = self.random_access_curve_points(point_windows[j], point_multiples);
+                result = Some(match &result {
+                    Some(acc) => self.curve_add(acc, &to_add),
+                    None => to_add,
+                });
+            }
+            k = k * window_size + C::ScalarField::ONE;
+        }
+
+        let num_terms = scalars.len();
+        let num_offsets = C::ScalarField::from_canonical_usize(num_terms * (num_terms + 1) / 2);
+        let correction =
+            -(CurveScalar(k * num_offsets) * starting_point::<C>().to_projective()).to_affine();
+        let correction = self.constant_affine_point(correction);
+        self.curve_add(&result.expect("Empty scalar"), &correction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use num::BigUint;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::curve::curve_types::{Curve, CurveScalar};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, Sample};
+    use crate::gadgets::range_check::RangeCheckMode;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// Multiplications by full size scalars take tens of thousands of gates even with lookup
+    /// range checks, so we check the witnesses against mock circuits, and exercise all the
+    /// multiplications with a single limb scalar.
+    #[test]
+    fn test_curve_scalar_mul_windowed() {
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        builder.set_range_check_mode(RangeCheckMode::Lookup {
+            limb_bits: BIGUINT_LIMB_BITS,
+        });
+
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let p = (CurveScalar(Secp256K1Scalar::rand()) * g.to_projective()).to_affine();
+        let n = OsRng.gen_range(1..1u64 << 16);
+        let n_ff = Secp256K1Scalar::from_canonical_u64(n);
+        let np_expected = (CurveScalar(n_ff) * p.to_projective()).to_affine();
+        let ng_expected = (CurveScalar(n_ff) * g.to_projective()).to_affine();
+
+        let p_target = builder.add_virtual_affine_point_target::<Secp256K1>();
+        let n_biguint = builder.add_virtual_biguint_target(1);
+        let n_target = builder.biguint_to_nonnative::<Secp256K1Scalar>(&n_biguint);
+        let np = builder.curve_scalar_mul_windowed(&p_target, &n_target);
+        let ng = builder.fixed_base_curve_mul(g, &n_target);
+        let ng_plus_np = builder.fixed_base_curve_msm(g, &n_target, &p_target, &n_target);
+
+        let ng_plus_np_expected =
+            (np_expected.to_projective() + ng_expected.to_projective()).to_affine();
+        let np_expected = builder.constant_affine_point(np_expected);
+        let ng_expected = builder.constant_affine_point(ng_expected);
+        let ng_plus_np_expected = builder.constant_affine_point(ng_plus_np_expected);
+        builder.connect_affine_point(&np, &np_expected);
+        builder.connect_affine_point(&ng, &ng_expected);
+        builder.connect_affine_point(&ng_plus_np, &ng_plus_np_expected);
+
+        pw.set_affine_point_target(&p_target, &p);
+        pw.set_biguint_target(&n_biguint, &BigUint::from(n));
+
+        let data = builder.mock_build::<C>();
+        assert!(data.check_witness(pw).is_empty());
+    }
+
+    /// Checking a multiplication by a full size scalar takes a couple of gigabytes of memory, so
+    /// it doesn't run by default.
+    #[test]
+    #[ignore]
+    fn test_curve_scalar_mul_windowed_full_scalar() {
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        builder.set_range_check_mode(RangeCheckMode::Lookup {
+            limb_bits: BIGUINT_LIMB_BITS,
+        });
+
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let p = (CurveScalar(Secp256K1Scalar::rand()) * g.to_projective()).to_affine();
+        let n = Secp256K1Scalar::rand();
+        let np_expected = (CurveScalar(n) * p.to_projective()).to_affine();
+
+        let p_target = builder.add_virtual_affine_point_target::<Secp256K1>();
+        let n_target = builder.add_virtual_nonnative_target::<Secp256K1Scalar>();
+        let np = builder.curve_scalar_mul_windowed(&p_target, &n_target);
+
+        let np_expected = builder.constant_affine_point(np_expected);
+        builder.connect_affine_point(&np, &np_expected);
+
+        pw.set_affine_point_target(&p_target, &p);
+        pw.set_nonnative_target(&n_target, n);
+
+        let data = builder.mock_build::<C>();
+        assert!(data.check_witness(pw).is_empty());
+    }
+
+    /// Proves a single step of the windowed multiplications used by `verify_ecdsa`, which doubles
+    /// the accumulator once per window bit and adds the multiple selected by the window.
+    #[test]
+    fn test_curve_window_step() -> Result<()> {
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let start = starting_point::<Secp256K1>();
+        let acc = (CurveScalar(Secp256K1Scalar::rand()) * g.to_projective()).to_affine();
+        let window = OsRng.gen_range(0..WINDOW_SIZE);
+        let window_ff = Secp256K1Scalar::from_canonical_usize(window);
+        let expected = (CurveScalar(Secp256K1Scalar::from_canonical_usize(WINDOW_SIZE))
+            * acc.to_projective()
+            + start.to_projective()
+            + CurveScalar(window_ff) * g.to_projective())
+        .to_affine();
+
+        let acc_target = builder.add_virtual_affine_point_target::<Secp256K1>();
+        let window_target = builder.add_virtual_target();
+        let multiples = builder.precompute_window_const(g, start);
+        let doubled = builder.curve_repeated_double(&acc_target, WINDOW_BITS);
+        let to_add = builder.random_access_curve_points(window_target, &multiples);
+        let result = builder.curve_add(&doubled, &to_add);
+
+        let expected = builder.constant_affine_point(expected);
+        builder.connect_affine_point(&result, &expected);
+
+        pw.set_affine_point_target(&acc_target, &acc);
+        pw.set_target(window_target, F::from_canonical_usize(window));
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
@@ -0,0 +1,247 @@
+use crate::curve::curve_types::Curve;
+use crate::field::extension::Extendable;
+use crate::gadgets::biguint::BIGUINT_LIMB_BITS;
+use crate::gadgets::curve::AffinePointTarget;
+use crate::gadgets::nonnative::NonNativeTarget;
+use crate::gadgets::range_check::RangeCheckMode;
+use crate::hash::hash_types::RichField;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+#[derive(Clone, Debug)]
+pub struct ECDSAPublicKeyTarget<C: Curve>(pub AffinePointTarget<C>);
+
+#[derive(Clone, Debug)]
+pub struct ECDSASignatureTarget<C: Curve> {
+    pub r: NonNativeTarget<C::ScalarField>,
+    pub s: NonNativeTarget<C::ScalarField>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Adds a virtual public key target, constrained to lie on the curve.
+    pub fn add_virtual_ecdsa_public_key_target<C: Curve>(&mut self) -> ECDSAPublicKeyTarget<C> {
+        let point = self.add_virtual_affine_point_target();
+        self.curve_assert_valid(&point);
+        ECDSAPublicKeyTarget(point)
+    }
+
+    pub fn add_virtual_ecdsa_signature_target<C: Curve>(&mut self) -> ECDSASignatureTarget<C> {
+        ECDSASignatureTarget {
+            r: self.add_virtual_nonnative_target(),
+            s: self.add_virtual_nonnative_target(),
+        }
+    }
+
+    /// Asserts that `sig` is a valid signature of the message hash `msg` under `pk`, following
+    /// [`verify_message`](crate::curve::ecdsa::verify_message). `pk` must lie on the curve.
+    pub fn verify_ecdsa<C: Curve>(
+        &mut self,
+        msg: &NonNativeTarget<C::ScalarField>,
+        sig: &ECDSASignatureTarget<C>,
+        pk: &ECDSAPublicKeyTarget<C>,
+    ) {
+        // Most of the circuit consists of range checks of 16-bit limbs, which are several times
+        // cheaper with lookups than with bit decompositions.
+        let range_check_mode = self.range_check_mode();
+        if range_check_mode == RangeCheckMode::Bits {
+            self.set_range_check_mode(RangeCheckMode::Lookup {
+                limb_bits: BIGUINT_LIMB_BITS,
+            });
+        }
+
+        let (u1, u2) = self.ecdsa_msm_scalars(msg, sig);
+        let point = self.fixed_base_curve_msm(C::GENERATOR_AFFINE, &u1, &pk.0, &u2);
+
+        let x = self.reduce::<C::ScalarField>(&point.x.value);
+        self.connect_nonnative(&sig.r, &x);
+
+        self.set_range_check_mode(range_check_mode);
+    }
+
+    /// Returns the scalars `u1 = msg / s` and `u2 = r / s` of the multi-scalar multiplication
+    /// `u1 G + u2 pk`, whose `x` coordinate must equal `r` for a valid signature.
+    fn ecdsa_msm_scalars<C: Curve>(
+        &mut self,
+        msg: &NonNativeTarget<C::ScalarField>,
+        sig: &ECDSASignatureTarget<C>,
+    ) -> (
+        NonNativeTarget<C::ScalarField>,
+        NonNativeTarget<C::ScalarField>,
+    ) {
+        let ECDSASignatureTarget { r, s } = sig;
+
+        // Inverting `r` and `s` enforces that both are nonzero.
+        self.inv_nonnative(r);
+        let c = self.inv_nonnative(s);
+        let u1 = self.mul_nonnative(msg, &c);
+        let u2 = self.mul_nonnative(r, &c);
+        (u1, u2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::curve::ecdsa::{sign_message, ECDSAPublicKey, ECDSASecretKey, ECDSASignature};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, Sample};
+    use crate::gadgets::biguint::BIGUINT_LIMB_BITS;
+    use crate::gadgets::range_check::RangeCheckMode;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::diagnostics::UnsatisfiedConstraint;
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type Curve = Secp256K1;
+
+    fn ecdsa_circuit(
+        msg: Secp256K1Scalar,
+        sig: ECDSASignature<Curve>,
+        pk: ECDSAPublicKey<Curve>,
+    ) -> (CircuitBuilder<F, D>, PartialWitness<F>) {
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let msg_target = builder.add_virtual_nonnative_target();
+        let sig_target = builder.add_virtual_ecdsa_signature_target();
+        let pk_target = builder.add_virtual_ecdsa_public_key_target();
+        builder.verify_ecdsa::<Curve>(&msg_target, &sig_target, &pk_target);
+
+        pw.set_nonnative_target(&msg_target, msg);
+        pw.set_ecdsa_signature_target(&sig_target, &sig);
+        pw.set_ecdsa_public_key_target(&pk_target, &pk);
+
+        (builder, pw)
+    }
+
+    /// Checks the witness of the verification circuit against a mock build, which is much faster
+    /// than generating a proof, and returns the unsatisfied constraints.
+    fn check_ecdsa_witness(
+        msg: Secp256K1Scalar,
+        sig: ECDSASignature<Curve>,
+        pk: ECDSAPublicKey<Curve>,
+    ) -> Vec<UnsatisfiedConstraint<F>> {
+        let (builder, pw) = ecdsa_circuit(msg, sig, pk);
+        let data = builder.mock_build::<C>();
+        data.check_witness(pw)
+    }
+
+    fn random_signature() -> (
+        Secp256K1Scalar,
+        ECDSASignature<Curve>,
+        ECDSAPublicKey<Curve>,
+    ) {
+        let msg = Secp256K1Scalar::rand();
+        let sk = ECDSASecretKey::<Curve>(Secp256K1Scalar::rand());
+        (msg, sign_message(msg, sk), sk.to_public())
+    }
+
+    /// Builds a circuit computing the scalars of the multi-scalar multiplication, which is much
+    /// smaller than the whole verification circuit.
+    fn msm_scalars_circuit(
+        msg: Secp256K1Scalar,
+        sig: ECDSASignature<Curve>,
+    ) -> (CircuitBuilder<F, D>, PartialWitness<F>) {
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        builder.set_range_check_mode(RangeCheckMode::Lookup {
+            limb_bits: BIGUINT_LIMB_BITS,
+        });
+
+        let msg_target = builder.add_virtual_nonnative_target();
+        let sig_target = builder.add_virtual_ecdsa_signature_target();
+        let (u1, u2) = builder.ecdsa_msm_scalars::<Curve>(&msg_target, &sig_target);
+
+        if let Some(s_inv) = sig.s.try_inverse() {
+            let u1_expected = builder.constant_nonnative(msg * s_inv);
+            let u2_expected = builder.constant_nonnative(sig.r * s_inv);
+            builder.connect_nonnative(&u1, &u1_expected);
+            builder.connect_nonnative(&u2, &u2_expected);
+        }
+
+        pw.set_nonnative_target(&msg_target, msg);
+        pw.set_ecdsa_signature_target(&sig_target, &sig);
+
+        (builder, pw)
+    }
+
+    #[test]
+    fn test_ecdsa_msm_scalars() -> Result<()> {
+        let (msg, sig, _) = random_signature();
+
+        let (builder, pw) = msm_scalars_circuit(msg, sig);
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_ecdsa_msm_scalars_zero_r() {
+        let (msg, sig, _) = random_signature();
+        let zero_r_sig = ECDSASignature {
+            r: Secp256K1Scalar::ZERO,
+            s: sig.s,
+        };
+
+        let (builder, pw) = msm_scalars_circuit(msg, zero_r_sig);
+        let data = builder.mock_build::<C>();
+        assert!(!data.check_witness(pw).is_empty());
+    }
+
+    /// The verification circuit has around 2^17 gates, and checking its witness takes a couple
+    /// of gigabytes of memory, so the tests on whole signatures don't run by default.
+    #[test]
+    #[ignore]
+    fn test_verify_ecdsa_witness() {
+        let (msg, sig, pk) = random_signature();
+        assert_eq!(check_ecdsa_witness(msg, sig, pk), vec![]);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_verify_ecdsa_wrong_message() {
+        let (msg, sig, pk) = random_signature();
+        assert!(!check_ecdsa_witness(msg + Secp256K1Scalar::ONE, sig, pk).is_empty());
+    }
+
+    #[test]
+    #[ignore]
+    fn test_verify_ecdsa_wrong_public_key() {
+        let (msg, sig, _) = random_signature();
+        let other_pk = ECDSASecretKey::<Curve>(Secp256K1Scalar::rand()).to_public();
+        assert!(!check_ecdsa_witness(msg, sig, other_pk).is_empty());
+    }
+
+    #[test]
+    #[ignore]
+    fn test_verify_ecdsa_wrong_signature() {
+        let (msg, sig, pk) = random_signature();
+        let bad_sig = ECDSASignature {
+            r: sig.r,
+            s: sig.s + Secp256K1Scalar::ONE,
+        };
+        assert!(!check_ecdsa_witness(msg, bad_sig, pk).is_empty());
+    }
+
+    /// Proving the verification circuit, of around 2^17 gates, is too slow to run by default.
+    #[test]
+    #[ignore]
+    fn test_verify_ecdsa() -> Result<()> {
+        let msg = Secp256K1Scalar::rand();
+        let sk = ECDSASecretKey::<Curve>(Secp256K1Scalar::rand());
+        let sig = sign_message(msg, sk);
+
+        let (builder, pw) = ecdsa_circuit(msg, sig, sk.to_public());
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
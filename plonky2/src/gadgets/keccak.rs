@@ -0,0 +1,198 @@
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use crate::field::extension::Extendable;
+use crate::gadgets::bytes::ByteLookups;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::ByteTarget;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// Number of bytes absorbed by each Keccak-256 permutation.
+pub const KECCAK256_RATE_BYTES: usize = 136;
+
+/// Number of bytes of a Keccak-256 digest.
+pub const KECCAK256_DIGEST_BYTES: usize = 32;
+
+const NUM_ROUNDS: usize = 24;
+
+const ROUND_CONSTANTS: [u64; NUM_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Rotation offsets of the rho step, indexed by `[x][y]`.
+const RHO_OFFSETS: [[usize; 5]; 5] = [
+    [0, 36, 3, 41, 18],
+    [1, 44, 10, 45, 2],
+    [62, 6, 43, 15, 61],
+    [28, 55, 25, 21, 56],
+    [27, 20, 39, 8, 14],
+];
+
+/// A 64-bit lane of the Keccak state, as little-endian bytes.
+type Lane = [ByteTarget; 8];
+
+/// The Keccak state, with lane `(x, y)` at index `x + 5 * y`.
+type State = [Lane; 25];
+
+/// Applies the Keccak-f[1600] permutation to `state`.
+fn keccak_f<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    lookups: &mut ByteLookups,
+    state: &mut State,
+) {
+    for round_constant in ROUND_CONSTANTS {
+        // Theta.
+        let mut c: [Lane; 5] = core::array::from_fn(|x| state[x]);
+        for x in 0..5 {
+            for y in 1..5 {
+                c[x] = lookups.xor_words(builder, &c[x], &state[x + 5 * y]);
+            }
+        }
+        for x in 0..5 {
+            let rotated = lookups.rotate_left(builder, &c[(x + 1) % 5], 1);
+            let d = lookups.xor_words(builder, &c[(x + 4) % 5], &rotated);
+            for y in 0..5 {
+                state[x + 5 * y] = lookups.xor_words(builder, &state[x + 5 * y], &d);
+            }
+        }
+
+        // Rho and pi.
+        let mut b = *state;
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] =
+                    lookups.rotate_left(builder, &state[x + 5 * y], RHO_OFFSETS[x][y]);
+            }
+        }
+
+        // Chi.
+        for y in 0..5 {
+            for x in 0..5 {
+                let t = lookups.and_not_words(
+                    builder,
+                    &b[(x + 1) % 5 + 5 * y],
+                    &b[(x + 2) % 5 + 5 * y],
+                );
+                state[x + 5 * y] = lookups.xor_words(builder, &b[x + 5 * y], &t);
+            }
+        }
+
+        // Iota.
+        let round_constant = round_constant
+            .to_le_bytes()
+            .map(|byte| builder.constant_byte(byte));
+        state[0] = lookups.xor_words(builder, &state[0], &round_constant);
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Computes the Keccak-256 digest of `input`, as used by Ethereum (i.e. with the original
+    /// Keccak padding rather than the SHA3 one).
+    ///
+    /// The bitwise steps of the permutation are computed byte by byte with lookups. Each block
+    /// of 136 bytes costs around 2200 rows, and the two tables of `2^16` entries used for xor and
+    /// chi add around 5000 rows to the circuit.
+    pub fn keccak256(&mut self, input: &[ByteTarget]) -> [ByteTarget; KECCAK256_DIGEST_BYTES] {
+        let mut lookups = ByteLookups::default();
+
+        // Pad with `0x01 0x00 ... 0x00 0x80`, or `0x81` if a single byte is needed.
+        let num_blocks = input.len() / KECCAK256_RATE_BYTES + 1;
+        let mut padding = vec![0u8; num_blocks * KECCAK256_RATE_BYTES - input.len()];
+        padding[0] |= 0x01;
+        *padding.last_mut().unwrap() |= 0x80;
+        let padding = padding.into_iter().map(|byte| self.constant_byte(byte));
+        let padded = input.iter().copied().chain(padding).collect::<Vec<_>>();
+
+        let zero = self.constant_byte(0);
+        let mut state = [[zero; 8]; 25];
+        for block in padded.chunks(KECCAK256_RATE_BYTES) {
+            for (i, &byte) in block.iter().enumerate() {
+                state[i / 8][i % 8] = lookups.xor(self, state[i / 8][i % 8], byte);
+            }
+            keccak_f(self, &mut lookups, &mut state);
+        }
+
+        core::array::from_fn(|i| state[i / 8][i % 8])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use keccak_hash::keccak;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// Checks the digests of random messages of the given lengths against `keccak_hash`.
+    fn test_keccak256_lengths(lengths: &[usize]) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut rng = OsRng;
+        for &len in lengths {
+            let msg = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let expected = keccak(&msg).0;
+
+            let msg_target = builder.add_virtual_byte_targets(len);
+            let digest = builder.keccak256(&msg_target);
+            for (&byte, &expected_byte) in digest.iter().zip(&expected) {
+                let expected_byte = builder.constant_byte(expected_byte);
+                builder.connect(byte.target, expected_byte.target);
+            }
+
+            for (&t, &byte) in msg_target.iter().zip(&msg) {
+                pw.set_byte_target(t, byte);
+            }
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_keccak256_single_block() -> Result<()> {
+        // 135 bytes leave room for a single padding byte `0x81`.
+        test_keccak256_lengths(&[0, 32, 135])
+    }
+
+    #[test]
+    fn test_keccak256_multi_block() -> Result<()> {
+        // A full block is followed by a block of padding only.
+        test_keccak256_lengths(&[136, 300])
+    }
+}
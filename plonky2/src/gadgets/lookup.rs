@@ -1,10 +1,16 @@
 #[cfg(not(feature = "std"))]
-use alloc::{borrow::ToOwned, vec};
+use alloc::{borrow::ToOwned, vec, vec::Vec};
 
 use crate::field::extension::Extendable;
+use crate::gates::dynamic_lookup::{
+    DynamicLookupGate, DynamicLookupGenerator, DynamicLookupTableGate,
+};
 use crate::gates::lookup::LookupGate;
 use crate::gates::lookup_table::{LookupTable, LookupTableGate};
 use crate::gates::noop::NoopGate;
+use crate::gates::tuple_lookup::{
+    TupleLookupGate, TupleLookupGenerator, TupleLookupTable, TupleLookupTableGate,
+};
 use crate::hash::hash_types::RichField;
 use crate::iop::target::Target;
 use crate::plonk::circuit_builder::CircuitBuilder;
@@ -75,6 +81,75 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         looking_out
     }
 
+    /// Adds a dynamic lookup table, whose `(input, output)` rows are targets set by the witness, and returns its index.
+    /// Lookups into it are checked by the same argument as lookups into fixed LUTs. Since nothing constrains the
+    /// rows beyond their copy constraints, callers need to constrain the table targets themselves.
+    pub fn add_dynamic_lookup_table(&mut self, table: &[(Target, Target)]) -> usize {
+        assert!(!table.is_empty(), "A dynamic lookup table can't be empty");
+        self.update_dynamic_luts(table.to_vec())
+    }
+
+    /// Adds a lookup into the dynamic LUT with index `dynamic_lut_index`. Takes a `Target` input and returns a `Target` output,
+    /// which is set to the output of the first row of the table with the same input.
+    pub fn add_dynamic_lookup(&mut self, looking_in: Target, dynamic_lut_index: usize) -> Target {
+        assert!(
+            dynamic_lut_index < self.num_dynamic_luts(),
+            "dynamic lut number {} not in dynamic luts (length = {})",
+            dynamic_lut_index,
+            self.num_dynamic_luts()
+        );
+        let looking_out = self.add_virtual_target();
+        self.update_dynamic_lookups(looking_in, looking_out, dynamic_lut_index);
+        self.add_simple_generator(DynamicLookupGenerator {
+            looking_in,
+            looking_out,
+            table: self.get_dynamic_lut(dynamic_lut_index).to_vec(),
+        });
+        looking_out
+    }
+
+    /// Adds a tuple lookup table, whose rows have several inputs and outputs of arbitrary field elements, and returns
+    /// its index. All tuple lookups in the circuit use slots as wide as the widest tuple table.
+    pub fn add_tuple_lookup_table(&mut self, table: TupleLookupTable<F>) -> usize {
+        let idx = self.update_tuple_luts(table);
+        let (num_inputs, num_outputs) = self.tuple_lookup_shape();
+        assert!(
+            num_inputs + num_outputs < self.config.num_routed_wires,
+            "tuple lookup tables with {} inputs and {} outputs don't fit in {} routed wires",
+            num_inputs,
+            num_outputs,
+            self.config.num_routed_wires
+        );
+        idx
+    }
+
+    /// Adds a lookup into the tuple LUT with index `tuple_lut_index`. Takes the input `Target`s and returns the
+    /// output `Target`s, which are set to the outputs of the first row of the table with the same inputs.
+    pub fn add_tuple_lookup(&mut self, inputs: &[Target], tuple_lut_index: usize) -> Vec<Target> {
+        assert!(
+            tuple_lut_index < self.num_tuple_luts(),
+            "tuple lut number {} not in tuple luts (length = {})",
+            tuple_lut_index,
+            self.num_tuple_luts()
+        );
+        let table = self.get_tuple_lut(tuple_lut_index).clone();
+        assert_eq!(
+            inputs.len(),
+            table.num_inputs(),
+            "tuple lut number {} expects {} inputs",
+            tuple_lut_index,
+            table.num_inputs()
+        );
+        let outputs = self.add_virtual_targets(table.num_outputs());
+        self.update_tuple_lookups(inputs.to_vec(), outputs.clone(), tuple_lut_index);
+        self.add_simple_generator(TupleLookupGenerator {
+            looking_inputs: inputs.to_vec(),
+            looking_outputs: outputs.clone(),
+            table,
+        });
+        outputs
+    }
+
     /// We call this function at the end of circuit building right before the PI gate to add all `LookupTableGate` and `LookupGate`.
     /// It also updates `self.lookup_rows` accordingly.
     pub fn add_all_lookups(&mut self) {
@@ -152,5 +227,105 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
                 self.add_lookup_rows(last_lu_gate, last_lut_gate, first_lut_gate);
             }
         }
+
+        // Tuple LUTs come next, so that the rows of the i-th tuple LUT are the `(self.num_luts() + i)`-th entry of
+        // `self.lookup_rows`. All of them use the same slot shape, and smaller tuples leave their last wires at zero.
+        let (num_inputs, num_outputs) = self.tuple_lookup_shape();
+        for tuple_lut_index in 0..self.num_tuple_luts() {
+            assert!(
+                !self.get_tuple_lut_lookups(tuple_lut_index).is_empty(),
+                "Tuple LUT number {:?} is unused",
+                tuple_lut_index
+            );
+
+            // Create LU gates. Connect them to the stored lookups.
+            let last_lu_gate = self.num_gates();
+            let lookups = self.get_tuple_lut_lookups(tuple_lut_index).to_owned();
+            let gate = TupleLookupGate::new_from_config(&self.config, num_inputs, num_outputs);
+            for (looking_inputs, looking_outputs) in lookups {
+                // The gate has no generators, so unused slots don't need to be avoided.
+                let (row, i) = self.find_slot(
+                    gate.clone(),
+                    &[F::from_canonical_usize(tuple_lut_index)],
+                    &[],
+                );
+                for (wire, target) in gate.wires_ith_looking_inp(i).zip(looking_inputs) {
+                    self.connect(Target::wire(row, wire), target);
+                }
+                for (wire, target) in gate.wires_ith_looking_out(i).zip(looking_outputs) {
+                    self.connect(Target::wire(row, wire), target);
+                }
+            }
+
+            // Create LUT gates. Nothing is connected to them: the prover sets the rows of the table.
+            let last_lut_gate = self.num_gates();
+            let gate = TupleLookupTableGate::new_from_config(&self.config, num_inputs, num_outputs);
+            let num_lut_rows = (self.get_tuple_lut(tuple_lut_index).len() - 1) / gate.num_slots + 1;
+            for _ in 0..num_lut_rows {
+                self.add_gate(gate.clone(), vec![]);
+            }
+            let first_lut_gate = self.num_gates() - 1;
+
+            self.add_gate(NoopGate, vec![]);
+
+            self.add_lookup_rows(last_lu_gate, last_lut_gate, first_lut_gate);
+        }
+
+        // Dynamic LUTs are placed after all the other LUTs, so that the rows of the i-th dynamic LUT are the
+        // `(self.num_luts() + self.num_tuple_luts() + i)`-th entry of `self.lookup_rows`.
+        for dynamic_lut_index in 0..self.num_dynamic_luts() {
+            assert!(
+                !self.get_dynamic_lut_lookups(dynamic_lut_index).is_empty(),
+                "Dynamic LUT number {:?} is unused",
+                dynamic_lut_index
+            );
+
+            // Create LU gates. Connect them to the stored lookups.
+            let last_lu_gate = self.num_gates();
+            let lookups = self.get_dynamic_lut_lookups(dynamic_lut_index).to_owned();
+            let gate = DynamicLookupGate::new_from_config(&self.config);
+            for (looking_in, looking_out) in lookups {
+                // The gate has no generators, so unused slots don't need to be avoided.
+                let (row, i) = self.find_slot(
+                    gate.clone(),
+                    &[F::from_canonical_usize(dynamic_lut_index)],
+                    &[],
+                );
+                let gate_in = Target::wire(row, LookupGate::wire_ith_looking_inp(i));
+                let gate_out = Target::wire(row, LookupGate::wire_ith_looking_out(i));
+                self.connect(gate_in, looking_in);
+                self.connect(gate_out, looking_out);
+            }
+
+            // Create LUT gates, and connect them to the rows of the table. Unused slots of the last gate are
+            // connected to the first row, so that they can't hold entries which are not in the table.
+            let last_lut_gate = self.num_gates();
+            let table = self.get_dynamic_lut(dynamic_lut_index).to_owned();
+            let gate = DynamicLookupTableGate::new_from_config(&self.config);
+            let num_lut_entries = gate.num_slots;
+            let num_lut_rows = (table.len() - 1) / num_lut_entries + 1;
+            for _ in 0..num_lut_rows {
+                self.add_gate(gate.clone(), vec![]);
+            }
+            let first_lut_gate = self.num_gates() - 1;
+            for lut_entry in 0..num_lut_rows * num_lut_entries {
+                let (inp, out) = table.get(lut_entry).copied().unwrap_or(table[0]);
+                // Same ordering as in `LookupTableGenerator`: the first entries are in the last gate.
+                let row = first_lut_gate - lut_entry / num_lut_entries;
+                let col = lut_entry % num_lut_entries;
+                self.connect(
+                    Target::wire(row, LookupTableGate::wire_ith_looked_inp(col)),
+                    inp,
+                );
+                self.connect(
+                    Target::wire(row, LookupTableGate::wire_ith_looked_out(col)),
+                    out,
+                );
+            }
+
+            self.add_gate(NoopGate, vec![]);
+
+            self.add_lookup_rows(last_lu_gate, last_lut_gate, first_lut_gate);
+        }
     }
 }
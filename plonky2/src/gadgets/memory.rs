@@ -0,0 +1,496 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use hashbrown::HashMap;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// Number of bits of addresses and timestamps.
+const MEMORY_BITS: usize = 32;
+
+/// An operation on a [`Memory`]: a read of `value` at `addr`, or a write of `value` to `addr` if
+/// `is_write` is true.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct MemoryOpTarget {
+    pub addr: Target,
+    pub timestamp: Target,
+    pub value: Target,
+    pub is_write: BoolTarget,
+}
+
+/// A read/write memory, checked with offline memory checking.
+///
+/// Operations are recorded in the order of their timestamps, which must be strictly increasing.
+/// Addresses and timestamps must be less than `2^32`, and memory cells hold zero before their
+/// first write. `finalize` checks that each read returns the value of the last write to its
+/// address.
+///
+/// The values of reads recorded with [`Memory::read`] are generated from the recorded operations,
+/// once all addresses, timestamps and written values are known. If a written value depends on a
+/// read value, that read value must be set by the witness instead. Values of operations recorded
+/// with [`Memory::add_op`] are never generated.
+///
+/// The check sorts the operations by address, then timestamp. Each sorted operation is looked up by
+/// its timestamp in dynamic LUTs of the addresses, values and kinds of the operations, and consecutive
+/// sorted operations are checked with a range check and a few arithmetic gates, so the cost is linear
+/// in the number of operations.
+#[derive(Clone, Debug, Default)]
+pub struct Memory {
+    ops: Vec<MemoryOpTarget>,
+    /// The indices of the operations recorded by `read`, whose values are generated.
+    reads: Vec<usize>,
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ops(&self) -> &[MemoryOpTarget] {
+        &self.ops
+    }
+
+    /// Records an operation, after all the operations recorded so far.
+    pub fn add_op(&mut self, op: MemoryOpTarget) {
+        self.ops.push(op);
+    }
+
+    /// Records a read of `addr` at `timestamp`, and returns the target holding the value read, which
+    /// is generated by `finalize` unless it is set by the witness.
+    pub fn read<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        addr: Target,
+        timestamp: Target,
+    ) -> Target {
+        let value = builder.add_virtual_target();
+        let is_write = builder._false();
+        self.reads.push(self.ops.len());
+        self.add_op(MemoryOpTarget {
+            addr,
+            timestamp,
+            value,
+            is_write,
+        });
+        value
+    }
+
+    /// Records a write of `value` to `addr` at `timestamp`.
+    pub fn write<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        addr: Target,
+        timestamp: Target,
+        value: Target,
+    ) {
+        let is_write = builder._true();
+        self.add_op(MemoryOpTarget {
+            addr,
+            timestamp,
+            value,
+            is_write,
+        });
+    }
+
+    /// Adds the constraints checking that the recorded operations are consistent.
+    pub fn finalize<F: RichField + Extendable<D>, const D: usize>(
+        self,
+        builder: &mut CircuitBuilder<F, D>,
+    ) {
+        let ops = self.ops;
+        if ops.is_empty() {
+            return;
+        }
+
+        if !self.reads.is_empty() {
+            builder.add_simple_generator(MemoryReadGenerator {
+                ops: ops.clone(),
+                reads: self.reads,
+            });
+        }
+
+        // Timestamps are strictly increasing, so that each of them identifies a single operation.
+        for op in &ops {
+            builder.range_check(op.addr, MEMORY_BITS);
+            builder.range_check(op.timestamp, MEMORY_BITS);
+        }
+        for pair in ops.windows(2) {
+            let gap = builder.sub(pair[1].timestamp, pair[0].timestamp);
+            let gap_minus_one = builder.add_const(gap, F::NEG_ONE);
+            builder.range_check(gap_minus_one, MEMORY_BITS);
+        }
+
+        let sorted = (0..ops.len())
+            .map(|_| MemoryOpTarget {
+                addr: builder.add_virtual_target(),
+                timestamp: builder.add_virtual_target(),
+                value: builder.add_virtual_target(),
+                is_write: builder.add_virtual_bool_target_unsafe(),
+            })
+            .collect::<Vec<_>>();
+        builder.add_simple_generator(MemorySortGenerator {
+            ops: ops.clone(),
+            sorted: sorted.clone(),
+        });
+
+        // Each sorted operation is one of the operations. Since sorted operations have distinct
+        // (address, timestamp) pairs and the address is a function of the timestamp, they have
+        // distinct timestamps, so the sorted operations are a permutation of the operations.
+        // The lookups are added directly, as the looking targets are set by `MemorySortGenerator`.
+        let addr_index = builder.add_dynamic_lookup_table(
+            &ops.iter()
+                .map(|op| (op.timestamp, op.addr))
+                .collect::<Vec<_>>(),
+        );
+        let value_index = builder.add_dynamic_lookup_table(
+            &ops.iter()
+                .map(|op| (op.timestamp, op.value))
+                .collect::<Vec<_>>(),
+        );
+        let is_write_index = builder.add_dynamic_lookup_table(
+            &ops.iter()
+                .map(|op| (op.timestamp, op.is_write.target))
+                .collect::<Vec<_>>(),
+        );
+        for op in &sorted {
+            builder.update_dynamic_lookups(op.timestamp, op.addr, addr_index);
+            builder.update_dynamic_lookups(op.timestamp, op.value, value_index);
+            builder.update_dynamic_lookups(op.timestamp, op.is_write.target, is_write_index);
+        }
+
+        // A read of a cell which hasn't been written to returns zero.
+        let first_read_value =
+            builder.mul_sub(sorted[0].is_write.target, sorted[0].value, sorted[0].value);
+        builder.assert_zero(first_read_value);
+
+        for pair in sorted.windows(2) {
+            let (prev, op) = (pair[0], pair[1]);
+
+            // Sorted operations are strictly increasing in (address, timestamp).
+            let same_addr = builder.is_equal(prev.addr, op.addr);
+            let addr_gap = builder.sub(op.addr, prev.addr);
+            let timestamp_gap = builder.sub(op.timestamp, prev.timestamp);
+            let gap = builder.select(same_addr, timestamp_gap, addr_gap);
+            let gap_minus_one = builder.add_const(gap, F::NEG_ONE);
+            builder.range_check(gap_minus_one, MEMORY_BITS);
+
+            // A read returns the previous value at the same address, or zero.
+            let expected = builder.mul(same_addr.target, prev.value);
+            let diff = builder.sub(op.value, expected);
+            let read_diff = builder.mul_sub(op.is_write.target, diff, diff);
+            builder.assert_zero(read_diff);
+        }
+    }
+}
+
+/// Generates the values of the reads at the given indices of `ops`, from the values of the previous
+/// writes to their addresses.
+#[derive(Debug, Default)]
+pub struct MemoryReadGenerator {
+    ops: Vec<MemoryOpTarget>,
+    reads: Vec<usize>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for MemoryReadGenerator {
+    fn id(&self) -> String {
+        "MemoryReadGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        let mut reads = self.reads.iter().peekable();
+        let mut deps = Vec::with_capacity(4 * self.ops.len());
+        for (i, op) in self.ops.iter().enumerate() {
+            deps.extend([op.addr, op.timestamp, op.is_write.target]);
+            if reads.next_if_eq(&&i).is_none() {
+                deps.push(op.value);
+            }
+        }
+        deps
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        // Operations are recorded in the order of their timestamps.
+        let mut cells = HashMap::new();
+        let mut reads = self.reads.iter().peekable();
+        for (i, op) in self.ops.iter().enumerate() {
+            let addr = witness.get_target(op.addr).to_canonical_u64();
+            if reads.next_if_eq(&&i).is_some() {
+                let value = cells.get(&addr).copied().unwrap_or(F::ZERO);
+                out_buffer.set_target(op.value, value);
+            } else if witness.get_bool_target(op.is_write) {
+                cells.insert(addr, witness.get_target(op.value));
+            }
+        }
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.ops.len())?;
+        for op in &self.ops {
+            dst.write_target(op.addr)?;
+            dst.write_target(op.timestamp)?;
+            dst.write_target(op.value)?;
+            dst.write_target_bool(op.is_write)?;
+        }
+        dst.write_usize_vec(&self.reads)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let len = src.read_usize()?;
+        let mut ops = Vec::with_capacity(len);
+        for _ in 0..len {
+            ops.push(MemoryOpTarget {
+                addr: src.read_target()?,
+                timestamp: src.read_target()?,
+                value: src.read_target()?,
+                is_write: src.read_target_bool()?,
+            });
+        }
+        let reads = src.read_usize_vec()?;
+
+        Ok(Self { ops, reads })
+    }
+}
+
+/// Sorts memory operations by address, then timestamp.
+#[derive(Debug, Default)]
+pub struct MemorySortGenerator {
+    ops: Vec<MemoryOpTarget>,
+    sorted: Vec<MemoryOpTarget>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for MemorySortGenerator {
+    fn id(&self) -> String {
+        "MemorySortGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.ops
+            .iter()
+            .flat_map(|op| [op.addr, op.timestamp, op.value, op.is_write.target])
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let mut ops = self
+            .ops
+            .iter()
+            .map(|op| {
+                [op.addr, op.timestamp, op.value, op.is_write.target].map(|t| witness.get_target(t))
+            })
+            .collect::<Vec<_>>();
+        ops.sort_by_key(|&[addr, timestamp, _, _]| {
+            (addr.to_canonical_u64(), timestamp.to_canonical_u64())
+        });
+
+        for (sorted, values) in self.sorted.iter().zip(ops) {
+            let targets = [
+                sorted.addr,
+                sorted.timestamp,
+                sorted.value,
+                sorted.is_write.target,
+            ];
+            for (target, value) in targets.into_iter().zip(values) {
+                out_buffer.set_target(target, value);
+            }
+        }
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.ops.len())?;
+        for op in self.ops.iter().chain(&self.sorted) {
+            dst.write_target(op.addr)?;
+            dst.write_target(op.timestamp)?;
+            dst.write_target(op.value)?;
+            dst.write_target_bool(op.is_write)?;
+        }
+
+        Ok(())
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let len = src.read_usize()?;
+        let mut ops = Vec::with_capacity(2 * len);
+        for _ in 0..2 * len {
+            ops.push(MemoryOpTarget {
+                addr: src.read_target()?,
+                timestamp: src.read_target()?,
+                value: src.read_target()?,
+                is_write: src.read_target_bool()?,
+            });
+        }
+        let sorted = ops.split_off(len);
+
+        Ok(Self { ops, sorted })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::field::types::{Field, Field64};
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn prove_and_verify(builder: CircuitBuilder<F, D>, pw: PartialWitness<F>) -> Result<()> {
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    /// Builds a circuit with the given operations, as `(addr, timestamp, value, is_write)`, whose
+    /// values are all set by the witness.
+    fn memory_circuit(ops: &[(u32, u32, u64, bool)]) -> (CircuitBuilder<F, D>, PartialWitness<F>) {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        let mut memory = Memory::new();
+        for &(addr, timestamp, value, is_write) in ops {
+            let op = MemoryOpTarget {
+                addr: builder.add_virtual_target(),
+                timestamp: builder.add_virtual_target(),
+                value: builder.add_virtual_target(),
+                is_write: builder.add_virtual_bool_target_safe(),
+            };
+            pw.set_target(op.addr, F::from_canonical_u32(addr));
+            pw.set_target(op.timestamp, F::from_canonical_u32(timestamp));
+            pw.set_target(op.value, F::from_canonical_u64(value));
+            pw.set_bool_target(op.is_write, is_write);
+            memory.add_op(op);
+        }
+        memory.finalize(&mut builder);
+
+        (builder, pw)
+    }
+
+    /// Returns random operations with consistent reads.
+    fn random_ops(num_ops: usize, num_addrs: u32) -> Vec<(u32, u32, u64, bool)> {
+        let mut rng = OsRng;
+        let mut cells = vec![0; num_addrs as usize];
+        let mut timestamp = rng.gen_range(0..100);
+        (0..num_ops)
+            .map(|_| {
+                timestamp += rng.gen_range(1..5);
+                let addr = rng.gen_range(0..num_addrs);
+                if rng.gen() {
+                    cells[addr as usize] = rng.gen::<u64>() % F::ORDER;
+                    (addr, timestamp, cells[addr as usize], true)
+                } else {
+                    (addr, timestamp, cells[addr as usize], false)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_memory() -> Result<()> {
+        let (builder, pw) = memory_circuit(&random_ops(1000, 16));
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_memory_counter() -> Result<()> {
+        // Increments a counter stored at an address, the value written depending on the value read.
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        let mut memory = Memory::new();
+        let addr = builder.constant(F::from_canonical_u32(u32::MAX));
+        let num_steps = 50;
+        for i in 0..num_steps {
+            let read_timestamp = builder.constant(F::from_canonical_usize(2 * i));
+            let write_timestamp = builder.constant(F::from_canonical_usize(2 * i + 1));
+            let value = memory.read(&mut builder, addr, read_timestamp);
+            pw.set_target(value, F::from_canonical_usize(i));
+            let incremented = builder.add_const(value, F::ONE);
+            memory.write(&mut builder, addr, write_timestamp, incremented);
+        }
+        let last_timestamp = builder.constant(F::from_canonical_usize(2 * num_steps));
+        let last_value = memory.read(&mut builder, addr, last_timestamp);
+        pw.set_target(last_value, F::from_canonical_usize(num_steps));
+        memory.finalize(&mut builder);
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_memory_generated_reads() -> Result<()> {
+        // Reads of values written by the witness, and of an unwritten cell, are generated.
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        let mut memory = Memory::new();
+        let ops = random_ops(100, 8);
+        let mut expected_reads = Vec::new();
+        for &(addr, timestamp, value, is_write) in &ops {
+            let addr = builder.constant(F::from_canonical_u32(addr));
+            let timestamp = builder.constant(F::from_canonical_u32(timestamp));
+            if is_write {
+                let value_t = builder.add_virtual_target();
+                pw.set_target(value_t, F::from_canonical_u64(value));
+                memory.write(&mut builder, addr, timestamp, value_t);
+            } else {
+                let read = memory.read(&mut builder, addr, timestamp);
+                expected_reads.push((read, value));
+            }
+        }
+        for (read, value) in expected_reads {
+            let expected = builder.constant(F::from_canonical_u64(value));
+            builder.connect(read, expected);
+        }
+        memory.finalize(&mut builder);
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn test_memory_wrong_read() {
+        let mut ops = random_ops(100, 4);
+        let read = ops.iter_mut().rev().find(|op| !op.3).unwrap();
+        read.2 += 1;
+        let (builder, pw) = memory_circuit(&ops);
+        prove_and_verify(builder, pw).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn test_memory_uninitialized_read() {
+        let (builder, pw) = memory_circuit(&[(3, 0, 5, true), (4, 1, 5, false)]);
+        prove_and_verify(builder, pw).unwrap();
+    }
+
+    #[test]
+    // The range check of the timestamp gap fails, which debug builds detect when generating it.
+    #[cfg_attr(debug_assertions, should_panic(expected = "Integer too large to fit"))]
+    #[cfg_attr(
+        not(debug_assertions),
+        should_panic(expected = "was set twice with different values")
+    )]
+    fn test_memory_repeated_timestamp() {
+        let (builder, pw) = memory_circuit(&[(0, 7, 1, true), (0, 7, 1, false)]);
+        prove_and_verify(builder, pw).unwrap();
+    }
+}
@@ -0,0 +1,603 @@
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec, vec::Vec};
+use core::any::type_name;
+use core::marker::PhantomData;
+
+use num::{BigUint, Integer, One, Zero};
+
+use crate::field::extension::Extendable;
+use crate::field::types::{Field, PrimeField};
+use crate::gadgets::biguint::{max_column_bits, BigUintTarget, BIGUINT_LIMB_BITS};
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::ceil_div_usize;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// An element of the prime field `FF`, represented by its canonical value as a [`BigUintTarget`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NonNativeTarget<FF: Field> {
+    pub value: BigUintTarget,
+    pub(crate) _phantom: PhantomData<FF>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Number of limbs needed to hold an element of `FF`.
+    pub fn num_nonnative_limbs<FF: Field>() -> usize {
+        ceil_div_usize(FF::BITS, BIGUINT_LIMB_BITS)
+    }
+
+    /// Reinterprets `x` as an element of `FF`. `x` must already be reduced modulo the order of
+    /// `FF`.
+    pub fn biguint_to_nonnative<FF: Field>(&mut self, x: &BigUintTarget) -> NonNativeTarget<FF> {
+        NonNativeTarget {
+            value: x.clone(),
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn nonnative_to_canonical_biguint<FF: Field>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> BigUintTarget {
+        x.value.clone()
+    }
+
+    pub fn constant_nonnative<FF: PrimeField>(&mut self, x: FF) -> NonNativeTarget<FF> {
+        let x_biguint = self.constant_biguint(&x.to_canonical_biguint());
+        self.biguint_to_nonnative(&x_biguint)
+    }
+
+    pub fn zero_nonnative<FF: PrimeField>(&mut self) -> NonNativeTarget<FF> {
+        self.constant_nonnative(FF::ZERO)
+    }
+
+    /// Reduces `x` modulo the order of `FF`.
+    pub fn reduce<FF: PrimeField>(&mut self, x: &BigUintTarget) -> NonNativeTarget<FF> {
+        let modulus = self.constant_biguint(&FF::order());
+        let rem = self.rem_biguint(x, &modulus);
+        self.biguint_to_nonnative(&rem)
+    }
+
+    /// Adds a virtual target holding an element of `FF`, with range-checked limbs and a
+    /// canonical value.
+    pub fn add_virtual_nonnative_target<FF: PrimeField>(&mut self) -> NonNativeTarget<FF> {
+        let value = self.add_virtual_biguint_target(Self::num_nonnative_limbs::<FF>());
+        let modulus = self.constant_biguint(&FF::order());
+        self.assert_lt_biguint(&value, &modulus);
+        self.biguint_to_nonnative(&value)
+    }
+
+    pub fn connect_nonnative<FF: Field>(
+        &mut self,
+        lhs: &NonNativeTarget<FF>,
+        rhs: &NonNativeTarget<FF>,
+    ) {
+        self.connect_biguint(&lhs.value, &rhs.value);
+    }
+
+    pub fn add_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let sum = self.add_virtual_nonnative_target::<FF>();
+        let overflow = self.add_virtual_bool_target_safe();
+
+        self.add_simple_generator(NonNativeAdditionGenerator::<FF> {
+            a: a.clone(),
+            b: b.clone(),
+            sum: sum.clone(),
+            overflow,
+            _phantom: PhantomData,
+        });
+
+        // a + b - overflow * p - sum == 0
+        let modulus = self.constant_biguint(&FF::order());
+        let overflow_biguint = BigUintTarget {
+            limbs: vec![overflow.target],
+        };
+        let mut columns = Vec::new();
+        self.accumulate_limb_columns(&mut columns, &a.value, false);
+        self.accumulate_limb_columns(&mut columns, &b.value, false);
+        self.accumulate_product_columns(&mut columns, &overflow_biguint, &modulus, true);
+        self.accumulate_limb_columns(&mut columns, &sum.value, true);
+        self.assert_zero_columns(&columns, max_column_bits(4));
+
+        sum
+    }
+
+    pub fn sub_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let diff = self.add_virtual_nonnative_target::<FF>();
+        let overflow = self.add_virtual_bool_target_safe();
+
+        self.add_simple_generator(NonNativeSubtractionGenerator::<FF> {
+            a: a.clone(),
+            b: b.clone(),
+            diff: diff.clone(),
+            overflow,
+            _phantom: PhantomData,
+        });
+
+        // a - b + overflow * p - diff == 0
+        let modulus = self.constant_biguint(&FF::order());
+        let overflow_biguint = BigUintTarget {
+            limbs: vec![overflow.target],
+        };
+        let mut columns = Vec::new();
+        self.accumulate_limb_columns(&mut columns, &a.value, false);
+        self.accumulate_limb_columns(&mut columns, &b.value, true);
+        self.accumulate_product_columns(&mut columns, &overflow_biguint, &modulus, false);
+        self.accumulate_limb_columns(&mut columns, &diff.value, true);
+        self.assert_zero_columns(&columns, max_column_bits(4));
+
+        diff
+    }
+
+    pub fn mul_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let prod = self.add_virtual_nonnative_target::<FF>();
+        let overflow = self.add_virtual_biguint_target(Self::num_nonnative_limbs::<FF>());
+
+        self.add_simple_generator(NonNativeMultiplicationGenerator::<FF> {
+            a: a.clone(),
+            b: b.clone(),
+            prod: prod.clone(),
+            overflow: overflow.clone(),
+            _phantom: PhantomData,
+        });
+
+        // a * b - overflow * p - prod == 0
+        let modulus = self.constant_biguint(&FF::order());
+        let mut columns = Vec::new();
+        self.accumulate_product_columns(&mut columns, &a.value, &b.value, false);
+        self.accumulate_product_columns(&mut columns, &overflow, &modulus, true);
+        self.accumulate_limb_columns(&mut columns, &prod.value, true);
+        let num_terms = a.value.num_limbs().min(b.value.num_limbs())
+            + overflow.num_limbs().min(modulus.num_limbs())
+            + 1;
+        self.assert_zero_columns(&columns, max_column_bits(num_terms));
+
+        prod
+    }
+
+    pub fn mul_nonnative_by_bool<FF: Field>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: BoolTarget,
+    ) -> NonNativeTarget<FF> {
+        NonNativeTarget {
+            value: self.mul_biguint_by_bool(&a.value, b),
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn neg_nonnative<FF: PrimeField>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let zero = self.zero_nonnative();
+        self.sub_nonnative(&zero, x)
+    }
+
+    /// Returns the inverse of `x`, which must be nonzero.
+    pub fn inv_nonnative<FF: PrimeField>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let inv = self.add_virtual_nonnative_target::<FF>();
+        let div = self.add_virtual_biguint_target(Self::num_nonnative_limbs::<FF>());
+
+        self.add_simple_generator(NonNativeInverseGenerator::<FF> {
+            x: x.clone(),
+            inv: inv.clone(),
+            div: div.clone(),
+            _phantom: PhantomData,
+        });
+
+        // x * inv - div * p - 1 == 0
+        let modulus = self.constant_biguint(&FF::order());
+        let one = self.constant_biguint(&BigUint::one());
+        let mut columns = Vec::new();
+        self.accumulate_product_columns(&mut columns, &x.value, &inv.value, false);
+        self.accumulate_product_columns(&mut columns, &div, &modulus, true);
+        self.accumulate_limb_columns(&mut columns, &one, true);
+        let num_terms = x.value.num_limbs().min(inv.value.num_limbs())
+            + div.num_limbs().min(modulus.num_limbs())
+            + 1;
+        self.assert_zero_columns(&columns, max_column_bits(num_terms));
+
+        inv
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NonNativeAdditionGenerator<FF: PrimeField> {
+    a: NonNativeTarget<FF>,
+    b: NonNativeTarget<FF>,
+    sum: NonNativeTarget<FF>,
+    overflow: BoolTarget,
+    _phantom: PhantomData<FF>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerator<F, D>
+    for NonNativeAdditionGenerator<FF>
+{
+    fn id(&self) -> String {
+        format!("NonNativeAdditionGenerator<{}>", type_name::<FF>())
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.a
+            .value
+            .limbs
+            .iter()
+            .chain(&self.b.value.limbs)
+            .copied()
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let a = witness.get_biguint_target(&self.a.value);
+        let b = witness.get_biguint_target(&self.b.value);
+        let (overflow, sum) = (a + b).div_rem(&FF::order());
+
+        out_buffer.set_biguint_target(&self.sum.value, &sum);
+        out_buffer.set_bool_target(self.overflow, !overflow.is_zero());
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.a.value.limbs)?;
+        dst.write_target_vec(&self.b.value.limbs)?;
+        dst.write_target_vec(&self.sum.value.limbs)?;
+        dst.write_target_bool(self.overflow)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = read_nonnative_target(src)?;
+        let b = read_nonnative_target(src)?;
+        let sum = read_nonnative_target(src)?;
+        let overflow = src.read_target_bool()?;
+        Ok(Self {
+            a,
+            b,
+            sum,
+            overflow,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NonNativeSubtractionGenerator<FF: PrimeField> {
+    a: NonNativeTarget<FF>,
+    b: NonNativeTarget<FF>,
+    diff: NonNativeTarget<FF>,
+    overflow: BoolTarget,
+    _phantom: PhantomData<FF>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerator<F, D>
+    for NonNativeSubtractionGenerator<FF>
+{
+    fn id(&self) -> String {
+        format!("NonNativeSubtractionGenerator<{}>", type_name::<FF>())
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.a
+            .value
+            .limbs
+            .iter()
+            .chain(&self.b.value.limbs)
+            .copied()
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let a = witness.get_biguint_target(&self.a.value);
+        let b = witness.get_biguint_target(&self.b.value);
+        let overflow = a < b;
+        let diff = if overflow { a + FF::order() - b } else { a - b };
+
+        out_buffer.set_biguint_target(&self.diff.value, &diff);
+        out_buffer.set_bool_target(self.overflow, overflow);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.a.value.limbs)?;
+        dst.write_target_vec(&self.b.value.limbs)?;
+        dst.write_target_vec(&self.diff.value.limbs)?;
+        dst.write_target_bool(self.overflow)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = read_nonnative_target(src)?;
+        let b = read_nonnative_target(src)?;
+        let diff = read_nonnative_target(src)?;
+        let overflow = src.read_target_bool()?;
+        Ok(Self {
+            a,
+            b,
+            diff,
+            overflow,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NonNativeMultiplicationGenerator<FF: PrimeField> {
+    a: NonNativeTarget<FF>,
+    b: NonNativeTarget<FF>,
+    prod: NonNativeTarget<FF>,
+    overflow: BigUintTarget,
+    _phantom: PhantomData<FF>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerator<F, D>
+    for NonNativeMultiplicationGenerator<FF>
+{
+    fn id(&self) -> String {
+        format!("NonNativeMultiplicationGenerator<{}>", type_name::<FF>())
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.a
+            .value
+            .limbs
+            .iter()
+            .chain(&self.b.value.limbs)
+            .copied()
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let a = witness.get_biguint_target(&self.a.value);
+        let b = witness.get_biguint_target(&self.b.value);
+        let (overflow, prod) = (a * b).div_rem(&FF::order());
+
+        out_buffer.set_biguint_target(&self.prod.value, &prod);
+        out_buffer.set_biguint_target(&self.overflow, &overflow);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.a.value.limbs)?;
+        dst.write_target_vec(&self.b.value.limbs)?;
+        dst.write_target_vec(&self.prod.value.limbs)?;
+        dst.write_target_vec(&self.overflow.limbs)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = read_nonnative_target(src)?;
+        let b = read_nonnative_target(src)?;
+        let prod = read_nonnative_target(src)?;
+        let overflow = BigUintTarget {
+            limbs: src.read_target_vec()?,
+        };
+        Ok(Self {
+            a,
+            b,
+            prod,
+            overflow,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NonNativeInverseGenerator<FF: PrimeField> {
+    x: NonNativeTarget<FF>,
+    inv: NonNativeTarget<FF>,
+    div: BigUintTarget,
+    _phantom: PhantomData<FF>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerator<F, D>
+    for NonNativeInverseGenerator<FF>
+{
+    fn id(&self) -> String {
+        format!("NonNativeInverseGenerator<{}>", type_name::<FF>())
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.x.value.limbs.clone()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let x = witness.get_nonnative_target(&self.x);
+        // Zero has no inverse, in which case the constraints can't be satisfied whatever we set.
+        let inv = x.try_inverse().unwrap_or(FF::ZERO);
+
+        let x_biguint = x.to_canonical_biguint();
+        let inv_biguint = inv.to_canonical_biguint();
+        let div = (x_biguint * &inv_biguint) / FF::order();
+
+        out_buffer.set_biguint_target(&self.inv.value, &inv_biguint);
+        out_buffer.set_biguint_target(&self.div, &div);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.x.value.limbs)?;
+        dst.write_target_vec(&self.inv.value.limbs)?;
+        dst.write_target_vec(&self.div.limbs)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let x = read_nonnative_target(src)?;
+        let inv = read_nonnative_target(src)?;
+        let div = BigUintTarget {
+            limbs: src.read_target_vec()?,
+        };
+        Ok(Self {
+            x,
+            inv,
+            div,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+fn read_nonnative_target<FF: Field>(src: &mut Buffer) -> IoResult<NonNativeTarget<FF>> {
+    Ok(NonNativeTarget {
+        value: BigUintTarget {
+            limbs: src.read_target_vec()?,
+        },
+        _phantom: PhantomData,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::secp256k1_base::Secp256K1Base;
+    use crate::field::types::{Field, PrimeField, Sample};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type FF = Secp256K1Base;
+
+    fn prove_and_verify(builder: CircuitBuilder<F, D>, pw: PartialWitness<F>) -> Result<()> {
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_nonnative_add() -> Result<()> {
+        let x_ff = FF::rand();
+        let y_ff = FF::rand();
+        let sum_ff = x_ff + y_ff;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_nonnative_target::<FF>();
+        let y = builder.add_virtual_nonnative_target::<FF>();
+        let sum = builder.add_nonnative(&x, &y);
+        let sum_expected = builder.constant_nonnative(sum_ff);
+        builder.connect_nonnative(&sum, &sum_expected);
+
+        pw.set_nonnative_target(&x, x_ff);
+        pw.set_nonnative_target(&y, y_ff);
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_nonnative_add_overflow() -> Result<()> {
+        let x_ff = FF::NEG_ONE;
+        let y_ff = FF::TWO;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.constant_nonnative(x_ff);
+        let y = builder.constant_nonnative(y_ff);
+        let sum = builder.add_nonnative(&x, &y);
+        let sum_expected = builder.constant_nonnative(FF::ONE);
+        builder.connect_nonnative(&sum, &sum_expected);
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_nonnative_sub() -> Result<()> {
+        let x_ff = FF::rand();
+        let y_ff = FF::rand();
+        let diff_ff = x_ff - y_ff;
+        let neg_ff = -x_ff;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.constant_nonnative(x_ff);
+        let y = builder.constant_nonnative(y_ff);
+        let diff = builder.sub_nonnative(&x, &y);
+        let diff_expected = builder.constant_nonnative(diff_ff);
+        builder.connect_nonnative(&diff, &diff_expected);
+
+        let neg = builder.neg_nonnative(&x);
+        let neg_expected = builder.constant_nonnative(neg_ff);
+        builder.connect_nonnative(&neg, &neg_expected);
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_nonnative_mul() -> Result<()> {
+        let x_ff = FF::rand();
+        let y_ff = FF::rand();
+        let product_ff = x_ff * y_ff;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_nonnative_target::<FF>();
+        let y = builder.add_virtual_nonnative_target::<FF>();
+        let product = builder.mul_nonnative(&x, &y);
+        let product_expected = builder.constant_nonnative(product_ff);
+        builder.connect_nonnative(&product, &product_expected);
+
+        pw.set_nonnative_target(&x, x_ff);
+        pw.set_nonnative_target(&y, y_ff);
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_nonnative_inv() -> Result<()> {
+        let x_ff = FF::rand();
+        let inv_x_ff = x_ff.inverse();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.constant_nonnative(x_ff);
+        let inv_x = builder.inv_nonnative(&x);
+        let inv_x_expected = builder.constant_nonnative(inv_x_ff);
+        builder.connect_nonnative(&inv_x, &inv_x_expected);
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_nonnative_reduce() -> Result<()> {
+        let x_ff = FF::rand();
+        let y_ff = FF::rand();
+        let x_biguint = x_ff.to_canonical_biguint() * y_ff.to_canonical_biguint();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.constant_biguint(&x_biguint);
+        let reduced = builder.reduce::<FF>(&x);
+        let reduced_expected = builder.constant_nonnative(x_ff * y_ff);
+        builder.connect_nonnative(&reduced, &reduced_expected);
+
+        prove_and_verify(builder, pw)
+    }
+}
@@ -12,12 +12,75 @@ use crate::iop::target::{BoolTarget, Target};
 use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::ceil_div_usize;
 use crate::util::serialization::{Buffer, IoResult, Read, Write};
 
+/// The ways of checking that a value fits in a number of bits.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum RangeCheckMode {
+    /// Decompose the value into bits with a `BaseSumGate`. It costs about one row per check.
+    #[default]
+    Bits,
+    /// Decompose the value into limbs of `limb_bits` bits, at most 16, and look each limb up in a range
+    /// LUT shared by all range checks with the same limb size. The LUT has `2^limb_bits` entries, so this
+    /// is cheaper for circuits with many range checks.
+    Lookup { limb_bits: usize },
+}
+
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
-    /// Checks that `x < 2^n_log` using a `BaseSumGate`.
+    /// Checks that `x < 2^n_log`, in the way given by the builder's range check mode, which can be
+    /// changed with `set_range_check_mode`.
     pub fn range_check(&mut self, x: Target, n_log: usize) {
-        self.split_le(x, n_log);
+        self.range_check_with_mode(x, n_log, self.range_check_mode());
+    }
+
+    /// Checks that `x < 2^n_log`, in the way given by `mode`.
+    pub fn range_check_with_mode(&mut self, x: Target, n_log: usize, mode: RangeCheckMode) {
+        match mode {
+            RangeCheckMode::Bits => {
+                self.split_le(x, n_log);
+            }
+            RangeCheckMode::Lookup { limb_bits } => self.range_check_lookup(x, n_log, limb_bits),
+        }
+    }
+
+    /// Checks that `x < 2^n_log` by splitting it into limbs of `limb_bits` bits, which are looked up in
+    /// a range LUT. The lookups are placed by `add_all_lookups`, with the other lookups into the LUT.
+    fn range_check_lookup(&mut self, x: Target, n_log: usize, limb_bits: usize) {
+        let lut_index = self.range_lut(limb_bits);
+        if n_log == 0 {
+            self.assert_zero(x);
+            return;
+        }
+
+        let num_limbs = ceil_div_usize(n_log, limb_bits);
+        let limbs = self.add_virtual_targets(num_limbs);
+        self.add_simple_generator(LimbSplitGenerator {
+            integer: x,
+            limb_bits,
+            limbs: limbs.clone(),
+        });
+
+        // The range LUT maps each limb to itself, so the looking output is the limb itself.
+        for &limb in &limbs {
+            self.update_lookups(limb, limb, lut_index);
+        }
+        // If the most significant limb has fewer bits, it must still be in range once shifted to the top of a limb.
+        let top_bits = n_log - (num_limbs - 1) * limb_bits;
+        if top_bits < limb_bits {
+            let shift = F::from_canonical_u64(1 << (limb_bits - top_bits));
+            let shifted = self.mul_const(shift, limbs[num_limbs - 1]);
+            self.update_lookups(shifted, shifted, lut_index);
+        }
+
+        let base = F::from_canonical_u64(1 << limb_bits);
+        let sum = limbs[..num_limbs - 1]
+            .iter()
+            .rev()
+            .fold(limbs[num_limbs - 1], |acc, &limb| {
+                self.mul_const_add(base, acc, limb)
+            });
+        self.connect(x, sum);
     }
 
     /// Returns the first `num_low_bits` little-endian bits of `x`.
@@ -103,3 +166,160 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Low
         })
     }
 }
+
+/// Splits an integer into little-endian limbs of `limb_bits` bits.
+#[derive(Debug, Default)]
+pub struct LimbSplitGenerator {
+    integer: Target,
+    limb_bits: usize,
+    limbs: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for LimbSplitGenerator {
+    fn id(&self) -> String {
+        "LimbSplitGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.integer]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let mut integer_value = witness.get_target(self.integer).to_canonical_u64();
+
+        for &limb in &self.limbs {
+            out_buffer.set_target(
+                limb,
+                F::from_canonical_u64(integer_value & ((1 << self.limb_bits) - 1)),
+            );
+            integer_value >>= self.limb_bits;
+        }
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target(self.integer)?;
+        dst.write_usize(self.limb_bits)?;
+        dst.write_target_vec(&self.limbs)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let integer = src.read_target()?;
+        let limb_bits = src.read_usize()?;
+        let limbs = src.read_target_vec()?;
+        Ok(Self {
+            integer,
+            limb_bits,
+            limbs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::field::types::Field;
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn new_builder(mode: RangeCheckMode) -> CircuitBuilder<F, D> {
+        let mut builder = CircuitBuilder::new(CircuitConfig::standard_recursion_config());
+        builder.set_range_check_mode(mode);
+        builder
+    }
+
+    fn prove_and_verify(builder: CircuitBuilder<F, D>, pw: PartialWitness<F>) -> Result<()> {
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    /// Range checks `value` on `n_log` bits in a circuit using `mode` for range checks.
+    fn check_value(mode: RangeCheckMode, value: u64, n_log: usize) -> Result<()> {
+        let mut builder = new_builder(mode);
+        let mut pw = PartialWitness::new();
+        let x = builder.add_virtual_target();
+        builder.range_check(x, n_log);
+        pw.set_target(x, F::from_canonical_u64(value));
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_range_check_lookup() -> Result<()> {
+        let mut builder = new_builder(RangeCheckMode::Lookup { limb_bits: 16 });
+        let mut pw = PartialWitness::new();
+
+        let mut rng = OsRng;
+        for n_log in [1, 8, 16, 20, 32, 48] {
+            for value in [0, (1 << n_log) - 1, rng.gen_range(0..1 << n_log)] {
+                let x = builder.add_virtual_target();
+                builder.range_check(x, n_log);
+                pw.set_target(x, F::from_canonical_u64(value));
+            }
+        }
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_range_check_with_mode() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        let mut rng = OsRng;
+        let modes = [
+            RangeCheckMode::Bits,
+            RangeCheckMode::Lookup { limb_bits: 8 },
+            RangeCheckMode::Lookup { limb_bits: 10 },
+        ];
+        for mode in modes {
+            for _ in 0..100 {
+                let x = builder.add_virtual_target();
+                builder.range_check_with_mode(x, 32, mode);
+                pw.set_target(x, F::from_canonical_u32(rng.gen()));
+            }
+        }
+
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_range_check_lookup_is_cheaper() {
+        let num_checks = 2000;
+        let degree_bits = |mode| {
+            let mut builder = new_builder(mode);
+            for _ in 0..num_checks {
+                let x = builder.add_virtual_target();
+                builder.range_check(x, 32);
+            }
+            builder.build::<C>().common.degree_bits()
+        };
+
+        assert!(
+            degree_bits(RangeCheckMode::Lookup { limb_bits: 8 })
+                < degree_bits(RangeCheckMode::Bits)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_range_check_lookup_out_of_range() {
+        check_value(RangeCheckMode::Lookup { limb_bits: 16 }, 1 << 32, 32).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_range_check_lookup_top_limb_out_of_range() {
+        check_value(RangeCheckMode::Lookup { limb_bits: 16 }, 1 << 20, 20).unwrap();
+    }
+}
@@ -0,0 +1,372 @@
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use crate::field::extension::Extendable;
+use crate::gadgets::bytes::{pack_le, ByteLookups};
+use crate::hash::hash_types::RichField;
+use crate::hash::sha256::{
+    sha256_pad, SHA256_BLOCK_BYTES, SHA256_DIGEST_BYTES, SHA256_INITIAL_STATE,
+    SHA256_ROUND_CONSTANTS,
+};
+use crate::iop::target::{BoolTarget, ByteTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// A 32-bit word, as little-endian bytes along with its value.
+#[derive(Copy, Clone, Debug)]
+struct Word {
+    bytes: [ByteTarget; 4],
+    value: Target,
+}
+
+impl Word {
+    fn from_bytes<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        bytes: [ByteTarget; 4],
+    ) -> Self {
+        let value = pack_le(builder, &bytes);
+        Self { bytes, value }
+    }
+
+    fn constant<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        x: u32,
+    ) -> Self {
+        Self {
+            bytes: x.to_le_bytes().map(|byte| builder.constant_byte(byte)),
+            value: builder.constant(F::from_canonical_u32(x)),
+        }
+    }
+}
+
+/// Returns the sum of `values` modulo `2^32`. The sum must be less than `2^40`.
+fn add_words<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    lookups: &mut ByteLookups,
+    values: &[Target],
+) -> Word {
+    let sum = builder.add_many(values);
+    if let Some(c) = builder.target_as_constant(sum) {
+        return Word::constant(builder, c.to_canonical_u64() as u32);
+    }
+
+    let bytes = lookups.split_le(builder, sum, 5);
+    let value = builder.mul_const_add(-F::from_canonical_u64(1 << 32), bytes[4].target, sum);
+    Word {
+        bytes: [bytes[0], bytes[1], bytes[2], bytes[3]],
+        value,
+    }
+}
+
+/// Returns `x.rotate_right(r0) ^ x.rotate_right(r1) ^ (x >> r2)`, or `x.rotate_right(r2)` for
+/// the last term if `rotate_last` is set.
+fn sigma<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    lookups: &mut ByteLookups,
+    x: &Word,
+    [r0, r1, r2]: [usize; 3],
+    rotate_last: bool,
+) -> Word {
+    let x0 = lookups.rotate_right(builder, &x.bytes, r0);
+    let x1 = lookups.rotate_right(builder, &x.bytes, r1);
+    let x2 = if rotate_last {
+        lookups.rotate_right(builder, &x.bytes, r2)
+    } else {
+        lookups.shift_right(builder, &x.bytes, r2)
+    };
+    let xor = lookups.xor_words(builder, &x0, &x1);
+    let xor = lookups.xor_words(builder, &xor, &x2);
+    Word::from_bytes(builder, xor)
+}
+
+/// Returns `(e & f) ^ (!e & g)`. The two terms have disjoint bits, so they can be added.
+fn ch<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    lookups: &mut ByteLookups,
+    e: &Word,
+    f: &Word,
+    g: &Word,
+) -> Word {
+    let e_and_f = lookups.and_words(builder, &e.bytes, &f.bytes);
+    let not_e_and_g = lookups.and_not_words(builder, &e.bytes, &g.bytes);
+    let bytes = core::array::from_fn(|i| {
+        ByteTarget::new_unsafe(builder.add(e_and_f[i].target, not_e_and_g[i].target))
+    });
+    Word::from_bytes(builder, bytes)
+}
+
+/// Returns `(a & b) ^ (a & c) ^ (b & c)`, computed as `(a & b) + (c & (a ^ b))` since the two
+/// terms have disjoint bits.
+fn maj<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    lookups: &mut ByteLookups,
+    a: &Word,
+    b: &Word,
+    c: &Word,
+) -> Word {
+    let a_and_b = lookups.and_words(builder, &a.bytes, &b.bytes);
+    let a_xor_b = lookups.xor_words(builder, &a.bytes, &b.bytes);
+    let c_and_a_xor_b = lookups.and_words(builder, &c.bytes, &a_xor_b);
+    let bytes = core::array::from_fn(|i| {
+        ByteTarget::new_unsafe(builder.add(a_and_b[i].target, c_and_a_xor_b[i].target))
+    });
+    Word::from_bytes(builder, bytes)
+}
+
+/// Applies the SHA-256 compression function to `state` and the 64 bytes of `block`.
+fn compress<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    lookups: &mut ByteLookups,
+    state: &mut [Word; 8],
+    block: &[ByteTarget],
+) {
+    debug_assert_eq!(block.len(), SHA256_BLOCK_BYTES);
+
+    // The message schedule. Input words are big-endian.
+    let mut w = Vec::with_capacity(64);
+    for word in block.chunks_exact(4) {
+        let word = Word::from_bytes(builder, [word[3], word[2], word[1], word[0]]);
+        w.push(word);
+    }
+    for t in 16..64 {
+        let s0 = sigma(builder, lookups, &w[t - 15], [7, 18, 3], false);
+        let s1 = sigma(builder, lookups, &w[t - 2], [17, 19, 10], false);
+        let word = add_words(
+            builder,
+            lookups,
+            &[s1.value, w[t - 7].value, s0.value, w[t - 16].value],
+        );
+        w.push(word);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for t in 0..64 {
+        let s1 = sigma(builder, lookups, &e, [6, 11, 25], true);
+        let ch = ch(builder, lookups, &e, &f, &g);
+        let k = builder.constant(F::from_canonical_u32(SHA256_ROUND_CONSTANTS[t]));
+        let t1 = builder.add_many([h.value, s1.value, ch.value, k, w[t].value]);
+        let s0 = sigma(builder, lookups, &a, [2, 13, 22], true);
+        let maj = maj(builder, lookups, &a, &b, &c);
+
+        h = g;
+        g = f;
+        f = e;
+        e = add_words(builder, lookups, &[d.value, t1]);
+        d = c;
+        c = b;
+        b = a;
+        a = add_words(builder, lookups, &[t1, s0.value, maj.value]);
+    }
+
+    for (s, x) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *s = add_words(builder, lookups, &[s.value, x.value]);
+    }
+}
+
+/// Returns the big-endian bytes of the digest given by `state`.
+fn digest(state: &[Word; 8]) -> [ByteTarget; SHA256_DIGEST_BYTES] {
+    core::array::from_fn(|i| state[i / 4].bytes[3 - i % 4])
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Computes the SHA-256 digest of `input`.
+    ///
+    /// Bitwise operations are computed byte by byte with lookups, and additions modulo `2^32`
+    /// decompose field sums into range-checked bytes. A call to the compression function costs
+    /// around 750 rows, and the xor and and-not tables of `2^16` entries add around 5000 rows to
+    /// the circuit (see the `sha256` benchmark).
+    pub fn sha256(&mut self, input: &[ByteTarget]) -> [ByteTarget; SHA256_DIGEST_BYTES] {
+        let mut lookups = ByteLookups::default();
+
+        let padding = sha256_pad(&vec![0; input.len()]).split_off(input.len());
+        let padding = padding.into_iter().map(|byte| self.constant_byte(byte));
+        let padded = input.iter().copied().chain(padding).collect::<Vec<_>>();
+
+        let mut state = SHA256_INITIAL_STATE.map(|x| Word::constant(self, x));
+        for block in padded.chunks_exact(SHA256_BLOCK_BYTES) {
+            compress(self, &mut lookups, &mut state, block);
+        }
+        digest(&state)
+    }
+
+    /// Computes the SHA-256 digest of the first `len` bytes of `input`, where `len` is a target
+    /// that must be at most `input.len()`.
+    ///
+    /// The circuit applies the compression function to as many blocks as needed for
+    /// `input.len()` bytes, and selects the state obtained after the last block of the padded
+    /// message.
+    pub fn sha256_variable_length(
+        &mut self,
+        input: &[ByteTarget],
+        len: Target,
+    ) -> [ByteTarget; SHA256_DIGEST_BYTES] {
+        let mut lookups = ByteLookups::default();
+        let max_len = input.len();
+        let num_blocks = (max_len + 8) / SHA256_BLOCK_BYTES + 1;
+
+        // `is_len[k]` is set iff `len = k`. Exactly one of them is set, which ensures that
+        // `len <= max_len`.
+        let is_len = (0..=max_len)
+            .map(|k| {
+                let k = self.constant(F::from_canonical_usize(k));
+                self.is_equal(len, k)
+            })
+            .collect::<Vec<_>>();
+        let num_set = self.add_many(is_len.iter().map(|b| b.target));
+        self.assert_one(num_set);
+
+        // `is_last_block[b]` is set iff `b` is the last block of the padded message.
+        let is_last_block = (0..num_blocks)
+            .map(|b| {
+                let is_len_in_block = (0..=max_len)
+                    .filter(|k| (k + 8) / SHA256_BLOCK_BYTES == b)
+                    .map(|k| is_len[k].target)
+                    .collect::<Vec<_>>();
+                BoolTarget::new_unsafe(self.add_many(is_len_in_block))
+            })
+            .collect::<Vec<_>>();
+
+        // The little-endian bytes of the bit length of the message.
+        let max_bit_len = 8 * max_len as u64;
+        let num_len_bytes = (64 - max_bit_len.leading_zeros() as usize).div_ceil(8);
+        let bit_len = self.mul_const(F::from_canonical_u8(8), len);
+        let bit_len_bytes = lookups.split_le(self, bit_len, num_len_bytes.max(1));
+
+        // Byte `p` of the padded message is the input byte if `p < len`, `0x80` if `p = len`, a
+        // byte of the bit length if `p` is among the last 8 bytes of the last block, and zero
+        // otherwise. At most one of these cases applies.
+        let mut padded = Vec::with_capacity(num_blocks * SHA256_BLOCK_BYTES);
+        // Set iff `p < len`.
+        let mut is_before_len = self.one();
+        for p in 0..num_blocks * SHA256_BLOCK_BYTES {
+            let mut byte = self.zero();
+            if p <= max_len {
+                is_before_len = self.sub(is_before_len, is_len[p].target);
+                byte = self.mul_const(F::from_canonical_u8(0x80), is_len[p].target);
+            }
+            if p < max_len {
+                byte = self.mul_add(is_before_len, input[p].target, byte);
+            }
+            let len_byte = SHA256_BLOCK_BYTES - 1 - p % SHA256_BLOCK_BYTES;
+            if len_byte < bit_len_bytes.len() {
+                let is_last_block = is_last_block[p / SHA256_BLOCK_BYTES];
+                byte = self.mul_add(is_last_block.target, bit_len_bytes[len_byte].target, byte);
+            }
+            padded.push(ByteTarget::new_unsafe(byte));
+        }
+
+        let mut state = SHA256_INITIAL_STATE.map(|x| Word::constant(self, x));
+        let mut result = [self.zero(); SHA256_DIGEST_BYTES];
+        for (block, is_last_block) in padded.chunks_exact(SHA256_BLOCK_BYTES).zip(is_last_block) {
+            compress(self, &mut lookups, &mut state, block);
+            for (r, byte) in result.iter_mut().zip(digest(&state)) {
+                *r = self.mul_add(is_last_block.target, byte.target, *r);
+            }
+        }
+        // Exactly one block is selected.
+        result.map(ByteTarget::new_unsafe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use crate::field::types::Field;
+    use crate::hash::sha256::sha256;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_sha256() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // 55 bytes fit in a single block with their padding, while 56 bytes do not.
+        let mut rng = OsRng;
+        for len in [0, 55, 56] {
+            let msg = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let expected = sha256(&msg);
+
+            let msg_target = builder.add_virtual_byte_targets(len);
+            let digest = builder.sha256(&msg_target);
+            for (&byte, &expected_byte) in digest.iter().zip(&expected) {
+                let expected_byte = builder.constant_byte(expected_byte);
+                builder.connect(byte.target, expected_byte.target);
+            }
+
+            for (&t, &byte) in msg_target.iter().zip(&msg) {
+                pw.set_byte_target(t, byte);
+            }
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_sha256_variable_length() -> Result<()> {
+        let max_len = 70;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let msg_target = builder.add_virtual_byte_targets(max_len);
+        let len_target = builder.add_virtual_target();
+        let digest_target = builder.add_virtual_byte_targets(32);
+        let digest = builder.sha256_variable_length(&msg_target, len_target);
+        for (&byte, &expected_byte) in digest.iter().zip(&digest_target) {
+            builder.connect(byte.target, expected_byte.target);
+        }
+        let data = builder.build::<C>();
+
+        // Lengths around the boundary between one and two blocks, and the maximum length.
+        let mut rng = OsRng;
+        for len in [3, 55, 56, max_len] {
+            let msg = (0..max_len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let expected = sha256(&msg[..len]);
+
+            let mut pw = PartialWitness::new();
+            for (&t, &byte) in msg_target.iter().zip(&msg) {
+                pw.set_byte_target(t, byte);
+            }
+            pw.set_target(len_target, F::from_canonical_usize(len));
+            for (&t, &byte) in digest_target.iter().zip(&expected) {
+                pw.set_byte_target(t, byte);
+            }
+
+            let proof = data.prove(pw)?;
+            data.verify(proof)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sha256_variable_length_too_long() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let msg_target = builder.add_virtual_byte_targets(4);
+        let len_target = builder.add_virtual_target();
+        builder.sha256_variable_length(&msg_target, len_target);
+
+        for &t in &msg_target {
+            pw.set_byte_target(t, 0);
+        }
+        pw.set_target(len_target, F::from_canonical_usize(5));
+
+        let data = builder.build::<C>();
+        data.prove(pw).unwrap();
+    }
+}
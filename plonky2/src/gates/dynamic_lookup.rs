@@ -0,0 +1,261 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::gates::gate::Gate;
+use crate::gates::lookup::{Lookup, LookupGate};
+use crate::gates::lookup_table::LookupTableGate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{CircuitConfig, CommonCircuitData};
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// A gate which stores (input, output) lookup pairs made elsewhere in the trace into a dynamic lookup
+/// table, i.e. a table whose rows are part of the witness. It has the same wire layout as [`LookupGate`],
+/// but no generators: its wires are set through copy constraints. It doesn't check any constraints itself.
+#[derive(Debug, Clone)]
+pub struct DynamicLookupGate {
+    /// Number of lookups per gate.
+    pub num_slots: usize,
+}
+
+impl DynamicLookupGate {
+    pub const fn new_from_config(config: &CircuitConfig) -> Self {
+        Self {
+            num_slots: LookupGate::num_slots(config),
+        }
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for DynamicLookupGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.num_slots)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let num_slots = src.read_usize()?;
+        Ok(Self { num_slots })
+    }
+
+    fn eval_unfiltered(&self, _vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        // No main trace constraints for lookups.
+        vec![]
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        // No main trace constraints for lookups.
+        vec![]
+    }
+
+    fn generators(&self, _row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        vec![]
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_slots * 2
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        0
+    }
+
+    fn num_constraints(&self) -> usize {
+        0
+    }
+
+    fn num_ops(&self) -> usize {
+        self.num_slots
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for DynamicLookupGate {
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        _vars: EvaluationVarsBasePacked<P>,
+        mut _yield_constr: StridedConstraintConsumer<P>,
+    ) {
+    }
+}
+
+/// A gate which stores the (input, output) rows of a dynamic lookup table, and their multiplicities.
+/// It has the same wire layout as [`LookupTableGate`]. The rows are connected to the table targets,
+/// and the multiplicities are set by the prover. It doesn't check any constraints itself.
+#[derive(Debug, Clone)]
+pub struct DynamicLookupTableGate {
+    /// Number of lookup entries per gate.
+    pub num_slots: usize,
+}
+
+impl DynamicLookupTableGate {
+    pub const fn new_from_config(config: &CircuitConfig) -> Self {
+        Self {
+            num_slots: LookupTableGate::num_slots(config),
+        }
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for DynamicLookupTableGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.num_slots)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let num_slots = src.read_usize()?;
+        Ok(Self { num_slots })
+    }
+
+    fn eval_unfiltered(&self, _vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        // No main trace constraints for the lookup table.
+        vec![]
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        // No main trace constraints for the lookup table.
+        vec![]
+    }
+
+    fn generators(&self, _row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        vec![]
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_slots * 3
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        0
+    }
+
+    fn num_constraints(&self) -> usize {
+        0
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D>
+    for DynamicLookupTableGate
+{
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        _vars: EvaluationVarsBasePacked<P>,
+        mut _yield_constr: StridedConstraintConsumer<P>,
+    ) {
+    }
+}
+
+/// Sets the output of a lookup into a dynamic lookup table, once the input and all the rows of the
+/// table are known. If several rows share the input, the first one is used.
+#[derive(Clone, Debug, Default)]
+pub struct DynamicLookupGenerator {
+    pub(crate) looking_in: Target,
+    pub(crate) looking_out: Target,
+    pub(crate) table: Lookup,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for DynamicLookupGenerator
+{
+    fn id(&self) -> String {
+        "DynamicLookupGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        let mut deps = vec![self.looking_in];
+        deps.extend(self.table.iter().flat_map(|&(inp, out)| [inp, out]));
+        deps
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let input_val = witness.get_target(self.looking_in);
+        // An input which is not in the table gets a zero output, so that the rest of the witness
+        // can still be generated. The lookup is rejected when setting the lookup wires.
+        let output_val = self
+            .table
+            .iter()
+            .find(|&&(inp, _)| witness.get_target(inp) == input_val)
+            .map_or(F::ZERO, |&(_, out)| witness.get_target(out));
+
+        out_buffer.set_target(self.looking_out, output_val);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target(self.looking_in)?;
+        dst.write_target(self.looking_out)?;
+        dst.write_target_lut(&self.table)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let looking_in = src.read_target()?;
+        let looking_out = src.read_target()?;
+        let table = src.read_target_lut()?;
+
+        Ok(Self {
+            looking_in,
+            looking_out,
+            table,
+        })
+    }
+}
@@ -193,25 +193,19 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Loo
         let get_wire = |wire: usize| -> F { witness.get_target(Target::wire(self.row, wire)) };
 
         let input_val = get_wire(LookupGate::wire_ith_looking_inp(self.slot_nb));
-        let (input, output) = self.lut[input_val.to_canonical_u64() as usize];
-        if input_val == F::from_canonical_u16(input) {
-            let output_val = F::from_canonical_u16(output);
-
-            let out_wire = Target::wire(self.row, LookupGate::wire_ith_looking_out(self.slot_nb));
-            out_buffer.set_target(out_wire, output_val);
-        } else {
-            for (input, output) in self.lut.iter() {
-                if input_val == F::from_canonical_u16(*input) {
-                    let output_val = F::from_canonical_u16(*output);
-
-                    let out_wire =
-                        Target::wire(self.row, LookupGate::wire_ith_looking_out(self.slot_nb));
-                    out_buffer.set_target(out_wire, output_val);
-                    return;
-                }
-            }
-            panic!("Incorrect input value provided");
-        };
+        // Tables are usually indexed by their inputs, otherwise we search for the input.
+        let is_input = |&&(input, _): &&(u16, u16)| input_val == F::from_canonical_u16(input);
+        let row = usize::try_from(input_val.to_canonical_u64())
+            .ok()
+            .and_then(|index| self.lut.get(index))
+            .filter(is_input)
+            .or_else(|| self.lut.iter().find(is_input));
+
+        // An input which is not in the table gets a zero output, so that the rest of the witness
+        // can still be generated. The lookup is rejected when setting the lookup wires.
+        let output_val = row.map_or(F::ZERO, |&(_, output)| F::from_canonical_u16(output));
+        let out_wire = Target::wire(self.row, LookupGate::wire_ith_looking_out(self.slot_nb));
+        out_buffer.set_target(out_wire, output_val);
     }
 
     fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
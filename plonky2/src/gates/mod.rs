@@ -26,8 +26,10 @@
 pub mod arithmetic_base;
 pub mod arithmetic_extension;
 pub mod base_sum;
+pub mod comparison;
 pub mod constant;
 pub mod coset_interpolation;
+pub mod dynamic_lookup;
 pub mod exponentiation;
 pub mod gate;
 pub mod lookup;
@@ -36,12 +38,16 @@ pub mod multiplication_extension;
 pub mod noop;
 pub mod packed_util;
 pub mod poseidon;
+pub mod poseidon2;
 pub mod poseidon_mds;
 pub mod public_input;
 pub mod random_access;
 pub mod reducing;
 pub mod reducing_extension;
 pub(crate) mod selectors;
+pub mod tuple_lookup;
+pub mod u32_arithmetic;
+pub mod u32_subtraction;
 pub mod util;
 
 // Can't use #[cfg(test)] here because it needs to be visible to other crates.
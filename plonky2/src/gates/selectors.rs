@@ -31,6 +31,9 @@ impl SelectorsInfo {
 /// - `InitSre` is for the initial constraint of Sum and Re.
 /// - `LastLdc` is for the final LDC (and Sum) constraint.
 /// - `StartEnd` indicates where lookup end selectors begin.
+///
+/// If the circuit has tuple LUTs, the end selectors are followed by separate `TransSre` and `TransLdc` selectors for
+/// the rows of tuple LUTs, in that order.
 pub enum LookupSelectors {
     TransSre = 0,
     TransLdc,
@@ -46,10 +49,14 @@ pub enum LookupSelectors {
 /// - {first_lut_row + 1} where we check the initial values of sum and RE (which are 0),
 /// - {last_lu_row} where we check that the last value of LDC is 0.
 /// Conceptually they're part of the selector ends lookups, but since we can have one polynomial for *all* LUTs it's here.
+///
+/// The rows of tuple LUTs, in `tuple_rows`, get the two last selectors only: their transitions use the selectors
+/// returned by `selectors_tuple_lookup`.
 pub(crate) fn selectors_lookup<F: RichField + Extendable<D>, const D: usize>(
     _gates: &[GateRef<F, D>],
     instances: &[GateInstance<F, D>],
     lookup_rows: &[LookupWire],
+    tuple_rows: Range<usize>,
 ) -> Vec<PolynomialValues<F>> {
     let n = instances.len();
     let mut lookup_selectors = Vec::with_capacity(LookupSelectors::StartEnd as usize);
@@ -57,26 +64,56 @@ pub(crate) fn selectors_lookup<F: RichField + Extendable<D>, const D: usize>(
         lookup_selectors.push(PolynomialValues::<F>::new(vec![F::ZERO; n]));
     }
 
-    for &LookupWire {
+    for (i, lookup_wire) in lookup_rows.iter().enumerate() {
+        if !tuple_rows.contains(&i) {
+            set_transition_selectors(&mut lookup_selectors, lookup_wire);
+        }
+        lookup_selectors[LookupSelectors::InitSre as usize].values
+            [lookup_wire.first_lut_gate + 1] = F::ONE;
+        lookup_selectors[LookupSelectors::LastLdc as usize].values[lookup_wire.last_lu_gate] =
+            F::ONE;
+    }
+    lookup_selectors
+}
+
+/// Returns the `TransSre` and `TransLdc` selectors of the rows of tuple LUTs, which come after all the other lookup
+/// selectors. There are none if the circuit has no tuple LUTs.
+pub(crate) fn selectors_tuple_lookup<F: RichField + Extendable<D>, const D: usize>(
+    tuple_lookup_rows: &[LookupWire],
+    instances: &[GateInstance<F, D>],
+) -> Vec<PolynomialValues<F>> {
+    if tuple_lookup_rows.is_empty() {
+        return vec![];
+    }
+
+    let n = instances.len();
+    let mut lookup_selectors = vec![PolynomialValues::<F>::new(vec![F::ZERO; n]); 2];
+    for lookup_wire in tuple_lookup_rows {
+        set_transition_selectors(&mut lookup_selectors, lookup_wire);
+    }
+    lookup_selectors
+}
+
+fn set_transition_selectors<F: RichField>(
+    lookup_selectors: &mut [PolynomialValues<F>],
+    &LookupWire {
         last_lu_gate: last_lu_row,
         last_lut_gate: last_lut_row,
         first_lut_gate: first_lut_row,
-    } in lookup_rows
-    {
-        for row in last_lut_row..first_lut_row + 1 {
-            lookup_selectors[LookupSelectors::TransSre as usize].values[row] = F::ONE;
-        }
-        for row in last_lu_row..last_lut_row {
-            lookup_selectors[LookupSelectors::TransLdc as usize].values[row] = F::ONE;
-        }
-        lookup_selectors[LookupSelectors::InitSre as usize].values[first_lut_row + 1] = F::ONE;
-        lookup_selectors[LookupSelectors::LastLdc as usize].values[last_lu_row] = F::ONE;
+    }: &LookupWire,
+) {
+    for row in last_lut_row..first_lut_row + 1 {
+        lookup_selectors[LookupSelectors::TransSre as usize].values[row] = F::ONE;
+    }
+    for row in last_lu_row..last_lut_row {
+        lookup_selectors[LookupSelectors::TransLdc as usize].values[row] = F::ONE;
     }
-    lookup_selectors
 }
 
 /// Returns selectors for checking the validity of the LUTs.
-/// Each selector equals one on its respective LUT's `last_lut_row`, and 0 elsewhere.
+/// Each selector equals one on its respective LUT's `last_lut_row`, and 0 elsewhere. Tuple LUTs have their
+/// selectors after the ones of the other fixed LUTs.
+/// Dynamic LUTs have no such selector, since their rows are constrained by copy constraints.
 pub(crate) fn selector_ends_lookups<F: RichField + Extendable<D>, const D: usize>(
     lookup_rows: &[LookupWire],
     instances: &[GateInstance<F, D>],
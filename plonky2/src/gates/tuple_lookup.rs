@@ -0,0 +1,439 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::field::types::Field;
+use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{CircuitConfig, CommonCircuitData};
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// A lookup table whose rows are tuples of `num_inputs` inputs followed by `num_outputs` outputs,
+/// all of them arbitrary field elements.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct TupleLookupTable<F: Field> {
+    num_inputs: usize,
+    num_outputs: usize,
+    /// The rows of the table, one after the other.
+    values: Arc<Vec<F>>,
+}
+
+impl<F: Field> TupleLookupTable<F> {
+    /// Creates a table from its rows, each made of `num_inputs` inputs followed by `num_outputs` outputs.
+    pub fn new(num_inputs: usize, num_outputs: usize, rows: &[Vec<F>]) -> Self {
+        assert!(
+            num_inputs > 0,
+            "A tuple lookup table needs at least one input"
+        );
+        assert!(!rows.is_empty(), "A tuple lookup table can't be empty");
+        let width = num_inputs + num_outputs;
+        let mut values = Vec::with_capacity(rows.len() * width);
+        for row in rows {
+            assert_eq!(
+                row.len(),
+                width,
+                "Row {row:?} doesn't have {width} elements"
+            );
+            values.extend_from_slice(row);
+        }
+
+        Self {
+            num_inputs,
+            num_outputs,
+            values: Arc::new(values),
+        }
+    }
+
+    /// Creates a table mapping each input tuple in `inputs` to the `num_outputs` outputs of `f`.
+    pub fn from_fn<G: Fn(&[F]) -> Vec<F>>(
+        num_inputs: usize,
+        num_outputs: usize,
+        inputs: &[Vec<F>],
+        f: G,
+    ) -> Self {
+        let rows = inputs
+            .iter()
+            .map(|input| [input.clone(), f(input)].concat())
+            .collect::<Vec<_>>();
+        Self::new(num_inputs, num_outputs, &rows)
+    }
+
+    pub const fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    pub const fn num_outputs(&self) -> usize {
+        self.num_outputs
+    }
+
+    /// Number of elements in each row.
+    pub const fn width(&self) -> usize {
+        self.num_inputs + self.num_outputs
+    }
+
+    /// Number of rows.
+    pub fn len(&self) -> usize {
+        self.values.len() / self.width()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the `i`-th row, inputs first.
+    pub fn row(&self, i: usize) -> &[F] {
+        &self.values[i * self.width()..(i + 1) * self.width()]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[F]> {
+        self.values.chunks_exact(self.width())
+    }
+
+    /// Returns the index of the first row whose inputs are `inputs`, if any.
+    pub fn find(&self, inputs: &[F]) -> Option<usize> {
+        self.rows()
+            .position(|row| &row[..self.num_inputs] == inputs)
+    }
+}
+
+/// Returns the numbers of inputs and outputs of the slots of tuple lookup gates, which are the largest
+/// numbers of inputs and outputs over `tables`.
+pub fn tuple_lookup_shape<F: Field>(tables: &[TupleLookupTable<F>]) -> (usize, usize) {
+    tables.iter().fold((0, 0), |(k, m), table| {
+        (k.max(table.num_inputs()), m.max(table.num_outputs()))
+    })
+}
+
+/// Lookups into a tuple lookup table, as (inputs, outputs) pairs of targets.
+pub type TupleLookup = Vec<(Vec<Target>, Vec<Target>)>;
+
+/// A gate which stores tuple lookups into a [`TupleLookupTable`]. Each slot holds `num_inputs` inputs followed by
+/// `num_outputs` outputs; these are the largest numbers of inputs and outputs over the circuit's tuple tables, and
+/// smaller tuples are padded with zeros. With one input and one output, it has the same wire layout as `LookupGate`.
+/// The outputs are set by `TupleLookupGenerator`s and the gate doesn't check any constraints itself.
+#[derive(Debug, Clone)]
+pub struct TupleLookupGate {
+    /// Number of lookups per gate.
+    pub num_slots: usize,
+    pub num_inputs: usize,
+    pub num_outputs: usize,
+}
+
+impl TupleLookupGate {
+    pub const fn new_from_config(
+        config: &CircuitConfig,
+        num_inputs: usize,
+        num_outputs: usize,
+    ) -> Self {
+        Self {
+            num_slots: config.num_routed_wires / (num_inputs + num_outputs),
+            num_inputs,
+            num_outputs,
+        }
+    }
+
+    const fn wires_per_lookup(&self) -> usize {
+        self.num_inputs + self.num_outputs
+    }
+
+    /// Wires for the looking inputs of the `i`-th slot.
+    pub const fn wires_ith_looking_inp(&self, i: usize) -> Range<usize> {
+        let start = i * self.wires_per_lookup();
+        start..start + self.num_inputs
+    }
+
+    /// Wires for the looking outputs of the `i`-th slot.
+    pub const fn wires_ith_looking_out(&self, i: usize) -> Range<usize> {
+        let start = i * self.wires_per_lookup() + self.num_inputs;
+        start..start + self.num_outputs
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for TupleLookupGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.num_slots)?;
+        dst.write_usize(self.num_inputs)?;
+        dst.write_usize(self.num_outputs)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let num_slots = src.read_usize()?;
+        let num_inputs = src.read_usize()?;
+        let num_outputs = src.read_usize()?;
+        Ok(Self {
+            num_slots,
+            num_inputs,
+            num_outputs,
+        })
+    }
+
+    fn eval_unfiltered(&self, _vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        // No main trace constraints for lookups.
+        vec![]
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        // No main trace constraints for lookups.
+        vec![]
+    }
+
+    fn generators(&self, _row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        vec![]
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_slots * self.wires_per_lookup()
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        0
+    }
+
+    fn num_constraints(&self) -> usize {
+        0
+    }
+
+    fn num_ops(&self) -> usize {
+        self.num_slots
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for TupleLookupGate {
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        _vars: EvaluationVarsBasePacked<P>,
+        mut _yield_constr: StridedConstraintConsumer<P>,
+    ) {
+    }
+}
+
+/// A gate which stores the rows of a [`TupleLookupTable`], padded like in [`TupleLookupGate`], and their multiplicities.
+/// With one input and one output, it has the same wire layout as `LookupTableGate`. All its wires are set by the
+/// prover, and it doesn't check any constraints itself.
+#[derive(Debug, Clone)]
+pub struct TupleLookupTableGate {
+    /// Number of lookup entries per gate.
+    pub num_slots: usize,
+    pub num_inputs: usize,
+    pub num_outputs: usize,
+}
+
+impl TupleLookupTableGate {
+    pub const fn new_from_config(
+        config: &CircuitConfig,
+        num_inputs: usize,
+        num_outputs: usize,
+    ) -> Self {
+        Self {
+            num_slots: config.num_routed_wires / (num_inputs + num_outputs + 1),
+            num_inputs,
+            num_outputs,
+        }
+    }
+
+    const fn wires_per_entry(&self) -> usize {
+        self.num_inputs + self.num_outputs + 1
+    }
+
+    /// Wires for the looked inputs of the `i`-th slot.
+    pub const fn wires_ith_looked_inp(&self, i: usize) -> Range<usize> {
+        let start = i * self.wires_per_entry();
+        start..start + self.num_inputs
+    }
+
+    /// Wires for the looked outputs of the `i`-th slot.
+    pub const fn wires_ith_looked_out(&self, i: usize) -> Range<usize> {
+        let start = i * self.wires_per_entry() + self.num_inputs;
+        start..start + self.num_outputs
+    }
+
+    /// Wire for the multiplicity of the `i`-th slot. Set after the trace has been generated.
+    pub const fn wire_ith_multiplicity(&self, i: usize) -> usize {
+        i * self.wires_per_entry() + self.num_inputs + self.num_outputs
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for TupleLookupTableGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.num_slots)?;
+        dst.write_usize(self.num_inputs)?;
+        dst.write_usize(self.num_outputs)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let num_slots = src.read_usize()?;
+        let num_inputs = src.read_usize()?;
+        let num_outputs = src.read_usize()?;
+        Ok(Self {
+            num_slots,
+            num_inputs,
+            num_outputs,
+        })
+    }
+
+    fn eval_unfiltered(&self, _vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        // No main trace constraints for the lookup table.
+        vec![]
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        // No main trace constraints for the lookup table.
+        vec![]
+    }
+
+    fn generators(&self, _row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        vec![]
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_slots * self.wires_per_entry()
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        0
+    }
+
+    fn num_constraints(&self) -> usize {
+        0
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D>
+    for TupleLookupTableGate
+{
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        _vars: EvaluationVarsBasePacked<P>,
+        mut _yield_constr: StridedConstraintConsumer<P>,
+    ) {
+    }
+}
+
+/// Sets the outputs of a lookup into a [`TupleLookupTable`], from the first row matching its inputs.
+#[derive(Clone, Debug, Default)]
+pub struct TupleLookupGenerator<F: Field> {
+    pub(crate) looking_inputs: Vec<Target>,
+    pub(crate) looking_outputs: Vec<Target>,
+    pub(crate) table: TupleLookupTable<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for TupleLookupGenerator<F>
+{
+    fn id(&self) -> String {
+        "TupleLookupGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.looking_inputs.clone()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let inputs = witness.get_targets(&self.looking_inputs);
+        // Inputs which are not in the table get zero outputs, so that the rest of the witness can
+        // still be generated. The lookup is rejected when setting the lookup wires.
+        let outputs = match self.table.find(&inputs) {
+            Some(row) => self.table.row(row)[self.table.num_inputs()..].to_vec(),
+            None => vec![F::ZERO; self.table.num_outputs()],
+        };
+
+        for (&target, value) in self.looking_outputs.iter().zip(outputs) {
+            out_buffer.set_target(target, value);
+        }
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.looking_inputs)?;
+        dst.write_target_vec(&self.looking_outputs)?;
+        for (i, table) in common_data.tuple_luts.iter().enumerate() {
+            if table == &self.table {
+                return dst.write_usize(i);
+            }
+        }
+
+        panic!("The associated tuple lookup table couldn't be found.")
+    }
+
+    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let looking_inputs = src.read_target_vec()?;
+        let looking_outputs = src.read_target_vec()?;
+        let table_index = src.read_usize()?;
+
+        Ok(Self {
+            looking_inputs,
+            looking_outputs,
+            table: common_data.tuple_luts[table_index].clone(),
+        })
+    }
+}
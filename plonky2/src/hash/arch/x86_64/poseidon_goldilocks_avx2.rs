@@ -0,0 +1,303 @@
+use core::arch::x86_64::*;
+use core::ops::{Add, Neg, Sub};
+
+use static_assertions::const_assert;
+use unroll::unroll_for_loops;
+
+use crate::field::goldilocks_field::GoldilocksField;
+use crate::field::packed::PackedField;
+use crate::hash::poseidon::{Poseidon, SPONGE_WIDTH};
+
+// The MDS layer below applies the FFT-based MDS multiplication of `poseidon_goldilocks.rs` to the
+// 32-bit halves of the elements of four states at once, one state in each 64-bit lane, and
+// reduces the results only once at the end.
+
+const MDS_MATRIX_CIRC: [u64; SPONGE_WIDTH] = <GoldilocksField as Poseidon>::MDS_MATRIX_CIRC;
+const MDS_MATRIX_DIAG: [u64; SPONGE_WIDTH] = <GoldilocksField as Poseidon>::MDS_MATRIX_DIAG;
+
+const MDS_FREQ_BLOCK_ONE: [i64; 3] = [16, 32, 16];
+const MDS_FREQ_BLOCK_TWO: [(i64, i64); 3] = [(2, -1), (-4, 1), (16, 1)];
+const MDS_FREQ_BLOCK_THREE: [i64; 3] = [-1, -8, 2];
+
+/// The frequency blocks above are specific to the MDS matrix below. We want this file to fail to
+/// compile if it has been changed.
+#[allow(dead_code)]
+const fn check_mds_matrix() -> bool {
+    let wanted_circ = [17, 15, 41, 16, 2, 28, 13, 13, 39, 18, 34, 20];
+    let wanted_diag = [8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    let mut i = 0;
+    while i < SPONGE_WIDTH {
+        if MDS_MATRIX_CIRC[i] != wanted_circ[i] || MDS_MATRIX_DIAG[i] != wanted_diag[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+const_assert!(check_mds_matrix());
+
+/// Four 64-bit integers, with wrapping arithmetic.
+#[derive(Copy, Clone)]
+struct Lanes(__m256i);
+
+impl Add for Lanes {
+    type Output = Self;
+    #[inline(always)]
+    fn add(self, rhs: Self) -> Self {
+        Self(unsafe { _mm256_add_epi64(self.0, rhs.0) })
+    }
+}
+
+impl Sub for Lanes {
+    type Output = Self;
+    #[inline(always)]
+    fn sub(self, rhs: Self) -> Self {
+        Self(unsafe { _mm256_sub_epi64(self.0, rhs.0) })
+    }
+}
+
+impl Neg for Lanes {
+    type Output = Self;
+    #[inline(always)]
+    fn neg(self) -> Self {
+        Self(unsafe { _mm256_sub_epi64(_mm256_setzero_si256(), self.0) })
+    }
+}
+
+impl Lanes {
+    /// Multiplies by a small constant. Powers of two, which are most of the constants of the
+    /// frequency blocks, only take a shift.
+    #[inline(always)]
+    fn mul_small(self, c: i64) -> Self {
+        let abs = c.unsigned_abs();
+        let product = unsafe {
+            if abs.is_power_of_two() {
+                _mm256_sllv_epi64(self.0, _mm256_set1_epi64x(abs.trailing_zeros() as i64))
+            } else {
+                // Only the low 64 bits of the product are kept, so the high half of `self` can be
+                // taken as unsigned.
+                let abs = _mm256_set1_epi64x(abs as i64);
+                let lo = _mm256_mul_epu32(self.0, abs);
+                let hi = _mm256_mul_epu32(_mm256_srli_epi64::<32>(self.0), abs);
+                _mm256_add_epi64(lo, _mm256_slli_epi64::<32>(hi))
+            }
+        };
+        if c < 0 {
+            -Self(product)
+        } else {
+            Self(product)
+        }
+    }
+}
+
+/// Same as `poseidon12_mds::mds_multiply_freq` in `poseidon_goldilocks.rs`, in each lane.
+#[inline(always)]
+fn mds_multiply_freq(state: [Lanes; 12]) -> [Lanes; 12] {
+    let [s0, s1, s2, s3, s4, s5, s6, s7, s8, s9, s10, s11] = state;
+
+    let (u0, u1, u2) = fft4_real([s0, s3, s6, s9]);
+    let (u4, u5, u6) = fft4_real([s1, s4, s7, s10]);
+    let (u8, u9, u10) = fft4_real([s2, s5, s8, s11]);
+
+    let [v0, v4, v8] = block1([u0, u4, u8], MDS_FREQ_BLOCK_ONE);
+    let [v1, v5, v9] = block2([u1, u5, u9], MDS_FREQ_BLOCK_TWO);
+    let [v2, v6, v10] = block3([u2, u6, u10], MDS_FREQ_BLOCK_THREE);
+
+    let [s0, s3, s6, s9] = ifft4_real_unreduced((v0, v1, v2));
+    let [s1, s4, s7, s10] = ifft4_real_unreduced((v4, v5, v6));
+    let [s2, s5, s8, s11] = ifft4_real_unreduced((v8, v9, v10));
+
+    [s0, s1, s2, s3, s4, s5, s6, s7, s8, s9, s10, s11]
+}
+
+#[inline(always)]
+fn block1(x: [Lanes; 3], y: [i64; 3]) -> [Lanes; 3] {
+    let [x0, x1, x2] = x;
+    let [y0, y1, y2] = y;
+    let z0 = x0.mul_small(y0) + x1.mul_small(y2) + x2.mul_small(y1);
+    let z1 = x0.mul_small(y1) + x1.mul_small(y0) + x2.mul_small(y2);
+    let z2 = x0.mul_small(y2) + x1.mul_small(y1) + x2.mul_small(y0);
+
+    [z0, z1, z2]
+}
+
+#[inline(always)]
+fn block2(x: [(Lanes, Lanes); 3], y: [(i64, i64); 3]) -> [(Lanes, Lanes); 3] {
+    let [(x0r, x0i), (x1r, x1i), (x2r, x2i)] = x;
+    let [(y0r, y0i), (y1r, y1i), (y2r, y2i)] = y;
+    let x0s = x0r + x0i;
+    let x1s = x1r + x1i;
+    let x2s = x2r + x2i;
+    let y0s = y0r + y0i;
+    let y1s = y1r + y1i;
+    let y2s = y2r + y2i;
+
+    // Compute x0​y0​ − ix1​y2​ − ix2​y1​ using Karatsuba for complex numbers multiplication
+    let m0 = (x0r.mul_small(y0r), x0i.mul_small(y0i));
+    let m1 = (x1r.mul_small(y2r), x1i.mul_small(y2i));
+    let m2 = (x2r.mul_small(y1r), x2i.mul_small(y1i));
+    let z0r =
+        (m0.0 - m0.1) + (x1s.mul_small(y2s) - m1.0 - m1.1) + (x2s.mul_small(y1s) - m2.0 - m2.1);
+    let z0i = (x0s.mul_small(y0s) - m0.0 - m0.1) + (-m1.0 + m1.1) + (-m2.0 + m2.1);
+    let z0 = (z0r, z0i);
+
+    // Compute x0​y1​ + x1​y0​ − ix2​y2 using Karatsuba for complex numbers multiplication
+    let m0 = (x0r.mul_small(y1r), x0i.mul_small(y1i));
+    let m1 = (x1r.mul_small(y0r), x1i.mul_small(y0i));
+    let m2 = (x2r.mul_small(y2r), x2i.mul_small(y2i));
+    let z1r = (m0.0 - m0.1) + (m1.0 - m1.1) + (x2s.mul_small(y2s) - m2.0 - m2.1);
+    let z1i =
+        (x0s.mul_small(y1s) - m0.0 - m0.1) + (x1s.mul_small(y0s) - m1.0 - m1.1) + (-m2.0 + m2.1);
+    let z1 = (z1r, z1i);
+
+    // Compute x0​y2​ + x1​y1​ + x2​y0​ using Karatsuba for complex numbers multiplication
+    let m0 = (x0r.mul_small(y2r), x0i.mul_small(y2i));
+    let m1 = (x1r.mul_small(y1r), x1i.mul_small(y1i));
+    let m2 = (x2r.mul_small(y0r), x2i.mul_small(y0i));
+    let z2r = (m0.0 - m0.1) + (m1.0 - m1.1) + (m2.0 - m2.1);
+    let z2i = (x0s.mul_small(y2s) - m0.0 - m0.1)
+        + (x1s.mul_small(y1s) - m1.0 - m1.1)
+        + (x2s.mul_small(y0s) - m2.0 - m2.1);
+    let z2 = (z2r, z2i);
+
+    [z0, z1, z2]
+}
+
+#[inline(always)]
+fn block3(x: [Lanes; 3], y: [i64; 3]) -> [Lanes; 3] {
+    let [x0, x1, x2] = x;
+    let [y0, y1, y2] = y;
+    let z0 = x0.mul_small(y0) - x1.mul_small(y2) - x2.mul_small(y1);
+    let z1 = x0.mul_small(y1) + x1.mul_small(y0) - x2.mul_small(y2);
+    let z2 = x0.mul_small(y2) + x1.mul_small(y1) + x2.mul_small(y0);
+
+    [z0, z1, z2]
+}
+
+/// Real 4-FFT.
+#[inline(always)]
+fn fft4_real(x: [Lanes; 4]) -> (Lanes, (Lanes, Lanes), Lanes) {
+    let (z0, z2) = (x[0] + x[2], x[0] - x[2]);
+    let (z1, z3) = (x[1] + x[3], x[1] - x[3]);
+    let y0 = z0 + z1;
+    let y1 = (z2, -z3);
+    let y2 = z0 - z1;
+    (y0, y1, y2)
+}
+
+/// Real 4-iFFT.
+/// Division by four to complete the inverse FFT is not performed here.
+#[inline(always)]
+fn ifft4_real_unreduced(y: (Lanes, (Lanes, Lanes), Lanes)) -> [Lanes; 4] {
+    let z0 = y.0 + y.2;
+    let z1 = y.0 - y.2;
+    let z2 = y.1 .0;
+    let z3 = -y.1 .1;
+
+    let (x0, x2) = (z0 + z2, z0 - z2);
+    let (x1, x3) = (z1 + z3, z1 - z3);
+
+    [x0, x1, x2, x3]
+}
+
+/// Reduces `lo + hi * 2^32`, where `lo, hi < 2^63`, to a possibly non-canonical element.
+#[inline(always)]
+unsafe fn reduce_halves(lo: __m256i, hi: __m256i) -> __m256i {
+    let sign_bit = _mm256_set1_epi64x(i64::MIN);
+    let epsilon = _mm256_set1_epi64x(0xffffffff);
+
+    // With `hi = hi_hi * 2^32 + hi_lo` and `2^64 = EPSILON`, the value is
+    // `lo + hi_hi * EPSILON + hi_lo * 2^32`. The first two terms cannot overflow.
+    let hi_hi = _mm256_srli_epi64::<32>(hi);
+    let hi_lo_shifted = _mm256_slli_epi64::<32>(hi);
+    let partial = _mm256_add_epi64(lo, _mm256_mul_epu32(hi_hi, epsilon));
+    let res_wrapped = _mm256_add_epi64(partial, hi_lo_shifted);
+
+    // The addition overflowed iff `res_wrapped < partial`, in which case we add back
+    // `2^64 = EPSILON`. This cannot overflow again, as then `res_wrapped < hi_lo * 2^32`.
+    let overflowed = _mm256_cmpgt_epi64(
+        _mm256_xor_si256(partial, sign_bit),
+        _mm256_xor_si256(res_wrapped, sign_bit),
+    );
+    _mm256_add_epi64(res_wrapped, _mm256_and_si256(overflowed, epsilon))
+}
+
+/// Same as `Poseidon::mds_layer` for a packing of `GoldilocksField` with four lanes, such as
+/// its AVX2 packing.
+#[inline]
+#[unroll_for_loops]
+pub(crate) fn mds_layer_packed<P: PackedField<Scalar = GoldilocksField>>(
+    state: &[P; SPONGE_WIDTH],
+) -> [P; SPONGE_WIDTH] {
+    assert_eq!(P::WIDTH, 4);
+
+    let mut result = [P::ZEROS; SPONGE_WIDTH];
+    unsafe {
+        // As in `Poseidon::mds_layer`, the low and high halves of the elements are multiplied
+        // separately, so that the sums of each row fit in 64 bits: they are less than 2^32 times
+        // the sum of the entries of the row.
+        let mask_lo = _mm256_set1_epi64x(0xffffffff);
+        let mut state_l = [Lanes(_mm256_setzero_si256()); SPONGE_WIDTH];
+        let mut state_h = [Lanes(_mm256_setzero_si256()); SPONGE_WIDTH];
+        for i in 0..12 {
+            let s = _mm256_loadu_si256(state[i].as_slice().as_ptr().cast());
+            state_l[i] = Lanes(_mm256_and_si256(s, mask_lo));
+            state_h[i] = Lanes(_mm256_srli_epi64::<32>(s));
+        }
+
+        let sums_l = mds_multiply_freq(state_l);
+        let sums_h = mds_multiply_freq(state_h);
+
+        for r in 0..12 {
+            let diag = _mm256_set1_epi64x(MDS_MATRIX_DIAG[r] as i64);
+            let sum_l = _mm256_add_epi64(sums_l[r].0, _mm256_mul_epu32(state_l[r].0, diag));
+            let sum_h = _mm256_add_epi64(sums_h[r].0, _mm256_mul_epu32(state_h[r].0, diag));
+            _mm256_storeu_si256(
+                result[r].as_slice_mut().as_mut_ptr().cast(),
+                reduce_halves(sum_l, sum_h),
+            );
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::field::goldilocks_field::GoldilocksField as F;
+    use crate::field::packable::Packable;
+    use crate::field::packed::PackedField;
+    use crate::field::types::{Field, Sample};
+    use crate::hash::arch::x86_64::poseidon_goldilocks_avx2::mds_layer_packed;
+    use crate::hash::poseidon::{Poseidon, SPONGE_WIDTH};
+
+    #[test]
+    fn test_mds_layer_packed() {
+        type P = <F as Packable>::Packing;
+        if P::WIDTH != 4 {
+            return;
+        }
+
+        // Random states, and states of maximal non-canonical elements, which maximize the sums of
+        // the products in the MDS layer.
+        let states = [
+            F::rand_array::<SPONGE_WIDTH>(),
+            F::rand_array::<SPONGE_WIDTH>(),
+            [F::NEG_ONE; SPONGE_WIDTH],
+            [F(u64::MAX); SPONGE_WIDTH],
+        ];
+        let mut packed_state = [P::ZEROS; SPONGE_WIDTH];
+        for (i, packed) in packed_state.iter_mut().enumerate() {
+            for (lane, state) in packed.as_slice_mut().iter_mut().zip(&states) {
+                *lane = state[i];
+            }
+        }
+
+        let packed_result = mds_layer_packed(&packed_state);
+        for (lane, state) in states.iter().enumerate() {
+            let result = F::mds_layer(state);
+            for i in 0..SPONGE_WIDTH {
+                assert_eq!(packed_result[i].as_slice()[lane], result[i]);
+            }
+        }
+    }
+}
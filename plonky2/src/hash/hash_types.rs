@@ -2,8 +2,10 @@
 use alloc::vec::Vec;
 
 use anyhow::ensure;
+use num::BigUint;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::field::bn254_scalar::Bn254Scalar;
 use crate::field::goldilocks_field::GoldilocksField;
 use crate::field::types::{Field, PrimeField64, Sample};
 use crate::hash::poseidon::Poseidon;
@@ -209,3 +211,48 @@ impl<'de, const N: usize> Deserialize<'de> for BytesHash<N> {
         todo!()
     }
 }
+
+/// Hash consisting of a single BN254 scalar field element.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Bn254HashOut(pub Bn254Scalar);
+
+impl Sample for Bn254HashOut {
+    #[inline]
+    fn sample<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + ?Sized,
+    {
+        Self(Bn254Scalar::sample(rng))
+    }
+}
+
+impl<F: RichField> GenericHashOut<F> for Bn254HashOut {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0
+            .to_canonical_limbs()
+            .iter()
+            .flat_map(|limb| limb.to_le_bytes())
+            .collect()
+    }
+
+    /// Reduces `bytes` modulo the field order, so that `hash_or_noop` can pack any 32 bytes.
+    /// [`Read::read_hash`](crate::util::serialization::Read::read_hash) rejects non-canonical
+    /// encodings.
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self(Bn254Scalar::from_noncanonical_biguint(
+            BigUint::from_bytes_le(bytes),
+        ))
+    }
+
+    fn to_vec(&self) -> Vec<F> {
+        <Self as GenericHashOut<F>>::to_bytes(self)
+            // Chunks of 7 bytes since 8 bytes would allow collisions.
+            .chunks(7)
+            .map(|bytes| {
+                let mut arr = [0; 8];
+                arr[..bytes.len()].copy_from_slice(bytes);
+                F::from_canonical_u64(u64::from_le_bytes(arr))
+            })
+            .collect()
+    }
+}
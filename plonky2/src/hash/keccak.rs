@@ -1,70 +1,55 @@
 #[cfg(not(feature = "std"))]
 use alloc::{vec, vec::Vec};
+use core::fmt::Debug;
 use core::mem::size_of;
 
 use itertools::Itertools;
 use keccak_hash::keccak;
 
-use crate::hash::hash_types::{BytesHash, RichField};
+use crate::field::extension::Extendable;
+use crate::field::goldilocks_field::GoldilocksField;
+use crate::gadgets::bytes::{pack_le, pack_le_with_borrow, ByteLookups};
+use crate::hash::hash_types::{BytesHash, HashOutTarget, RichField, NUM_HASH_OUT_ELTS};
 use crate::hash::hashing::PlonkyPermutation;
-use crate::plonk::config::Hasher;
+use crate::iop::target::{BoolTarget, ByteTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::{CircuitHasher, Hasher};
 use crate::util::serialization::Write;
 
 pub const SPONGE_RATE: usize = 8;
 pub const SPONGE_CAPACITY: usize = 4;
 pub const SPONGE_WIDTH: usize = SPONGE_RATE + SPONGE_CAPACITY;
 
+/// Number of Keccak-256 digests computed by the in-circuit permutation. Each digest holds four
+/// words, so that up to four non-canonical words of the hash onion can be skipped.
+const NUM_CIRCUIT_DIGESTS: usize = 4;
+
 /// Keccak-256 pseudo-permutation (not necessarily one-to-one) used in the challenger.
 /// A state `input: [F; 12]` is sent to the field representation of `H(input) || H(H(input)) || H(H(H(input)))`
 /// where `H` is the Keccak-256 hash.
 #[derive(Copy, Clone, Default, Debug, PartialEq)]
-pub struct KeccakPermutation<F: RichField> {
-    state: [F; SPONGE_WIDTH],
+pub struct KeccakPermutation<T> {
+    state: [T; SPONGE_WIDTH],
 }
 
-impl<F: RichField> Eq for KeccakPermutation<F> {}
+impl<T: Eq> Eq for KeccakPermutation<T> {}
 
-impl<F: RichField> AsRef<[F]> for KeccakPermutation<F> {
-    fn as_ref(&self) -> &[F] {
+impl<T> AsRef<[T]> for KeccakPermutation<T> {
+    fn as_ref(&self) -> &[T] {
         &self.state
     }
 }
 
-// TODO: Several implementations here are copied from
-// PoseidonPermutation; they should be refactored.
-impl<F: RichField> PlonkyPermutation<F> for KeccakPermutation<F> {
-    const RATE: usize = SPONGE_RATE;
-    const WIDTH: usize = SPONGE_WIDTH;
-
-    fn new<I: IntoIterator<Item = F>>(elts: I) -> Self {
-        let mut perm = Self {
-            state: [F::default(); SPONGE_WIDTH],
-        };
-        perm.set_from_iter(elts, 0);
-        perm
-    }
-
-    fn set_elt(&mut self, elt: F, idx: usize) {
-        self.state[idx] = elt;
-    }
-
-    fn set_from_slice(&mut self, elts: &[F], start_idx: usize) {
-        let begin = start_idx;
-        let end = start_idx + elts.len();
-        self.state[begin..end].copy_from_slice(elts);
-    }
-
-    fn set_from_iter<I: IntoIterator<Item = F>>(&mut self, elts: I, start_idx: usize) {
-        for (s, e) in self.state[start_idx..].iter_mut().zip(elts) {
-            *s = e;
-        }
-    }
+trait Permuter: Sized {
+    fn permute(input: [Self; SPONGE_WIDTH]) -> [Self; SPONGE_WIDTH];
+}
 
-    fn permute(&mut self) {
+impl<F: RichField> Permuter for F {
+    fn permute(input: [Self; SPONGE_WIDTH]) -> [Self; SPONGE_WIDTH] {
         let mut state_bytes = vec![0u8; SPONGE_WIDTH * size_of::<u64>()];
         for i in 0..SPONGE_WIDTH {
             state_bytes[i * size_of::<u64>()..(i + 1) * size_of::<u64>()]
-                .copy_from_slice(&self.state[i].to_canonical_u64().to_le_bytes());
+                .copy_from_slice(&input[i].to_canonical_u64().to_le_bytes());
         }
 
         let hash_onion = core::iter::repeat_with(|| {
@@ -86,14 +71,57 @@ impl<F: RichField> PlonkyPermutation<F> for KeccakPermutation<F> {
             .filter(|&word| word < F::ORDER)
             .map(F::from_canonical_u64);
 
-        self.state = hash_onion_elems
+        hash_onion_elems
             .take(SPONGE_WIDTH)
             .collect_vec()
             .try_into()
-            .unwrap();
+            .unwrap()
+    }
+}
+
+impl Permuter for Target {
+    fn permute(_input: [Self; SPONGE_WIDTH]) -> [Self; SPONGE_WIDTH] {
+        panic!("Call `permute_circuit()` instead of `permute()`");
+    }
+}
+
+// TODO: Several implementations here are copied from
+// PoseidonPermutation; they should be refactored.
+impl<T: Copy + Debug + Default + Eq + Permuter + Send + Sync> PlonkyPermutation<T>
+    for KeccakPermutation<T>
+{
+    const RATE: usize = SPONGE_RATE;
+    const WIDTH: usize = SPONGE_WIDTH;
+
+    fn new<I: IntoIterator<Item = T>>(elts: I) -> Self {
+        let mut perm = Self {
+            state: [T::default(); SPONGE_WIDTH],
+        };
+        perm.set_from_iter(elts, 0);
+        perm
+    }
+
+    fn set_elt(&mut self, elt: T, idx: usize) {
+        self.state[idx] = elt;
+    }
+
+    fn set_from_slice(&mut self, elts: &[T], start_idx: usize) {
+        let begin = start_idx;
+        let end = start_idx + elts.len();
+        self.state[begin..end].copy_from_slice(elts);
+    }
+
+    fn set_from_iter<I: IntoIterator<Item = T>>(&mut self, elts: I, start_idx: usize) {
+        for (s, e) in self.state[start_idx..].iter_mut().zip(elts) {
+            *s = e;
+        }
+    }
+
+    fn permute(&mut self) {
+        self.state = T::permute(self.state);
     }
 
-    fn squeeze(&self) -> &[F] {
+    fn squeeze(&self) -> &[T] {
         &self.state[..Self::RATE]
     }
 }
@@ -124,3 +152,286 @@ impl<F: RichField, const N: usize> Hasher<F> for KeccakHash<N> {
         BytesHash(arr)
     }
 }
+
+/// Returns the field elements of the digest `bytes`, i.e. its chunks of 7 little-endian bytes, as
+/// in the `GenericHashOut` implementation of [`BytesHash`].
+fn digest_to_hash_out<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    bytes: &[ByteTarget],
+) -> HashOutTarget {
+    assert_eq!(
+        bytes.len().div_ceil(7),
+        NUM_HASH_OUT_ELTS,
+        "Digests must fit in a `HashOutTarget`"
+    );
+    HashOutTarget::from_vec(
+        bytes
+            .chunks(7)
+            .map(|chunk| pack_le(builder, chunk))
+            .collect(),
+    )
+}
+
+/// Returns the `N` bytes of a digest from its field elements, checking that they are chunks of 7
+/// bytes as in the `GenericHashOut` implementation of [`BytesHash`].
+fn hash_out_to_digest<F: RichField + Extendable<D>, const D: usize, const N: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    lookups: &mut ByteLookups,
+    hash: HashOutTarget,
+) -> Vec<ByteTarget> {
+    assert_eq!(
+        N.div_ceil(7),
+        NUM_HASH_OUT_ELTS,
+        "Digests must fit in a `HashOutTarget`"
+    );
+    hash.elements
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &x)| lookups.split_le(builder, x, (N - 7 * i).min(7)))
+        .collect()
+}
+
+/// Returns the first `SPONGE_WIDTH` canonical words among `words`, given as their values and borrow
+/// bits, which are set for non-canonical words. The `j`-th canonical word is among the words
+/// `j..=j + max_skipped`, where `max_skipped` is the number of words which may be skipped.
+fn select_canonical_words<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    words: &[(Target, BoolTarget)],
+) -> Vec<Target> {
+    let max_skipped = words.len() - SPONGE_WIDTH;
+
+    // The number of canonical words before each word.
+    let mut num_canonical = builder.zero();
+    let mut nums_canonical_before = Vec::with_capacity(words.len());
+    let mut is_canonical = Vec::with_capacity(words.len());
+    for &(_, borrow) in words {
+        nums_canonical_before.push(num_canonical);
+        let canonical = builder.not(borrow);
+        num_canonical = builder.add(num_canonical, canonical.target);
+        is_canonical.push(canonical);
+    }
+
+    (0..SPONGE_WIDTH)
+        .map(|j| {
+            let index = builder.constant(F::from_canonical_usize(j));
+            let mut output = builder.zero();
+            let mut num_selected = builder.zero();
+            for i in j..=j + max_skipped {
+                let is_jth = builder.is_equal(nums_canonical_before[i], index);
+                let selected = builder.and(is_jth, is_canonical[i]);
+                output = builder.mul_add(selected.target, words[i].0, output);
+                num_selected = builder.add(num_selected, selected.target);
+            }
+            // If the last canonical word is found, all the previous ones are found as well.
+            if j == SPONGE_WIDTH - 1 {
+                builder.assert_one(num_selected);
+            }
+            output
+        })
+        .collect()
+}
+
+/// In circuits, field elements are serialized to their canonical little-endian bytes before being
+/// hashed with the Keccak-256 gadget. This is only implemented for Goldilocks, since a generic
+/// implementation would overlap with the one of algebraic hashers.
+impl<const N: usize> CircuitHasher<GoldilocksField> for KeccakHash<N> {
+    type CircuitPermutation = KeccakPermutation<Target>;
+
+    /// As in [`KeccakPermutation`], words of the hash onion which are not canonical field elements
+    /// are skipped. The circuit computes `NUM_CIRCUIT_DIGESTS` digests, so it is unsatisfiable if
+    /// more than four of their sixteen words are not canonical, which happens with probability
+    /// less than `2^-140`.
+    fn permute_circuit<const D: usize>(
+        inputs: Self::CircuitPermutation,
+        builder: &mut CircuitBuilder<GoldilocksField, D>,
+    ) -> Self::CircuitPermutation
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let mut lookups = ByteLookups::default();
+        let mut bytes = inputs
+            .as_ref()
+            .iter()
+            .flat_map(|&x| lookups.split_canonical_le(builder, x))
+            .collect::<Vec<_>>();
+
+        let mut words = Vec::with_capacity(NUM_CIRCUIT_DIGESTS * 4);
+        for _ in 0..NUM_CIRCUIT_DIGESTS {
+            let digest = builder.keccak256(&bytes);
+            for word in digest.chunks_exact(size_of::<u64>()) {
+                words.push(pack_le_with_borrow(builder, word.try_into().unwrap()));
+            }
+            bytes = digest.to_vec();
+        }
+        Self::CircuitPermutation::new(select_canonical_words(builder, &words))
+    }
+
+    fn hash_or_noop_circuit<const D: usize>(
+        inputs: Vec<Target>,
+        builder: &mut CircuitBuilder<GoldilocksField, D>,
+    ) -> HashOutTarget
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let mut lookups = ByteLookups::default();
+        let mut bytes = inputs
+            .iter()
+            .flat_map(|&x| lookups.split_canonical_le(builder, x))
+            .collect::<Vec<_>>();
+
+        if bytes.len() <= N {
+            let zero = builder.constant_byte(0);
+            bytes.resize(N, zero);
+            digest_to_hash_out(builder, &bytes)
+        } else {
+            let digest = builder.keccak256(&bytes);
+            digest_to_hash_out(builder, &digest[..N])
+        }
+    }
+
+    fn two_to_one_swapped_circuit<const D: usize>(
+        left: HashOutTarget,
+        right: HashOutTarget,
+        swap: BoolTarget,
+        builder: &mut CircuitBuilder<GoldilocksField, D>,
+    ) -> HashOutTarget
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let mut lookups = ByteLookups::default();
+        let mut bytes = Vec::with_capacity(2 * N);
+        for (x, y) in [(left, right), (right, left)] {
+            let hash = HashOutTarget {
+                elements: core::array::from_fn(|i| {
+                    builder.select(swap, y.elements[i], x.elements[i])
+                }),
+            };
+            bytes.extend(hash_out_to_digest::<GoldilocksField, D, N>(
+                builder,
+                &mut lookups,
+                hash,
+            ));
+        }
+
+        let digest = builder.keccak256(&bytes);
+        digest_to_hash_out(builder, &digest[..N])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::field::types::{Field, Field64, Sample};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, GenericHashOut, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_select_canonical_words() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let num_words = NUM_CIRCUIT_DIGESTS * 4;
+        for non_canonical_indices in [vec![], vec![0], vec![3, 11], vec![12, 13, 14, 15]] {
+            let words = (0..num_words)
+                .map(|i| {
+                    if non_canonical_indices.contains(&i) {
+                        OsRng.gen_range(F::ORDER..=u64::MAX)
+                    } else {
+                        OsRng.gen_range(0..F::ORDER)
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let word_targets = words
+                .iter()
+                .map(|word| {
+                    let bytes = builder.add_virtual_byte_targets(size_of::<u64>());
+                    for (&t, byte) in bytes.iter().zip(word.to_le_bytes()) {
+                        pw.set_byte_target(t, byte);
+                    }
+                    pack_le_with_borrow(&mut builder, &bytes.try_into().unwrap())
+                })
+                .collect::<Vec<_>>();
+            let selected = select_canonical_words(&mut builder, &word_targets);
+
+            // The words are selected as in `KeccakPermutation`.
+            let expected = words
+                .iter()
+                .filter(|&&word| word < F::ORDER)
+                .take(SPONGE_WIDTH)
+                .map(|&word| F::from_canonical_u64(word));
+            for (&t, value) in selected.iter().zip(expected) {
+                let value = builder.constant(value);
+                builder.connect(t, value);
+            }
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_circuit_hasher_matches_native() -> Result<()> {
+        type H = KeccakHash<25>;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut expected = Vec::<F>::new();
+
+        let state = F::rand_vec(SPONGE_WIDTH);
+        let mut permutation = KeccakPermutation::new(state.iter().copied());
+        permutation.permute();
+        expected.extend_from_slice(permutation.as_ref());
+        let state_t = builder.add_virtual_targets(SPONGE_WIDTH);
+        pw.set_target_arr(&state_t, &state);
+        let permutation_t = H::permute_circuit(KeccakPermutation::new(state_t), &mut builder);
+        builder.register_public_inputs(permutation_t.as_ref());
+
+        // Inputs of 3 elements fit in a digest, so they are not hashed.
+        for len in [3, 10] {
+            let inputs = F::rand_vec(len);
+            expected.extend(GenericHashOut::<F>::to_vec(
+                &<H as Hasher<F>>::hash_or_noop(&inputs),
+            ));
+            let inputs_t = builder.add_virtual_targets(len);
+            pw.set_target_arr(&inputs_t, &inputs);
+            let hash_t = H::hash_or_noop_circuit(inputs_t, &mut builder);
+            builder.register_public_inputs(&hash_t.elements);
+        }
+
+        let left = <H as Hasher<F>>::hash_or_noop(&F::rand_vec(10));
+        let right = <H as Hasher<F>>::hash_or_noop(&F::rand_vec(10));
+        for swap in [false, true] {
+            let hash = if swap {
+                <H as Hasher<F>>::two_to_one(right, left)
+            } else {
+                <H as Hasher<F>>::two_to_one(left, right)
+            };
+            expected.extend(GenericHashOut::<F>::to_vec(&hash));
+            let left_t = builder.add_virtual_hash();
+            let right_t = builder.add_virtual_hash();
+            pw.set_target_arr(&left_t.elements, &left.to_vec());
+            pw.set_target_arr(&right_t.elements, &right.to_vec());
+            let swap_t = builder.constant_bool(swap);
+            let hash_t = H::two_to_one_swapped_circuit(left_t, right_t, swap_t, &mut builder);
+            builder.register_public_inputs(&hash_t.elements);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        assert_eq!(proof.public_inputs, expected);
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
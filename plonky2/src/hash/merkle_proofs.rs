@@ -7,12 +7,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::field::extension::Extendable;
 use crate::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField, NUM_HASH_OUT_ELTS};
-use crate::hash::hashing::PlonkyPermutation;
 use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::target::{BoolTarget, Target};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::VerifierCircuitTarget;
-use crate::plonk::config::{AlgebraicHasher, Hasher};
+use crate::plonk::config::{CircuitHasher, Hasher};
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(bound = "")]
@@ -79,7 +78,7 @@ pub fn verify_merkle_proof_to_cap<F: RichField, H: Hasher<F>>(
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     /// Verifies that the given leaf data is present at the given index in the Merkle tree with the
     /// given root. The index is given by its little-endian bits.
-    pub fn verify_merkle_proof<H: AlgebraicHasher<F>>(
+    pub fn verify_merkle_proof<H: CircuitHasher<F>>(
         &mut self,
         leaf_data: Vec<Target>,
         leaf_index_bits: &[BoolTarget],
@@ -92,7 +91,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
 
     /// Verifies that the given leaf data is present at the given index in the Merkle tree with the
     /// given cap. The index is given by its little-endian bits.
-    pub fn verify_merkle_proof_to_cap<H: AlgebraicHasher<F>>(
+    pub fn verify_merkle_proof_to_cap<H: CircuitHasher<F>>(
         &mut self,
         leaf_data: Vec<Target>,
         leaf_index_bits: &[BoolTarget],
@@ -111,7 +110,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
 
     /// Same as `verify_merkle_proof_to_cap`, except with the final "cap index" as separate parameter,
     /// rather than being contained in `leaf_index_bits`.
-    pub(crate) fn verify_merkle_proof_to_cap_with_cap_index<H: AlgebraicHasher<F>>(
+    pub(crate) fn verify_merkle_proof_to_cap_with_cap_index<H: CircuitHasher<F>>(
         &mut self,
         leaf_data: Vec<Target>,
         leaf_index_bits: &[BoolTarget],
@@ -119,27 +118,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         merkle_cap: &MerkleCapTarget,
         proof: &MerkleProofTarget,
     ) {
-        debug_assert!(H::AlgebraicPermutation::RATE >= NUM_HASH_OUT_ELTS);
-
-        let zero = self.zero();
-        let mut state: HashOutTarget = self.hash_or_noop::<H>(leaf_data);
+        let mut state: HashOutTarget = H::hash_or_noop_circuit(leaf_data, self);
         debug_assert_eq!(state.elements.len(), NUM_HASH_OUT_ELTS);
 
         for (&bit, &sibling) in leaf_index_bits.iter().zip(&proof.siblings) {
             debug_assert_eq!(sibling.elements.len(), NUM_HASH_OUT_ELTS);
-
-            let mut perm_inputs = H::AlgebraicPermutation::default();
-            perm_inputs.set_from_slice(&state.elements, 0);
-            perm_inputs.set_from_slice(&sibling.elements, NUM_HASH_OUT_ELTS);
-            // Ensure the rest of the state, if any, is zero:
-            perm_inputs.set_from_iter(core::iter::repeat(zero), 2 * NUM_HASH_OUT_ELTS);
-            let perm_outs = self.permute_swapped::<H>(perm_inputs, bit);
-            let hash_outs = perm_outs.squeeze()[0..NUM_HASH_OUT_ELTS]
-                .try_into()
-                .unwrap();
-            state = HashOutTarget {
-                elements: hash_outs,
-            };
+            state = H::two_to_one_swapped_circuit(state, sibling, bit, self);
         }
 
         for i in 0..NUM_HASH_OUT_ELTS {
@@ -179,7 +163,9 @@ mod tests {
     use crate::hash::merkle_tree::MerkleTree;
     use crate::iop::witness::{PartialWitness, WitnessWrite};
     use crate::plonk::circuit_data::CircuitConfig;
-    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::config::{
+        GenericConfig, GenericHashOut, KeccakGoldilocksConfig, PoseidonGoldilocksConfig,
+    };
     use crate::plonk::verifier::verify;
 
     fn random_data<F: Field>(n: usize, k: usize) -> Vec<Vec<F>> {
@@ -230,4 +216,49 @@ mod tests {
 
         verify(proof, &data.verifier_only, &data.common)
     }
+
+    #[test]
+    fn test_recursive_keccak_merkle_proof() -> Result<()> {
+        const D: usize = 2;
+        type C = KeccakGoldilocksConfig;
+        type H = <C as GenericConfig<D>>::Hasher;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let log_n = 4;
+        let n = 1 << log_n;
+        let cap_height = 1;
+        // Leaves of 3 elements fit in a digest, so they are not hashed.
+        for leaf_len in [3, 7] {
+            let leaves = random_data::<F>(n, leaf_len);
+            let tree = MerkleTree::<F, H>::new(leaves, cap_height);
+            let i: usize = OsRng.gen_range(0..n);
+            let proof = tree.prove(i);
+
+            let proof_t = MerkleProofTarget {
+                siblings: builder.add_virtual_hashes(proof.siblings.len()),
+            };
+            for (t, sibling) in proof_t.siblings.iter().zip(&proof.siblings) {
+                pw.set_target_arr(&t.elements, &sibling.to_vec());
+            }
+
+            let cap_t = builder.add_virtual_cap(cap_height);
+            pw.set_cap_target(&cap_t, &tree.cap);
+
+            let i_c = builder.constant(F::from_canonical_usize(i));
+            let i_bits = builder.split_le(i_c, log_n);
+
+            let data = builder.add_virtual_targets(leaf_len);
+            pw.set_target_arr(&data, &tree.leaves[i]);
+
+            builder.verify_merkle_proof_to_cap::<H>(data, &i_bits, &cap_t, &proof_t);
+        }
+
+        let data = builder.build::<PoseidonGoldilocksConfig>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
 }
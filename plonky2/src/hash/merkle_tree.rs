@@ -1,8 +1,9 @@
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{vec, vec::Vec};
 use core::mem::MaybeUninit;
 use core::slice;
 
+use itertools::Itertools;
 use plonky2_maybe_rayon::*;
 use serde::{Deserialize, Serialize};
 
@@ -199,29 +200,93 @@ impl<F: RichField, H: Hasher<F>> MerkleTree<F, H> {
         };
 
         // Mask out high bits to get the index within the sub-tree.
-        let mut pair_index = leaf_index & ((1 << num_layers) - 1);
+        let mut node_index = leaf_index & ((1 << num_layers) - 1);
         let siblings = (0..num_layers)
             .map(|i| {
-                let parity = pair_index & 1;
-                pair_index >>= 1;
-
-                // The layers' data is interleaved as follows:
-                // [layer 0, layer 1, layer 0, layer 2, layer 0, layer 1, layer 0, layer 3, ...].
-                // Each of the above is a pair of siblings.
-                // `pair_index` is the index of the pair within layer `i`.
-                // The index of that the pair within `digests` is
-                // `pair_index * 2 ** (i + 1) + (2 ** i - 1)`.
-                let siblings_index = (pair_index << (i + 1)) + (1 << i) - 1;
-                // We have an index for the _pair_, but we want the index of the _sibling_.
-                // Double the pair index to get the index of the left sibling. Conditionally add `1`
-                // if we are to retrieve the right sibling.
-                let sibling_index = 2 * siblings_index + (1 - parity);
+                let sibling_index = subtree_digest_index(i, node_index ^ 1);
+                node_index >>= 1;
                 digest_tree[sibling_index]
             })
             .collect();
 
         MerkleProof { siblings }
     }
+
+    /// Replaces the leaf at index `leaf_index`, and recomputes the digests on its path.
+    pub fn update_leaf(&mut self, leaf_index: usize, leaf: Vec<F>) {
+        self.update_leaves(vec![(leaf_index, leaf)]);
+    }
+
+    /// Replaces the leaves at the given indices, and recomputes the digests on their paths. If an
+    /// index appears several times, its last leaf is kept.
+    ///
+    /// The digests of each layer are recomputed in parallel, and each digest is only recomputed
+    /// once, even if it is on the path of several updated leaves.
+    pub fn update_leaves(&mut self, updates: Vec<(usize, Vec<F>)>) {
+        let cap_height = log2_strict(self.cap.len());
+        let num_layers = log2_strict(self.leaves.len()) - cap_height;
+
+        let mut indices = Vec::with_capacity(updates.len());
+        for (leaf_index, leaf) in updates {
+            self.leaves[leaf_index] = leaf;
+            indices.push(leaf_index);
+        }
+        indices.sort_unstable();
+        indices.dedup();
+
+        let mut new_digests = indices
+            .par_iter()
+            .map(|&i| H::hash_or_noop(&self.leaves[i]))
+            .collect::<Vec<_>>();
+        for layer in 0..num_layers {
+            for (&i, &digest) in indices.iter().zip(&new_digests) {
+                let digest_index = self.digest_index(layer, i);
+                self.digests[digest_index] = digest;
+            }
+
+            indices = indices.into_iter().map(|i| i >> 1).dedup().collect();
+            new_digests = indices
+                .par_iter()
+                .map(|&i| {
+                    let left = self.digests[self.digest_index(layer, 2 * i)];
+                    let right = self.digests[self.digest_index(layer, 2 * i + 1)];
+                    H::two_to_one(left, right)
+                })
+                .collect();
+        }
+
+        for (i, digest) in indices.into_iter().zip(new_digests) {
+            self.cap.0[i] = digest;
+        }
+    }
+
+    /// Index in `digests` of the digest of the `node_index`-th node of layer `layer`, where layer
+    /// `0` holds the leaves.
+    fn digest_index(&self, layer: usize, node_index: usize) -> usize {
+        let cap_height = log2_strict(self.cap.len());
+        let num_layers = log2_strict(self.leaves.len()) - cap_height;
+        let subtree_layers = num_layers - layer;
+        let tree_index = node_index >> subtree_layers;
+        let tree_len = self.digests.len() >> cap_height;
+        tree_len * tree_index
+            + subtree_digest_index(layer, node_index & ((1 << subtree_layers) - 1))
+    }
+}
+
+/// Index in the digests of a sub-tree of the digest of the `node_index`-th node of layer `layer`
+/// of that sub-tree, where layer `0` holds the leaves.
+fn subtree_digest_index(layer: usize, node_index: usize) -> usize {
+    // The layers' data is interleaved as follows:
+    // [layer 0, layer 1, layer 0, layer 2, layer 0, layer 1, layer 0, layer 3, ...].
+    // Each of the above is a pair of siblings.
+    // `pair_index` is the index of the pair within layer `layer`.
+    // The index of that the pair within `digests` is
+    // `pair_index * 2 ** (layer + 1) + (2 ** layer - 1)`.
+    let pair_index = node_index >> 1;
+    let siblings_index = (pair_index << (layer + 1)) + (1 << layer) - 1;
+    // We have an index for the _pair_, but we want the index of the node. Double the pair index to
+    // get the index of the left sibling. Add `1` if the node is the right sibling.
+    2 * siblings_index + (node_index & 1)
 }
 
 #[cfg(test)]
@@ -230,6 +295,7 @@ mod tests {
 
     use super::*;
     use crate::field::extension::Extendable;
+    use crate::field::types::Sample;
     use crate::hash::merkle_proofs::verify_merkle_proof_to_cap;
     use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
 
@@ -282,6 +348,58 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_update_leaves() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type H = <C as GenericConfig<D>>::Hasher;
+
+        let log_n = 6;
+        let n = 1 << log_n;
+        for cap_height in [0, 2, log_n] {
+            let mut leaves = random_data::<F>(n, 7);
+            let mut tree = MerkleTree::<F, H>::new(leaves.clone(), cap_height);
+
+            let leaf = F::rand_vec(7);
+            tree.update_leaf(5, leaf.clone());
+            leaves[5] = leaf;
+            assert_eq!(tree, MerkleTree::new(leaves.clone(), cap_height));
+
+            // Updates with shared paths and repeated indices, whose last leaf is kept.
+            let updates = [0, 1, 3, 17, 17, 32, n - 1]
+                .into_iter()
+                .map(|i| (i, F::rand_vec(7)))
+                .collect::<Vec<_>>();
+            for (i, leaf) in &updates {
+                leaves[*i] = leaf.clone();
+            }
+            tree.update_leaves(updates);
+            assert_eq!(tree, MerkleTree::new(leaves.clone(), cap_height));
+
+            for (i, leaf) in leaves.into_iter().enumerate() {
+                verify_merkle_proof_to_cap(leaf, i, &tree.cap, &tree.prove(i))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_all_leaves() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type H = <C as GenericConfig<D>>::Hasher;
+
+        let log_n = 8;
+        let n = 1 << log_n;
+        let mut tree = MerkleTree::<F, H>::new(random_data::<F>(n, 7), 1);
+        let leaves = random_data::<F>(n, 7);
+        tree.update_leaves(leaves.clone().into_iter().enumerate().collect());
+        assert_eq!(tree, MerkleTree::new(leaves, 1));
+    }
+
     #[test]
     fn test_merkle_trees() -> Result<()> {
         const D: usize = 2;
@@ -1,6 +1,7 @@
 #[cfg(not(feature = "std"))]
 use alloc::{vec, vec::Vec};
 
+use anyhow::anyhow;
 use hashbrown::HashMap;
 use num::Integer;
 
@@ -53,13 +54,14 @@ pub(crate) fn compress_merkle_proofs<F: RichField, H: Hasher<F>>(
 
 /// Decompress compressed Merkle proofs.
 /// Note: The data and indices must be in the same order as in `compress_merkle_proofs`.
+/// Returns an error if the compressed proofs are missing siblings.
 pub(crate) fn decompress_merkle_proofs<F: RichField, H: Hasher<F>>(
     leaves_data: &[Vec<F>],
     leaves_indices: &[usize],
     compressed_proofs: &[MerkleProof<F, H>],
     height: usize,
     cap_height: usize,
-) -> Vec<MerkleProof<F, H>> {
+) -> anyhow::Result<Vec<MerkleProof<F, H>>> {
     let num_leaves = 1 << height;
     let compressed_proofs = compressed_proofs.to_vec();
     let mut decompressed_proofs = Vec::with_capacity(compressed_proofs.len());
@@ -82,9 +84,16 @@ pub(crate) fn decompress_merkle_proofs<F: RichField, H: Hasher<F>>(
             let index = (i + num_leaves) >> layer_height;
             let current_hash = seen[&index];
             let sibling_index = index ^ 1;
-            let sibling_hash = *seen
-                .entry(sibling_index)
-                .or_insert_with(|| *p.next().unwrap());
+            let sibling_hash = match seen.get(&sibling_index) {
+                Some(&hash) => hash,
+                None => {
+                    let hash = *p
+                        .next()
+                        .ok_or_else(|| anyhow!("Missing sibling in compressed Merkle proof."))?;
+                    seen.insert(sibling_index, hash);
+                    hash
+                }
+            };
             let parent_hash = if index.is_even() {
                 H::two_to_one(current_hash, sibling_hash)
             } else {
@@ -109,7 +118,7 @@ pub(crate) fn decompress_merkle_proofs<F: RichField, H: Hasher<F>>(
         decompressed_proofs.push(decompressed_proof);
     }
 
-    decompressed_proofs
+    Ok(decompressed_proofs)
 }
 
 #[cfg(test)]
@@ -144,7 +153,8 @@ mod tests {
             &compressed_proofs,
             h,
             cap_height,
-        );
+        )
+        .unwrap();
 
         assert_eq!(proofs, decompressed_proofs);
 
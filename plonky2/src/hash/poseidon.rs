@@ -9,11 +9,12 @@ use plonky2_field::packed::PackedField;
 use unroll::unroll_for_loops;
 
 use crate::field::extension::{Extendable, FieldExtension};
+use crate::field::packable::Packable;
 use crate::field::types::{Field, PrimeField64};
 use crate::gates::gate::Gate;
 use crate::gates::poseidon::PoseidonGate;
 use crate::gates::poseidon_mds::PoseidonMdsGate;
-use crate::hash::hash_types::{HashOut, RichField};
+use crate::hash::hash_types::{HashOut, RichField, NUM_HASH_OUT_ELTS};
 use crate::hash::hashing::{compress, hash_n_to_hash_no_pad, PlonkyPermutation};
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::{BoolTarget, Target};
@@ -776,6 +777,94 @@ pub trait Poseidon: PrimeField64 {
         state
     }
 
+    /// Same as `full_rounds` for `PackedField`.
+    #[inline]
+    fn full_rounds_packed_field<
+        F: RichField + Extendable<D>,
+        const D: usize,
+        FE,
+        P,
+        const D2: usize,
+    >(
+        state: &mut [P; SPONGE_WIDTH],
+        round_ctr: &mut usize,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        for _ in 0..HALF_N_FULL_ROUNDS {
+            Self::constant_layer_packed_field::<F, D, FE, P, D2>(state, *round_ctr);
+            for i in 0..SPONGE_WIDTH {
+                state[i] = sbox_monomial_packed(state[i]);
+            }
+            *state = Self::mds_layer_packed_field::<F, D, FE, P, D2>(state);
+            *round_ctr += 1;
+        }
+    }
+
+    /// Same as `partial_rounds` for `PackedField`.
+    #[inline]
+    fn partial_rounds_packed_field<
+        F: RichField + Extendable<D>,
+        const D: usize,
+        FE,
+        P,
+        const D2: usize,
+    >(
+        state: &mut [P; SPONGE_WIDTH],
+        round_ctr: &mut usize,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        Self::partial_first_constant_layer_packed_field::<F, D, FE, P, D2>(state);
+        *state = Self::mds_partial_layer_init_packed_field::<F, D, FE, P, D2>(state);
+
+        for i in 0..N_PARTIAL_ROUNDS {
+            state[0] = sbox_monomial_packed(state[0]);
+            state[0] += P::Scalar::from_canonical_u64(Self::FAST_PARTIAL_ROUND_CONSTANTS[i]);
+            *state = Self::mds_partial_layer_fast_packed_field::<F, D, FE, P, D2>(state, i);
+        }
+        *round_ctr += N_PARTIAL_ROUNDS;
+    }
+
+    /// Same as `poseidon` for `PackedField`.
+    #[inline]
+    fn poseidon_packed_field<F: RichField + Extendable<D>, const D: usize, FE, P, const D2: usize>(
+        input: [P; SPONGE_WIDTH],
+    ) -> [P; SPONGE_WIDTH]
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let mut state = input;
+        let mut round_ctr = 0;
+
+        Self::full_rounds_packed_field::<F, D, FE, P, D2>(&mut state, &mut round_ctr);
+        Self::partial_rounds_packed_field::<F, D, FE, P, D2>(&mut state, &mut round_ctr);
+        Self::full_rounds_packed_field::<F, D, FE, P, D2>(&mut state, &mut round_ctr);
+        debug_assert_eq!(round_ctr, N_ROUNDS);
+
+        state
+    }
+
+    /// Applies `poseidon` to `P::WIDTH` independent states at once, one in each lane of `P`. By
+    /// default, each lane is permuted separately; fields with a vectorized packing override this
+    /// with `poseidon_packed_field`.
+    #[inline]
+    fn poseidon_packed<P: PackedField<Scalar = Self>>(
+        input: [P; SPONGE_WIDTH],
+    ) -> [P; SPONGE_WIDTH] {
+        let mut output = input;
+        for lane in 0..P::WIDTH {
+            let lane_output = Self::poseidon(core::array::from_fn(|i| input[i].as_slice()[lane]));
+            for (o, x) in output.iter_mut().zip(lane_output) {
+                o.as_slice_mut()[lane] = x;
+            }
+        }
+        output
+    }
+
     // For testing only, to ensure that various tricks are correct.
     #[inline]
     fn partial_rounds_naive(state: &mut [Self; SPONGE_WIDTH], round_ctr: &mut usize) {
@@ -801,6 +890,52 @@ pub trait Poseidon: PrimeField64 {
     }
 }
 
+#[inline(always)]
+fn sbox_monomial_packed<P: PackedField>(x: P) -> P {
+    // x |--> x^7
+    let x2 = x.square();
+    let x4 = x2.square();
+    let x3 = x * x2;
+    x3 * x4
+}
+
+/// Computes `PoseidonHash::hash_or_noop` of each input. Consecutive groups of `P::WIDTH` inputs of
+/// the same length are hashed at once, with one state in each lane of `P`.
+fn hash_or_noop_packed<F: RichField, P: PackedField<Scalar = F>>(
+    inputs: &[Vec<F>],
+) -> Vec<HashOut<F>> {
+    let mut outputs = Vec::with_capacity(inputs.len());
+    for group in inputs.chunks(P::WIDTH) {
+        let len = group[0].len();
+        if P::WIDTH == 1
+            || group.len() < P::WIDTH
+            || len <= NUM_HASH_OUT_ELTS
+            || group.iter().any(|input| input.len() != len)
+        {
+            outputs.extend(group.iter().map(|input| PoseidonHash::hash_or_noop(input)));
+            continue;
+        }
+
+        let mut state = [P::ZEROS; SPONGE_WIDTH];
+        for chunk_start in (0..len).step_by(SPONGE_RATE) {
+            // Overwrite the first elements of each lane with its next input chunk, as in
+            // `hash_n_to_m_no_pad`.
+            for i in chunk_start..len.min(chunk_start + SPONGE_RATE) {
+                let lanes = state[i - chunk_start].as_slice_mut();
+                for (lane, input) in lanes.iter_mut().zip(group) {
+                    *lane = input[i];
+                }
+            }
+            state = F::poseidon_packed(state);
+        }
+
+        outputs.extend((0..P::WIDTH).map(|lane| HashOut {
+            elements: core::array::from_fn(|i| state[i].as_slice()[lane]),
+        }));
+    }
+    outputs
+}
+
 #[derive(Copy, Clone, Default, Debug, PartialEq)]
 pub struct PoseidonPermutation<T> {
     state: [T; SPONGE_WIDTH],
@@ -881,6 +1016,10 @@ impl<F: RichField> Hasher<F> for PoseidonHash {
         hash_n_to_hash_no_pad::<F, Self::Permutation>(input)
     }
 
+    fn hash_or_noop_batch(inputs: &[Vec<F>]) -> Vec<Self::Hash> {
+        hash_or_noop_packed::<F, <F as Packable>::Packing>(inputs)
+    }
+
     fn two_to_one(left: Self::Hash, right: Self::Hash) -> Self::Hash {
         compress::<F, Self::Permutation>(left, right)
     }
@@ -941,6 +1080,29 @@ pub(crate) mod test_helpers {
         }
     }
 
+    /// Checks `Poseidon::poseidon_packed` against `Poseidon::poseidon` in each lane, for the
+    /// default packing of `F`.
+    pub(crate) fn check_packed_consistency<F: Poseidon>() {
+        type P<F> = <F as Packable>::Packing;
+        let inputs = (0..P::<F>::WIDTH)
+            .map(|_| core::array::from_fn(|_| F::rand()))
+            .collect::<Vec<[F; SPONGE_WIDTH]>>();
+        let mut packed_input = [P::<F>::ZEROS; SPONGE_WIDTH];
+        for (i, packed) in packed_input.iter_mut().enumerate() {
+            for (lane, input) in packed.as_slice_mut().iter_mut().zip(&inputs) {
+                *lane = input[i];
+            }
+        }
+
+        let packed_output = F::poseidon_packed(packed_input);
+        for (lane, input) in inputs.into_iter().enumerate() {
+            let output = F::poseidon(input);
+            for i in 0..SPONGE_WIDTH {
+                assert_eq!(packed_output[i].as_slice()[lane], output[i]);
+            }
+        }
+    }
+
     pub(crate) fn check_consistency<F: Field>()
     where
         F: Poseidon,
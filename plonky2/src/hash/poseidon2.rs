@@ -0,0 +1,435 @@
+//! Implementation of the Poseidon2 hash function, as described in
+//! <https://eprint.iacr.org/2023/323.pdf>
+//!
+//! Poseidon2 uses the same sponge as [`PoseidonHash`](crate::hash::poseidon::PoseidonHash), but
+//! replaces the dense MDS matrix with cheaper linear layers: a matrix built from 4x4 blocks in the
+//! full (external) rounds, and a diagonal-plus-constant matrix in the partial (internal) rounds.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::fmt::Debug;
+
+use crate::field::extension::{Extendable, FieldExtension};
+use crate::field::types::PrimeField64;
+use crate::gates::poseidon2::Poseidon2Gate;
+use crate::hash::hash_types::{HashOut, RichField};
+use crate::hash::hashing::{compress, hash_n_to_hash_no_pad, PlonkyPermutation};
+use crate::hash::poseidon::{SPONGE_RATE, SPONGE_WIDTH};
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::{AlgebraicHasher, Hasher};
+
+// Same number of rounds as Poseidon, for width 12 and s-box x^7.
+//
+// NB: Changing any of these values will require regenerating the round
+// constants of all the implementations of `Poseidon2`.
+pub const HALF_N_FULL_ROUNDS: usize = 4;
+pub const N_FULL_ROUNDS_TOTAL: usize = 2 * HALF_N_FULL_ROUNDS;
+pub const N_PARTIAL_ROUNDS: usize = 22;
+
+pub trait Poseidon2: PrimeField64 {
+    /// Round constants of the full rounds. The first `HALF_N_FULL_ROUNDS` rows are used before the
+    /// partial rounds, the others after them.
+    const EXTERNAL_ROUND_CONSTANTS: [[u64; SPONGE_WIDTH]; N_FULL_ROUNDS_TOTAL];
+
+    /// Round constants of the partial rounds, only added to the first state element.
+    const INTERNAL_ROUND_CONSTANTS: [u64; N_PARTIAL_ROUNDS];
+
+    /// The matrix of the partial rounds is `J + diag(INTERNAL_MATRIX_DIAG_M_1)`, where `J` is the
+    /// all-ones matrix.
+    const INTERNAL_MATRIX_DIAG_M_1: [u64; SPONGE_WIDTH];
+
+    #[inline(always)]
+    fn sbox_p<F: FieldExtension<D, BaseField = Self>, const D: usize>(x: F) -> F {
+        // x |--> x^7
+        let x2 = x.square();
+        let x4 = x2.square();
+        let x3 = x * x2;
+        x3 * x4
+    }
+
+    /// Recursive version of `sbox_p`.
+    fn sbox_p_circuit<const D: usize>(
+        builder: &mut CircuitBuilder<Self, D>,
+        x: ExtensionTarget<D>,
+    ) -> ExtensionTarget<D>
+    where
+        Self: RichField + Extendable<D>,
+    {
+        // x |--> x^7
+        builder.exp_u64_extension(x, 7)
+    }
+
+    /// Multiplies four state elements by the matrix
+    /// `[[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]]`, using only additions.
+    #[inline(always)]
+    fn matmul_m4<F: FieldExtension<D, BaseField = Self>, const D: usize>(x: &mut [F]) {
+        let t0 = x[0] + x[1];
+        let t1 = x[2] + x[3];
+        let t2 = x[1].double() + t1;
+        let t3 = x[3].double() + t0;
+        let t4 = t1.double().double() + t3;
+        let t5 = t0.double().double() + t2;
+        x[0] = t3 + t5;
+        x[1] = t5;
+        x[2] = t2 + t4;
+        x[3] = t4;
+    }
+
+    /// Recursive version of `matmul_m4`.
+    fn matmul_m4_circuit<const D: usize>(
+        builder: &mut CircuitBuilder<Self, D>,
+        x: &mut [ExtensionTarget<D>],
+    ) where
+        Self: RichField + Extendable<D>,
+    {
+        let t0 = builder.add_extension(x[0], x[1]);
+        let t1 = builder.add_extension(x[2], x[3]);
+        let t2 = builder.mul_const_add_extension(Self::TWO, x[1], t1);
+        let t3 = builder.mul_const_add_extension(Self::TWO, x[3], t0);
+        let t4 = builder.mul_const_add_extension(Self::from_canonical_u64(4), t1, t3);
+        let t5 = builder.mul_const_add_extension(Self::from_canonical_u64(4), t0, t2);
+        x[0] = builder.add_extension(t3, t5);
+        x[1] = t5;
+        x[2] = builder.add_extension(t2, t4);
+        x[3] = t4;
+    }
+
+    /// The linear layer of the full rounds, `circ(2 M4, M4, M4)`.
+    #[inline(always)]
+    fn external_linear_layer<F: FieldExtension<D, BaseField = Self>, const D: usize>(
+        state: &mut [F; SPONGE_WIDTH],
+    ) {
+        for chunk in state.chunks_exact_mut(4) {
+            Self::matmul_m4(chunk);
+        }
+
+        let mut sums = [F::ZERO; 4];
+        for i in 0..SPONGE_WIDTH {
+            sums[i % 4] += state[i];
+        }
+        for i in 0..SPONGE_WIDTH {
+            state[i] += sums[i % 4];
+        }
+    }
+
+    /// Recursive version of `external_linear_layer`.
+    fn external_linear_layer_circuit<const D: usize>(
+        builder: &mut CircuitBuilder<Self, D>,
+        state: &mut [ExtensionTarget<D>; SPONGE_WIDTH],
+    ) where
+        Self: RichField + Extendable<D>,
+    {
+        for chunk in state.chunks_exact_mut(4) {
+            Self::matmul_m4_circuit(builder, chunk);
+        }
+
+        let sums: [ExtensionTarget<D>; 4] = core::array::from_fn(|i| {
+            builder.add_many_extension((i..SPONGE_WIDTH).step_by(4).map(|j| state[j]))
+        });
+        for i in 0..SPONGE_WIDTH {
+            state[i] = builder.add_extension(state[i], sums[i % 4]);
+        }
+    }
+
+    /// The linear layer of the partial rounds.
+    #[inline(always)]
+    fn internal_linear_layer<F: FieldExtension<D, BaseField = Self>, const D: usize>(
+        state: &mut [F; SPONGE_WIDTH],
+    ) {
+        let sum = state.iter().fold(F::ZERO, |acc, &x| acc + x);
+        for i in 0..SPONGE_WIDTH {
+            let diag = F::from_canonical_u64(Self::INTERNAL_MATRIX_DIAG_M_1[i]);
+            state[i] = state[i] * diag + sum;
+        }
+    }
+
+    /// Recursive version of `internal_linear_layer`.
+    fn internal_linear_layer_circuit<const D: usize>(
+        builder: &mut CircuitBuilder<Self, D>,
+        state: &mut [ExtensionTarget<D>; SPONGE_WIDTH],
+    ) where
+        Self: RichField + Extendable<D>,
+    {
+        let sum = builder.add_many_extension(state.iter());
+        for i in 0..SPONGE_WIDTH {
+            let diag = Self::from_canonical_u64(Self::INTERNAL_MATRIX_DIAG_M_1[i]);
+            state[i] = builder.mul_const_add_extension(diag, state[i], sum);
+        }
+    }
+
+    /// Adds the round constants of the `round`-th full round.
+    #[inline(always)]
+    fn external_constant_layer<F: FieldExtension<D, BaseField = Self>, const D: usize>(
+        state: &mut [F; SPONGE_WIDTH],
+        round: usize,
+    ) {
+        for i in 0..SPONGE_WIDTH {
+            state[i] += F::from_canonical_u64(Self::EXTERNAL_ROUND_CONSTANTS[round][i]);
+        }
+    }
+
+    /// Recursive version of `external_constant_layer`.
+    fn external_constant_layer_circuit<const D: usize>(
+        builder: &mut CircuitBuilder<Self, D>,
+        state: &mut [ExtensionTarget<D>; SPONGE_WIDTH],
+        round: usize,
+    ) where
+        Self: RichField + Extendable<D>,
+    {
+        for i in 0..SPONGE_WIDTH {
+            let c = Self::from_canonical_u64(Self::EXTERNAL_ROUND_CONSTANTS[round][i]);
+            let c = builder.constant_extension(Self::Extension::from_basefield(c));
+            state[i] = builder.add_extension(state[i], c);
+        }
+    }
+
+    /// Applies the `round`-th full round.
+    #[inline(always)]
+    fn external_round<F: FieldExtension<D, BaseField = Self>, const D: usize>(
+        state: &mut [F; SPONGE_WIDTH],
+        round: usize,
+    ) {
+        Self::external_constant_layer(state, round);
+        for s in state.iter_mut() {
+            *s = Self::sbox_p(*s);
+        }
+        Self::external_linear_layer(state);
+    }
+
+    /// Applies the `round`-th partial round.
+    #[inline(always)]
+    fn internal_round<F: FieldExtension<D, BaseField = Self>, const D: usize>(
+        state: &mut [F; SPONGE_WIDTH],
+        round: usize,
+    ) {
+        state[0] += F::from_canonical_u64(Self::INTERNAL_ROUND_CONSTANTS[round]);
+        state[0] = Self::sbox_p(state[0]);
+        Self::internal_linear_layer(state);
+    }
+
+    #[inline]
+    fn poseidon2(input: [Self; SPONGE_WIDTH]) -> [Self; SPONGE_WIDTH] {
+        let mut state = input;
+
+        Self::external_linear_layer::<Self, 1>(&mut state);
+        for r in 0..HALF_N_FULL_ROUNDS {
+            Self::external_round::<Self, 1>(&mut state, r);
+        }
+        for r in 0..N_PARTIAL_ROUNDS {
+            Self::internal_round::<Self, 1>(&mut state, r);
+        }
+        for r in HALF_N_FULL_ROUNDS..N_FULL_ROUNDS_TOTAL {
+            Self::external_round::<Self, 1>(&mut state, r);
+        }
+
+        state
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub struct Poseidon2Permutation<T> {
+    state: [T; SPONGE_WIDTH],
+}
+
+impl<T: Eq> Eq for Poseidon2Permutation<T> {}
+
+impl<T> AsRef<[T]> for Poseidon2Permutation<T> {
+    fn as_ref(&self) -> &[T] {
+        &self.state
+    }
+}
+
+trait Permuter2: Sized {
+    fn permute(input: [Self; SPONGE_WIDTH]) -> [Self; SPONGE_WIDTH];
+}
+
+impl<F: Poseidon2> Permuter2 for F {
+    fn permute(input: [Self; SPONGE_WIDTH]) -> [Self; SPONGE_WIDTH] {
+        <F as Poseidon2>::poseidon2(input)
+    }
+}
+
+impl Permuter2 for Target {
+    fn permute(_input: [Self; SPONGE_WIDTH]) -> [Self; SPONGE_WIDTH] {
+        panic!("Call `permute_swapped()` instead of `permute()`");
+    }
+}
+
+impl<T: Copy + Debug + Default + Eq + Permuter2 + Send + Sync> PlonkyPermutation<T>
+    for Poseidon2Permutation<T>
+{
+    const RATE: usize = SPONGE_RATE;
+    const WIDTH: usize = SPONGE_WIDTH;
+
+    fn new<I: IntoIterator<Item = T>>(elts: I) -> Self {
+        let mut perm = Self {
+            state: [T::default(); SPONGE_WIDTH],
+        };
+        perm.set_from_iter(elts, 0);
+        perm
+    }
+
+    fn set_elt(&mut self, elt: T, idx: usize) {
+        self.state[idx] = elt;
+    }
+
+    fn set_from_slice(&mut self, elts: &[T], start_idx: usize) {
+        let begin = start_idx;
+        let end = start_idx + elts.len();
+        self.state[begin..end].copy_from_slice(elts);
+    }
+
+    fn set_from_iter<I: IntoIterator<Item = T>>(&mut self, elts: I, start_idx: usize) {
+        for (s, e) in self.state[start_idx..].iter_mut().zip(elts) {
+            *s = e;
+        }
+    }
+
+    fn permute(&mut self) {
+        self.state = T::permute(self.state);
+    }
+
+    fn squeeze(&self) -> &[T] {
+        &self.state[..Self::RATE]
+    }
+}
+
+/// Poseidon2 hash function.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Poseidon2Hash;
+impl<F: RichField + Poseidon2> Hasher<F> for Poseidon2Hash {
+    const HASH_SIZE: usize = 4 * 8;
+    type Hash = HashOut<F>;
+    type Permutation = Poseidon2Permutation<F>;
+
+    fn hash_no_pad(input: &[F]) -> Self::Hash {
+        hash_n_to_hash_no_pad::<F, Self::Permutation>(input)
+    }
+
+    fn two_to_one(left: Self::Hash, right: Self::Hash) -> Self::Hash {
+        compress::<F, Self::Permutation>(left, right)
+    }
+}
+
+impl<F: RichField + Poseidon2> AlgebraicHasher<F> for Poseidon2Hash {
+    type AlgebraicPermutation = Poseidon2Permutation<Target>;
+
+    fn permute_swapped<const D: usize>(
+        inputs: Self::AlgebraicPermutation,
+        swap: BoolTarget,
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self::AlgebraicPermutation
+    where
+        F: RichField + Extendable<D>,
+    {
+        let gate_type = Poseidon2Gate::<F, D>::new();
+        let gate = builder.add_gate(gate_type, vec![]);
+
+        let swap_wire = Poseidon2Gate::<F, D>::WIRE_SWAP;
+        let swap_wire = Target::wire(gate, swap_wire);
+        builder.connect(swap.target, swap_wire);
+
+        // Route input wires.
+        let inputs = inputs.as_ref();
+        for i in 0..SPONGE_WIDTH {
+            let in_wire = Poseidon2Gate::<F, D>::wire_input(i);
+            let in_wire = Target::wire(gate, in_wire);
+            builder.connect(inputs[i], in_wire);
+        }
+
+        // Collect output wires.
+        Self::AlgebraicPermutation::new(
+            (0..SPONGE_WIDTH).map(|i| Target::wire(gate, Poseidon2Gate::<F, D>::wire_output(i))),
+        )
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_helpers {
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    pub(crate) fn check_test_vectors<F: Poseidon2>(
+        test_vectors: Vec<([u64; SPONGE_WIDTH], [u64; SPONGE_WIDTH])>,
+    ) {
+        for (input_, expected_output_) in test_vectors.into_iter() {
+            let input = input_.map(F::from_canonical_u64);
+            let output = F::poseidon2(input);
+            for i in 0..SPONGE_WIDTH {
+                let ex_output = F::from_canonical_u64(expected_output_[i]);
+                assert_eq!(output[i], ex_output);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::{GenericConfig, Poseidon2GoldilocksConfig};
+    use crate::util::serialization::{Poseidon2GateSerializer, Poseidon2GeneratorSerializer};
+
+    #[test]
+    fn test_circuit_hash_matches_native() -> Result<()> {
+        const D: usize = 2;
+        type C = Poseidon2GoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let inputs = F::rand_vec(19);
+        let expected = Poseidon2Hash::hash_no_pad(&inputs);
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let input_targets = builder.add_virtual_targets(inputs.len());
+        let hash = builder.hash_n_to_hash_no_pad::<Poseidon2Hash>(input_targets.clone());
+        let expected_target = builder.constant_hash(expected);
+        builder.connect_hashes(hash, expected_target);
+
+        let mut pw = PartialWitness::new();
+        pw.set_target_arr(&input_targets, &inputs);
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_circuit_serialization() -> Result<()> {
+        const D: usize = 2;
+        type C = Poseidon2GoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let input_targets = builder.add_virtual_targets(8);
+        let hash = builder.hash_n_to_hash_no_pad::<Poseidon2Hash>(input_targets.clone());
+        builder.register_public_inputs(&hash.elements);
+        let data = builder.build::<C>();
+
+        let gate_serializer = Poseidon2GateSerializer;
+        let generator_serializer = Poseidon2GeneratorSerializer::<C, D>::default();
+        let bytes = data
+            .to_bytes(&gate_serializer, &generator_serializer)
+            .unwrap();
+        let data_from_bytes =
+            CircuitData::<F, C, D>::from_bytes(&bytes, &gate_serializer, &generator_serializer)
+                .unwrap();
+        assert_eq!(data.common, data_from_bytes.common);
+
+        let inputs = F::rand_vec(8);
+        let mut pw = PartialWitness::new();
+        pw.set_target_arr(&input_targets, &inputs);
+        let proof = data_from_bytes.prove(pw)?;
+        assert_eq!(
+            proof.public_inputs,
+            Poseidon2Hash::hash_no_pad(&inputs).elements
+        );
+        data.verify(proof)
+    }
+}
@@ -4,10 +4,20 @@
 //! `poseidon_constants.sage` script in the `0xPolygonZero/hash-constants`
 //! repository.
 
+#[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+use core::any::Any;
+
+#[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+use plonky2_field::extension::{Extendable, FieldExtension};
+#[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+use plonky2_field::packable::Packable;
+use plonky2_field::packed::PackedField;
 #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
 use plonky2_field::types::Field;
 
 use crate::field::goldilocks_field::GoldilocksField;
+#[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+use crate::hash::hash_types::RichField;
 use crate::hash::poseidon::{Poseidon, N_PARTIAL_ROUNDS};
 
 #[rustfmt::skip]
@@ -247,6 +257,43 @@ impl Poseidon for GoldilocksField {
         result
     }
 
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+    #[inline]
+    fn mds_layer_packed_field<
+        F: RichField + Extendable<D>,
+        const D: usize,
+        FE,
+        P,
+        const D2: usize,
+    >(
+        state: &[P; 12],
+    ) -> [P; 12]
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        // The AVX2 packing of `GoldilocksField` has its own MDS layer, which multiplies by the
+        // small entries of the matrix much faster than generic packed multiplications.
+        type Packing = <GoldilocksField as Packable>::Packing;
+        if Packing::WIDTH == 4 {
+            if let Some(state) = (state as &dyn Any).downcast_ref::<[Packing; 12]>() {
+                let result = crate::hash::arch::x86_64::poseidon_goldilocks_avx2::mds_layer_packed(state);
+                return *(&result as &dyn Any).downcast_ref().unwrap();
+            }
+        }
+
+        let mut result = [P::ZEROS; 12];
+        for r in 0..12 {
+            result[r] = Self::mds_row_shf_packed_field(r, state);
+        }
+        result
+    }
+
+    #[inline]
+    fn poseidon_packed<P: PackedField<Scalar = Self>>(input: [P; 12]) -> [P; 12] {
+        Self::poseidon_packed_field::<Self, 1, Self, P, 1>(input)
+    }
+
     // #[cfg(all(target_arch="x86_64", target_feature="avx2", target_feature="bmi2"))]
     // #[inline]
     // fn poseidon(input: [Self; 12]) -> [Self; 12] {
@@ -448,8 +495,12 @@ mod tests {
     use alloc::{vec, vec::Vec};
 
     use crate::field::goldilocks_field::GoldilocksField as F;
-    use crate::field::types::{Field, PrimeField64};
-    use crate::hash::poseidon::test_helpers::{check_consistency, check_test_vectors};
+    use crate::field::types::{Field, PrimeField64, Sample};
+    use crate::hash::poseidon::test_helpers::{
+        check_consistency, check_packed_consistency, check_test_vectors,
+    };
+    use crate::hash::poseidon::PoseidonHash;
+    use crate::plonk::config::Hasher;
 
     #[test]
     fn test_vectors() {
@@ -493,4 +544,26 @@ mod tests {
     fn consistency() {
         check_consistency::<F>();
     }
+
+    #[test]
+    fn packed_consistency() {
+        check_packed_consistency::<F>();
+    }
+
+    #[test]
+    fn hash_or_noop_batch() {
+        // Groups of inputs of equal lengths, which are hashed at once when `F` has a packing,
+        // followed by inputs of different lengths and a partial group.
+        let mut inputs = Vec::new();
+        for len in [0, 4, 5, 8, 9, 16, 135] {
+            inputs.extend((0..8).map(|_| F::rand_vec(len)));
+        }
+        inputs.extend((0..7).map(|i| F::rand_vec(3 * i)));
+
+        let expected = inputs
+            .iter()
+            .map(|input| PoseidonHash::hash_or_noop(input))
+            .collect::<Vec<_>>();
+        assert_eq!(PoseidonHash::hash_or_noop_batch(&inputs), expected);
+    }
 }
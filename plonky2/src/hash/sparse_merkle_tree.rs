@@ -0,0 +1,744 @@
+//! Sparse Merkle trees, keyed by up to 256-bit keys, and gadgets to verify their membership and
+//! update proofs in circuits.
+//!
+//! A sparse Merkle tree of depth `d` has a leaf for each of the `2^d` keys, and the leaf of a key
+//! `k` is at index `k` of a dense tree of height `d`. Absent keys hold the all-zero value, so a
+//! non-membership proof is a membership proof of the zero value, and setting a key to zero removes
+//! it. Only non-empty subtrees are stored, and the digests of empty subtrees are precomputed.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use anyhow::{ensure, Result};
+use hashbrown::HashMap;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::{HashOut, HashOutTarget, MerkleCapTarget, RichField};
+use crate::hash::merkle_proofs::{MerkleProof, MerkleProofTarget};
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::{CircuitHasher, Hasher};
+
+/// Maximum depth of a sparse Merkle tree, which is the number of bits of a key.
+pub const MAX_SMT_DEPTH: usize = 256;
+
+/// A key in a sparse Merkle tree, as a 256-bit integer given by its little-endian 64-bit limbs.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct SmtKey(pub [u64; 4]);
+
+impl SmtKey {
+    /// The key given by the canonical value of a field element.
+    pub fn from_field<F: RichField>(key: F) -> Self {
+        Self([key.to_canonical_u64(), 0, 0, 0])
+    }
+
+    /// The 256-bit key given by the canonical values of the elements of a hash.
+    pub fn from_hash<F: RichField>(key: HashOut<F>) -> Self {
+        Self(key.elements.map(|x| x.to_canonical_u64()))
+    }
+
+    /// Returns the `i`-th bit of the key, starting from the least significant one.
+    pub fn bit(&self, i: usize) -> bool {
+        (self.0[i / 64] >> (i % 64)) & 1 == 1
+    }
+
+    /// Returns the `n` least significant bits of the key, in little-endian order.
+    pub fn to_bits(&self, n: usize) -> Vec<bool> {
+        (0..n).map(|i| self.bit(i)).collect()
+    }
+
+    /// Returns whether the key fits in `num_bits` bits.
+    fn fits_in(&self, num_bits: usize) -> bool {
+        (num_bits..MAX_SMT_DEPTH).all(|i| !self.bit(i))
+    }
+
+    /// The key of the node at height `height` on the path of this key, i.e. this key with its
+    /// `height` least significant bits cleared.
+    fn ancestor(mut self, height: usize) -> Self {
+        for (i, limb) in self.0.iter_mut().enumerate() {
+            let cleared_bits = height.saturating_sub(64 * i).min(64);
+            if cleared_bits == 64 {
+                *limb = 0;
+            } else {
+                *limb &= !((1 << cleared_bits) - 1);
+            }
+        }
+        self
+    }
+
+    /// The key of the sibling of the node at height `height` on the path of this key.
+    fn sibling(self, height: usize) -> Self {
+        let mut ancestor = self.ancestor(height);
+        ancestor.0[height / 64] ^= 1 << (height % 64);
+        ancestor
+    }
+}
+
+/// A sparse Merkle tree, whose leaves are values of a fixed length.
+#[derive(Clone, Debug)]
+pub struct SparseMerkleTree<F: RichField, H: Hasher<F>> {
+    depth: usize,
+    value_len: usize,
+    /// The values of the keys which are present in the tree.
+    values: HashMap<SmtKey, Vec<F>>,
+    /// The digests of the non-empty nodes, indexed by their height and the key of their leftmost
+    /// leaf.
+    nodes: HashMap<(usize, SmtKey), H::Hash>,
+    /// The digests of empty subtrees of each height, from `0` to `depth`.
+    empty_digests: Vec<H::Hash>,
+}
+
+impl<F: RichField, H: Hasher<F>> SparseMerkleTree<F, H> {
+    /// Creates an empty tree of the given depth, whose values are `value_len` field elements.
+    pub fn new(depth: usize, value_len: usize) -> Self {
+        assert!(
+            depth <= MAX_SMT_DEPTH,
+            "Sparse Merkle trees have depth at most {MAX_SMT_DEPTH}, got {depth}"
+        );
+
+        let mut empty_digests = Vec::with_capacity(depth + 1);
+        empty_digests.push(H::hash_or_noop(&vec![F::ZERO; value_len]));
+        for height in 0..depth {
+            let child = empty_digests[height];
+            empty_digests.push(H::two_to_one(child, child));
+        }
+
+        Self {
+            depth,
+            value_len,
+            values: HashMap::new(),
+            nodes: HashMap::new(),
+            empty_digests,
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn value_len(&self) -> usize {
+        self.value_len
+    }
+
+    /// The digest of the empty subtree of the given height.
+    pub fn empty_digest(&self, height: usize) -> H::Hash {
+        self.empty_digests[height]
+    }
+
+    pub fn root(&self) -> H::Hash {
+        self.node(self.depth, SmtKey::default())
+    }
+
+    /// Returns the value of `key`, which is all zeros if `key` is absent.
+    pub fn get(&self, key: SmtKey) -> Vec<F> {
+        self.check_key(key);
+        self.values
+            .get(&key)
+            .cloned()
+            .unwrap_or_else(|| vec![F::ZERO; self.value_len])
+    }
+
+    pub fn contains(&self, key: SmtKey) -> bool {
+        self.check_key(key);
+        self.values.contains_key(&key)
+    }
+
+    /// Returns a proof that `key` has the value returned by `get`. If `key` is absent, this is a
+    /// non-membership proof.
+    pub fn prove(&self, key: SmtKey) -> MerkleProof<F, H> {
+        self.check_key(key);
+        MerkleProof {
+            siblings: (0..self.depth)
+                .map(|height| self.node(height, key.sibling(height)))
+                .collect(),
+        }
+    }
+
+    /// Sets the value of `key`, and returns a proof of this update. Setting a key to the zero value
+    /// removes it.
+    pub fn update(&mut self, key: SmtKey, value: Vec<F>) -> SmtUpdateProof<F, H> {
+        assert_eq!(value.len(), self.value_len, "Invalid value length");
+        let old_root = self.root();
+        let old_value = self.get(key);
+        let proof = self.prove(key);
+
+        if value.iter().all(|x| x.is_zero()) {
+            self.values.remove(&key);
+        } else {
+            self.values.insert(key, value.clone());
+        }
+
+        let mut digest = H::hash_or_noop(&value);
+        self.set_node(0, key, digest);
+        for (height, &sibling) in proof.siblings.iter().enumerate() {
+            digest = if key.bit(height) {
+                H::two_to_one(sibling, digest)
+            } else {
+                H::two_to_one(digest, sibling)
+            };
+            self.set_node(height + 1, key.ancestor(height + 1), digest);
+        }
+
+        SmtUpdateProof {
+            key,
+            old_value,
+            new_value: value,
+            old_root,
+            new_root: digest,
+            proof,
+        }
+    }
+
+    fn node(&self, height: usize, key: SmtKey) -> H::Hash {
+        self.nodes
+            .get(&(height, key))
+            .copied()
+            .unwrap_or(self.empty_digests[height])
+    }
+
+    fn set_node(&mut self, height: usize, key: SmtKey, digest: H::Hash) {
+        if digest == self.empty_digests[height] {
+            self.nodes.remove(&(height, key));
+        } else {
+            self.nodes.insert((height, key), digest);
+        }
+    }
+
+    fn check_key(&self, key: SmtKey) {
+        assert!(
+            key.fits_in(self.depth),
+            "Key does not fit in {} bits",
+            self.depth
+        );
+    }
+}
+
+/// A proof that updating the value of `key` from `old_value` to `new_value` changes the root of a
+/// sparse Merkle tree from `old_root` to `new_root`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SmtUpdateProof<F: RichField, H: Hasher<F>> {
+    pub key: SmtKey,
+    pub old_value: Vec<F>,
+    pub new_value: Vec<F>,
+    pub old_root: H::Hash,
+    pub new_root: H::Hash,
+    /// The siblings of the path of `key`, which are unchanged by the update.
+    pub proof: MerkleProof<F, H>,
+}
+
+/// Computes the root of a sparse Merkle tree in which `key` has the given value, from the siblings
+/// of its path.
+fn smt_root<F: RichField, H: Hasher<F>>(
+    key: SmtKey,
+    value: &[F],
+    proof: &MerkleProof<F, H>,
+) -> H::Hash {
+    let mut digest = H::hash_or_noop(value);
+    for (height, &sibling) in proof.siblings.iter().enumerate() {
+        digest = if key.bit(height) {
+            H::two_to_one(sibling, digest)
+        } else {
+            H::two_to_one(digest, sibling)
+        };
+    }
+    digest
+}
+
+/// Verifies that `key` has the given value in the sparse Merkle tree with the given root, depth and
+/// value length. A zero value proves that `key` is absent.
+///
+/// The depth and value length must come from the verifier rather than the proof: with a shorter
+/// proof, an internal node could otherwise be opened as a leaf whose value is its digest.
+pub fn verify_smt_membership<F: RichField, H: Hasher<F>>(
+    key: SmtKey,
+    value: &[F],
+    root: H::Hash,
+    proof: &MerkleProof<F, H>,
+    depth: usize,
+    value_len: usize,
+) -> Result<()> {
+    ensure!(
+        depth <= MAX_SMT_DEPTH && key.fits_in(depth),
+        "Key does not fit in the tree."
+    );
+    ensure!(proof.len() == depth, "Invalid sparse Merkle proof length.");
+    ensure!(value.len() == value_len, "Invalid value length.");
+    ensure!(
+        smt_root(key, value, proof) == root,
+        "Invalid sparse Merkle proof."
+    );
+    Ok(())
+}
+
+/// Verifies a proof of an update of a sparse Merkle tree with the given depth and value length.
+pub fn verify_smt_update<F: RichField, H: Hasher<F>>(
+    update: &SmtUpdateProof<F, H>,
+    depth: usize,
+    value_len: usize,
+) -> Result<()> {
+    ensure!(update.new_value.len() == value_len, "Invalid value length.");
+    verify_smt_membership(
+        update.key,
+        &update.old_value,
+        update.old_root,
+        &update.proof,
+        depth,
+        value_len,
+    )?;
+    ensure!(
+        smt_root(update.key, &update.new_value, &update.proof) == update.new_root,
+        "Invalid sparse Merkle update."
+    );
+    Ok(())
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Returns the `depth` little-endian bits of the key given by the canonical value of `key`, as
+    /// in [`SmtKey::from_field`], and checks that the key fits in `depth` bits.
+    ///
+    /// The bits are checked to be canonical: with `split_le(key, 64)`, a key `x` could also be
+    /// opened at the leaf `x + p` whenever that fits in 64 bits.
+    pub fn smt_key_bits(&mut self, key: Target, depth: usize) -> Vec<BoolTarget> {
+        self.smt_key_bits_from_elements(&[key], depth)
+    }
+
+    /// Returns the `depth` little-endian bits of the key given by the canonical values of the
+    /// elements of `key`, as in [`SmtKey::from_hash`], and checks that the key fits in `depth`
+    /// bits.
+    pub fn smt_key_bits_from_hash(&mut self, key: HashOutTarget, depth: usize) -> Vec<BoolTarget> {
+        self.smt_key_bits_from_elements(&key.elements, depth)
+    }
+
+    fn smt_key_bits_from_elements(&mut self, limbs: &[Target], depth: usize) -> Vec<BoolTarget> {
+        assert!(
+            depth <= 64 * limbs.len(),
+            "Keys of {} elements have at most {} bits, got depth {depth}",
+            limbs.len(),
+            64 * limbs.len()
+        );
+
+        let mut bits = Vec::with_capacity(depth);
+        for (i, &limb) in limbs.iter().enumerate() {
+            let num_bits = depth.saturating_sub(64 * i).min(64);
+            // Values of less than 64 bits are below the field order, so their bits are canonical.
+            let limb_bits = self.split_le(limb, num_bits);
+            if num_bits == 64 {
+                assert_canonical_bits(self, &limb_bits);
+            }
+            bits.extend(limb_bits);
+        }
+        bits
+    }
+
+    /// Verifies that the key with the given little-endian bits has the given value in the sparse
+    /// Merkle tree with the given root. A zero value proves that the key is absent.
+    ///
+    /// Keys given by field elements should be decomposed with [`Self::smt_key_bits`] or
+    /// [`Self::smt_key_bits_from_hash`], whose bits are canonical.
+    pub fn verify_smt_membership<H: CircuitHasher<F>>(
+        &mut self,
+        root: HashOutTarget,
+        key_bits: &[BoolTarget],
+        value: Vec<Target>,
+        proof: &MerkleProofTarget,
+    ) {
+        assert_eq!(
+            key_bits.len(),
+            proof.siblings.len(),
+            "Keys must have one bit per level of the tree"
+        );
+        let cap = MerkleCapTarget(vec![root]);
+        self.verify_merkle_proof_to_cap::<H>(value, key_bits, &cap, proof);
+    }
+
+    /// Verifies that the key with the given little-endian bits is absent from the sparse Merkle
+    /// tree with the given root, whose values have `value_len` elements.
+    pub fn verify_smt_non_membership<H: CircuitHasher<F>>(
+        &mut self,
+        root: HashOutTarget,
+        key_bits: &[BoolTarget],
+        value_len: usize,
+        proof: &MerkleProofTarget,
+    ) {
+        let zero = self.zero();
+        self.verify_smt_membership::<H>(root, key_bits, vec![zero; value_len], proof);
+    }
+
+    /// Verifies that the key with the given little-endian bits has the value `old_value` in the
+    /// sparse Merkle tree with root `old_root`, and returns the root of the tree after setting its
+    /// value to `new_value`. Insertions and deletions are updates from and to the zero value.
+    pub fn process_smt_update<H: CircuitHasher<F>>(
+        &mut self,
+        old_root: HashOutTarget,
+        key_bits: &[BoolTarget],
+        old_value: Vec<Target>,
+        new_value: Vec<Target>,
+        proof: &MerkleProofTarget,
+    ) -> HashOutTarget {
+        assert_eq!(
+            old_value.len(),
+            new_value.len(),
+            "Old and new values must have the same length"
+        );
+        self.verify_smt_membership::<H>(old_root, key_bits, old_value, proof);
+
+        // The siblings are unchanged by the update, so the new root is computed from the same path.
+        let mut state = H::hash_or_noop_circuit(new_value, self);
+        for (&bit, &sibling) in key_bits.iter().zip(&proof.siblings) {
+            state = H::two_to_one_swapped_circuit(state, sibling, bit, self);
+        }
+        state
+    }
+}
+
+/// Checks that the 64 little-endian bits `bits` represent a value less than the field order.
+fn assert_canonical_bits<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    bits: &[BoolTarget],
+) {
+    assert_eq!(bits.len(), 64);
+    // From the most significant bit, while the bits match those of `p - 1`, a set bit where
+    // `p - 1` has a zero bit means the value is at least `p`.
+    let max = F::ORDER - 1;
+    let mut prefix_matches = builder._true();
+    for (i, &bit) in bits.iter().enumerate().rev() {
+        if (max >> i) & 1 == 1 {
+            prefix_matches = builder.and(prefix_matches, bit);
+        } else {
+            let exceeds = builder.and(prefix_matches, bit);
+            builder.assert_zero(exceeds.target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::{Field, Field64, Sample};
+    use crate::hash::keccak::KeccakHash;
+    use crate::hash::poseidon::PoseidonHash;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, GenericHashOut, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn prove_and_verify(builder: CircuitBuilder<F, D>, pw: PartialWitness<F>) -> Result<()> {
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    fn key(k: u64) -> SmtKey {
+        SmtKey::from_field(F::from_canonical_u64(k))
+    }
+
+    #[test]
+    fn test_smt_empty_root() {
+        let tree = SparseMerkleTree::<F, PoseidonHash>::new(3, 2);
+        let leaves = vec![vec![F::ZERO; 2]; 8];
+        let dense = crate::hash::merkle_tree::MerkleTree::<F, PoseidonHash>::new(leaves, 0);
+        assert_eq!(tree.root(), dense.cap.0[0]);
+    }
+
+    #[test]
+    fn test_smt_matches_dense_tree() -> Result<()> {
+        let depth = 4;
+        let value_len = 3;
+        let mut tree = SparseMerkleTree::<F, PoseidonHash>::new(depth, value_len);
+        let mut leaves = vec![vec![F::ZERO; value_len]; 1 << depth];
+        for k in [0, 3, 7, 8, 15] {
+            let value = F::rand_vec(value_len);
+            let update = tree.update(key(k), value.clone());
+            verify_smt_update(&update, depth, value_len)?;
+            leaves[k as usize] = value;
+        }
+
+        let dense = crate::hash::merkle_tree::MerkleTree::<F, PoseidonHash>::new(leaves, 0);
+        assert_eq!(tree.root(), dense.cap.0[0]);
+        for k in 0..1 << depth {
+            assert_eq!(tree.prove(key(k)), dense.prove(k as usize));
+            verify_smt_membership(
+                key(k),
+                &tree.get(key(k)),
+                tree.root(),
+                &tree.prove(key(k)),
+                depth,
+                value_len,
+            )?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_smt_membership_and_non_membership() -> Result<()> {
+        let mut tree = SparseMerkleTree::<F, PoseidonHash>::new(64, 2);
+        let present = SmtKey::from_field(F::NEG_ONE);
+        let absent = key(12345);
+        let value = vec![F::ONE, F::TWO];
+        tree.update(present, value.clone());
+
+        assert!(tree.contains(present));
+        assert!(!tree.contains(absent));
+        let verify = |key, value: &[F]| {
+            verify_smt_membership(key, value, tree.root(), &tree.prove(key), 64, 2)
+        };
+        verify(present, &value)?;
+        verify(absent, &[F::ZERO; 2])?;
+        assert!(verify(absent, &value).is_err());
+        assert!(verify(present, &[F::ZERO; 2]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_smt_deletion_restores_root() -> Result<()> {
+        let mut tree = SparseMerkleTree::<F, PoseidonHash>::new(MAX_SMT_DEPTH, 4);
+        let empty_root = tree.root();
+        let keys = (0..5)
+            .map(|_| SmtKey::from_hash(HashOut::<F>::rand()))
+            .collect::<Vec<_>>();
+
+        for &k in &keys {
+            verify_smt_update(&tree.update(k, F::rand_vec(4)), MAX_SMT_DEPTH, 4)?;
+        }
+        let root = tree.root();
+        assert_ne!(root, empty_root);
+
+        // Updating a key to its current value leaves the root unchanged.
+        let value = tree.get(keys[2]);
+        let update = tree.update(keys[2], value);
+        assert_eq!(update.old_root, update.new_root);
+
+        for &k in &keys {
+            verify_smt_update(&tree.update(k, vec![F::ZERO; 4]), MAX_SMT_DEPTH, 4)?;
+        }
+        assert_eq!(tree.root(), empty_root);
+        assert!(tree.nodes.is_empty());
+        assert!(tree.values.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_smt_invalid_update() {
+        let mut tree = SparseMerkleTree::<F, PoseidonHash>::new(8, 1);
+        tree.update(key(1), vec![F::ONE]);
+        let mut update = tree.update(key(2), vec![F::TWO]);
+        update.old_value = vec![F::ONE];
+        assert!(verify_smt_update(&update, 8, 1).is_err());
+    }
+
+    #[test]
+    fn test_smt_shortened_proof() {
+        let depth = 8;
+        let mut tree = SparseMerkleTree::<F, PoseidonHash>::new(depth, 4);
+        let k = key(6);
+        tree.update(k, F::rand_vec(4));
+
+        // Values of four elements are not hashed, so the node of height 1 on the path of `k`
+        // opens like a leaf of a tree of depth 7, at the key given by the remaining bits.
+        let node = tree.node(1, k.ancestor(1));
+        let mut proof = tree.prove(k);
+        proof.siblings.remove(0);
+        let shifted_key = key(6 >> 1);
+        assert_eq!(smt_root(shifted_key, &node.elements, &proof), tree.root());
+
+        assert!(
+            verify_smt_membership(shifted_key, &node.elements, tree.root(), &proof, depth, 4)
+                .is_err()
+        );
+        assert!(verify_smt_membership(
+            shifted_key,
+            &node.elements,
+            tree.root(),
+            &proof,
+            depth - 1,
+            4
+        )
+        .is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "Key does not fit")]
+    fn test_smt_key_too_large() {
+        let tree = SparseMerkleTree::<F, PoseidonHash>::new(8, 1);
+        tree.get(key(256));
+    }
+
+    fn add_proof<H: Hasher<F>>(
+        builder: &mut CircuitBuilder<F, D>,
+        pw: &mut PartialWitness<F>,
+        proof: &MerkleProof<F, H>,
+    ) -> MerkleProofTarget {
+        let proof_t = MerkleProofTarget {
+            siblings: builder.add_virtual_hashes(proof.len()),
+        };
+        for (t, sibling) in proof_t.siblings.iter().zip(&proof.siblings) {
+            pw.set_target_arr(&t.elements, &sibling.to_vec());
+        }
+        proof_t
+    }
+
+    /// Adds `key` to the witness, and returns its bits as given by `smt_key_bits_from_hash`.
+    fn add_key_bits(
+        builder: &mut CircuitBuilder<F, D>,
+        pw: &mut PartialWitness<F>,
+        key: SmtKey,
+        depth: usize,
+    ) -> Vec<BoolTarget> {
+        let key_t = builder.add_virtual_hash();
+        pw.set_target_arr(&key_t.elements, &key.0.map(F::from_canonical_u64));
+        builder.smt_key_bits_from_hash(key_t, depth)
+    }
+
+    /// Builds a circuit which processes `updates` in order, starting from their first old root, and
+    /// checks the membership of `key` in the resulting tree. Keys and proofs are witnesses.
+    fn updates_circuit<H: CircuitHasher<F>>(
+        depth: usize,
+        updates: &[SmtUpdateProof<F, H>],
+        membership: (SmtKey, Vec<F>, MerkleProof<F, H>),
+    ) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        let initial_root = builder.add_virtual_hash();
+        let mut root = initial_root;
+        for update in updates {
+            let proof = add_proof(&mut builder, &mut pw, &update.proof);
+            let bits = add_key_bits(&mut builder, &mut pw, update.key, depth);
+            let old_value = builder.constants(&update.old_value);
+            let new_value = builder.constants(&update.new_value);
+            root = builder.process_smt_update::<H>(root, &bits, old_value, new_value, &proof);
+        }
+
+        let (key, value, proof) = membership;
+        let proof = add_proof(&mut builder, &mut pw, &proof);
+        let bits = add_key_bits(&mut builder, &mut pw, key, depth);
+        let value = builder.constants(&value);
+        builder.verify_smt_membership::<H>(root, &bits, value, &proof);
+
+        pw.set_target_arr(&initial_root.elements, &updates[0].old_root.to_vec());
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    fn test_process_smt_updates() -> Result<()> {
+        let depth = 32;
+        let mut tree = SparseMerkleTree::<F, PoseidonHash>::new(depth, 2);
+        tree.update(key(5), F::rand_vec(2));
+
+        let updates = vec![
+            // Insertion.
+            tree.update(key(7), F::rand_vec(2)),
+            // Update.
+            tree.update(key(7), F::rand_vec(2)),
+            // Deletion.
+            tree.update(key(5), vec![F::ZERO; 2]),
+        ];
+        let absent = key(5);
+        updates_circuit(
+            depth,
+            &updates,
+            (absent, tree.get(absent), tree.prove(absent)),
+        )
+    }
+
+    #[test]
+    fn test_process_smt_updates_256_bit_keys() -> Result<()> {
+        let mut tree = SparseMerkleTree::<F, PoseidonHash>::new(MAX_SMT_DEPTH, 4);
+        let k = SmtKey::from_hash(HashOut::<F>::rand());
+        let updates = vec![tree.update(k, F::rand_vec(4))];
+        updates_circuit(MAX_SMT_DEPTH, &updates, (k, tree.get(k), tree.prove(k)))
+    }
+
+    #[test]
+    fn test_process_smt_updates_keccak() -> Result<()> {
+        let depth = 4;
+        let mut tree = SparseMerkleTree::<F, KeccakHash<25>>::new(depth, 3);
+        let updates = vec![tree.update(key(9), F::rand_vec(3))];
+        updates_circuit(
+            depth,
+            &updates,
+            (key(9), tree.get(key(9)), tree.prove(key(9))),
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn test_process_smt_update_wrong_old_value() {
+        let depth = 16;
+        let mut tree = SparseMerkleTree::<F, PoseidonHash>::new(depth, 1);
+        tree.update(key(3), vec![F::ONE]);
+        let mut update = tree.update(key(3), vec![F::TWO]);
+        update.old_value = vec![F::ZERO];
+        updates_circuit(depth, &[update], (key(3), vec![F::TWO], tree.prove(key(3)))).unwrap();
+    }
+
+    #[test]
+    fn test_smt_key_bits_field_keys() -> Result<()> {
+        let depth = 64;
+        let mut tree = SparseMerkleTree::<F, PoseidonHash>::new(depth, 2);
+        let keys = [F::ZERO, F::ONE, F::from_canonical_u64(1 << 63), F::NEG_ONE];
+        for &k in &keys {
+            tree.update(SmtKey::from_field(k), F::rand_vec(2));
+        }
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+        let root = builder.add_virtual_hash();
+        pw.set_hash_target(root, tree.root());
+        for &k in &keys {
+            let key = SmtKey::from_field(k);
+            let key_t = builder.add_virtual_target();
+            pw.set_target(key_t, k);
+            let bits = builder.smt_key_bits(key_t, depth);
+            let value = builder.constants(&tree.get(key));
+            let proof = add_proof(&mut builder, &mut pw, &tree.prove(key));
+            builder.verify_smt_membership::<PoseidonHash>(root, &bits, value, &proof);
+        }
+        prove_and_verify(builder, pw)
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn test_smt_key_bits_too_large() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+        let key = builder.add_virtual_target();
+        builder.smt_key_bits(key, 8);
+        pw.set_target(key, F::from_canonical_u64(256));
+        prove_and_verify(builder, pw).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn test_smt_key_bits_non_canonical() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        // The bits of `5 + p`, which `split_le(5, 64)` would also accept.
+        let aliased = 5 + F::ORDER;
+        let bits = (0..64)
+            .map(|_| builder.add_virtual_bool_target_safe())
+            .collect::<Vec<_>>();
+        for (i, &bit) in bits.iter().enumerate() {
+            pw.set_bool_target(bit, (aliased >> i) & 1 == 1);
+        }
+        let sum = bits
+            .iter()
+            .enumerate()
+            .fold(builder.zero(), |acc, (i, bit)| {
+                builder.mul_const_add(F::from_canonical_u64(1 << i), bit.target, acc)
+            });
+        let five = builder.constant(F::from_canonical_u64(5));
+        builder.connect(sum, five);
+        assert_canonical_bits(&mut builder, &bits);
+        prove_and_verify(builder, pw).unwrap();
+    }
+}
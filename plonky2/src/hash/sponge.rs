@@ -0,0 +1,443 @@
+//! Stateful sponges, to absorb and squeeze field elements incrementally, natively and in circuits.
+//!
+//! [`Sponge`] and [`SpongeTarget`] follow the same absorb/squeeze schedule, so that a circuit
+//! using [`SpongeTarget`] computes exactly the outputs of [`Sponge`] on the same inputs. With
+//! [`SpongePadding::NoPad`] and no domain separator, absorbing a message then squeezing gives the
+//! same outputs as [`hash_n_to_m_no_pad`](crate::hash::hashing::hash_n_to_m_no_pad), and with
+//! [`SpongePadding::Pad10Star1`] the same outputs as [`Hasher::hash_pad`].
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::{HashOut, HashOutTarget, RichField, NUM_HASH_OUT_ELTS};
+use crate::hash::hashing::PlonkyPermutation;
+use crate::iop::target::Target;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::{CircuitHasher, Hasher};
+
+/// How a sponge pads the inputs absorbed before each squeeze.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SpongePadding {
+    /// Absorb the inputs as they are. Note that this can enable length-extension attacks, so it
+    /// should only be used for inputs of a fixed length.
+    NoPad,
+    /// Pad the inputs using the `pad10*1` rule, as in [`Hasher::hash_pad`].
+    Pad10Star1,
+}
+
+/// Appends `pad10*1` padding to `inputs`, so that its length becomes a multiple of `rate`.
+fn pad<T: Copy>(inputs: &mut Vec<T>, rate: usize, zero: T, one: T) {
+    inputs.push(one);
+    while (inputs.len() + 1) % rate != 0 {
+        inputs.push(zero);
+    }
+    inputs.push(one);
+}
+
+fn assert_valid_domain<F: RichField>(domain: F) {
+    // A sponge without a domain has a zero in the domain element, so zero can't be a domain.
+    assert!(domain.is_nonzero(), "The domain separator must be nonzero");
+}
+
+/// Index of the state element holding the domain separator, which is part of the capacity so that
+/// inputs never overwrite it.
+fn domain_index<T: Copy + Default, P: PlonkyPermutation<T>>() -> usize {
+    assert!(
+        P::WIDTH > P::RATE,
+        "Domain separation requires a sponge with some capacity"
+    );
+    P::WIDTH - 1
+}
+
+/// A sponge in overwrite mode, which can alternate between absorbing inputs and squeezing outputs.
+///
+/// Inputs are buffered until the next squeeze, when they are padded and absorbed. Outputs are then
+/// squeezed in order, permuting the state again whenever the available ones are exhausted.
+#[derive(Clone, Debug)]
+pub struct Sponge<F: RichField, H: Hasher<F>> {
+    state: H::Permutation,
+    padding: SpongePadding,
+    /// Whether inputs were absorbed since the last squeeze. A fresh sponge counts as absorbing an
+    /// empty message.
+    absorbing: bool,
+    input_buffer: Vec<F>,
+    /// Remaining outputs of the last permutation, in reverse order.
+    output_buffer: Vec<F>,
+}
+
+impl<F: RichField, H: Hasher<F>> Sponge<F, H> {
+    pub fn new(padding: SpongePadding) -> Self {
+        Self {
+            state: H::Permutation::new(core::iter::repeat(F::ZERO)),
+            padding,
+            absorbing: true,
+            input_buffer: Vec::new(),
+            output_buffer: Vec::new(),
+        }
+    }
+
+    /// Creates a sponge whose outputs are separated from those of sponges with other `domain`s,
+    /// by setting the last element of its capacity to `domain`, which must be nonzero.
+    pub fn new_with_domain(padding: SpongePadding, domain: F) -> Self {
+        assert_valid_domain(domain);
+        let mut sponge = Self::new(padding);
+        sponge
+            .state
+            .set_elt(domain, domain_index::<F, H::Permutation>());
+        sponge
+    }
+
+    pub fn absorb(&mut self, element: F) {
+        // Any buffered outputs are now invalid, since they wouldn't reflect this input.
+        self.output_buffer.clear();
+        self.absorbing = true;
+        self.input_buffer.push(element);
+    }
+
+    pub fn absorb_elements(&mut self, elements: &[F]) {
+        for &element in elements {
+            self.absorb(element);
+        }
+    }
+
+    pub fn absorb_hash(&mut self, hash: HashOut<F>) {
+        self.absorb_elements(&hash.elements);
+    }
+
+    pub fn squeeze(&mut self) -> F {
+        if self.absorbing {
+            self.absorb_buffered_inputs();
+        } else if self.output_buffer.is_empty() {
+            self.state.permute();
+            self.fill_output_buffer();
+        }
+
+        self.output_buffer
+            .pop()
+            .expect("Output buffer should be non-empty")
+    }
+
+    pub fn squeeze_n(&mut self, n: usize) -> Vec<F> {
+        (0..n).map(|_| self.squeeze()).collect()
+    }
+
+    pub fn squeeze_hash(&mut self) -> HashOut<F> {
+        HashOut::from_vec(self.squeeze_n(NUM_HASH_OUT_ELTS))
+    }
+
+    /// Pads and absorbs any buffered inputs. After calling this, the input buffer will be empty,
+    /// and the output buffer will be full.
+    fn absorb_buffered_inputs(&mut self) {
+        if self.padding == SpongePadding::Pad10Star1 {
+            pad(
+                &mut self.input_buffer,
+                H::Permutation::RATE,
+                F::ZERO,
+                F::ONE,
+            );
+        }
+
+        for input_chunk in self.input_buffer.chunks(H::Permutation::RATE) {
+            // Overwrite the first r elements with the inputs, as in `hash_n_to_m_no_pad`.
+            self.state.set_from_slice(input_chunk, 0);
+            self.state.permute();
+        }
+        self.input_buffer.clear();
+        self.absorbing = false;
+        self.fill_output_buffer();
+    }
+
+    fn fill_output_buffer(&mut self) {
+        self.output_buffer.clear();
+        self.output_buffer
+            .extend(self.state.squeeze().iter().rev().copied());
+    }
+}
+
+/// A circuit version of [`Sponge`], which constrains its outputs to be those [`Sponge`] would
+/// squeeze after absorbing the same inputs.
+#[derive(Clone, Debug)]
+pub struct SpongeTarget<F: RichField + Extendable<D>, H: CircuitHasher<F>, const D: usize> {
+    state: H::CircuitPermutation,
+    padding: SpongePadding,
+    absorbing: bool,
+    input_buffer: Vec<Target>,
+    output_buffer: Vec<Target>,
+    _phantom: PhantomData<(F, H)>,
+}
+
+impl<F: RichField + Extendable<D>, H: CircuitHasher<F>, const D: usize> SpongeTarget<F, H, D> {
+    pub fn new(builder: &mut CircuitBuilder<F, D>, padding: SpongePadding) -> Self {
+        let zero = builder.zero();
+        Self {
+            state: H::CircuitPermutation::new(core::iter::repeat(zero)),
+            padding,
+            absorbing: true,
+            input_buffer: Vec::new(),
+            output_buffer: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a sponge with the domain separator `domain`, as in [`Sponge::new_with_domain`].
+    pub fn new_with_domain(
+        builder: &mut CircuitBuilder<F, D>,
+        padding: SpongePadding,
+        domain: F,
+    ) -> Self {
+        assert_valid_domain(domain);
+        let mut sponge = Self::new(builder, padding);
+        let domain = builder.constant(domain);
+        sponge
+            .state
+            .set_elt(domain, domain_index::<Target, H::CircuitPermutation>());
+        sponge
+    }
+
+    pub fn absorb(&mut self, target: Target) {
+        // Any buffered outputs are now invalid, since they wouldn't reflect this input.
+        self.output_buffer.clear();
+        self.absorbing = true;
+        self.input_buffer.push(target);
+    }
+
+    pub fn absorb_elements(&mut self, targets: &[Target]) {
+        for &target in targets {
+            self.absorb(target);
+        }
+    }
+
+    pub fn absorb_hash(&mut self, hash: HashOutTarget) {
+        self.absorb_elements(&hash.elements);
+    }
+
+    pub fn squeeze(&mut self, builder: &mut CircuitBuilder<F, D>) -> Target {
+        if self.absorbing {
+            self.absorb_buffered_inputs(builder);
+        } else if self.output_buffer.is_empty() {
+            self.state = H::permute_circuit(self.state, builder);
+            self.fill_output_buffer();
+        }
+
+        self.output_buffer
+            .pop()
+            .expect("Output buffer should be non-empty")
+    }
+
+    pub fn squeeze_n(&mut self, builder: &mut CircuitBuilder<F, D>, n: usize) -> Vec<Target> {
+        (0..n).map(|_| self.squeeze(builder)).collect()
+    }
+
+    pub fn squeeze_hash(&mut self, builder: &mut CircuitBuilder<F, D>) -> HashOutTarget {
+        HashOutTarget::from_vec(self.squeeze_n(builder, NUM_HASH_OUT_ELTS))
+    }
+
+    /// Pads and absorbs any buffered inputs. After calling this, the input buffer will be empty,
+    /// and the output buffer will be full.
+    fn absorb_buffered_inputs(&mut self, builder: &mut CircuitBuilder<F, D>) {
+        if self.padding == SpongePadding::Pad10Star1 {
+            let zero = builder.zero();
+            let one = builder.one();
+            pad(
+                &mut self.input_buffer,
+                H::CircuitPermutation::RATE,
+                zero,
+                one,
+            );
+        }
+
+        for input_chunk in self.input_buffer.chunks(H::CircuitPermutation::RATE) {
+            self.state.set_from_slice(input_chunk, 0);
+            self.state = H::permute_circuit(self.state, builder);
+        }
+        self.input_buffer.clear();
+        self.absorbing = false;
+        self.fill_output_buffer();
+    }
+
+    fn fill_output_buffer(&mut self) {
+        self.output_buffer.clear();
+        self.output_buffer
+            .extend(self.state.squeeze().iter().rev().copied());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "std"))]
+    use alloc::vec;
+
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::{Field, Sample};
+    use crate::hash::hashing::hash_n_to_m_no_pad;
+    use crate::hash::keccak::KeccakHash;
+    use crate::hash::poseidon::PoseidonHash;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// A sequence of sponge operations.
+    #[derive(Clone, Debug)]
+    enum Op {
+        Absorb(Vec<F>),
+        Squeeze(usize),
+    }
+
+    fn run_native<H: Hasher<F>>(sponge: &mut Sponge<F, H>, ops: &[Op]) -> Vec<F> {
+        let mut outputs = Vec::new();
+        for op in ops {
+            match op {
+                Op::Absorb(inputs) => sponge.absorb_elements(inputs),
+                Op::Squeeze(n) => outputs.extend(sponge.squeeze_n(*n)),
+            }
+        }
+        outputs
+    }
+
+    /// Checks that a circuit running `ops` on a `SpongeTarget` squeezes the same outputs as a
+    /// native `Sponge`.
+    fn check_circuit_matches_native<H: CircuitHasher<F>>(
+        padding: SpongePadding,
+        domain: Option<F>,
+        ops: &[Op],
+    ) -> Result<()> {
+        let mut native_sponge = match domain {
+            Some(domain) => Sponge::<F, H>::new_with_domain(padding, domain),
+            None => Sponge::<F, H>::new(padding),
+        };
+        let expected = run_native(&mut native_sponge, ops);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+        let mut sponge = match domain {
+            Some(domain) => SpongeTarget::<F, H, D>::new_with_domain(&mut builder, padding, domain),
+            None => SpongeTarget::<F, H, D>::new(&mut builder, padding),
+        };
+        let mut outputs = Vec::new();
+        for op in ops {
+            match op {
+                Op::Absorb(inputs) => {
+                    let targets = builder.add_virtual_targets(inputs.len());
+                    pw.set_target_arr(&targets, inputs);
+                    sponge.absorb_elements(&targets);
+                }
+                Op::Squeeze(n) => outputs.extend(sponge.squeeze_n(&mut builder, *n)),
+            }
+        }
+        builder.register_public_inputs(&outputs);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        assert_eq!(proof.public_inputs, expected);
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    fn duplex_ops() -> Vec<Op> {
+        vec![
+            Op::Squeeze(3),
+            Op::Absorb(F::rand_vec(5)),
+            Op::Squeeze(2),
+            Op::Absorb(F::rand_vec(8)),
+            Op::Absorb(F::rand_vec(11)),
+            Op::Squeeze(19),
+            Op::Squeeze(4),
+            Op::Absorb(F::rand_vec(7)),
+            Op::Squeeze(1),
+        ]
+    }
+
+    #[test]
+    fn test_sponge_matches_one_shot_hashes() {
+        type P = <PoseidonHash as Hasher<F>>::Permutation;
+        for len in [0, 1, 7, 8, 9, 16, 25] {
+            let inputs = F::rand_vec(len);
+
+            let mut sponge = Sponge::<F, PoseidonHash>::new(SpongePadding::NoPad);
+            sponge.absorb_elements(&inputs);
+            assert_eq!(
+                sponge.squeeze_n(21),
+                hash_n_to_m_no_pad::<F, P>(&inputs, 21)
+            );
+
+            let mut sponge = Sponge::<F, PoseidonHash>::new(SpongePadding::Pad10Star1);
+            sponge.absorb_elements(&inputs);
+            assert_eq!(sponge.squeeze_hash(), PoseidonHash::hash_pad(&inputs));
+        }
+    }
+
+    #[test]
+    fn test_sponge_absorption_is_incremental() {
+        let inputs = F::rand_vec(20);
+
+        let mut one_shot = Sponge::<F, PoseidonHash>::new(SpongePadding::Pad10Star1);
+        one_shot.absorb_elements(&inputs);
+
+        let mut incremental = Sponge::<F, PoseidonHash>::new(SpongePadding::Pad10Star1);
+        for chunk in inputs.chunks(3) {
+            incremental.absorb_elements(chunk);
+        }
+
+        assert_eq!(one_shot.squeeze_n(10), incremental.squeeze_n(10));
+    }
+
+    #[test]
+    fn test_sponge_separates_domains_and_paddings() {
+        let inputs = F::rand_vec(6);
+        let hash = |padding, domain: Option<F>| {
+            let mut sponge = match domain {
+                Some(domain) => Sponge::<F, PoseidonHash>::new_with_domain(padding, domain),
+                None => Sponge::<F, PoseidonHash>::new(padding),
+            };
+            sponge.absorb_elements(&inputs);
+            sponge.squeeze_hash()
+        };
+
+        let no_domain = hash(SpongePadding::Pad10Star1, None);
+        let domain_1 = hash(SpongePadding::Pad10Star1, Some(F::ONE));
+        let domain_2 = hash(SpongePadding::Pad10Star1, Some(F::TWO));
+        let no_pad = hash(SpongePadding::NoPad, Some(F::ONE));
+        assert_ne!(no_domain, domain_1);
+        assert_ne!(domain_1, domain_2);
+        assert_ne!(domain_1, no_pad);
+        assert_eq!(domain_1, hash(SpongePadding::Pad10Star1, Some(F::ONE)));
+    }
+
+    #[test]
+    #[should_panic(expected = "The domain separator must be nonzero")]
+    fn test_sponge_rejects_zero_domain() {
+        Sponge::<F, PoseidonHash>::new_with_domain(SpongePadding::Pad10Star1, F::ZERO);
+    }
+
+    #[test]
+    fn test_sponge_target_no_pad() -> Result<()> {
+        check_circuit_matches_native::<PoseidonHash>(SpongePadding::NoPad, None, &duplex_ops())
+    }
+
+    #[test]
+    fn test_sponge_target_pad_with_domain() -> Result<()> {
+        check_circuit_matches_native::<PoseidonHash>(
+            SpongePadding::Pad10Star1,
+            Some(F::from_canonical_u64(42)),
+            &duplex_ops(),
+        )
+    }
+
+    #[test]
+    fn test_sponge_target_keccak() -> Result<()> {
+        check_circuit_matches_native::<KeccakHash<25>>(
+            SpongePadding::Pad10Star1,
+            Some(F::ONE),
+            &[Op::Absorb(F::rand_vec(10)), Op::Squeeze(6)],
+        )
+    }
+}
@@ -9,7 +9,7 @@ use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::Target;
 use crate::plonk::circuit_builder::CircuitBuilder;
-use crate::plonk::config::{AlgebraicHasher, GenericHashOut, Hasher};
+use crate::plonk::config::{AlgebraicHasher, CircuitHasher, GenericHashOut, Hasher};
 
 /// Observes prover messages, and generates challenges by hashing the transcript, a la Fiat-Shamir.
 #[derive(Clone, Debug)]
@@ -162,28 +162,27 @@ impl<F: RichField, H: AlgebraicHasher<F>> Default for Challenger<F, H> {
 /// buffer can grow beyond `H::Permutation::RATE`. This is so that `observe_element` etc do not need access
 /// to the `CircuitBuilder`.
 #[derive(Debug)]
-pub struct RecursiveChallenger<F: RichField + Extendable<D>, H: AlgebraicHasher<F>, const D: usize>
-{
-    sponge_state: H::AlgebraicPermutation,
+pub struct RecursiveChallenger<F: RichField + Extendable<D>, H: CircuitHasher<F>, const D: usize> {
+    sponge_state: H::CircuitPermutation,
     input_buffer: Vec<Target>,
     output_buffer: Vec<Target>,
     __: PhantomData<(F, H)>,
 }
 
-impl<F: RichField + Extendable<D>, H: AlgebraicHasher<F>, const D: usize>
+impl<F: RichField + Extendable<D>, H: CircuitHasher<F>, const D: usize>
     RecursiveChallenger<F, H, D>
 {
     pub fn new(builder: &mut CircuitBuilder<F, D>) -> Self {
         let zero = builder.zero();
         Self {
-            sponge_state: H::AlgebraicPermutation::new(core::iter::repeat(zero)),
+            sponge_state: H::CircuitPermutation::new(core::iter::repeat(zero)),
             input_buffer: Vec::new(),
             output_buffer: Vec::new(),
             __: PhantomData,
         }
     }
 
-    pub fn from_state(sponge_state: H::AlgebraicPermutation) -> Self {
+    pub fn from_state(sponge_state: H::CircuitPermutation) -> Self {
         Self {
             sponge_state,
             input_buffer: vec![],
@@ -230,7 +229,7 @@ impl<F: RichField + Extendable<D>, H: AlgebraicHasher<F>, const D: usize>
 
         if self.output_buffer.is_empty() {
             // Evaluate the permutation to produce `r` new outputs.
-            self.sponge_state = builder.permute::<H>(self.sponge_state);
+            self.sponge_state = H::permute_circuit(self.sponge_state, builder);
             self.output_buffer = self.sponge_state.squeeze().to_vec();
         }
 
@@ -272,12 +271,12 @@ impl<F: RichField + Extendable<D>, H: AlgebraicHasher<F>, const D: usize>
             return;
         }
 
-        for input_chunk in self.input_buffer.chunks(H::AlgebraicPermutation::RATE) {
+        for input_chunk in self.input_buffer.chunks(H::CircuitPermutation::RATE) {
             // Overwrite the first r elements with the inputs. This differs from a standard sponge,
             // where we would xor or add in the inputs. This is a well-known variant, though,
             // sometimes called "overwrite mode".
             self.sponge_state.set_from_slice(input_chunk, 0);
-            self.sponge_state = builder.permute::<H>(self.sponge_state);
+            self.sponge_state = H::permute_circuit(self.sponge_state, builder);
         }
 
         self.output_buffer = self.sponge_state.squeeze().to_vec();
@@ -285,7 +284,7 @@ impl<F: RichField + Extendable<D>, H: AlgebraicHasher<F>, const D: usize>
         self.input_buffer.clear();
     }
 
-    pub fn compact(&mut self, builder: &mut CircuitBuilder<F, D>) -> H::AlgebraicPermutation {
+    pub fn compact(&mut self, builder: &mut CircuitBuilder<F, D>) -> H::CircuitPermutation {
         self.absorb_buffered_inputs(builder);
         self.output_buffer.clear();
         self.sponge_state
@@ -304,13 +303,16 @@ mod tests {
     use crate::iop::witness::{PartialWitness, Witness};
     use crate::plonk::circuit_builder::CircuitBuilder;
     use crate::plonk::circuit_data::CircuitConfig;
-    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::config::{
+        CircuitHasher, GenericConfig, KeccakGoldilocksConfig, PoseidonGoldilocksConfig,
+    };
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
 
     #[test]
     fn no_duplicate_challenges() {
-        const D: usize = 2;
-        type C = PoseidonGoldilocksConfig;
-        type F = <C as GenericConfig<D>>::F;
         let mut challenger = Challenger::<F, <C as GenericConfig<D>>::InnerHasher>::new();
         let mut challenges = Vec::new();
 
@@ -328,12 +330,7 @@ mod tests {
     }
 
     /// Tests for consistency between `Challenger` and `RecursiveChallenger`.
-    #[test]
-    fn test_consistency() {
-        const D: usize = 2;
-        type C = PoseidonGoldilocksConfig;
-        type F = <C as GenericConfig<D>>::F;
-
+    fn test_consistency_with<H: CircuitHasher<F>>() {
         // These are mostly arbitrary, but we want to test some rounds with enough inputs/outputs to
         // trigger multiple absorptions/squeezes.
         let num_inputs_per_round = [2, 5, 3];
@@ -345,7 +342,7 @@ mod tests {
             .map(|&n| F::rand_vec(n))
             .collect();
 
-        let mut challenger = Challenger::<F, <C as GenericConfig<D>>::InnerHasher>::new();
+        let mut challenger = Challenger::<F, H>::new();
         let mut outputs_per_round: Vec<Vec<F>> = Vec::new();
         for (r, inputs) in inputs_per_round.iter().enumerate() {
             challenger.observe_elements(inputs);
@@ -354,8 +351,7 @@ mod tests {
 
         let config = CircuitConfig::standard_recursion_config();
         let mut builder = CircuitBuilder::<F, D>::new(config);
-        let mut recursive_challenger =
-            RecursiveChallenger::<F, <C as GenericConfig<D>>::InnerHasher, D>::new(&mut builder);
+        let mut recursive_challenger = RecursiveChallenger::<F, H, D>::new(&mut builder);
         let mut recursive_outputs_per_round: Vec<Vec<Target>> = Vec::new();
         for (r, inputs) in inputs_per_round.iter().enumerate() {
             recursive_challenger.observe_elements(&builder.constants(inputs));
@@ -373,4 +369,14 @@ mod tests {
 
         assert_eq!(outputs_per_round, recursive_output_values_per_round);
     }
+
+    #[test]
+    fn test_consistency() {
+        test_consistency_with::<<C as GenericConfig<D>>::InnerHasher>();
+    }
+
+    #[test]
+    fn test_consistency_keccak() {
+        test_consistency_with::<<KeccakGoldilocksConfig as GenericConfig<D>>::Hasher>();
+    }
 }
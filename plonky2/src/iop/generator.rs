@@ -8,6 +8,10 @@ use alloc::{
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
+use hashbrown::HashMap;
+#[cfg(feature = "parallel")]
+use plonky2_maybe_rayon::*;
+
 use crate::field::extension::Extendable;
 use crate::field::types::Field;
 use crate::hash::hash_types::RichField;
@@ -21,6 +25,11 @@ use crate::util::serialization::{Buffer, IoResult, Read, Write};
 
 /// Given a `PartitionWitness` that has only inputs set, populates the rest of the witness using the
 /// given set of generators.
+///
+/// This uses [`generate_partial_witness_serial`], unless the `parallel_witness_generation` feature
+/// is enabled, in which case it uses `generate_partial_witness_parallel`. The latter is faster for
+/// wide circuits, but each wave costs a synchronization, which can make it slower for circuits
+/// made of long chains of dependent generators, e.g. hash chains.
 pub fn generate_partial_witness<
     'a,
     F: RichField + Extendable<D>,
@@ -31,19 +40,73 @@ pub fn generate_partial_witness<
     prover_data: &'a ProverOnlyCircuitData<F, C, D>,
     common_data: &'a CommonCircuitData<F, D>,
 ) -> PartitionWitness<'a, F> {
-    let config = &common_data.config;
-    let generators = &prover_data.generators;
-    let generator_indices_by_watches = &prover_data.generator_indices_by_watches;
+    generate_with_recorder(inputs, prover_data, common_data, &mut None)
+}
 
-    let mut witness = PartitionWitness::new(
-        config.num_wires,
-        common_data.degree(),
-        &prover_data.representative_map,
-    );
+/// Same as [`generate_partial_witness`], but values output by generators which conflict with the
+/// value already held by their partition are dropped and returned, instead of panicking.
+pub(crate) fn generate_partial_witness_with_conflicts<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+) -> (PartitionWitness<'a, F>, Vec<ConflictingValue<F>>) {
+    let mut recorder = Some(ConflictRecorder::default());
+    let witness = generate_with_recorder(inputs, prover_data, common_data, &mut recorder);
+    let conflicts = recorder.map(|r| r.conflicts).unwrap_or_default();
+    (witness, conflicts)
+}
 
-    for (t, v) in inputs.target_values.into_iter() {
-        witness.set_target(t, v);
+fn generate_with_recorder<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+    recorder: &mut Option<ConflictRecorder<F>>,
+) -> PartitionWitness<'a, F> {
+    #[cfg(feature = "parallel_witness_generation")]
+    {
+        generate_parallel(inputs, prover_data, common_data, recorder)
     }
+    #[cfg(not(feature = "parallel_witness_generation"))]
+    {
+        generate_serial(inputs, prover_data, common_data, recorder)
+    }
+}
+
+/// Populates the witness by running generators one at a time, each one seeing the values set by
+/// all the generators run before it.
+pub fn generate_partial_witness_serial<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+) -> PartitionWitness<'a, F> {
+    generate_serial(inputs, prover_data, common_data, &mut None)
+}
+
+fn generate_serial<'a, F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+    recorder: &mut Option<ConflictRecorder<F>>,
+) -> PartitionWitness<'a, F> {
+    let generators = &prover_data.generators;
+    let generator_indices_by_watches = &prover_data.generator_indices_by_watches;
+
+    let mut witness = initial_partition_witness(inputs, prover_data, common_data, recorder);
 
     // Build a list of "pending" generators which are queued to be run. Initially, all generators
     // are queued.
@@ -75,7 +138,8 @@ pub fn generate_partial_witness<
             let new_target_reps = buffer
                 .target_values
                 .drain(..)
-                .flat_map(|(t, v)| witness.set_target_returning_rep(t, v));
+                .filter_map(|(t, v)| merge_value(&mut witness, t, v, recorder))
+                .collect::<Vec<_>>();
 
             // Enqueue unfinished generators that were watching one of the newly populated targets.
             for watch in new_target_reps {
@@ -102,6 +166,184 @@ pub fn generate_partial_witness<
     witness
 }
 
+/// Populates the witness in waves. All the generators queued in a wave are run in parallel
+/// against the same witness, then their outputs are merged in increasing generator index order,
+/// so the resulting witness, and any conflicting-value panic, does not depend on thread scheduling.
+#[cfg(feature = "parallel")]
+pub fn generate_partial_witness_parallel<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+) -> PartitionWitness<'a, F> {
+    generate_parallel(inputs, prover_data, common_data, &mut None)
+}
+
+#[cfg(feature = "parallel")]
+fn generate_parallel<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+    recorder: &mut Option<ConflictRecorder<F>>,
+) -> PartitionWitness<'a, F> {
+    let generators = &prover_data.generators;
+    let generator_indices_by_watches = &prover_data.generator_indices_by_watches;
+
+    let mut witness = initial_partition_witness(inputs, prover_data, common_data, recorder);
+
+    let mut pending_generator_indices: Vec<_> = (0..generators.len()).collect();
+    let mut generator_is_expired = vec![false; generators.len()];
+    let mut remaining_generators = generators.len();
+
+    while !pending_generator_indices.is_empty() {
+        // A generator may have been queued by several targets, or may have finished after being
+        // queued, so only keep one copy of each generator still to be run.
+        pending_generator_indices.sort_unstable();
+        pending_generator_indices.dedup();
+        pending_generator_indices.retain(|&generator_idx| !generator_is_expired[generator_idx]);
+
+        let wave_outputs: Vec<_> = pending_generator_indices
+            .par_iter()
+            .map(|&generator_idx| {
+                let mut buffer = GeneratedValues::empty();
+                let finished = generators[generator_idx].0.run(&witness, &mut buffer);
+                (finished, buffer)
+            })
+            .collect();
+
+        let mut next_pending_generator_indices = Vec::new();
+        for (&generator_idx, (finished, buffer)) in
+            pending_generator_indices.iter().zip(wave_outputs)
+        {
+            if finished {
+                generator_is_expired[generator_idx] = true;
+                remaining_generators -= 1;
+            }
+
+            let new_target_reps = buffer
+                .target_values
+                .into_iter()
+                .filter_map(|(t, v)| merge_value(&mut witness, t, v, recorder))
+                .collect::<Vec<_>>();
+
+            for watch in new_target_reps {
+                if let Some(watchers) = generator_indices_by_watches.get(&watch) {
+                    next_pending_generator_indices.extend(
+                        watchers
+                            .iter()
+                            .filter(|&&watching_generator_idx| {
+                                !generator_is_expired[watching_generator_idx]
+                            })
+                            .copied(),
+                    );
+                }
+            }
+        }
+
+        pending_generator_indices = next_pending_generator_indices;
+    }
+
+    assert_eq!(
+        remaining_generators, 0,
+        "{} generators weren't run",
+        remaining_generators,
+    );
+
+    witness
+}
+
+/// Builds a `PartitionWitness` with only the given inputs set.
+fn initial_partition_witness<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+    recorder: &mut Option<ConflictRecorder<F>>,
+) -> PartitionWitness<'a, F> {
+    let mut witness = PartitionWitness::new(
+        common_data.config.num_wires,
+        common_data.degree(),
+        &prover_data.representative_map,
+    );
+
+    for (t, v) in inputs.target_values.into_iter() {
+        merge_value(&mut witness, t, v, recorder);
+    }
+
+    witness
+}
+
+/// A value set for a target whose partition already holds a different value.
+#[derive(Clone, Debug)]
+pub(crate) struct ConflictingValue<F: Field> {
+    /// The target which set the value already held by the partition.
+    pub(crate) first_target: Target,
+    pub(crate) first_value: F,
+    pub(crate) target: Target,
+    pub(crate) value: F,
+}
+
+/// Records conflicting values instead of panicking on them.
+struct ConflictRecorder<F: Field> {
+    /// The target which set the value of each partition, by representative index.
+    setters: HashMap<usize, Target>,
+    conflicts: Vec<ConflictingValue<F>>,
+}
+
+impl<F: Field> Default for ConflictRecorder<F> {
+    fn default() -> Self {
+        Self {
+            setters: HashMap::new(),
+            conflicts: Vec::new(),
+        }
+    }
+}
+
+/// Sets `target` in the witness, returning the representative index of its partition if it was
+/// not set yet. A conflicting value panics, unless a recorder is given, in which case it is
+/// recorded and dropped.
+fn merge_value<F: Field>(
+    witness: &mut PartitionWitness<F>,
+    target: Target,
+    value: F,
+    recorder: &mut Option<ConflictRecorder<F>>,
+) -> Option<usize> {
+    let Some(recorder) = recorder else {
+        return witness.set_target_returning_rep(target, value);
+    };
+    match witness.try_set_target_returning_rep(target, value) {
+        Ok(new_rep) => {
+            if let Some(rep) = new_rep {
+                recorder.setters.insert(rep, target);
+            }
+            new_rep
+        }
+        Err(first_value) => {
+            let rep = witness.representative_map[witness.target_index(target)];
+            recorder.conflicts.push(ConflictingValue {
+                first_target: recorder.setters[&rep],
+                first_value,
+                target,
+                value,
+            });
+            None
+        }
+    }
+}
+
 /// A generator participates in the generation of the witness.
 pub trait WitnessGenerator<F: RichField + Extendable<D>, const D: usize>:
     'static + Send + Sync + Debug
@@ -418,3 +660,72 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Con
         })
     }
 }
+
+#[cfg(all(test, feature = "parallel"))]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::hash::poseidon::PoseidonHash;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+    use crate::plonk::verifier::verify;
+
+    #[test]
+    fn test_parallel_witness_generation_matches_serial() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let xs = builder.add_virtual_targets(8);
+        let mut acc = builder.one();
+        let mut checked_targets = Vec::new();
+        for (i, &x) in xs.iter().enumerate() {
+            let inv = builder.inverse(x);
+            let pow = builder.exp(x, acc, 8);
+            let hash = builder.hash_n_to_hash_no_pad::<PoseidonHash>(vec![x, inv, pow]);
+            let index = builder.constant(F::from_canonical_usize(i % 4));
+            let elem = builder.random_access(index, hash.elements.to_vec());
+            let bits = builder.split_le(elem, 64);
+            acc = builder.le_sum(bits[..8].iter());
+            checked_targets.extend([inv, pow, elem, acc]);
+            checked_targets.extend(hash.elements);
+        }
+        builder.register_public_input(acc);
+
+        let data = builder.build::<C>();
+        let mut inputs = PartialWitness::new();
+        for (i, &x) in xs.iter().enumerate() {
+            inputs.set_target(x, F::from_canonical_usize(i + 2));
+        }
+
+        let serial =
+            generate_partial_witness_serial(inputs.clone(), &data.prover_only, &data.common);
+        let parallel =
+            generate_partial_witness_parallel(inputs.clone(), &data.prover_only, &data.common);
+        // Unused public input wires are filled with random values, so only compare which
+        // partitions were set, and the values of the targets computed above.
+        assert_eq!(
+            serial
+                .values
+                .iter()
+                .map(Option::is_some)
+                .collect::<Vec<_>>(),
+            parallel
+                .values
+                .iter()
+                .map(Option::is_some)
+                .collect::<Vec<_>>()
+        );
+        for t in checked_targets {
+            assert_eq!(serial.get_target(t), parallel.get_target(t));
+        }
+
+        let proof = data.prove(inputs)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
@@ -84,3 +84,21 @@ impl BoolTarget {
         }
     }
 }
+
+/// A `Target` which has already been constrained such that it can only be in `[0, 256)`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[allow(clippy::manual_non_exhaustive)]
+pub struct ByteTarget {
+    pub target: Target,
+    /// This private field is here to force all instantiations to go through `new_unsafe`.
+    _private: (),
+}
+
+impl ByteTarget {
+    pub const fn new_unsafe(target: Target) -> ByteTarget {
+        ByteTarget {
+            target,
+            _private: (),
+        }
+    }
+}
@@ -3,18 +3,26 @@ use alloc::{vec, vec::Vec};
 
 use hashbrown::HashMap;
 use itertools::{zip_eq, Itertools};
+use num::BigUint;
 
+use crate::curve::curve_types::{AffinePoint, Curve};
+use crate::curve::ecdsa::{ECDSAPublicKey, ECDSASignature};
 use crate::field::extension::{Extendable, FieldExtension};
-use crate::field::types::Field;
+use crate::field::types::{Field, PrimeField};
 use crate::fri::structure::{FriOpenings, FriOpeningsTarget};
 use crate::fri::witness_util::set_fri_proof_target;
+use crate::gadgets::arithmetic_u32::U32Target;
+use crate::gadgets::biguint::{biguint_from_limbs, biguint_to_limbs, BigUintTarget};
+use crate::gadgets::curve::AffinePointTarget;
+use crate::gadgets::ecdsa::{ECDSAPublicKeyTarget, ECDSASignatureTarget};
+use crate::gadgets::nonnative::NonNativeTarget;
 use crate::hash::hash_types::{HashOut, HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::ext_target::ExtensionTarget;
-use crate::iop::target::{BoolTarget, Target};
+use crate::iop::target::{BoolTarget, ByteTarget, Target};
 use crate::iop::wire::Wire;
 use crate::plonk::circuit_data::{VerifierCircuitTarget, VerifierOnlyCircuitData};
-use crate::plonk::config::{AlgebraicHasher, GenericConfig, Hasher};
+use crate::plonk::config::{AlgebraicHasher, CircuitHasher, GenericConfig, GenericHashOut, Hasher};
 use crate::plonk::proof::{Proof, ProofTarget, ProofWithPublicInputs, ProofWithPublicInputsTarget};
 
 pub trait WitnessWrite<F: Field> {
@@ -27,18 +35,77 @@ pub trait WitnessWrite<F: Field> {
             .for_each(|(&t, x)| self.set_target(t, x));
     }
 
-    fn set_cap_target<H: AlgebraicHasher<F>>(
-        &mut self,
-        ct: &MerkleCapTarget,
-        value: &MerkleCap<F, H>,
-    ) where
+    fn set_cap_target<H: Hasher<F>>(&mut self, ct: &MerkleCapTarget, value: &MerkleCap<F, H>)
+    where
         F: RichField,
     {
         for (ht, h) in ct.0.iter().zip(&value.0) {
-            self.set_hash_target(*ht, *h);
+            self.set_target_arr(&ht.elements, &h.to_vec());
         }
     }
 
+    fn set_u32_target(&mut self, target: U32Target, value: u32) {
+        self.set_target(target.0, F::from_canonical_u32(value));
+    }
+
+    fn set_biguint_target(&mut self, target: &BigUintTarget, value: &BigUint)
+    where
+        F: RichField,
+    {
+        let limbs = biguint_to_limbs(value);
+        assert!(
+            limbs.len() <= target.num_limbs(),
+            "Value does not fit in {} limbs",
+            target.num_limbs()
+        );
+        for (i, &t) in target.limbs.iter().enumerate() {
+            self.set_target(t, F::from_canonical_u64(limbs.get(i).copied().unwrap_or(0)));
+        }
+    }
+
+    fn set_nonnative_target<FF: PrimeField>(&mut self, target: &NonNativeTarget<FF>, value: FF)
+    where
+        F: RichField,
+    {
+        self.set_biguint_target(&target.value, &value.to_canonical_biguint());
+    }
+
+    fn set_affine_point_target<C: Curve>(
+        &mut self,
+        target: &AffinePointTarget<C>,
+        value: &AffinePoint<C>,
+    ) where
+        F: RichField,
+    {
+        assert!(
+            !value.zero,
+            "The point at infinity has no affine coordinates"
+        );
+        self.set_nonnative_target(&target.x, value.x);
+        self.set_nonnative_target(&target.y, value.y);
+    }
+
+    fn set_ecdsa_public_key_target<C: Curve>(
+        &mut self,
+        target: &ECDSAPublicKeyTarget<C>,
+        value: &ECDSAPublicKey<C>,
+    ) where
+        F: RichField,
+    {
+        self.set_affine_point_target(&target.0, &value.0);
+    }
+
+    fn set_ecdsa_signature_target<C: Curve>(
+        &mut self,
+        target: &ECDSASignatureTarget<C>,
+        value: &ECDSASignature<C>,
+    ) where
+        F: RichField,
+    {
+        self.set_nonnative_target(&target.r, value.r);
+        self.set_nonnative_target(&target.s, value.s);
+    }
+
     fn set_extension_target<const D: usize>(&mut self, et: ExtensionTarget<D>, value: F::Extension)
     where
         F: RichField + Extendable<D>,
@@ -67,6 +134,10 @@ pub trait WitnessWrite<F: Field> {
         self.set_target(target.target, F::from_bool(value))
     }
 
+    fn set_byte_target(&mut self, target: ByteTarget, value: u8) {
+        self.set_target(target.target, F::from_canonical_u8(value))
+    }
+
     /// Set the targets in a `ProofWithPublicInputsTarget` to their corresponding values in a
     /// `ProofWithPublicInputs`.
     fn set_proof_with_pis_target<C: GenericConfig<D, F = F>, const D: usize>(
@@ -75,7 +146,7 @@ pub trait WitnessWrite<F: Field> {
         proof_with_pis: &ProofWithPublicInputs<F, C, D>,
     ) where
         F: RichField + Extendable<D>,
-        C::Hasher: AlgebraicHasher<F>,
+        C::Hasher: CircuitHasher<F>,
     {
         let ProofWithPublicInputs {
             proof,
@@ -101,7 +172,7 @@ pub trait WitnessWrite<F: Field> {
         proof: &Proof<F, C, D>,
     ) where
         F: RichField + Extendable<D>,
-        C::Hasher: AlgebraicHasher<F>,
+        C::Hasher: CircuitHasher<F>,
     {
         self.set_cap_target(&proof_target.wires_cap, &proof.wires_cap);
         self.set_cap_target(
@@ -140,10 +211,10 @@ pub trait WitnessWrite<F: Field> {
         vd: &VerifierOnlyCircuitData<C, D>,
     ) where
         F: RichField + Extendable<D>,
-        C::Hasher: AlgebraicHasher<F>,
+        C::Hasher: CircuitHasher<F>,
     {
         self.set_cap_target(&vdt.constants_sigmas_cap, &vd.constants_sigmas_cap);
-        self.set_hash_target(vdt.circuit_digest, vd.circuit_digest);
+        self.set_target_arr(&vdt.circuit_digest.elements, &vd.circuit_digest.to_vec());
     }
 
     fn set_wire(&mut self, wire: Wire, value: F) {
@@ -205,6 +276,14 @@ pub trait Witness<F: Field>: WitnessWrite<F> {
             .collect()
     }
 
+    fn get_byte_target(&self, target: ByteTarget) -> u8
+    where
+        F: RichField,
+    {
+        let value = self.get_target(target.target).to_canonical_u64();
+        u8::try_from(value).expect("not a byte")
+    }
+
     fn get_bool_target(&self, target: BoolTarget) -> bool {
         let value = self.get_target(target.target);
         if value.is_zero() {
@@ -216,6 +295,33 @@ pub trait Witness<F: Field>: WitnessWrite<F> {
         panic!("not a bool")
     }
 
+    fn get_u32_target(&self, target: U32Target) -> u32
+    where
+        F: RichField,
+    {
+        let value = self.get_target(target.0).to_canonical_u64();
+        u32::try_from(value).expect("Value does not fit in 32 bits")
+    }
+
+    fn get_biguint_target(&self, target: &BigUintTarget) -> BigUint
+    where
+        F: RichField,
+    {
+        biguint_from_limbs(
+            target
+                .limbs
+                .iter()
+                .map(|&t| self.get_target(t).to_canonical_u64()),
+        )
+    }
+
+    fn get_nonnative_target<FF: PrimeField>(&self, target: &NonNativeTarget<FF>) -> FF
+    where
+        F: RichField,
+    {
+        FF::from_noncanonical_biguint(self.get_biguint_target(&target.value))
+    }
+
     fn get_hash_target(&self, ht: HashOutTarget) -> HashOut<F> {
         HashOut {
             elements: self.get_targets(&ht.elements).try_into().unwrap(),
@@ -318,18 +424,31 @@ impl<'a, F: Field> PartitionWitness<'a, F> {
     /// Set a `Target`. On success, returns the representative index of the newly-set target. If the
     /// target was already set, returns `None`.
     pub fn set_target_returning_rep(&mut self, target: Target, value: F) -> Option<usize> {
+        self.try_set_target_returning_rep(target, value)
+            .unwrap_or_else(|old_value| {
+                panic!(
+                    "Partition containing {:?} was set twice with different values: {} != {}",
+                    target, old_value, value
+                )
+            })
+    }
+
+    /// Same as `set_target_returning_rep`, but if the partition of `target` already holds a
+    /// different value, leaves it unchanged and returns this value as an error instead of panicking.
+    pub(crate) fn try_set_target_returning_rep(
+        &mut self,
+        target: Target,
+        value: F,
+    ) -> Result<Option<usize>, F> {
         let rep_index = self.representative_map[self.target_index(target)];
         let rep_value = &mut self.values[rep_index];
-        if let Some(old_value) = *rep_value {
-            assert_eq!(
-                value, old_value,
-                "Partition containing {:?} was set twice with different values: {} != {}",
-                target, old_value, value
-            );
-            None
-        } else {
-            *rep_value = Some(value);
-            Some(rep_index)
+        match *rep_value {
+            Some(old_value) if old_value != value => Err(old_value),
+            Some(_) => Ok(None),
+            None => {
+                *rep_value = Some(value);
+                Ok(Some(rep_index))
+            }
         }
     }
 
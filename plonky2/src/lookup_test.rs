@@ -6,10 +6,11 @@ use std::sync::{Arc, Once};
 use itertools::Itertools;
 use log::Level;
 
-use crate::field::types::Field;
+use crate::field::types::{Field, PrimeField64};
 use crate::gadgets::lookup::{OTHER_TABLE, SMALLER_TABLE, TIP5_TABLE};
 use crate::gates::lookup_table::LookupTable;
 use crate::gates::noop::NoopGate;
+use crate::gates::tuple_lookup::TupleLookupTable;
 use crate::iop::witness::{PartialWitness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CircuitConfig;
@@ -510,6 +511,294 @@ fn test_many_lookups_on_big_lut() -> anyhow::Result<()> {
     data.verify(proof)
 }
 
+// Tests lookups into a dynamic LUT whose outputs are computed in the circuit, next to a fixed LUT.
+#[test]
+fn test_dynamic_lookup() -> anyhow::Result<()> {
+    init_logger();
+
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+
+    // The table maps `x` to `x^3 + seed`, where `seed` is only known to the prover. It has more rows
+    // than a single `DynamicLookupTableGate` can hold.
+    let seed = builder.add_virtual_target();
+    let table_len = 40;
+    let table = (0..table_len)
+        .map(|i| {
+            let inp = builder.constant(F::from_canonical_usize(i));
+            let cube = builder.exp_u64(inp, 3);
+            (inp, builder.add(cube, seed))
+        })
+        .collect::<Vec<_>>();
+    let dynamic_index = builder.add_dynamic_lookup_table(&table);
+
+    // Enough lookups to fill more than one `DynamicLookupGate`.
+    let num_lookups = 100;
+    let inputs = builder.add_virtual_targets(num_lookups);
+    for &inp in &inputs {
+        let out = builder.add_dynamic_lookup(inp, dynamic_index);
+        builder.register_public_input(out);
+    }
+
+    let tip5_table = TIP5_TABLE.to_vec();
+    let tip5_index =
+        builder.add_lookup_table_from_pairs(Arc::new((0..256).zip_eq(tip5_table).collect()));
+    let tip5_input = builder.add_virtual_target();
+    let tip5_output = builder.add_lookup_from_index(tip5_input, tip5_index);
+    builder.register_public_input(tip5_output);
+
+    let data = builder.build::<C>();
+
+    let mut pw = PartialWitness::new();
+    let seed_value = 12345;
+    pw.set_target(seed, F::from_canonical_u64(seed_value));
+    for (i, &inp) in inputs.iter().enumerate() {
+        pw.set_target(inp, F::from_canonical_usize((7 * i) % table_len));
+    }
+    pw.set_target(tip5_input, F::from_canonical_u16(3));
+
+    let proof = data.prove(pw)?;
+    for i in 0..num_lookups {
+        let x = ((7 * i) % table_len) as u64;
+        assert_eq!(
+            proof.public_inputs[i],
+            F::from_canonical_u64(x * x * x + seed_value)
+        );
+    }
+    assert_eq!(
+        proof.public_inputs[num_lookups],
+        F::from_canonical_u16(TIP5_TABLE[3])
+    );
+
+    data.verify(proof)
+}
+
+// Tests two dynamic LUTs with the same rows, one of which contains a duplicated row.
+#[test]
+fn test_two_dynamic_luts() -> anyhow::Result<()> {
+    init_logger();
+
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+
+    let table = (0..3)
+        .map(|_| (builder.add_virtual_target(), builder.add_virtual_target()))
+        .collect::<Vec<_>>();
+    let index_a = builder.add_dynamic_lookup_table(&table);
+    let index_b = builder.add_dynamic_lookup_table(&[table.clone(), table.clone()].concat());
+
+    let inp = builder.add_virtual_target();
+    let out_a = builder.add_dynamic_lookup(inp, index_a);
+    let out_b = builder.add_dynamic_lookup(inp, index_b);
+    builder.connect(out_a, out_b);
+    builder.register_public_input(out_a);
+
+    let data = builder.build::<C>();
+
+    let mut pw = PartialWitness::new();
+    for (i, &(t_inp, t_out)) in table.iter().enumerate() {
+        pw.set_target(t_inp, F::from_canonical_usize(i + 10));
+        pw.set_target(t_out, F::from_canonical_usize(i * 100));
+    }
+    pw.set_target(inp, F::from_canonical_usize(12));
+
+    let proof = data.prove(pw)?;
+    assert_eq!(proof.public_inputs[0], F::from_canonical_usize(200));
+
+    data.verify(proof)
+}
+
+#[should_panic]
+#[test]
+fn test_dynamic_lookup_table_not_used() {
+    init_logger();
+
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+
+    let table = [(builder.add_virtual_target(), builder.add_virtual_target())];
+    builder.add_dynamic_lookup_table(&table);
+
+    builder.build::<C>();
+}
+
+#[should_panic(expected = "A dynamic lookup table can't be empty")]
+#[test]
+fn test_empty_dynamic_lookup_table() {
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+
+    builder.add_dynamic_lookup_table(&[]);
+}
+
+#[should_panic]
+#[test]
+fn test_dynamic_lookup_not_in_table() {
+    init_logger();
+
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+
+    let table = [(builder.zero(), builder.one())];
+    let dynamic_index = builder.add_dynamic_lookup_table(&table);
+    let inp = builder.add_virtual_target();
+    builder.add_dynamic_lookup(inp, dynamic_index);
+
+    let data = builder.build::<C>();
+
+    let mut pw = PartialWitness::new();
+    pw.set_target(inp, F::ONE);
+    data.prove(pw).unwrap();
+}
+
+/// A table of the XOR of two bytes, with two inputs and one output.
+fn byte_xor_table() -> TupleLookupTable<F> {
+    let inputs = (0..256)
+        .cartesian_product(0..256)
+        .map(|(a, b)| vec![F::from_canonical_u64(a), F::from_canonical_u64(b)])
+        .collect::<Vec<_>>();
+    TupleLookupTable::from_fn(2, 1, &inputs, |inp| {
+        vec![F::from_canonical_u64(
+            inp[0].to_canonical_u64() ^ inp[1].to_canonical_u64(),
+        )]
+    })
+}
+
+/// A table mapping an opcode to three outputs, some of which don't fit in a `u16`.
+fn opcode_table() -> TupleLookupTable<F> {
+    let rows = (0..20u64)
+        .map(|op| {
+            vec![
+                F::from_canonical_u64(op),
+                F::from_canonical_u64(op << 40),
+                F::NEG_ONE - F::from_canonical_u64(op),
+                F::from_canonical_u64(op % 3),
+            ]
+        })
+        .collect::<Vec<_>>();
+    TupleLookupTable::new(1, 3, &rows)
+}
+
+// Tests tuple lookups into tables of different shapes, along with lookups into a standard LUT.
+#[test]
+fn test_tuple_lookups() -> anyhow::Result<()> {
+    init_logger();
+
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+
+    let xor_index = builder.add_tuple_lookup_table(byte_xor_table());
+    let opcode_index = builder.add_tuple_lookup_table(opcode_table());
+    assert_eq!(builder.tuple_lookup_shape(), (2, 3));
+
+    let xor_inputs = (0..50)
+        .map(|_| builder.add_virtual_targets(2))
+        .collect::<Vec<_>>();
+    for inputs in &xor_inputs {
+        let outputs = builder.add_tuple_lookup(inputs, xor_index);
+        builder.register_public_inputs(&outputs);
+    }
+    let opcode = builder.add_virtual_target();
+    let outputs = builder.add_tuple_lookup(&[opcode], opcode_index);
+    builder.register_public_inputs(&outputs);
+
+    let tip5_table = TIP5_TABLE.to_vec();
+    let tip5_index =
+        builder.add_lookup_table_from_pairs(Arc::new((0..256).zip_eq(tip5_table).collect()));
+    let tip5_input = builder.add_virtual_target();
+    let tip5_output = builder.add_lookup_from_index(tip5_input, tip5_index);
+    builder.register_public_input(tip5_output);
+
+    let data = builder.build::<C>();
+
+    let mut pw = PartialWitness::new();
+    for (i, inputs) in xor_inputs.iter().enumerate() {
+        pw.set_target(inputs[0], F::from_canonical_usize(37 * i % 256));
+        pw.set_target(inputs[1], F::from_canonical_usize(101 * i % 256));
+    }
+    pw.set_target(opcode, F::from_canonical_u64(17));
+    pw.set_target(tip5_input, F::from_canonical_u16(5));
+
+    let proof = data.prove(pw)?;
+    for i in 0..xor_inputs.len() {
+        assert_eq!(
+            proof.public_inputs[i],
+            F::from_canonical_usize((37 * i % 256) ^ (101 * i % 256))
+        );
+    }
+    let num_xors = xor_inputs.len();
+    assert_eq!(
+        proof.public_inputs[num_xors..num_xors + 3],
+        [
+            F::from_canonical_u64(17 << 40),
+            F::NEG_ONE - F::from_canonical_u64(17),
+            F::from_canonical_u64(2),
+        ]
+    );
+    assert_eq!(
+        proof.public_inputs[num_xors + 3],
+        F::from_canonical_u16(TIP5_TABLE[5])
+    );
+
+    data.verify(proof)
+}
+
+// Tests that circuits with tuple lookups can be serialized, and still prove after deserialization.
+#[cfg(feature = "std")]
+#[test]
+fn test_tuple_lookup_serialization() -> anyhow::Result<()> {
+    use crate::plonk::circuit_data::CircuitData;
+    use crate::util::serialization::{DefaultGateSerializer, DefaultGeneratorSerializer};
+
+    init_logger();
+
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+
+    let opcode_index = builder.add_tuple_lookup_table(opcode_table());
+    let opcode = builder.add_virtual_target();
+    let outputs = builder.add_tuple_lookup(&[opcode], opcode_index);
+    builder.register_public_inputs(&outputs);
+
+    let data = builder.build::<C>();
+
+    let gate_serializer = DefaultGateSerializer;
+    let generator_serializer = DefaultGeneratorSerializer::<C, D>::default();
+    let bytes = data
+        .to_bytes(&gate_serializer, &generator_serializer)
+        .unwrap();
+    let data_from_bytes =
+        CircuitData::<F, C, D>::from_bytes(&bytes, &gate_serializer, &generator_serializer)
+            .unwrap();
+    assert_eq!(data.common, data_from_bytes.common);
+
+    let mut pw = PartialWitness::new();
+    pw.set_target(opcode, F::from_canonical_u64(4));
+    let proof = data_from_bytes.prove(pw)?;
+    assert_eq!(proof.public_inputs[2], F::ONE);
+
+    data.verify(proof)
+}
+
+#[should_panic]
+#[test]
+fn test_tuple_lookup_not_in_table() {
+    init_logger();
+
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+
+    let opcode_index = builder.add_tuple_lookup_table(opcode_table());
+    let opcode = builder.add_virtual_target();
+    builder.add_tuple_lookup(&[opcode], opcode_index);
+
+    let data = builder.build::<C>();
+
+    let mut pw = PartialWitness::new();
+    pw.set_target(opcode, F::from_canonical_u64(20));
+    data.prove(pw).unwrap();
+}
+
 fn init_logger() {
     #[cfg(feature = "std")]
     {
@@ -21,6 +21,7 @@ use crate::fri::{FriConfig, FriParams};
 use crate::gadgets::arithmetic::BaseArithmeticOperation;
 use crate::gadgets::arithmetic_extension::ExtensionArithmeticOperation;
 use crate::gadgets::polynomial::PolynomialCoeffsExtTarget;
+use crate::gadgets::range_check::RangeCheckMode;
 use crate::gates::arithmetic_base::ArithmeticGate;
 use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
 use crate::gates::constant::ConstantGate;
@@ -29,7 +30,12 @@ use crate::gates::lookup::{Lookup, LookupGate};
 use crate::gates::lookup_table::LookupTable;
 use crate::gates::noop::NoopGate;
 use crate::gates::public_input::PublicInputGate;
-use crate::gates::selectors::{selector_ends_lookups, selector_polynomials, selectors_lookup};
+use crate::gates::selectors::{
+    selector_ends_lookups, selector_polynomials, selectors_lookup, selectors_tuple_lookup,
+};
+use crate::gates::tuple_lookup::{
+    tuple_lookup_shape, TupleLookup, TupleLookupGate, TupleLookupTable,
+};
 use crate::hash::hash_types::{HashOut, HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_proofs::MerkleProofTarget;
 use crate::hash::merkle_tree::MerkleCap;
@@ -37,7 +43,7 @@ use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::generator::{
     ConstantGenerator, CopyGenerator, RandomValueGenerator, SimpleGenerator, WitnessGeneratorRef,
 };
-use crate::iop::target::{BoolTarget, Target};
+use crate::iop::target::{BoolTarget, ByteTarget, Target};
 use crate::iop::wire::Wire;
 use crate::plonk::circuit_data::{
     CircuitConfig, CircuitData, CommonCircuitData, MockCircuitData, ProverCircuitData,
@@ -45,6 +51,7 @@ use crate::plonk::circuit_data::{
 };
 use crate::plonk::config::{AlgebraicHasher, GenericConfig, GenericHashOut, Hasher};
 use crate::plonk::copy_constraint::CopyConstraint;
+use crate::plonk::diagnostics::CircuitDebugInfo;
 use crate::plonk::permutation_argument::Forest;
 use crate::plonk::plonk_common::PlonkOracle;
 use crate::timed;
@@ -56,19 +63,44 @@ use crate::util::{log2_ceil, log2_strict, transpose, transpose_poly_values};
 /// Number of random coins needed for lookups (for each challenge).
 /// A coin is a randomly sampled extension field element from the verifier,
 /// consisting internally of `CircuitConfig::num_challenges` field elements.
-pub const NUM_COINS_LOOKUP: usize = 4;
+pub const NUM_COINS_LOOKUP: usize = 5;
+
+/// Arranges lookup coins as `NUM_COINS_LOOKUP` consecutive coins per challenge. The first
+/// `NUM_COINS_LOOKUP - 1` coins of each challenge are taken in order from `coins`, and its
+/// `ChallengeTuple` coin from `tuple_coins`. These are only sampled for circuits with tuple LUTs,
+/// so that the challenges of other lookup circuits are unchanged, and are `zero` otherwise.
+pub(crate) fn arrange_lookup_coins<T: Copy>(
+    coins: &[T],
+    tuple_coins: Option<&[T]>,
+    zero: T,
+) -> Vec<T> {
+    coins
+        .chunks_exact(NUM_COINS_LOOKUP - 1)
+        .enumerate()
+        .flat_map(|(i, challenge_coins)| {
+            let tuple_coin = tuple_coins.map_or(zero, |tuple_coins| tuple_coins[i]);
+            challenge_coins
+                .iter()
+                .copied()
+                .chain(core::iter::once(tuple_coin))
+        })
+        .collect()
+}
 
 /// Enum listing the different types of lookup challenges.
 /// `ChallengeA` is used for the linear combination of input and output pairs in Sum and LDC.
 /// `ChallengeB` is used for the linear combination of input and output pairs in the polynomial RE.
 /// `ChallengeAlpha` is used for the running sums: 1/(alpha - combo_i).
 /// `ChallengeDelta` is a challenge on which to evaluate the interpolated LUT function.
+/// `ChallengeTuple` is used to compress the inputs and the outputs of tuple LUTs: (x_0, ..., x_{k-1}) becomes sum_j(c^j * x_j),
+/// where c is the challenge.
 #[derive(Debug)]
 pub enum LookupChallenges {
     ChallengeA = 0,
     ChallengeB = 1,
     ChallengeAlpha = 2,
     ChallengeDelta = 3,
+    ChallengeTuple = 4,
 }
 
 /// Structure containing, for each lookup table, the indices of the last lookup row,
@@ -182,7 +214,8 @@ pub struct CircuitBuilder<F: RichField + Extendable<D>, const D: usize> {
     constant_generators: Vec<ConstantGenerator<F>>,
 
     /// Rows for each LUT: [`LookupWire`] contains: first [`LookupGate`], first and last
-    /// [LookupTableGate](crate::gates::lookup_table::LookupTableGate).
+    /// [LookupTableGate](crate::gates::lookup_table::LookupTableGate). The rows of the LUTs in
+    /// `luts` come first, then the rows of the tuple LUTs, then the rows of the dynamic LUTs.
     lookup_rows: Vec<LookupWire>,
 
     /// For each LUT index, vector of `(looking_in, looking_out)` pairs.
@@ -191,6 +224,24 @@ pub struct CircuitBuilder<F: RichField + Extendable<D>, const D: usize> {
     // Lookup tables in the form of `Vec<(input_value, output_value)>`.
     luts: Vec<LookupTable>,
 
+    /// Lookup tables whose rows are tuples of field elements.
+    tuple_luts: Vec<TupleLookupTable<F>>,
+
+    /// For each tuple LUT index, vector of `(looking_inputs, looking_outputs)` pairs.
+    tuple_lut_to_lookups: Vec<TupleLookup>,
+
+    /// Dynamic lookup tables, whose `(input, output)` rows are targets.
+    dynamic_luts: Vec<Lookup>,
+
+    /// For each dynamic LUT index, vector of `(looking_in, looking_out)` pairs.
+    dynamic_lut_to_lookups: Vec<Lookup>,
+
+    /// How `range_check` checks that values fit in a number of bits.
+    range_check_mode: RangeCheckMode,
+
+    /// Indices of the range LUTs used by lookup range checks, by number of bits.
+    range_luts: HashMap<usize, usize>,
+
     /// Optional common data. When it is `Some(goal_data)`, the `build` function panics if the resulting
     /// common data doesn't equal `goal_data`.
     /// This is used in cyclic recursion.
@@ -226,6 +277,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             lookup_rows: Vec::new(),
             lut_to_lookups: Vec::new(),
             luts: Vec::new(),
+            tuple_luts: Vec::new(),
+            tuple_lut_to_lookups: Vec::new(),
+            dynamic_luts: Vec::new(),
+            dynamic_lut_to_lookups: Vec::new(),
+            range_check_mode: RangeCheckMode::default(),
+            range_luts: HashMap::new(),
             goal_common_data: None,
             verifier_data_public_input: None,
         };
@@ -263,6 +320,17 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.domain_separator = Some(separator);
     }
 
+    /// Sets how `range_check` checks that values fit in a number of bits, for the checks added after
+    /// this call.
+    pub fn set_range_check_mode(&mut self, mode: RangeCheckMode) {
+        self.range_check_mode = mode;
+    }
+
+    /// Returns how `range_check` currently checks that values fit in a number of bits.
+    pub const fn range_check_mode(&self) -> RangeCheckMode {
+        self.range_check_mode
+    }
+
     /// Outputs the number of gates in this circuit.
     pub fn num_gates(&self) -> usize {
         self.gate_instances.len()
@@ -319,6 +387,112 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         &self.lut_to_lookups[lut_index]
     }
 
+    /// Returns the index of the LUT mapping each value of `num_bits` bits to itself, adding it if needed.
+    pub(crate) fn range_lut(&mut self, num_bits: usize) -> usize {
+        assert!(
+            0 < num_bits && num_bits <= 16,
+            "Range LUTs have between 1 and 16 bits, got {num_bits}"
+        );
+        if let Some(&idx) = self.range_luts.get(&num_bits) {
+            return idx;
+        }
+        let table: LookupTable = Arc::new(
+            (0..1u32 << num_bits)
+                .map(|i| (i as u16, i as u16))
+                .collect(),
+        );
+        let idx = self.update_luts_from_pairs(table);
+        self.range_luts.insert(num_bits, idx);
+        idx
+    }
+
+    /// Adds a tuple lookup table, if it isn't already stored, and returns its index.
+    pub fn update_tuple_luts(&mut self, table: TupleLookupTable<F>) -> usize {
+        if let Some(idx) = self.tuple_luts.iter().position(|elt| *elt == table) {
+            idx
+        } else {
+            self.tuple_luts.push(table);
+            self.tuple_lut_to_lookups.push(vec![]);
+            self.tuple_luts.len() - 1
+        }
+    }
+
+    /// Adds a looking (inputs, outputs) pair to the corresponding tuple LUT.
+    pub fn update_tuple_lookups(
+        &mut self,
+        looking_inputs: Vec<Target>,
+        looking_outputs: Vec<Target>,
+        tuple_lut_index: usize,
+    ) {
+        assert!(
+            tuple_lut_index < self.tuple_lut_to_lookups.len(),
+            "The tuple LUT with index {} has not been created",
+            tuple_lut_index,
+        );
+        self.tuple_lut_to_lookups[tuple_lut_index].push((looking_inputs, looking_outputs));
+    }
+
+    /// Outputs the number of tuple lookup tables in this circuit.
+    pub fn num_tuple_luts(&self) -> usize {
+        self.tuple_luts.len()
+    }
+
+    /// Given an index, outputs the corresponding tuple lookup table.
+    pub fn get_tuple_lut(&self, tuple_lut_index: usize) -> &TupleLookupTable<F> {
+        &self.tuple_luts[tuple_lut_index]
+    }
+
+    /// Given an index, outputs the lookups made into the corresponding tuple lookup table,
+    /// as a sequence of `(inputs, outputs)` pairs.
+    pub fn get_tuple_lut_lookups(&self, tuple_lut_index: usize) -> &[(Vec<Target>, Vec<Target>)] {
+        &self.tuple_lut_to_lookups[tuple_lut_index]
+    }
+
+    /// Returns the number of inputs and outputs of the slots of tuple lookup gates, i.e. the largest
+    /// numbers of inputs and outputs over all tuple lookup tables.
+    pub fn tuple_lookup_shape(&self) -> (usize, usize) {
+        tuple_lookup_shape(&self.tuple_luts)
+    }
+
+    /// Adds a dynamic lookup table, given as `(input, output)` target pairs, and returns its index.
+    pub fn update_dynamic_luts(&mut self, table: Lookup) -> usize {
+        assert!(!table.is_empty(), "Dynamic lookup tables can't be empty");
+        self.dynamic_luts.push(table);
+        self.dynamic_lut_to_lookups.push(vec![]);
+        self.dynamic_luts.len() - 1
+    }
+
+    /// Adds a looking (input, output) pair to the corresponding dynamic LUT.
+    pub fn update_dynamic_lookups(
+        &mut self,
+        looking_in: Target,
+        looking_out: Target,
+        dynamic_lut_index: usize,
+    ) {
+        assert!(
+            dynamic_lut_index < self.dynamic_lut_to_lookups.len(),
+            "The dynamic LUT with index {} has not been created",
+            dynamic_lut_index,
+        );
+        self.dynamic_lut_to_lookups[dynamic_lut_index].push((looking_in, looking_out));
+    }
+
+    /// Outputs the number of dynamic lookup tables in this circuit.
+    pub fn num_dynamic_luts(&self) -> usize {
+        self.dynamic_luts.len()
+    }
+
+    /// Given an index, outputs the rows of the corresponding dynamic lookup table.
+    pub fn get_dynamic_lut(&self, dynamic_lut_index: usize) -> &[(Target, Target)] {
+        &self.dynamic_luts[dynamic_lut_index]
+    }
+
+    /// Given an index, outputs the lookups made into the corresponding dynamic lookup table,
+    /// as a sequence of target tuples `(input, output)`.
+    pub fn get_dynamic_lut_lookups(&self, dynamic_lut_index: usize) -> &[(Target, Target)] {
+        &self.dynamic_lut_to_lookups[dynamic_lut_index]
+    }
+
     /// Adds a new "virtual" target. This is not an actual wire in the witness, but just a target
     /// that help facilitate witness generation. In particular, a generator can assign a values to a
     /// virtual target, which can then be copied to other (virtual or concrete) targets. When we
@@ -401,6 +575,17 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         b
     }
 
+    /// Adds a virtual target range-checked to hold a byte.
+    pub fn add_virtual_byte_target(&mut self) -> ByteTarget {
+        let b = self.add_virtual_target();
+        self.range_check(b, 8);
+        ByteTarget::new_unsafe(b)
+    }
+
+    pub fn add_virtual_byte_targets(&mut self, n: usize) -> Vec<ByteTarget> {
+        (0..n).map(|_| self.add_virtual_byte_target()).collect()
+    }
+
     /// Add a virtual target and register it as a public input.
     pub fn add_virtual_public_input(&mut self) -> Target {
         let t = self.add_virtual_target();
@@ -625,6 +810,11 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         }
     }
 
+    /// Returns a routable target with the given constant byte value.
+    pub fn constant_byte(&mut self, b: u8) -> ByteTarget {
+        ByteTarget::new_unsafe(self.constant(F::from_canonical_u8(b)))
+    }
+
     /// Returns a routable [`HashOutTarget`].
     pub fn constant_hash(&mut self, h: HashOut<F>) -> HashOutTarget {
         HashOutTarget {
@@ -963,8 +1153,18 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     }
 
     fn sigma_vecs(&self, k_is: &[F], subgroup: &[F]) -> (Vec<PolynomialValues<F>>, Forest) {
+        let degree_log = log2_strict(self.gate_instances.len());
+        let mut forest = self.forest();
+        let wire_partition = forest.wire_partition();
+        (
+            wire_partition.get_sigma_polys(degree_log, k_is, subgroup),
+            forest,
+        )
+    }
+
+    /// Builds the partition of all targets induced by the copy constraints.
+    fn forest(&self) -> Forest {
         let degree = self.gate_instances.len();
-        let degree_log = log2_strict(degree);
         let config = &self.config;
         let mut forest = Forest::new(
             config.num_wires,
@@ -991,12 +1191,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         }
 
         forest.compress_paths();
-
-        let wire_partition = forest.wire_partition();
-        (
-            wire_partition.get_sigma_polys(degree_log, k_is, subgroup),
-            forest,
-        )
+        forest
     }
 
     pub fn print_gate_counts(&self, min_delta: usize) {
@@ -1042,9 +1237,21 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     }
 
     pub fn try_build_with_options<C: GenericConfig<D, F = F>>(
-        mut self,
+        self,
         commit_to_sigma: bool,
     ) -> (CircuitData<F, C, D>, bool) {
+        let (circuit_data, success, _) = self.try_build_with_debug_info(commit_to_sigma, false);
+        (circuit_data, success)
+    }
+
+    /// Same as `try_build_with_options`, but also outputs the layout information needed to locate
+    /// the constraints not satisfied by a witness. Mock circuits check copy constraints on the
+    /// partition of targets directly, so their sigma polynomials are left empty.
+    fn try_build_with_debug_info<C: GenericConfig<D, F = F>>(
+        mut self,
+        commit_to_sigma: bool,
+        mock: bool,
+    ) -> (CircuitData<F, C, D>, bool, CircuitDebugInfo<F, D>) {
         let mut timing = TimingTree::new("preprocess", Level::Trace);
 
         #[cfg(feature = "std")]
@@ -1052,8 +1259,10 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
 
         let rate_bits = self.config.fri_config.rate_bits;
         let cap_height = self.config.fri_config.cap_height;
-        // Total number of LUTs.
+        // Total number of LUTs, not counting dynamic LUTs.
         let num_luts = self.get_luts_length();
+        let num_tuple_luts = self.num_tuple_luts();
+        let has_lookups = num_luts != 0 || num_tuple_luts != 0 || self.num_dynamic_luts() != 0;
         // Hash the public inputs, and route them to a `PublicInputGate` which will enforce that
         // those hash wires match the claimed public inputs.
         let num_public_inputs = self.public_inputs.len();
@@ -1123,11 +1332,23 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             selector_polynomials(&gates, &self.gate_instances, quotient_degree_factor + 1);
 
         // Get the lookup selectors.
-        let num_lookup_selectors = if num_luts != 0 {
-            let selector_lookups =
-                selectors_lookup(&gates, &self.gate_instances, &self.lookup_rows);
-            let selector_ends = selector_ends_lookups(&self.lookup_rows, &self.gate_instances);
-            let all_lookup_selectors = [selector_lookups, selector_ends].concat();
+        let num_lookup_selectors = if has_lookups {
+            let tuple_rows = num_luts..num_luts + num_tuple_luts;
+            let selector_lookups = selectors_lookup(
+                &gates,
+                &self.gate_instances,
+                &self.lookup_rows,
+                tuple_rows.clone(),
+            );
+            // Dynamic LUTs come last and have no end selector.
+            let selector_ends = selector_ends_lookups(
+                &self.lookup_rows[..num_luts + num_tuple_luts],
+                &self.gate_instances,
+            );
+            let selector_tuple_lookups =
+                selectors_tuple_lookup(&self.lookup_rows[tuple_rows], &self.gate_instances);
+            let all_lookup_selectors =
+                [selector_lookups, selector_ends, selector_tuple_lookups].concat();
             let num_lookup_selectors = all_lookup_selectors.len();
             constant_vecs.extend(all_lookup_selectors);
             num_lookup_selectors
@@ -1141,11 +1362,15 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let subgroup = F::two_adic_subgroup(degree_bits);
 
         let k_is = get_unique_coset_shifts(degree, self.config.num_routed_wires);
-        let (sigma_vecs, forest) = timed!(
-            timing,
-            "generate sigma polynomials",
-            self.sigma_vecs(&k_is, &subgroup)
-        );
+        let (sigma_vecs, forest) = if mock {
+            (Vec::new(), self.forest())
+        } else {
+            timed!(
+                timing,
+                "generate sigma polynomials",
+                self.sigma_vecs(&k_is, &subgroup)
+            )
+        };
 
         // Precompute FFT roots.
         let max_fft_points = 1 << (degree_bits + max(rate_bits, log2_ceil(quotient_degree_factor)));
@@ -1181,7 +1406,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
                     let mut gens = gate.gate_ref.0.generators(index, &gate.constants);
                     // Remove unused generators, if any.
                     if let Some(&op) = incomplete_gates.get(&index) {
-                        gens.drain(op..);
+                        gens.truncate(op);
                     }
                     gens
                 })
@@ -1215,11 +1440,22 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             num_partial_products(self.config.num_routed_wires, quotient_degree_factor);
 
         let lookup_degree = self.config.max_quotient_degree_factor - 1;
-        let num_lookup_polys = if num_luts == 0 {
+        let num_lookup_polys = if !has_lookups {
             0
         } else {
-            // There is 1 RE polynomial and multiple Sum/LDC polynomials.
-            ceil_div_usize(LookupGate::num_slots(&self.config), lookup_degree) + 1
+            // There is 1 RE polynomial and multiple Sum/LDC polynomials, enough for the
+            // lookup gates with the most slots.
+            let (num_inputs, num_outputs) = self.tuple_lookup_shape();
+            let num_lu_slots = if num_tuple_luts == 0 {
+                LookupGate::num_slots(&self.config)
+            } else {
+                max(
+                    LookupGate::num_slots(&self.config),
+                    TupleLookupGate::new_from_config(&self.config, num_inputs, num_outputs)
+                        .num_slots,
+                )
+            };
+            ceil_div_usize(num_lu_slots, lookup_degree) + 1
         };
         let constants_sigmas_cap = constants_sigmas_commitment.merkle_tree.cap.clone();
         let domain_separator = self.domain_separator.unwrap_or_default();
@@ -1249,6 +1485,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             num_lookup_polys,
             num_lookup_selectors,
             luts: self.luts,
+            tuple_luts: self.tuple_luts,
         };
 
         let mut success = true;
@@ -1264,7 +1501,11 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             generators: self.generators,
             generator_indices_by_watches,
             constants_sigmas_commitment,
-            sigmas: transpose_poly_values(sigma_vecs),
+            sigmas: if mock {
+                Vec::new()
+            } else {
+                transpose_poly_values(sigma_vecs)
+            },
             subgroup,
             public_inputs: self.public_inputs,
             representative_map: forest.parents,
@@ -1272,6 +1513,9 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             circuit_digest,
             lookup_rows: self.lookup_rows.clone(),
             lut_to_lookups: self.lut_to_lookups.clone(),
+            tuple_lut_to_lookups: self.tuple_lut_to_lookups.clone(),
+            dynamic_luts: self.dynamic_luts.clone(),
+            dynamic_lut_to_lookups: self.dynamic_lut_to_lookups.clone(),
         };
 
         let verifier_only = VerifierOnlyCircuitData::<C, D> {
@@ -1279,6 +1523,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             circuit_digest,
         };
 
+        let debug_info = CircuitDebugInfo {
+            gate_instances: self.gate_instances,
+            copy_constraints: self.copy_constraints,
+            context_tree: self.context_log,
+        };
+
         timing.print();
         #[cfg(feature = "std")]
         debug!("Building circuit took {}s", start.elapsed().as_secs_f32());
@@ -1289,6 +1539,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
                 common,
             },
             success,
+            debug_info,
         )
     }
 
@@ -1297,11 +1548,17 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.build_with_options(true)
     }
 
+    /// Builds a "mock circuit", with data needed to generate and check witnesses but not proofs.
+    /// See [`MockCircuitData::check_witness`].
     pub fn mock_build<C: GenericConfig<D, F = F>>(self) -> MockCircuitData<F, C, D> {
-        let circuit_data = self.build_with_options(false);
+        let (circuit_data, success, debug_info) = self.try_build_with_debug_info(false, true);
+        if !success {
+            panic!("Failed to build circuit");
+        }
         MockCircuitData {
             prover_only: circuit_data.prover_only,
             common: circuit_data.common,
+            debug_info,
         }
     }
     /// Builds a "prover circuit", with data needed to generate proofs but not verify them.
@@ -36,14 +36,21 @@ use crate::gates::gate::GateRef;
 use crate::gates::lookup::Lookup;
 use crate::gates::lookup_table::LookupTable;
 use crate::gates::selectors::SelectorsInfo;
+use crate::gates::tuple_lookup::{tuple_lookup_shape, TupleLookup, TupleLookupTable};
 use crate::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::ext_target::ExtensionTarget;
-use crate::iop::generator::{generate_partial_witness, WitnessGeneratorRef};
+use crate::iop::generator::{
+    generate_partial_witness, generate_partial_witness_with_conflicts, WitnessGeneratorRef,
+};
 use crate::iop::target::Target;
 use crate::iop::witness::{PartialWitness, PartitionWitness};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::config::{GenericConfig, Hasher};
+use crate::plonk::diagnostics::{
+    check_copy_constraints, check_generated_values, check_witness, CircuitDebugInfo,
+    UnsatisfiedConstraint,
+};
 use crate::plonk::plonk_common::PlonkOracle;
 use crate::plonk::proof::{CompressedProofWithPublicInputs, ProofWithPublicInputs};
 use crate::plonk::prover::prove;
@@ -146,6 +153,8 @@ pub struct MockCircuitData<F: RichField + Extendable<D>, C: GenericConfig<D, F =
 {
     pub prover_only: ProverOnlyCircuitData<F, C, D>,
     pub common: CommonCircuitData<F, D>,
+    /// Layout of the circuit, used to locate the constraints not satisfied by a witness.
+    pub debug_info: CircuitDebugInfo<F, D>,
 }
 
 impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
@@ -154,6 +163,42 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
     pub fn generate_witness(&self, inputs: PartialWitness<F>) -> PartitionWitness<F> {
         generate_partial_witness::<F, C, D>(inputs, &self.prover_only, &self.common)
     }
+
+    /// Generates the witness from the given inputs, and outputs all the gate, copy and lookup
+    /// constraints of the circuit which it does not satisfy. An empty output means that the
+    /// witness is valid, and that proof generation should succeed with the full circuit data.
+    pub fn check_witness(&self, inputs: PartialWitness<F>) -> Vec<UnsatisfiedConstraint<F>> {
+        // Conflicting values, whether in the inputs or output by generators, are reported and
+        // dropped, so that the rest of the witness can still be generated and checked.
+        let (mut unsatisfied, inputs) = check_copy_constraints(
+            &self.debug_info,
+            &self.prover_only.representative_map,
+            &self.common,
+            inputs,
+        );
+        let (partition_witness, conflicts) = generate_partial_witness_with_conflicts::<F, C, D>(
+            inputs,
+            &self.prover_only,
+            &self.common,
+        );
+        unsatisfied.extend(check_generated_values(&self.debug_info, conflicts));
+        unsatisfied.extend(self.check_partition_witness(partition_witness));
+        unsatisfied
+    }
+
+    /// Same as `check_witness`, for an already generated witness. Its copy constraints are satisfied
+    /// by construction, since it holds a single value per partition.
+    pub fn check_partition_witness(
+        &self,
+        partition_witness: PartitionWitness<F>,
+    ) -> Vec<UnsatisfiedConstraint<F>> {
+        check_witness::<F, C, D>(
+            &self.prover_only,
+            &self.common,
+            &self.debug_info,
+            partition_witness,
+        )
+    }
 }
 
 /// Circuit data required by the prover or the verifier.
@@ -365,6 +410,12 @@ pub struct ProverOnlyCircuitData<
     pub lookup_rows: Vec<LookupWire>,
     /// A vector of (looking_in, looking_out) pairs for for each lookup table index.
     pub lut_to_lookups: Vec<Lookup>,
+    /// A vector of (looking_inputs, looking_outputs) pairs for each tuple lookup table index.
+    pub tuple_lut_to_lookups: Vec<TupleLookup>,
+    /// The (input, output) rows of each dynamic lookup table.
+    pub dynamic_luts: Vec<Lookup>,
+    /// A vector of (looking_in, looking_out) pairs for each dynamic lookup table index.
+    pub dynamic_lut_to_lookups: Vec<Lookup>,
 }
 
 impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
@@ -451,6 +502,9 @@ pub struct CommonCircuitData<F: RichField + Extendable<D>, const D: usize> {
 
     /// The stored lookup tables.
     pub luts: Vec<LookupTable>,
+
+    /// The stored lookup tables with tuple rows.
+    pub tuple_luts: Vec<TupleLookupTable<F>>,
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
@@ -468,6 +522,11 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
         buffer.read_common_circuit_data(gate_serializer)
     }
 
+    /// Returns the numbers of inputs and outputs of the slots of tuple lookup gates.
+    pub fn tuple_lookup_shape(&self) -> (usize, usize) {
+        tuple_lookup_shape(&self.tuple_luts)
+    }
+
     pub const fn degree_bits(&self) -> usize {
         self.fri_params.degree_bits
     }
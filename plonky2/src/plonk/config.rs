@@ -1,10 +1,13 @@
 //! Hashing configuration to be used when building a circuit.
 //!
 //! This module defines a [`Hasher`] trait as well as its recursive
-//! counterpart [`AlgebraicHasher`] for in-circuit hashing. It also
-//! provides concrete configurations, one fully recursive leveraging
-//! the Poseidon hash function both internally and natively, and one
-//! mixing Poseidon internally and truncated Keccak externally.
+//! counterparts [`CircuitHasher`], for hash functions which can be
+//! evaluated in circuits, and [`AlgebraicHasher`] for in-circuit hashing
+//! with a sponge. It also
+//! provides concrete configurations, two fully recursive leveraging
+//! the Poseidon or Poseidon2 hash functions both internally and natively,
+//! and two mixing Poseidon internally with either Poseidon over BN254 or
+//! truncated Keccak externally.
 
 #[cfg(not(feature = "std"))]
 use alloc::{vec, vec::Vec};
@@ -16,10 +19,12 @@ use serde::Serialize;
 use crate::field::extension::quadratic::QuadraticExtension;
 use crate::field::extension::{Extendable, FieldExtension};
 use crate::field::goldilocks_field::GoldilocksField;
-use crate::hash::hash_types::{HashOut, RichField};
+use crate::hash::hash_types::{HashOut, HashOutTarget, RichField};
 use crate::hash::hashing::PlonkyPermutation;
 use crate::hash::keccak::KeccakHash;
 use crate::hash::poseidon::PoseidonHash;
+use crate::hash::poseidon2::Poseidon2Hash;
+use crate::hash::poseidon_bn254::PoseidonBn254Hash;
 use crate::iop::target::{BoolTarget, Target};
 use crate::plonk::circuit_builder::CircuitBuilder;
 
@@ -73,9 +78,53 @@ pub trait Hasher<F: RichField>: Sized + Copy + Debug + Eq + PartialEq {
         }
     }
 
+    /// Computes `hash_or_noop` of each input. Hashers may override this to hash several inputs at
+    /// once, for example with a vectorized permutation.
+    fn hash_or_noop_batch(inputs: &[Vec<F>]) -> Vec<Self::Hash> {
+        inputs
+            .iter()
+            .map(|input| Self::hash_or_noop(input))
+            .collect()
+    }
+
     fn two_to_one(left: Self::Hash, right: Self::Hash) -> Self::Hash;
 }
 
+/// Trait for hash functions which can be evaluated in circuits, so that proofs using them for
+/// Merkle trees and the challenger can be verified recursively. In circuits, a hash is represented
+/// by a [`HashOutTarget`] holding the elements of [`GenericHashOut::to_vec`].
+pub trait CircuitHasher<F: RichField>: Hasher<F> {
+    /// The state of [`Hasher::Permutation`] in circuits.
+    type CircuitPermutation: PlonkyPermutation<Target>;
+
+    /// Circuit to apply [`Hasher::Permutation`].
+    fn permute_circuit<const D: usize>(
+        inputs: Self::CircuitPermutation,
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self::CircuitPermutation
+    where
+        F: RichField + Extendable<D>;
+
+    /// Circuit to compute [`Hasher::hash_or_noop`].
+    fn hash_or_noop_circuit<const D: usize>(
+        inputs: Vec<Target>,
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> HashOutTarget
+    where
+        F: RichField + Extendable<D>;
+
+    /// Circuit to conditionally swap two hashes (useful in verifying Merkle proofs), then compute
+    /// [`Hasher::two_to_one`].
+    fn two_to_one_swapped_circuit<const D: usize>(
+        left: HashOutTarget,
+        right: HashOutTarget,
+        swap: BoolTarget,
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> HashOutTarget
+    where
+        F: RichField + Extendable<D>;
+}
+
 /// Trait for algebraic hash functions, built from a permutation using the sponge construction.
 pub trait AlgebraicHasher<F: RichField>: Hasher<F, Hash = HashOut<F>> {
     type AlgebraicPermutation: PlonkyPermutation<Target>;
@@ -91,6 +140,43 @@ pub trait AlgebraicHasher<F: RichField>: Hasher<F, Hash = HashOut<F>> {
         F: RichField + Extendable<D>;
 }
 
+/// Algebraic hash functions are evaluated in circuits with their permutation gate.
+impl<F: RichField, H: AlgebraicHasher<F>> CircuitHasher<F> for H {
+    type CircuitPermutation = H::AlgebraicPermutation;
+
+    fn permute_circuit<const D: usize>(
+        inputs: Self::CircuitPermutation,
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self::CircuitPermutation
+    where
+        F: RichField + Extendable<D>,
+    {
+        builder.permute::<H>(inputs)
+    }
+
+    fn hash_or_noop_circuit<const D: usize>(
+        inputs: Vec<Target>,
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> HashOutTarget
+    where
+        F: RichField + Extendable<D>,
+    {
+        builder.hash_or_noop::<H>(inputs)
+    }
+
+    fn two_to_one_swapped_circuit<const D: usize>(
+        left: HashOutTarget,
+        right: HashOutTarget,
+        swap: BoolTarget,
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> HashOutTarget
+    where
+        F: RichField + Extendable<D>,
+    {
+        builder.two_to_one_swapped::<H>(left, right, swap)
+    }
+}
+
 /// Generic configuration trait.
 pub trait GenericConfig<const D: usize>:
     Debug + Clone + Sync + Sized + Send + Eq + PartialEq
@@ -115,7 +201,29 @@ impl GenericConfig<2> for PoseidonGoldilocksConfig {
     type InnerHasher = PoseidonHash;
 }
 
-/// Configuration using truncated Keccak over the Goldilocks field.
+/// Configuration using Poseidon2 over the Goldilocks field.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize)]
+pub struct Poseidon2GoldilocksConfig;
+impl GenericConfig<2> for Poseidon2GoldilocksConfig {
+    type F = GoldilocksField;
+    type FE = QuadraticExtension<Self::F>;
+    type Hasher = Poseidon2Hash;
+    type InnerHasher = Poseidon2Hash;
+}
+
+/// Configuration using Poseidon internally and Poseidon over the BN254 scalar field externally,
+/// for proofs that are to be verified over BN254.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize)]
+pub struct PoseidonBn254GoldilocksConfig;
+impl GenericConfig<2> for PoseidonBn254GoldilocksConfig {
+    type F = GoldilocksField;
+    type FE = QuadraticExtension<Self::F>;
+    type Hasher = PoseidonBn254Hash;
+    type InnerHasher = PoseidonHash;
+}
+
+/// Configuration using truncated Keccak over the Goldilocks field. Its proofs can be verified
+/// recursively, but each Keccak-256 call costs around 2200 rows in the verifier circuit.
 #[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
 pub struct KeccakGoldilocksConfig;
 impl GenericConfig<2> for KeccakGoldilocksConfig {
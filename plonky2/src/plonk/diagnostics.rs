@@ -0,0 +1,695 @@
+//! Diagnostics to locate the constraints of a circuit which are not satisfied by a witness.
+//!
+//! With an invalid witness, the prover only fails late, when the quotient polynomials cannot be
+//! computed, without indicating which constraint is violated. The checks of this module evaluate
+//! every constraint of the circuit row by row instead, and report each failing gate along with
+//! the scope (see [`with_context`](crate::with_context)) in which it was added.
+//! They can be run through [`MockCircuitData::check_witness`](crate::plonk::circuit_data::MockCircuitData::check_witness).
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
+use hashbrown::{HashMap, HashSet};
+use plonky2_maybe_rayon::*;
+
+use crate::field::extension::Extendable;
+use crate::field::types::Field;
+use crate::gates::gate::GateInstance;
+use crate::gates::selectors::{selector_ends_lookups, selectors_lookup, selectors_tuple_lookup};
+use crate::hash::hash_types::RichField;
+use crate::iop::challenger::Challenger;
+use crate::iop::generator::ConflictingValue;
+use crate::iop::target::Target;
+use crate::iop::witness::{PartialWitness, PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::{arrange_lookup_coins, LookupChallenges, NUM_COINS_LOOKUP};
+use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
+use crate::plonk::config::{GenericConfig, Hasher};
+use crate::plonk::copy_constraint::CopyConstraint;
+use crate::plonk::prover::{compute_lookup_polys, set_lookup_wires};
+use crate::plonk::vanishing_poly::{check_lookup_constraints_batch, get_lut_poly, lookup_layout};
+use crate::plonk::vars::EvaluationVarsBaseBatch;
+use crate::util::context_tree::ContextTree;
+
+/// Layout information about a circuit, which is not needed to generate proofs but allows to
+/// locate the constraints not satisfied by a witness.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CircuitDebugInfo<F: RichField + Extendable<D>, const D: usize> {
+    /// The gate placed at each row of the circuit, along with its constants.
+    pub(crate) gate_instances: Vec<GateInstance<F, D>>,
+    /// All copy constraints of the circuit, named after the scope in which they were added.
+    pub(crate) copy_constraints: Vec<CopyConstraint>,
+    /// The tree of named scopes in which the gates were added.
+    pub(crate) context_tree: ContextTree,
+}
+
+/// A constraint of a circuit which is not satisfied by a witness.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UnsatisfiedConstraint<F: RichField> {
+    /// Some constraints of the gate placed at `row`, given by their index in the output of
+    /// [`Gate::eval_unfiltered`](crate::gates::gate::Gate::eval_unfiltered).
+    Gate {
+        row: usize,
+        gate_id: String,
+        scope: String,
+        constraints: Vec<usize>,
+    },
+    /// Two targets which are connected by copy constraints, but are assigned different values by
+    /// the inputs or by witness generation. `pair.0` is the first target of the partition with a
+    /// value, and `scope` is the scope in which a copy constraint of `pair.1` was added.
+    Copy {
+        pair: (Target, Target),
+        values: (F, F),
+        scope: String,
+    },
+    /// Some lookup constraints evaluated at `row`, given by their index in the output of
+    /// `check_lookup_constraints`, or the `slots` of the lookup gate placed at `row` whose values
+    /// are not in their table. Lookup constraints are only evaluated if all lookups are in their
+    /// tables, since the prover can't compute the multiplicities of the lookup argument otherwise.
+    Lookup {
+        row: usize,
+        gate_id: String,
+        scope: String,
+        constraints: Vec<usize>,
+        slots: Vec<usize>,
+    },
+}
+
+impl<F: RichField> Display for UnsatisfiedConstraint<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Gate {
+                row,
+                gate_id,
+                scope,
+                constraints,
+            } => write!(
+                f,
+                "Row {row} ({gate_id}, in `{scope}`): unsatisfied gate constraints {constraints:?}."
+            ),
+            Self::Copy {
+                pair: (a, b),
+                values: (value_a, value_b),
+                scope,
+            } => write!(
+                f,
+                "Copy constraint between {a:?} and {b:?} (in `{scope}`): unsatisfied with values {value_a} and {value_b}."
+            ),
+            Self::Lookup {
+                row,
+                gate_id,
+                scope,
+                slots,
+                ..
+            } if !slots.is_empty() => write!(
+                f,
+                "Row {row} ({gate_id}, in `{scope}`): lookups in slots {slots:?} are not in their table."
+            ),
+            Self::Lookup {
+                row,
+                gate_id,
+                scope,
+                constraints,
+                ..
+            } => write!(
+                f,
+                "Row {row} ({gate_id}, in `{scope}`): unsatisfied lookup constraints {constraints:?}."
+            ),
+        }
+    }
+}
+
+/// Checks the given witness against the gate constraints of every row and the lookup constraints
+/// of the circuit, and outputs all those which are not satisfied. A partition witness has a single
+/// value per partition, so copy constraints are checked on the inputs by `check_copy_constraints`.
+pub(crate) fn check_witness<F, C, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    debug_info: &CircuitDebugInfo<F, D>,
+    mut partition_witness: PartitionWitness<F>,
+) -> Vec<UnsatisfiedConstraint<F>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    // The lookup wires can only be set if all lookups are in their tables.
+    let mut unsatisfied =
+        check_lookup_values(prover_data, common_data, debug_info, &partition_witness);
+    let lookups_in_tables = unsatisfied.is_empty();
+    if lookups_in_tables {
+        set_lookup_wires(prover_data, common_data, &mut partition_witness);
+    }
+
+    let public_inputs = prover_data
+        .public_inputs
+        .iter()
+        .map(|&t| partition_witness.try_get_target(t).unwrap_or(F::ZERO))
+        .collect::<Vec<_>>();
+    let public_inputs_hash = C::InnerHasher::hash_no_pad(&public_inputs);
+    let witness = partition_witness.full_witness();
+    let degree = common_data.degree();
+    let num_constants = common_data.config.num_constants;
+    let gate_id = |row: usize| debug_info.gate_instances[row].gate_ref.0.id();
+    let scope = |row: usize| debug_info.context_tree.gate_stack(row);
+
+    // Check the gate constraints of every row.
+    let gate_failures = (0..degree)
+        .into_par_iter()
+        .filter_map(|row| {
+            let instance = &debug_info.gate_instances[row];
+            let mut local_constants = instance.constants.clone();
+            local_constants.resize(num_constants.max(local_constants.len()), F::ZERO);
+            let local_wires = (0..common_data.config.num_wires)
+                .map(|column| witness.get_wire(row, column))
+                .collect::<Vec<_>>();
+            let vars = EvaluationVarsBaseBatch::new(
+                1,
+                &local_constants,
+                &local_wires,
+                &public_inputs_hash,
+            );
+
+            // Some gates only implement the batched evaluation, hence we use a batch of one row.
+            let constraint_values = instance.gate_ref.0.eval_unfiltered_base_batch(vars);
+            let constraints = failing_indices(&constraint_values);
+            (!constraints.is_empty()).then(|| UnsatisfiedConstraint::Gate {
+                row,
+                gate_id: gate_id(row),
+                scope: scope(row),
+                constraints,
+            })
+        })
+        .collect::<Vec<_>>();
+    unsatisfied.extend(gate_failures);
+
+    // Check the lookup constraints of every row, for a single set of challenges.
+    if common_data.num_lookup_polys != 0 && lookups_in_tables {
+        let mut challenger = Challenger::<F, C::Hasher>::new();
+        challenger.observe_elements(&public_inputs);
+        let coins = challenger.get_n_challenges(NUM_COINS_LOOKUP - 1);
+        let tuple_coins =
+            (!common_data.tuple_luts.is_empty()).then(|| challenger.get_n_challenges(1));
+        let deltas: [F; NUM_COINS_LOOKUP] =
+            arrange_lookup_coins(&coins, tuple_coins.as_deref(), F::ZERO)
+                .try_into()
+                .expect("Expected exactly as many challenges as lookup coins.");
+        let lookup_polys = compute_lookup_polys(&witness, &deltas, prover_data, common_data);
+        let num_luts = common_data.luts.len();
+        let tuple_rows = num_luts..num_luts + common_data.tuple_luts.len();
+        let lookup_selectors = [
+            selectors_lookup(
+                &common_data.gates,
+                &debug_info.gate_instances,
+                &prover_data.lookup_rows,
+                tuple_rows.clone(),
+            ),
+            selector_ends_lookups(
+                &prover_data.lookup_rows[..tuple_rows.end],
+                &debug_info.gate_instances,
+            ),
+            selectors_tuple_lookup(
+                &prover_data.lookup_rows[tuple_rows],
+                &debug_info.gate_instances,
+            ),
+        ]
+        .concat();
+
+        // The final RE value of each fixed LUT only depends on the challenges, and evaluating it
+        // takes time linear in the size of the LUT, so it is done once for all rows.
+        let lut_re_poly_evals = (0..num_luts + common_data.tuple_luts.len())
+            .map(|r| {
+                get_lut_poly(common_data, r, &deltas)
+                    .eval(deltas[LookupChallenges::ChallengeDelta as usize])
+            })
+            .collect::<Vec<_>>();
+
+        let lookup_failures = (0..degree)
+            .into_par_iter()
+            .filter_map(|row| {
+                let next_row = (row + 1) % degree;
+                let local_wires = (0..common_data.config.num_wires)
+                    .map(|column| witness.get_wire(row, column))
+                    .collect::<Vec<_>>();
+                let local_lookup_zs = lookup_polys
+                    .iter()
+                    .map(|poly| poly.values[row])
+                    .collect::<Vec<_>>();
+                let next_lookup_zs = lookup_polys
+                    .iter()
+                    .map(|poly| poly.values[next_row])
+                    .collect::<Vec<_>>();
+                let local_lookup_selectors = lookup_selectors
+                    .iter()
+                    .map(|poly| poly.values[row])
+                    .collect::<Vec<_>>();
+                let vars = EvaluationVarsBaseBatch::new(1, &[], &local_wires, &public_inputs_hash);
+
+                let constraint_values = check_lookup_constraints_batch(
+                    common_data,
+                    vars.view(0),
+                    &local_lookup_zs,
+                    &next_lookup_zs,
+                    &local_lookup_selectors,
+                    &deltas,
+                    &lut_re_poly_evals,
+                );
+                let constraints = failing_indices(&constraint_values);
+                (!constraints.is_empty()).then(|| UnsatisfiedConstraint::Lookup {
+                    row,
+                    gate_id: gate_id(row),
+                    scope: scope(row),
+                    constraints,
+                    slots: Vec::new(),
+                })
+            })
+            .collect::<Vec<_>>();
+        unsatisfied.extend(lookup_failures);
+    }
+
+    unsatisfied
+}
+
+/// Checks that the values of every used slot of the lookup gates are a row of their table, and
+/// outputs the slots whose values are not. Unlike `set_lookup_wires`, this doesn't panic on them.
+fn check_lookup_values<F, C, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    debug_info: &CircuitDebugInfo<F, D>,
+    partition_witness: &PartitionWitness<F>,
+) -> Vec<UnsatisfiedConstraint<F>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    // Unset targets are set to zero by the prover.
+    let get_target = |t: Target| partition_witness.try_get_target(t).unwrap_or(F::ZERO);
+    let num_luts = common_data.luts.len();
+    let num_tuple_luts = common_data.tuple_luts.len();
+
+    let mut unsatisfied = Vec::new();
+    for (lut_index, lookup_wire) in prover_data.lookup_rows.iter().enumerate() {
+        // The rows of the table, each made of its inputs followed by its outputs.
+        let (table, num_inputs, num_outputs): (HashSet<Vec<F>>, usize, usize) =
+            if lut_index < num_luts {
+                let table = common_data.luts[lut_index]
+                    .iter()
+                    .map(|&(inp, out)| vec![F::from_canonical_u16(inp), F::from_canonical_u16(out)])
+                    .collect();
+                (table, 1, 1)
+            } else if lut_index < num_luts + num_tuple_luts {
+                let table = &common_data.tuple_luts[lut_index - num_luts];
+                let rows = table.rows().map(<[F]>::to_vec).collect();
+                (rows, table.num_inputs(), table.num_outputs())
+            } else {
+                let table = prover_data.dynamic_luts[lut_index - num_luts - num_tuple_luts]
+                    .iter()
+                    .map(|&(inp, out)| vec![get_target(inp), get_target(out)])
+                    .collect();
+                (table, 1, 1)
+            };
+        let tuple = (num_luts..num_luts + num_tuple_luts).contains(&lut_index);
+        let (lu_gate, _) = lookup_layout(common_data, tuple);
+
+        for row in lookup_wire.last_lu_gate..lookup_wire.last_lut_gate {
+            let slots = (0..lu_gate.num_slots)
+                .filter(|&slot| {
+                    let mut inputs = lu_gate.wires_ith_looking_inp(slot).take(num_inputs);
+                    // Slots without any lookup are only padded when setting the lookup wires.
+                    let first_input = Target::wire(row, inputs.next().unwrap());
+                    if partition_witness.try_get_target(first_input).is_none() {
+                        return false;
+                    }
+                    let values = core::iter::once(first_input)
+                        .chain(inputs.map(|wire| Target::wire(row, wire)))
+                        .chain(
+                            lu_gate
+                                .wires_ith_looking_out(slot)
+                                .take(num_outputs)
+                                .map(|wire| Target::wire(row, wire)),
+                        )
+                        .map(get_target)
+                        .collect::<Vec<_>>();
+                    !table.contains(&values)
+                })
+                .collect::<Vec<_>>();
+            if !slots.is_empty() {
+                unsatisfied.push(UnsatisfiedConstraint::Lookup {
+                    row,
+                    gate_id: debug_info.gate_instances[row].gate_ref.0.id(),
+                    scope: debug_info.context_tree.gate_stack(row),
+                    constraints: Vec::new(),
+                    slots,
+                });
+            }
+        }
+    }
+    unsatisfied
+}
+
+/// Checks that all the targets of each partition of the copy constraints are assigned the same
+/// value by `inputs`, comparing each of them to the first target of the partition which is set.
+/// Partitions without any set target are skipped, since the prover sets them to zero.
+///
+/// Returns the unsatisfied copy constraints, along with `inputs` without the conflicting values,
+/// from which the rest of the witness can be generated.
+pub(crate) fn check_copy_constraints<F: RichField + Extendable<D>, const D: usize>(
+    debug_info: &CircuitDebugInfo<F, D>,
+    representative_map: &[usize],
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+) -> (Vec<UnsatisfiedConstraint<F>>, PartialWitness<F>) {
+    let num_wires = common_data.config.num_wires;
+    let degree = common_data.degree();
+    let index = |t: Target| t.index(num_wires, degree);
+    let scopes = copy_constraint_scopes(debug_info);
+
+    let mut targets = inputs.target_values.into_iter().collect::<Vec<_>>();
+    targets.sort_unstable_by_key(|&(t, _)| index(t));
+
+    let mut first_values = HashMap::new();
+    let mut unsatisfied = Vec::new();
+    let mut consistent_inputs = PartialWitness::new();
+    for (target, value) in targets {
+        let (first_target, first_value) = *first_values
+            .entry(representative_map[index(target)])
+            .or_insert((target, value));
+        if value == first_value {
+            consistent_inputs.set_target(target, value);
+        } else {
+            unsatisfied.push(UnsatisfiedConstraint::Copy {
+                pair: (first_target, target),
+                values: (first_value, value),
+                scope: scopes
+                    .get(&target)
+                    .map_or_else(String::new, |name| (*name).clone()),
+            });
+        }
+    }
+    (unsatisfied, consistent_inputs)
+}
+
+/// Outputs the copy constraints broken by values which generators output for partitions already
+/// holding a different value, e.g. the output of a gate connected to a wrongly set input.
+pub(crate) fn check_generated_values<F: RichField + Extendable<D>, const D: usize>(
+    debug_info: &CircuitDebugInfo<F, D>,
+    conflicts: Vec<ConflictingValue<F>>,
+) -> Vec<UnsatisfiedConstraint<F>> {
+    let scopes = copy_constraint_scopes(debug_info);
+    conflicts
+        .into_iter()
+        .map(
+            |ConflictingValue {
+                 first_target,
+                 first_value,
+                 target,
+                 value,
+             }| UnsatisfiedConstraint::Copy {
+                pair: (first_target, target),
+                values: (first_value, value),
+                scope: scopes
+                    .get(&target)
+                    .or_else(|| scopes.get(&first_target))
+                    .map_or_else(String::new, |name| (*name).clone()),
+            },
+        )
+        .collect()
+}
+
+/// Outputs, for each target, the name of the first copy constraint involving it.
+fn copy_constraint_scopes<F: RichField + Extendable<D>, const D: usize>(
+    debug_info: &CircuitDebugInfo<F, D>,
+) -> HashMap<Target, &String> {
+    let mut scopes = HashMap::new();
+    for CopyConstraint { pair, name } in &debug_info.copy_constraints {
+        scopes.entry(pair.0).or_insert(name);
+        scopes.entry(pair.1).or_insert(name);
+    }
+    scopes
+}
+
+/// Outputs the indices of the non-zero constraint evaluations.
+fn failing_indices<K: Field>(constraint_values: &[K]) -> Vec<usize> {
+    constraint_values
+        .iter()
+        .enumerate()
+        .filter_map(|(i, value)| (!value.is_zero()).then_some(i))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "std"))]
+    use alloc::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::gates::lookup_table::LookupTable;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+    use crate::with_context;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_check_valid_witness() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let table: LookupTable = Arc::new((0..16).map(|i| (i, i * i)).collect());
+        let table_index = builder.add_lookup_table_from_pairs(table);
+
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = with_context!(builder, "mul", builder.mul(x, y));
+        let w = with_context!(
+            builder,
+            "square",
+            builder.add_lookup_from_index(x, table_index)
+        );
+        builder.register_public_input(z);
+        builder.register_public_input(w);
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        pw.set_target(y, F::from_canonical_u64(5));
+
+        let data = builder.mock_build::<C>();
+        assert_eq!(data.check_witness(pw), vec![]);
+    }
+
+    #[test]
+    fn test_check_unsatisfied_gate() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = with_context!(builder, "outer", {
+            with_context!(builder, "mul", builder.mul(x, y))
+        });
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        pw.set_target(y, F::from_canonical_u64(5));
+
+        let data = builder.mock_build::<C>();
+        let mut partition_witness = data.generate_witness(pw);
+        let rep = partition_witness.representative_map[partition_witness.target_index(z)];
+        partition_witness.values[rep] = Some(F::from_canonical_u64(16));
+
+        let unsatisfied = data.check_partition_witness(partition_witness);
+        let [UnsatisfiedConstraint::Gate {
+            gate_id,
+            scope,
+            constraints,
+            ..
+        }] = unsatisfied.as_slice()
+        else {
+            panic!("Expected a single unsatisfied gate, got {unsatisfied:?}.");
+        };
+        assert!(gate_id.starts_with("ArithmeticGate"));
+        assert_eq!(scope, "root > outer > mul");
+        assert_eq!(constraints, &vec![0]);
+    }
+
+    #[test]
+    fn test_check_unset_copy_constraint() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        with_context!(builder, "connect", builder.connect(x, y));
+
+        // The prover sets both targets to zero, which satisfies the copy constraint.
+        let data = builder.mock_build::<C>();
+        assert_eq!(data.check_witness(PartialWitness::new()), vec![]);
+    }
+
+    #[test]
+    fn test_check_unsatisfied_copy_constraint() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.add_virtual_target();
+        with_context!(builder, "connect x y", builder.connect(x, y));
+        with_context!(builder, "connect y z", builder.connect(y, z));
+        let w = builder.add_virtual_target();
+        let w_squared = builder.square(w);
+        builder.register_public_input(w_squared);
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::ONE);
+        pw.set_target(z, F::TWO);
+        pw.set_target(w, F::from_canonical_u64(3));
+
+        // `z` is only connected to `x` through `y`, and the rest of the witness is still checked.
+        let data = builder.mock_build::<C>();
+        assert_eq!(
+            data.check_witness(pw),
+            vec![UnsatisfiedConstraint::Copy {
+                pair: (x, z),
+                values: (F::ONE, F::TWO),
+                scope: "root > connect y z".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_check_wrong_generated_value() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.add_virtual_target();
+        let product = builder.mul(x, y);
+        with_context!(builder, "connect", builder.connect(product, z));
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        pw.set_target(y, F::from_canonical_u64(5));
+        pw.set_target(z, F::from_canonical_u64(16));
+
+        // The product output by the arithmetic generator conflicts with the input `z`, which is
+        // kept, so the multiplication itself is not satisfied either.
+        let data = builder.mock_build::<C>();
+        let unsatisfied = data.check_witness(pw);
+        let [copy, UnsatisfiedConstraint::Gate {
+            row,
+            gate_id,
+            constraints,
+            ..
+        }] = unsatisfied.as_slice()
+        else {
+            panic!("Expected a copy and a gate failure, got {unsatisfied:?}.");
+        };
+        assert_eq!(
+            copy,
+            &UnsatisfiedConstraint::Copy {
+                pair: (z, product),
+                values: (F::from_canonical_u64(16), F::from_canonical_u64(15)),
+                scope: "root > connect".into(),
+            }
+        );
+        assert!(matches!(product, Target::Wire(wire) if wire.row == *row));
+        assert!(gate_id.starts_with("ArithmeticGate"));
+        assert_eq!(constraints, &vec![0]);
+    }
+
+    #[test]
+    fn test_check_unsatisfied_lookup() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let table: LookupTable = Arc::new((0..16).map(|i| (i, i * i)).collect());
+        let table_index = builder.add_lookup_table_from_pairs(table);
+        let x = builder.add_virtual_target();
+        let w = builder.add_lookup_from_index(x, table_index);
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+
+        let data = builder.mock_build::<C>();
+        let mut partition_witness = data.generate_witness(pw);
+        let rep = partition_witness.representative_map[partition_witness.target_index(w)];
+        partition_witness.values[rep] = Some(F::from_canonical_u64(10));
+
+        let unsatisfied = data.check_partition_witness(partition_witness);
+        assert!(!unsatisfied.is_empty());
+        assert!(unsatisfied
+            .iter()
+            .all(|c| matches!(c, UnsatisfiedConstraint::Lookup { .. })));
+    }
+
+    #[test]
+    fn test_check_lookup_not_in_table() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let table: LookupTable = Arc::new((0..16).map(|i| (i, i * i)).collect());
+        let table_index = builder.add_lookup_table_from_pairs(table);
+        let x = builder.add_virtual_target();
+        let w = builder.add_lookup_from_index(x, table_index);
+        let y = builder.add_virtual_target();
+        let z = builder.mul(w, y);
+        builder.register_public_input(z);
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(20));
+        pw.set_target(y, F::from_canonical_u64(5));
+
+        // The lookup gets a zero output, from which the rest of the witness is still generated.
+        let data = builder.mock_build::<C>();
+        let unsatisfied = data.check_witness(pw);
+        let [UnsatisfiedConstraint::Lookup {
+            gate_id,
+            constraints,
+            slots,
+            ..
+        }] = unsatisfied.as_slice()
+        else {
+            panic!("Expected a single unsatisfied lookup, got {unsatisfied:?}.");
+        };
+        assert!(gate_id.starts_with("LookupGate"));
+        assert!(constraints.is_empty());
+        assert_eq!(slots, &vec![0]);
+    }
+
+    #[test]
+    fn test_check_dynamic_lookup_not_in_table() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let table = (0..4)
+            .map(|_| (builder.add_virtual_target(), builder.add_virtual_target()))
+            .collect::<Vec<_>>();
+        let table_index = builder.add_dynamic_lookup_table(&table);
+        let x = builder.add_virtual_target();
+        builder.add_dynamic_lookup(x, table_index);
+
+        let mut pw = PartialWitness::new();
+        for (i, &(inp, out)) in table.iter().enumerate() {
+            pw.set_target(inp, F::from_canonical_usize(i));
+            pw.set_target(out, F::from_canonical_usize(i + 1));
+        }
+        pw.set_target(x, F::from_canonical_u64(7));
+
+        let data = builder.mock_build::<C>();
+        let unsatisfied = data.check_witness(pw);
+        assert!(
+            matches!(
+                unsatisfied.as_slice(),
+                [UnsatisfiedConstraint::Lookup { slots, .. }] if slots == &vec![0]
+            ),
+            "Expected a single unsatisfied lookup, got {unsatisfied:?}."
+        );
+    }
+}
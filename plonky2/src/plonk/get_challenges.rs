@@ -1,13 +1,10 @@
 #[cfg(not(feature = "std"))]
 use alloc::{vec, vec::Vec};
 
-use hashbrown::HashSet;
-
-use super::circuit_builder::NUM_COINS_LOOKUP;
+use super::circuit_builder::{arrange_lookup_coins, NUM_COINS_LOOKUP};
 use crate::field::extension::Extendable;
 use crate::field::polynomial::PolynomialCoeffs;
-use crate::fri::proof::{CompressedFriProof, FriChallenges, FriProof, FriProofTarget};
-use crate::fri::verifier::{compute_evaluation, fri_combine_initial, PrecomputedReducedOpenings};
+use crate::fri::proof::{CompressedFriProof, FriProof, FriProofTarget};
 use crate::gadgets::polynomial::PolynomialCoeffsExtTarget;
 use crate::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_tree::MerkleCap;
@@ -15,13 +12,12 @@ use crate::iop::challenger::{Challenger, RecursiveChallenger};
 use crate::iop::target::Target;
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CommonCircuitData;
-use crate::plonk::config::{AlgebraicHasher, GenericConfig, Hasher};
+use crate::plonk::config::{CircuitHasher, GenericConfig, Hasher};
 use crate::plonk::proof::{
     CompressedProof, CompressedProofWithPublicInputs, FriInferredElements, OpeningSet,
     OpeningSetTarget, Proof, ProofChallenges, ProofChallengesTarget, ProofTarget,
     ProofWithPublicInputs, ProofWithPublicInputsTarget,
 };
-use crate::util::reverse_bits;
 
 fn get_challenges<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
     public_inputs_hash: <<C as GenericConfig<D>>::InnerHasher as Hasher<F>>::Hash,
@@ -52,14 +48,17 @@ fn get_challenges<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, cons
     // If there are lookups in the circuit, we should get delta challenges as well.
     // But we can use the already generated `plonk_betas` and `plonk_gammas` as the first `plonk_deltas` challenges.
     let plonk_deltas = if has_lookup {
-        let num_lookup_challenges = NUM_COINS_LOOKUP * num_challenges;
+        let num_lookup_challenges = (NUM_COINS_LOOKUP - 1) * num_challenges;
         let mut deltas = Vec::with_capacity(num_lookup_challenges);
         let num_additional_challenges = num_lookup_challenges - 2 * num_challenges;
         let additional = challenger.get_n_challenges(num_additional_challenges);
         deltas.extend(&plonk_betas);
         deltas.extend(&plonk_gammas);
         deltas.extend(additional);
-        deltas
+        // The challenges compressing tuples are only sampled if there are tuple LUTs.
+        let tuple_coins = (!common_data.tuple_luts.is_empty())
+            .then(|| challenger.get_n_challenges(num_challenges));
+        arrange_lookup_coins(&deltas, tuple_coins.as_deref(), F::ZERO)
     } else {
         vec![]
     };
@@ -183,73 +182,12 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         challenges: &ProofChallenges<F, D>,
         common_data: &CommonCircuitData<F, D>,
     ) -> FriInferredElements<F, D> {
-        let ProofChallenges {
-            plonk_zeta,
-            fri_challenges:
-                FriChallenges {
-                    fri_alpha,
-                    fri_betas,
-                    fri_query_indices,
-                    ..
-                },
-            ..
-        } = challenges;
-        let mut fri_inferred_elements = Vec::new();
-        // Holds the indices that have already been seen at each reduction depth.
-        let mut seen_indices_by_depth =
-            vec![HashSet::new(); common_data.fri_params.reduction_arity_bits.len()];
-        let precomputed_reduced_evals = PrecomputedReducedOpenings::from_os_and_alpha(
+        self.proof.opening_proof.get_inferred_elements::<C>(
+            &challenges.fri_challenges,
+            &common_data.get_fri_instance(challenges.plonk_zeta),
             &self.proof.openings.to_fri_openings(),
-            *fri_alpha,
-        );
-        let log_n = common_data.degree_bits() + common_data.config.fri_config.rate_bits;
-        // Simulate the proof verification and collect the inferred elements.
-        // The content of the loop is basically the same as the `fri_verifier_query_round` function.
-        for &(mut x_index) in fri_query_indices {
-            let mut subgroup_x = F::MULTIPLICATIVE_GROUP_GENERATOR
-                * F::primitive_root_of_unity(log_n).exp_u64(reverse_bits(x_index, log_n) as u64);
-            let mut old_eval = fri_combine_initial::<F, C, D>(
-                &common_data.get_fri_instance(*plonk_zeta),
-                &self
-                    .proof
-                    .opening_proof
-                    .query_round_proofs
-                    .initial_trees_proofs[&x_index],
-                *fri_alpha,
-                subgroup_x,
-                &precomputed_reduced_evals,
-                &common_data.fri_params,
-            );
-            for (i, &arity_bits) in common_data
-                .fri_params
-                .reduction_arity_bits
-                .iter()
-                .enumerate()
-            {
-                let coset_index = x_index >> arity_bits;
-                if !seen_indices_by_depth[i].insert(coset_index) {
-                    // If this index has already been seen, we can skip the rest of the reductions.
-                    break;
-                }
-                fri_inferred_elements.push(old_eval);
-                let arity = 1 << arity_bits;
-                let mut evals = self.proof.opening_proof.query_round_proofs.steps[i][&coset_index]
-                    .evals
-                    .clone();
-                let x_index_within_coset = x_index & (arity - 1);
-                evals.insert(x_index_within_coset, old_eval);
-                old_eval = compute_evaluation(
-                    subgroup_x,
-                    x_index_within_coset,
-                    arity_bits,
-                    &evals,
-                    fri_betas[i],
-                );
-                subgroup_x = subgroup_x.exp_power_of_2(arity_bits);
-                x_index = coset_index;
-            }
-        }
-        FriInferredElements(fri_inferred_elements)
+            &common_data.fri_params,
+        )
     }
 }
 
@@ -268,7 +206,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         inner_common_data: &CommonCircuitData<F, D>,
     ) -> ProofChallengesTarget<D>
     where
-        C::Hasher: AlgebraicHasher<F>,
+        C::Hasher: CircuitHasher<F>,
     {
         let config = &inner_common_data.config;
         let num_challenges = config.num_challenges;
@@ -288,14 +226,18 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         // If there are lookups in the circuit, we should get delta challenges as well.
         // But we can use the already generated `plonk_betas` and `plonk_gammas` as the first `plonk_deltas` challenges.
         let plonk_deltas = if has_lookup {
-            let num_lookup_challenges = NUM_COINS_LOOKUP * num_challenges;
+            let num_lookup_challenges = (NUM_COINS_LOOKUP - 1) * num_challenges;
             let mut deltas = Vec::with_capacity(num_lookup_challenges);
             let num_additional_challenges = num_lookup_challenges - 2 * num_challenges;
             let additional = challenger.get_n_challenges(self, num_additional_challenges);
             deltas.extend(&plonk_betas);
             deltas.extend(&plonk_gammas);
             deltas.extend(additional);
-            deltas
+            // The challenges compressing tuples are only sampled if there are tuple LUTs.
+            let tuple_coins = (!inner_common_data.tuple_luts.is_empty())
+                .then(|| challenger.get_n_challenges(self, num_challenges));
+            let zero = self.zero();
+            arrange_lookup_coins(&deltas, tuple_coins.as_deref(), zero)
         } else {
             vec![]
         };
@@ -334,7 +276,7 @@ impl<const D: usize> ProofWithPublicInputsTarget<D> {
         inner_common_data: &CommonCircuitData<F, D>,
     ) -> ProofChallengesTarget<D>
     where
-        C::Hasher: AlgebraicHasher<F>,
+        C::Hasher: CircuitHasher<F>,
     {
         let ProofTarget {
             wires_cap,
@@ -4,6 +4,7 @@
 use alloc::{format, vec, vec::Vec};
 use core::cmp::min;
 use core::mem::swap;
+use core::ops::Range;
 
 use anyhow::{ensure, Result};
 use hashbrown::HashMap;
@@ -15,20 +16,18 @@ use crate::field::polynomial::{PolynomialCoeffs, PolynomialValues};
 use crate::field::types::Field;
 use crate::field::zero_poly_coset::ZeroPolyOnCoset;
 use crate::fri::oracle::PolynomialBatch;
-use crate::gates::lookup::LookupGate;
-use crate::gates::lookup_table::LookupTableGate;
-use crate::gates::selectors::LookupSelectors;
+use crate::gates::tuple_lookup::{TupleLookupGate, TupleLookupTableGate};
 use crate::hash::hash_types::RichField;
 use crate::iop::challenger::Challenger;
 use crate::iop::generator::generate_partial_witness;
 use crate::iop::target::Target;
 use crate::iop::witness::{MatrixWitness, PartialWitness, PartitionWitness, Witness, WitnessWrite};
-use crate::plonk::circuit_builder::NUM_COINS_LOOKUP;
+use crate::plonk::circuit_builder::{arrange_lookup_coins, NUM_COINS_LOOKUP};
 use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
 use crate::plonk::config::{GenericConfig, Hasher};
 use crate::plonk::plonk_common::PlonkOracle;
 use crate::plonk::proof::{OpeningSet, Proof, ProofWithPublicInputs};
-use crate::plonk::vanishing_poly::{eval_vanishing_poly_base_batch, get_lut_poly};
+use crate::plonk::vanishing_poly::{eval_vanishing_poly_base_batch, get_lut_poly, lookup_layout};
 use crate::plonk::vars::EvaluationVarsBaseBatch;
 use crate::timed;
 use crate::util::partial_products::{partial_products_and_z_gx, quotient_chunk_products};
@@ -38,6 +37,7 @@ use crate::util::{ceil_div_usize, log2_ceil, transpose};
 /// Set all the lookup gate wires (including multiplicities) and pad unused LU slots.
 /// Warning: rows are in descending order: the first gate to appear is the last LU gate, and
 /// the last gate to appear is the first LUT gate.
+/// Panics if the values of a lookup are not in its table.
 pub fn set_lookup_wires<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -47,18 +47,11 @@ pub fn set_lookup_wires<
     common_data: &CommonCircuitData<F, D>,
     pw: &mut PartitionWitness<F>,
 ) {
-    for (
-        lut_index,
-        &LookupWire {
-            last_lu_gate: _,
-            last_lut_gate,
-            first_lut_gate,
-        },
-    ) in prover_data.lookup_rows.iter().enumerate()
-    {
+    let num_luts = common_data.luts.len();
+    let num_tuple_luts = common_data.tuple_luts.len();
+    let single_layout = lookup_layout(common_data, false);
+    for (lut_index, lookup_wire) in prover_data.lookup_rows[..num_luts].iter().enumerate() {
         let lut_len = common_data.luts[lut_index].len();
-        let num_entries = LookupGate::num_slots(&common_data.config);
-        let num_lut_entries = LookupTableGate::num_slots(&common_data.config);
 
         // Compute multiplicities.
         let mut multiplicities = vec![0; lut_len];
@@ -71,41 +64,163 @@ pub fn set_lookup_wires<
 
         for (inp_target, _) in prover_data.lut_to_lookups[lut_index].iter() {
             let inp_value = pw.get_target(*inp_target);
-            let idx = table_value_to_idx
-                .get(&u16::try_from(inp_value.to_canonical_u64()).unwrap())
-                .unwrap();
+            let idx = u16::try_from(inp_value.to_canonical_u64())
+                .ok()
+                .and_then(|inp| table_value_to_idx.get(&inp))
+                .unwrap_or_else(|| panic!("Lookup {inp_value} is not in LUT number {lut_index}"));
 
             multiplicities[*idx] += 1;
         }
 
-        // Pad the last `LookupGate` with the first entry from the LUT.
-        let remaining_slots = (num_entries
-            - (prover_data.lut_to_lookups[lut_index].len() % num_entries))
-            % num_entries;
         let (first_inp_value, first_out_value) = common_data.luts[lut_index][0];
-        for slot in (num_entries - remaining_slots)..num_entries {
-            let inp_target =
-                Target::wire(last_lut_gate - 1, LookupGate::wire_ith_looking_inp(slot));
-            let out_target =
-                Target::wire(last_lut_gate - 1, LookupGate::wire_ith_looking_out(slot));
-            pw.set_target(inp_target, F::from_canonical_u16(first_inp_value));
-            pw.set_target(out_target, F::from_canonical_u16(first_out_value));
-
-            multiplicities[0] += 1;
-        }
+        set_padding_and_multiplicities(
+            pw,
+            &single_layout,
+            lookup_wire,
+            prover_data.lut_to_lookups[lut_index].len(),
+            (
+                &[F::from_canonical_u16(first_inp_value)],
+                &[F::from_canonical_u16(first_out_value)],
+            ),
+            multiplicities,
+        );
+    }
 
-        // We don't need to pad the last `LookupTableGate`; extra wires are set to 0 by default, which satisfies the constraints.
-        for lut_entry in 0..lut_len {
-            let row = first_lut_gate - lut_entry / num_lut_entries;
+    // The rows of tuple LUTs come after the ones of the LUTs in `common_data.luts`.
+    for (tuple_lut_index, lookup_wire) in prover_data.lookup_rows
+        [num_luts..num_luts + num_tuple_luts]
+        .iter()
+        .enumerate()
+    {
+        let tuple_layout = lookup_layout(common_data, true);
+        let (_, tuple_lut_gate) = &tuple_layout;
+        let table = &common_data.tuple_luts[tuple_lut_index];
+
+        // Set the rows of the table. The unused slots of the last `TupleLookupTableGate` hold the first row.
+        let num_lut_entries = tuple_lut_gate.num_slots;
+        let num_lut_rows = lookup_wire.first_lut_gate - lookup_wire.last_lut_gate + 1;
+        for lut_entry in 0..num_lut_rows * num_lut_entries {
+            let row = lookup_wire.first_lut_gate - lut_entry / num_lut_entries;
             let col = lut_entry % num_lut_entries;
+            let values = table.row(if lut_entry < table.len() {
+                lut_entry
+            } else {
+                0
+            });
+            let (inputs, outputs) = values.split_at(table.num_inputs());
+            for (wire, &value) in tuple_lut_gate.wires_ith_looked_inp(col).zip(inputs) {
+                pw.set_target(Target::wire(row, wire), value);
+            }
+            for (wire, &value) in tuple_lut_gate.wires_ith_looked_out(col).zip(outputs) {
+                pw.set_target(Target::wire(row, wire), value);
+            }
+        }
 
-            let mul_target = Target::wire(row, LookupTableGate::wire_ith_multiplicity(col));
+        // Compute multiplicities. Lookups matching several rows are attributed to the first one.
+        let mut multiplicities = vec![0; table.len()];
 
-            pw.set_target(
-                mul_target,
-                F::from_canonical_usize(multiplicities[lut_entry]),
-            );
+        let mut table_value_to_idx: HashMap<&[F], usize> = HashMap::new();
+        for (i, row) in table.rows().enumerate() {
+            table_value_to_idx.entry(row).or_insert(i);
+        }
+
+        for (inp_targets, out_targets) in prover_data.tuple_lut_to_lookups[tuple_lut_index].iter() {
+            let value = [pw.get_targets(inp_targets), pw.get_targets(out_targets)].concat();
+            let idx = table_value_to_idx.get(value.as_slice()).unwrap_or_else(|| {
+                panic!("Lookup {value:?} is not in tuple LUT number {tuple_lut_index}")
+            });
+
+            multiplicities[*idx] += 1;
+        }
+
+        set_padding_and_multiplicities(
+            pw,
+            &tuple_layout,
+            lookup_wire,
+            prover_data.tuple_lut_to_lookups[tuple_lut_index].len(),
+            table.row(0).split_at(table.num_inputs()),
+            multiplicities,
+        );
+    }
+
+    // The rows of dynamic LUTs come last.
+    for (dynamic_lut_index, lookup_wire) in prover_data.lookup_rows[num_luts + num_tuple_luts..]
+        .iter()
+        .enumerate()
+    {
+        let table: Vec<(F, F)> = prover_data.dynamic_luts[dynamic_lut_index]
+            .iter()
+            .map(|&(inp_target, out_target)| (pw.get_target(inp_target), pw.get_target(out_target)))
+            .collect();
+
+        // Compute multiplicities. Lookups matching several rows are attributed to the first one.
+        let mut multiplicities = vec![0; table.len()];
+
+        let mut table_value_to_idx: HashMap<(F, F), usize> = HashMap::new();
+        for (i, &row) in table.iter().enumerate() {
+            table_value_to_idx.entry(row).or_insert(i);
         }
+
+        for &(inp_target, out_target) in
+            prover_data.dynamic_lut_to_lookups[dynamic_lut_index].iter()
+        {
+            let value = (pw.get_target(inp_target), pw.get_target(out_target));
+            let idx = table_value_to_idx.get(&value).unwrap_or_else(|| {
+                panic!("Lookup {value:?} is not in dynamic LUT number {dynamic_lut_index}")
+            });
+
+            multiplicities[*idx] += 1;
+        }
+
+        set_padding_and_multiplicities(
+            pw,
+            &single_layout,
+            lookup_wire,
+            prover_data.dynamic_lut_to_lookups[dynamic_lut_index].len(),
+            (&[table[0].0], &[table[0].1]),
+            multiplicities,
+        );
+    }
+}
+
+/// Pads the last lookup gate of a LUT with its first entry, and sets the multiplicity wires
+/// of its lookup table gates. The wire layouts of both kinds of gates are given by the pair of gates.
+fn set_padding_and_multiplicities<F: Field>(
+    pw: &mut PartitionWitness<F>,
+    (lu_gate, lut_gate): &(TupleLookupGate, TupleLookupTableGate),
+    &LookupWire {
+        last_lu_gate: _,
+        last_lut_gate,
+        first_lut_gate,
+    }: &LookupWire,
+    num_lookups: usize,
+    (first_inputs, first_outputs): (&[F], &[F]),
+    mut multiplicities: Vec<usize>,
+) {
+    let num_entries = lu_gate.num_slots;
+    let num_lut_entries = lut_gate.num_slots;
+
+    // Pad the last lookup gate with the first entry from the LUT.
+    let remaining_slots = (num_entries - (num_lookups % num_entries)) % num_entries;
+    for slot in (num_entries - remaining_slots)..num_entries {
+        for (wire, &value) in lu_gate.wires_ith_looking_inp(slot).zip(first_inputs) {
+            pw.set_target(Target::wire(last_lut_gate - 1, wire), value);
+        }
+        for (wire, &value) in lu_gate.wires_ith_looking_out(slot).zip(first_outputs) {
+            pw.set_target(Target::wire(last_lut_gate - 1, wire), value);
+        }
+
+        multiplicities[0] += 1;
+    }
+
+    // We don't need to pad the last lookup table gate; extra multiplicities are set to 0 by default, which satisfies the constraints.
+    for (lut_entry, &multiplicity) in multiplicities.iter().enumerate() {
+        let row = first_lut_gate - lut_entry / num_lut_entries;
+        let col = lut_entry % num_lut_entries;
+
+        let mul_target = Target::wire(row, lut_gate.wire_ith_multiplicity(col));
+
+        pw.set_target(mul_target, F::from_canonical_usize(multiplicity));
     }
 }
 
@@ -142,7 +257,7 @@ where
     C::Hasher: Hasher<F>,
     C::InnerHasher: Hasher<F>,
 {
-    let has_lookup = !common_data.luts.is_empty();
+    let has_lookup = common_data.num_lookup_polys != 0;
     let config = &common_data.config;
     let num_challenges = config.num_challenges;
     let quotient_degree = common_data.quotient_degree();
@@ -190,21 +305,24 @@ where
 
     challenger.observe_cap::<C::Hasher>(&wires_commitment.merkle_tree.cap);
 
-    // We need 4 values per challenge: 2 for the combos, 1 for (X-combo) in the accumulators and 1 to prove that the lookup table was computed correctly.
+    // We need 5 values per challenge: 2 for the combos, 1 for (X-combo) in the accumulators, 1 to prove that the lookup table was computed correctly
+    // and 1 to compress the tuples of tuple LUTs, which is only sampled if there are tuple LUTs.
     // We can reuse betas and gammas for two of them.
-    let num_lookup_challenges = NUM_COINS_LOOKUP * num_challenges;
+    let num_lookup_challenges = (NUM_COINS_LOOKUP - 1) * num_challenges;
 
     let betas = challenger.get_n_challenges(num_challenges);
     let gammas = challenger.get_n_challenges(num_challenges);
 
     let deltas = if has_lookup {
-        let mut delts = Vec::with_capacity(2 * num_challenges);
+        let mut delts = Vec::with_capacity(num_lookup_challenges);
         let num_additional_challenges = num_lookup_challenges - 2 * num_challenges;
         let additional = challenger.get_n_challenges(num_additional_challenges);
         delts.extend(&betas);
         delts.extend(&gammas);
         delts.extend(additional);
-        delts
+        let tuple_coins = (!common_data.tuple_luts.is_empty())
+            .then(|| challenger.get_n_challenges(num_challenges));
+        arrange_lookup_coins(&delts, tuple_coins.as_deref(), F::ZERO)
     } else {
         vec![]
     };
@@ -449,22 +567,21 @@ fn wires_permutation_partial_products_and_zs<
 /// partial polynomials according to `max_quotient_degree_factor`.
 /// As another optimization, Sum and LDC polynomials are shared (in so called partial SLDC polynomials), and the last value
 /// of the last partial polynomial is Sum(end) - LDC(end). If the lookup argument is valid, then it must be equal to 0.
-fn compute_lookup_polys<
+pub(crate) fn compute_lookup_polys<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
     const D: usize,
 >(
     witness: &MatrixWitness<F>,
-    deltas: &[F; 4],
+    deltas: &[F; NUM_COINS_LOOKUP],
     prover_data: &ProverOnlyCircuitData<F, C, D>,
     common_data: &CommonCircuitData<F, D>,
 ) -> Vec<PolynomialValues<F>> {
     let degree = common_data.degree();
-    let num_lu_slots = LookupGate::num_slots(&common_data.config);
     let max_lookup_degree = common_data.config.max_quotient_degree_factor - 1;
-    let num_partial_lookups = ceil_div_usize(num_lu_slots, max_lookup_degree);
-    let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
-    let max_lookup_table_degree = ceil_div_usize(num_lut_slots, num_partial_lookups);
+    let num_partial_lookups = common_data.num_lookup_polys - 1;
+    let tuple_rows = common_data.luts.len()..common_data.luts.len() + common_data.tuple_luts.len();
+    let gamma = deltas[LookupChallenges::ChallengeTuple as usize];
 
     // First poly is RE, the rest are partial SLDCs.
     let mut final_poly_vecs = Vec::with_capacity(num_partial_lookups + 1);
@@ -472,21 +589,40 @@ fn compute_lookup_polys<
         final_poly_vecs.push(PolynomialValues::<F>::new(vec![F::ZERO; degree]));
     }
 
-    for LookupWire {
-        last_lu_gate: last_lu_row,
-        last_lut_gate: last_lut_row,
-        first_lut_gate: first_lut_row,
-    } in prover_data.lookup_rows.clone()
+    for (
+        i,
+        &LookupWire {
+            last_lu_gate: last_lu_row,
+            last_lut_gate: last_lut_row,
+            first_lut_gate: first_lut_row,
+        },
+    ) in prover_data.lookup_rows.iter().enumerate()
     {
+        let (lu_gate, lut_gate) = lookup_layout(common_data, tuple_rows.contains(&i));
+        let num_lu_slots = lu_gate.num_slots;
+        let num_lut_slots = lut_gate.num_slots;
+        let max_lookup_table_degree = ceil_div_usize(num_lut_slots, num_partial_lookups);
+
+        // Tuples are compressed with powers of gamma. Single values are left as they are.
+        let compress = |row: usize, wires: Range<usize>| {
+            wires
+                .rev()
+                .fold(F::ZERO, |acc, w| acc * gamma + witness.get_wire(row, w))
+        };
+
         // Set values for partial Sums and RE.
         for row in (last_lut_row..(first_lut_row + 1)).rev() {
+            let looked_inps: Vec<F> = (0..num_lut_slots)
+                .map(|s| compress(row, lut_gate.wires_ith_looked_inp(s)))
+                .collect();
+            let looked_outs: Vec<F> = (0..num_lut_slots)
+                .map(|s| compress(row, lut_gate.wires_ith_looked_out(s)))
+                .collect();
+
             // Get combos for Sum.
             let looked_combos: Vec<F> = (0..num_lut_slots)
                 .map(|s| {
-                    let looked_inp = witness.get_wire(row, LookupTableGate::wire_ith_looked_inp(s));
-                    let looked_out = witness.get_wire(row, LookupTableGate::wire_ith_looked_out(s));
-
-                    looked_inp + deltas[LookupChallenges::ChallengeA as usize] * looked_out
+                    looked_inps[s] + deltas[LookupChallenges::ChallengeA as usize] * looked_outs[s]
                 })
                 .collect();
             // Get (alpha - combo).
@@ -499,10 +635,7 @@ fn compute_lookup_polys<
             // Get lookup combos, used to check the well formation of the LUT.
             let lookup_combos: Vec<F> = (0..num_lut_slots)
                 .map(|s| {
-                    let looked_inp = witness.get_wire(row, LookupTableGate::wire_ith_looked_inp(s));
-                    let looked_out = witness.get_wire(row, LookupTableGate::wire_ith_looked_out(s));
-
-                    looked_inp + deltas[LookupChallenges::ChallengeB as usize] * looked_out
+                    looked_inps[s] + deltas[LookupChallenges::ChallengeB as usize] * looked_outs[s]
                 })
                 .collect();
 
@@ -524,7 +657,7 @@ fn compute_lookup_polys<
                 let sum = (slot * max_lookup_table_degree
                     ..min((slot + 1) * max_lookup_table_degree, num_lut_slots))
                     .fold(prev, |acc, s| {
-                        acc + witness.get_wire(row, LookupTableGate::wire_ith_multiplicity(s))
+                        acc + witness.get_wire(row, lut_gate.wire_ith_multiplicity(s))
                             * looked_combo_inverses[s]
                     });
                 final_poly_vecs[slot + 1].values[row] = sum;
@@ -536,8 +669,8 @@ fn compute_lookup_polys<
             // Get looking combos.
             let looking_combos: Vec<F> = (0..num_lu_slots)
                 .map(|s| {
-                    let looking_in = witness.get_wire(row, LookupGate::wire_ith_looking_inp(s));
-                    let looking_out = witness.get_wire(row, LookupGate::wire_ith_looking_out(s));
+                    let looking_in = compress(row, lu_gate.wires_ith_looking_inp(s));
+                    let looking_out = compress(row, lu_gate.wires_ith_looking_out(s));
 
                     looking_in + deltas[LookupChallenges::ChallengeA as usize] * looking_out
                 })
@@ -644,27 +777,14 @@ fn compute_quotient_polys<
     // and are the same each time in check_lookup_constraints_batched.
     // lut_poly_evals[i][j] gives the eval for the i'th challenge and the j'th lookup table
     let lut_re_poly_evals: Vec<Vec<F>> = if has_lookup {
-        let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
+        let num_fixed_luts = common_data.luts.len() + common_data.tuple_luts.len();
         (0..num_challenges)
             .map(move |i| {
                 let cur_deltas = &deltas[NUM_COINS_LOOKUP * i..NUM_COINS_LOOKUP * (i + 1)];
                 let cur_challenge_delta = cur_deltas[LookupChallenges::ChallengeDelta as usize];
 
-                (LookupSelectors::StartEnd as usize..common_data.num_lookup_selectors)
-                    .map(|r| {
-                        let lut_row_number = ceil_div_usize(
-                            common_data.luts[r - LookupSelectors::StartEnd as usize].len(),
-                            num_lut_slots,
-                        );
-
-                        get_lut_poly(
-                            common_data,
-                            r - LookupSelectors::StartEnd as usize,
-                            cur_deltas,
-                            num_lut_slots * lut_row_number,
-                        )
-                        .eval(cur_challenge_delta)
-                    })
+                (0..num_fixed_luts)
+                    .map(|r| get_lut_poly(common_data, r, cur_deltas).eval(cur_challenge_delta))
                     .collect()
             })
             .collect()
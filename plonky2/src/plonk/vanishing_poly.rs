@@ -1,6 +1,7 @@
 #[cfg(not(feature = "std"))]
 use alloc::{format, vec, vec::Vec};
 use core::cmp::min;
+use core::ops::Range;
 
 use plonky2_field::polynomial::PolynomialCoeffs;
 use plonky2_util::ceil_div_usize;
@@ -11,36 +12,87 @@ use crate::field::batch_util::batch_add_inplace;
 use crate::field::extension::{Extendable, FieldExtension};
 use crate::field::types::Field;
 use crate::field::zero_poly_coset::ZeroPolyOnCoset;
-use crate::gates::lookup::LookupGate;
 use crate::gates::lookup_table::LookupTableGate;
 use crate::gates::selectors::LookupSelectors;
+use crate::gates::tuple_lookup::{TupleLookupGate, TupleLookupTableGate};
 use crate::hash::hash_types::RichField;
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::Target;
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CommonCircuitData;
 use crate::plonk::plonk_common;
-use crate::plonk::plonk_common::eval_l_0_circuit;
+use crate::plonk::plonk_common::{
+    eval_l_0_circuit, reduce_with_powers, reduce_with_powers_circuit,
+};
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBaseBatch};
 use crate::util::partial_products::{check_partial_products, check_partial_products_circuit};
 use crate::util::reducing::ReducingFactorTarget;
 use crate::util::strided_view::PackedStridedView;
 use crate::with_context;
 
-/// Get the polynomial associated to a lookup table with current challenges.
+/// Returns the wire layouts of the lookup rows and lookup table rows. The rows of LUTs in `common_data.luts` and of
+/// dynamic LUTs have the layout of tuple lookup gates with one input and one output, while the rows of tuple LUTs
+/// use the circuit's tuple lookup shape.
+pub(crate) fn lookup_layout<F: RichField + Extendable<D>, const D: usize>(
+    common_data: &CommonCircuitData<F, D>,
+    tuple: bool,
+) -> (TupleLookupGate, TupleLookupTableGate) {
+    let (num_inputs, num_outputs) = if tuple {
+        common_data.tuple_lookup_shape()
+    } else {
+        (1, 1)
+    };
+    (
+        TupleLookupGate::new_from_config(&common_data.config, num_inputs, num_outputs),
+        TupleLookupTableGate::new_from_config(&common_data.config, num_inputs, num_outputs),
+    )
+}
+
+/// Returns the number of lookup constraints for each challenge.
+fn num_lookup_constraints<F: RichField + Extendable<D>, const D: usize>(
+    common_data: &CommonCircuitData<F, D>,
+) -> usize {
+    let num_sldc_polys = common_data.num_lookup_polys - 1;
+    let num_fixed_luts = common_data.luts.len() + common_data.tuple_luts.len();
+    let num_layouts = if common_data.tuple_luts.is_empty() {
+        1
+    } else {
+        2
+    };
+    3 + num_fixed_luts + num_layouts * (1 + 2 * num_sldc_polys)
+}
+
+/// Get the polynomial associated to a lookup table with current challenges. Tuple LUTs are indexed after the
+/// LUTs in `common_data.luts`.
 pub(crate) fn get_lut_poly<F: RichField + Extendable<D>, const D: usize>(
     common_data: &CommonCircuitData<F, D>,
     lut_index: usize,
     deltas: &[F],
-    degree: usize,
 ) -> PolynomialCoeffs<F> {
     let b = deltas[LookupChallenges::ChallengeB as usize];
-    let mut coeffs = Vec::with_capacity(common_data.luts[lut_index].len());
-    let n = common_data.luts[lut_index].len();
-    for (input, output) in common_data.luts[lut_index].iter() {
-        coeffs.push(F::from_canonical_u16(*input) + b * F::from_canonical_u16(*output));
-    }
-    coeffs.append(&mut vec![F::ZERO; degree - n]);
+    let mut coeffs = if lut_index < common_data.luts.len() {
+        let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
+        let n = common_data.luts[lut_index].len();
+        let mut coeffs = Vec::with_capacity(n);
+        for (input, output) in common_data.luts[lut_index].iter() {
+            coeffs.push(F::from_canonical_u16(*input) + b * F::from_canonical_u16(*output));
+        }
+        coeffs.resize(ceil_div_usize(n, num_lut_slots) * num_lut_slots, F::ZERO);
+        coeffs
+    } else {
+        let gamma = deltas[LookupChallenges::ChallengeTuple as usize];
+        let table = &common_data.tuple_luts[lut_index - common_data.luts.len()];
+        let (_, lut_gate) = lookup_layout(common_data, true);
+        let num_entries = ceil_div_usize(table.len(), lut_gate.num_slots) * lut_gate.num_slots;
+        // The unused slots of the last row hold the first row of the table.
+        (0..num_entries)
+            .map(|e| {
+                let row = table.row(if e < table.len() { e } else { 0 });
+                let (inputs, outputs) = row.split_at(table.num_inputs());
+                reduce_with_powers(inputs, gamma) + b * reduce_with_powers(outputs, gamma)
+            })
+            .collect()
+    };
     coeffs.reverse();
     PolynomialCoeffs::new(coeffs)
 }
@@ -77,10 +129,7 @@ pub(crate) fn eval_vanishing_poly<F: RichField + Extendable<D>, const D: usize>(
 
     // The terms checking the lookup constraints, if any.
     let mut vanishing_all_lookup_terms = if has_lookup {
-        let num_sldc_polys = common_data.num_lookup_polys - 1;
-        Vec::with_capacity(
-            common_data.config.num_challenges * (4 + common_data.luts.len() + 2 * num_sldc_polys),
-        )
+        Vec::with_capacity(common_data.config.num_challenges * num_lookup_constraints(common_data))
     } else {
         Vec::new()
     };
@@ -215,10 +264,7 @@ pub(crate) fn eval_vanishing_poly_base_batch<F: RichField + Extendable<D>, const
 
     // The terms checking the lookup constraints.
     let mut vanishing_all_lookup_terms = if has_lookup {
-        let num_sldc_polys = common_data.num_lookup_polys - 1;
-        Vec::with_capacity(
-            common_data.config.num_challenges * (4 + common_data.luts.len() + 2 * num_sldc_polys),
-        )
+        Vec::with_capacity(common_data.config.num_challenges * num_lookup_constraints(common_data))
     } else {
         Vec::new()
     };
@@ -333,58 +379,26 @@ pub(crate) fn eval_vanishing_poly_base_batch<F: RichField + Extendable<D>, const
 /// Sum and LDC are broken down in partial polynomials to lower the constraint degree, similarly to the permutation argument.
 /// They also share the same partial SLDC polynomials, so that the last SLDC value is Sum(end) - LDC(end). The final constraint
 /// Sum(end) = LDC(end) becomes simply SLDC(end) = 0, and we can remove the LDC initial constraint.
+///
+/// For tuple LUTs, inputs and outputs are tuples, which are first compressed with powers of another challenge.
 pub fn check_lookup_constraints<F: RichField + Extendable<D>, const D: usize>(
     common_data: &CommonCircuitData<F, D>,
     vars: EvaluationVars<F, D>,
     local_lookup_zs: &[F::Extension],
     next_lookup_zs: &[F::Extension],
     lookup_selectors: &[F::Extension],
-    deltas: &[F; 4],
+    deltas: &[F; NUM_COINS_LOOKUP],
 ) -> Vec<F::Extension> {
-    let num_lu_slots = LookupGate::num_slots(&common_data.config);
-    let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
-    let lu_degree = common_data.quotient_degree_factor - 1;
+    let num_fixed_luts = common_data.luts.len() + common_data.tuple_luts.len();
     let num_sldc_polys = local_lookup_zs.len() - 1;
-    let lut_degree = ceil_div_usize(num_lut_slots, num_sldc_polys);
 
-    let mut constraints = Vec::with_capacity(4 + common_data.luts.len() + 2 * num_sldc_polys);
+    let mut constraints = Vec::with_capacity(num_lookup_constraints(common_data));
 
     // RE is the first polynomial stored.
     let z_re = local_lookup_zs[0];
-    let next_z_re = next_lookup_zs[0];
 
     // Partial Sums and LDCs are both stored in the remaining SLDC polynomials.
     let z_x_lookup_sldcs = &local_lookup_zs[1..num_sldc_polys + 1];
-    let z_gx_lookup_sldcs = &next_lookup_zs[1..num_sldc_polys + 1];
-
-    let delta_challenge_a = F::Extension::from(deltas[LookupChallenges::ChallengeA as usize]);
-    let delta_challenge_b = F::Extension::from(deltas[LookupChallenges::ChallengeB as usize]);
-
-    // Compute all current looked and looking combos, i.e. the combos we need for the SLDC polynomials.
-    let current_looked_combos: Vec<F::Extension> = (0..num_lut_slots)
-        .map(|s| {
-            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-            input_wire + delta_challenge_a * output_wire
-        })
-        .collect();
-
-    let current_looking_combos: Vec<F::Extension> = (0..num_lu_slots)
-        .map(|s| {
-            let input_wire = vars.local_wires[LookupGate::wire_ith_looking_inp(s)];
-            let output_wire = vars.local_wires[LookupGate::wire_ith_looking_out(s)];
-            input_wire + delta_challenge_a * output_wire
-        })
-        .collect();
-
-    // Compute all current lookup combos, i.e. the combos used to check that the LUT is correct.
-    let current_lookup_combos: Vec<F::Extension> = (0..num_lut_slots)
-        .map(|s| {
-            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-            input_wire + delta_challenge_b * output_wire
-        })
-        .collect();
 
     // Check last LDC constraint.
     constraints.push(
@@ -399,50 +413,116 @@ pub fn check_lookup_constraints<F: RichField + Extendable<D>, const D: usize>(
 
     let current_delta = deltas[LookupChallenges::ChallengeDelta as usize];
 
-    // Check final RE constraints for each different LUT.
-    for r in LookupSelectors::StartEnd as usize..common_data.num_lookup_selectors {
-        let cur_ends_selector = lookup_selectors[r];
-        let lut_row_number = ceil_div_usize(
-            common_data.luts[r - LookupSelectors::StartEnd as usize].len(),
-            num_lut_slots,
-        );
-        let cur_function_eval = get_lut_poly(
-            common_data,
-            r - LookupSelectors::StartEnd as usize,
-            deltas,
-            num_lut_slots * lut_row_number,
-        )
-        .eval(current_delta);
+    // Check final RE constraints for each different fixed LUT.
+    for r in 0..num_fixed_luts {
+        let cur_ends_selector = lookup_selectors[LookupSelectors::StartEnd as usize + r];
+        let cur_function_eval = get_lut_poly(common_data, r, deltas).eval(current_delta);
 
         constraints.push(cur_ends_selector * (z_re - cur_function_eval.into()))
     }
 
+    let ext_deltas = deltas.map(F::Extension::from);
+
+    // Check the transition constraints of the rows with one input and one output.
+    let (lu_gate, lut_gate) = lookup_layout(common_data, false);
+    eval_lookup_transitions(
+        &mut constraints,
+        |i| vars.local_wires[i],
+        (&lu_gate, &lut_gate),
+        common_data.quotient_degree_factor - 1,
+        (local_lookup_zs, next_lookup_zs),
+        &ext_deltas,
+        lookup_selectors,
+    );
+
+    // Check the transition constraints of the rows of tuple LUTs, whose selectors come after the end selectors.
+    if !common_data.tuple_luts.is_empty() {
+        let (lu_gate, lut_gate) = lookup_layout(common_data, true);
+        eval_lookup_transitions(
+            &mut constraints,
+            |i| vars.local_wires[i],
+            (&lu_gate, &lut_gate),
+            common_data.quotient_degree_factor - 1,
+            (local_lookup_zs, next_lookup_zs),
+            &ext_deltas,
+            &lookup_selectors[LookupSelectors::StartEnd as usize + num_fixed_luts..],
+        );
+    }
+
+    constraints
+}
+
+/// Evaluates the RE, Sum and LDC transition constraints on the rows with the wire layout of `lu_gate` and
+/// `lut_gate`, and filters them with the `TransSre` and `TransLdc` selectors of `trans_selectors`. Wires are read
+/// through `wire`, so that both the extension and the base field cases are covered.
+fn eval_lookup_transitions<T: Field>(
+    constraints: &mut Vec<T>,
+    wire: impl Fn(usize) -> T,
+    (lu_gate, lut_gate): (&TupleLookupGate, &TupleLookupTableGate),
+    lu_degree: usize,
+    (local_lookup_zs, next_lookup_zs): (&[T], &[T]),
+    deltas: &[T; NUM_COINS_LOOKUP],
+    trans_selectors: &[T],
+) {
+    let num_lu_slots = lu_gate.num_slots;
+    let num_lut_slots = lut_gate.num_slots;
+    let num_sldc_polys = local_lookup_zs.len() - 1;
+    let lut_degree = ceil_div_usize(num_lut_slots, num_sldc_polys);
+
+    // RE is the first polynomial stored.
+    let z_re = local_lookup_zs[0];
+    let next_z_re = next_lookup_zs[0];
+
+    // Partial Sums and LDCs are both stored in the remaining SLDC polynomials.
+    let z_x_lookup_sldcs = &local_lookup_zs[1..num_sldc_polys + 1];
+    let z_gx_lookup_sldcs = &next_lookup_zs[1..num_sldc_polys + 1];
+
+    let challenge_a = deltas[LookupChallenges::ChallengeA as usize];
+    let challenge_b = deltas[LookupChallenges::ChallengeB as usize];
+    let alpha = deltas[LookupChallenges::ChallengeAlpha as usize];
+    let delta = deltas[LookupChallenges::ChallengeDelta as usize];
+    let gamma = deltas[LookupChallenges::ChallengeTuple as usize];
+
+    // Tuples are compressed with powers of gamma. Single values are left as they are.
+    let compress = |wires: Range<usize>| wires.rev().fold(T::ZERO, |acc, w| acc * gamma + wire(w));
+
+    // Compute all current looked and looking combos, i.e. the combos we need for the SLDC polynomials,
+    // and the lookup combos, i.e. the combos used to check that the LUT is correct.
+    let mut current_looked_combos = Vec::with_capacity(num_lut_slots);
+    let mut current_lookup_combos = Vec::with_capacity(num_lut_slots);
+    for s in 0..num_lut_slots {
+        let input = compress(lut_gate.wires_ith_looked_inp(s));
+        let output = compress(lut_gate.wires_ith_looked_out(s));
+        current_looked_combos.push(input + challenge_a * output);
+        current_lookup_combos.push(input + challenge_b * output);
+    }
+
+    let current_looking_combos: Vec<T> = (0..num_lu_slots)
+        .map(|s| {
+            let input = compress(lu_gate.wires_ith_looking_inp(s));
+            let output = compress(lu_gate.wires_ith_looking_out(s));
+            input + challenge_a * output
+        })
+        .collect();
+
     // Check RE row transition constraint.
     let mut cur_sum = next_z_re;
     for elt in &current_lookup_combos {
-        cur_sum =
-            cur_sum * F::Extension::from(deltas[LookupChallenges::ChallengeDelta as usize]) + *elt;
+        cur_sum = cur_sum * delta + *elt;
     }
     let unfiltered_re_line = z_re - cur_sum;
 
-    constraints.push(lookup_selectors[LookupSelectors::TransSre as usize] * unfiltered_re_line);
+    constraints.push(trans_selectors[LookupSelectors::TransSre as usize] * unfiltered_re_line);
 
     for poly in 0..num_sldc_polys {
         // Compute prod(alpha - combo) for the current slot for Sum.
-        let lut_prod: F::Extension = (poly * lut_degree
-            ..min((poly + 1) * lut_degree, num_lut_slots))
-            .map(|i| {
-                F::Extension::from(deltas[LookupChallenges::ChallengeAlpha as usize])
-                    - current_looked_combos[i]
-            })
+        let lut_prod: T = (poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots))
+            .map(|i| alpha - current_looked_combos[i])
             .product();
 
         // Compute prod(alpha - combo) for the current slot for LDC.
-        let lu_prod: F::Extension = (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
-            .map(|i| {
-                F::Extension::from(deltas[LookupChallenges::ChallengeAlpha as usize])
-                    - current_looking_combos[i]
-            })
+        let lu_prod: T = (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
+            .map(|i| alpha - current_looking_combos[i])
             .product();
 
         // Function which computes, given index i: prod_{j!=i}(alpha - combo_j) for Sum.
@@ -450,10 +530,9 @@ pub fn check_lookup_constraints<F: RichField + Extendable<D>, const D: usize>(
             (poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots))
                 .map(|j| {
                     if j != i {
-                        F::Extension::from(deltas[LookupChallenges::ChallengeAlpha as usize])
-                            - current_looked_combos[j]
+                        alpha - current_looked_combos[j]
                     } else {
-                        F::Extension::ONE
+                        T::ONE
                     }
                 })
                 .product()
@@ -464,23 +543,23 @@ pub fn check_lookup_constraints<F: RichField + Extendable<D>, const D: usize>(
             (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
                 .map(|j| {
                     if j != i {
-                        F::Extension::from(deltas[LookupChallenges::ChallengeAlpha as usize])
-                            - current_looking_combos[j]
+                        alpha - current_looking_combos[j]
                     } else {
-                        F::Extension::ONE
+                        T::ONE
                     }
                 })
                 .product()
         };
+
         // Compute sum_i(prod_{j!=i}(alpha - combo_j)) for LDC.
         let lu_sum_prods = (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
-            .fold(F::Extension::ZERO, |acc, i| acc + lu_prod_i(i));
+            .fold(T::ZERO, |acc, i| acc + lu_prod_i(i));
 
         // Compute sum_i(mul_i.prod_{j!=i}(alpha - combo_j)) for Sum.
         let lut_sum_prods_with_mul = (poly * lut_degree
             ..min((poly + 1) * lut_degree, num_lut_slots))
-            .fold(F::Extension::ZERO, |acc, i| {
-                acc + vars.local_wires[LookupTableGate::wire_ith_multiplicity(i)] * lut_prod_i(i)
+            .fold(T::ZERO, |acc, i| {
+                acc + wire(lut_gate.wire_ith_multiplicity(i)) * lut_prod_i(i)
             });
 
         // The previous element is the previous poly of the current row or the last poly of the next row.
@@ -494,15 +573,13 @@ pub fn check_lookup_constraints<F: RichField + Extendable<D>, const D: usize>(
         let unfiltered_sum_transition =
             lut_prod * (z_x_lookup_sldcs[poly] - prev) - lut_sum_prods_with_mul;
         constraints
-            .push(lookup_selectors[LookupSelectors::TransSre as usize] * unfiltered_sum_transition);
+            .push(trans_selectors[LookupSelectors::TransSre as usize] * unfiltered_sum_transition);
 
         // Check LDC row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
         let unfiltered_ldc_transition = lu_prod * (z_x_lookup_sldcs[poly] - prev) + lu_sum_prods;
         constraints
-            .push(lookup_selectors[LookupSelectors::TransLdc as usize] * unfiltered_ldc_transition);
+            .push(trans_selectors[LookupSelectors::TransLdc as usize] * unfiltered_ldc_transition);
     }
-
-    constraints
 }
 
 /// Same as `check_lookup_constraints`, but for the base field case.
@@ -512,50 +589,19 @@ pub fn check_lookup_constraints_batch<F: RichField + Extendable<D>, const D: usi
     local_lookup_zs: &[F],
     next_lookup_zs: &[F],
     lookup_selectors: &[F],
-    deltas: &[F; 4],
+    deltas: &[F; NUM_COINS_LOOKUP],
     lut_re_poly_evals: &[F],
 ) -> Vec<F> {
-    let num_lu_slots = LookupGate::num_slots(&common_data.config);
-    let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
-    let lu_degree = common_data.quotient_degree_factor - 1;
+    let num_fixed_luts = common_data.luts.len() + common_data.tuple_luts.len();
     let num_sldc_polys = local_lookup_zs.len() - 1;
-    let lut_degree = ceil_div_usize(num_lut_slots, num_sldc_polys);
 
-    let mut constraints = Vec::with_capacity(4 + common_data.luts.len() + 2 * num_sldc_polys);
+    let mut constraints = Vec::with_capacity(num_lookup_constraints(common_data));
 
     // RE is the first polynomial stored.
     let z_re = local_lookup_zs[0];
-    let next_z_re = next_lookup_zs[0];
 
     // Partial Sums and LDCs are both stored in the remaining polynomials.
     let z_x_lookup_sldcs = &local_lookup_zs[1..num_sldc_polys + 1];
-    let z_gx_lookup_sldcs = &next_lookup_zs[1..num_sldc_polys + 1];
-
-    // Compute all current looked and looking combos, i.e. the combos we need for the SLDC polynomials.
-    let current_looked_combos: Vec<F> = (0..num_lut_slots)
-        .map(|s| {
-            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-            input_wire + deltas[LookupChallenges::ChallengeA as usize] * output_wire
-        })
-        .collect();
-
-    let current_looking_combos: Vec<F> = (0..num_lu_slots)
-        .map(|s| {
-            let input_wire = vars.local_wires[LookupGate::wire_ith_looking_inp(s)];
-            let output_wire = vars.local_wires[LookupGate::wire_ith_looking_out(s)];
-            input_wire + deltas[LookupChallenges::ChallengeA as usize] * output_wire
-        })
-        .collect();
-
-    // Compute all current lookup combos, i.e. the combos used to check that the LUT is correct.
-    let current_lookup_combos: Vec<F> = (0..num_lut_slots)
-        .map(|s| {
-            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-            input_wire + deltas[LookupChallenges::ChallengeB as usize] * output_wire
-        })
-        .collect();
 
     // Check last LDC constraint.
     constraints.push(
@@ -568,92 +614,42 @@ pub fn check_lookup_constraints_batch<F: RichField + Extendable<D>, const D: usi
     // Check initial RE constraint.
     constraints.push(lookup_selectors[LookupSelectors::InitSre as usize] * z_re);
 
-    // Check final RE constraints for each different LUT.
-    for r in LookupSelectors::StartEnd as usize..common_data.num_lookup_selectors {
-        let cur_ends_selector = lookup_selectors[r];
+    // Check final RE constraints for each different fixed LUT.
+    for r in 0..num_fixed_luts {
+        let cur_ends_selector = lookup_selectors[LookupSelectors::StartEnd as usize + r];
 
         // Use the precomputed value for the lut poly evaluation
-        let re_poly_eval = lut_re_poly_evals[r - LookupSelectors::StartEnd as usize];
+        let re_poly_eval = lut_re_poly_evals[r];
 
         constraints.push(cur_ends_selector * (z_re - re_poly_eval))
     }
 
-    // Check RE row transition constraint.
-    let mut cur_sum = next_z_re;
-    for elt in &current_lookup_combos {
-        cur_sum = cur_sum * deltas[LookupChallenges::ChallengeDelta as usize] + *elt;
-    }
-    let unfiltered_re_line = z_re - cur_sum;
-
-    constraints.push(lookup_selectors[LookupSelectors::TransSre as usize] * unfiltered_re_line);
-
-    for poly in 0..num_sldc_polys {
-        // Compute prod(alpha - combo) for the current slot for Sum.
-        let lut_prod: F = (poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots))
-            .map(|i| deltas[LookupChallenges::ChallengeAlpha as usize] - current_looked_combos[i])
-            .product();
-
-        // Compute prod(alpha - combo) for the current slot for LDC.
-        let lu_prod: F = (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
-            .map(|i| deltas[LookupChallenges::ChallengeAlpha as usize] - current_looking_combos[i])
-            .product();
-
-        // Function which computes, given index i: prod_{j!=i}(alpha - combo_j) for Sum.
-        let lut_prod_i = |i| {
-            (poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots))
-                .map(|j| {
-                    if j != i {
-                        deltas[LookupChallenges::ChallengeAlpha as usize] - current_looked_combos[j]
-                    } else {
-                        F::ONE
-                    }
-                })
-                .product()
-        };
-
-        // Function which computes, given index i: prod_{j!=i}(alpha - combo_j) for LDC.
-        let lu_prod_i = |i| {
-            (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
-                .map(|j| {
-                    if j != i {
-                        deltas[LookupChallenges::ChallengeAlpha as usize]
-                            - current_looking_combos[j]
-                    } else {
-                        F::ONE
-                    }
-                })
-                .product()
-        };
-
-        // Compute sum_i(prod_{j!=i}(alpha - combo_j)) for LDC.
-        let lu_sum_prods = (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
-            .fold(F::ZERO, |acc, i| acc + lu_prod_i(i));
-
-        // Compute sum_i(mul_i.prod_{j!=i}(alpha - combo_j)) for Sum.
-        let lut_sum_prods_with_mul = (poly * lut_degree
-            ..min((poly + 1) * lut_degree, num_lut_slots))
-            .fold(F::ZERO, |acc, i| {
-                acc + vars.local_wires[LookupTableGate::wire_ith_multiplicity(i)] * lut_prod_i(i)
-            });
-
-        // The previous element is the previous poly of the current row or the last poly of the next row.
-        let prev = if poly == 0 {
-            z_gx_lookup_sldcs[num_sldc_polys - 1]
-        } else {
-            z_x_lookup_sldcs[poly - 1]
-        };
-
-        // Check Sum row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
-        let unfiltered_sum_transition =
-            lut_prod * (z_x_lookup_sldcs[poly] - prev) - lut_sum_prods_with_mul;
-        constraints
-            .push(lookup_selectors[LookupSelectors::TransSre as usize] * unfiltered_sum_transition);
+    // Check the transition constraints of the rows with one input and one output.
+    let (lu_gate, lut_gate) = lookup_layout(common_data, false);
+    eval_lookup_transitions(
+        &mut constraints,
+        |i| vars.local_wires[i],
+        (&lu_gate, &lut_gate),
+        common_data.quotient_degree_factor - 1,
+        (local_lookup_zs, next_lookup_zs),
+        deltas,
+        lookup_selectors,
+    );
 
-        // Check LDC row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
-        let unfiltered_ldc_transition = lu_prod * (z_x_lookup_sldcs[poly] - prev) + lu_sum_prods;
-        constraints
-            .push(lookup_selectors[LookupSelectors::TransLdc as usize] * unfiltered_ldc_transition);
+    // Check the transition constraints of the rows of tuple LUTs, whose selectors come after the end selectors.
+    if !common_data.tuple_luts.is_empty() {
+        let (lu_gate, lut_gate) = lookup_layout(common_data, true);
+        eval_lookup_transitions(
+            &mut constraints,
+            |i| vars.local_wires[i],
+            (&lu_gate, &lut_gate),
+            common_data.quotient_degree_factor - 1,
+            (local_lookup_zs, next_lookup_zs),
+            deltas,
+            &lookup_selectors[LookupSelectors::StartEnd as usize + num_fixed_luts..],
+        );
     }
+
     constraints
 }
 
@@ -752,11 +748,21 @@ pub(crate) fn get_lut_poly_circuit<F: RichField + Extendable<D>, const D: usize>
     common_data: &CommonCircuitData<F, D>,
     lut_index: usize,
     deltas: &[Target],
-    degree: usize,
 ) -> Target {
     let b = deltas[LookupChallenges::ChallengeB as usize];
     let delta = deltas[LookupChallenges::ChallengeDelta as usize];
+    if lut_index >= common_data.luts.len() {
+        return get_tuple_lut_poly_circuit(
+            builder,
+            common_data,
+            lut_index - common_data.luts.len(),
+            deltas,
+        );
+    }
+
+    let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
     let n = common_data.luts[lut_index].len();
+    let degree = ceil_div_usize(n, num_lut_slots) * num_lut_slots;
     let mut coeffs: Vec<Target> = common_data.luts[lut_index]
         .iter()
         .map(|(input, output)| {
@@ -777,6 +783,37 @@ pub(crate) fn get_lut_poly_circuit<F: RichField + Extendable<D>, const D: usize>
         })
 }
 
+/// Same as `get_lut_poly_circuit`, for the tuple LUT with index `tuple_lut_index`. Since the combos depend on the
+/// tuple challenge, each column of the table is evaluated separately before being combined.
+fn get_tuple_lut_poly_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    common_data: &CommonCircuitData<F, D>,
+    tuple_lut_index: usize,
+    deltas: &[Target],
+) -> Target {
+    let b = deltas[LookupChallenges::ChallengeB as usize];
+    let delta = deltas[LookupChallenges::ChallengeDelta as usize];
+    let gamma = deltas[LookupChallenges::ChallengeTuple as usize];
+    let table = &common_data.tuple_luts[tuple_lut_index];
+    let (_, lut_gate) = lookup_layout(common_data, true);
+    let num_entries = ceil_div_usize(table.len(), lut_gate.num_slots) * lut_gate.num_slots;
+
+    // Evaluate the polynomial of each column. The unused slots of the last row hold the first row of the table.
+    let column_evals = (0..table.width())
+        .map(|j| {
+            (0..num_entries).fold(builder.zero(), |acc, e| {
+                let row = table.row(if e < table.len() { e } else { 0 });
+                let temp = builder.mul(acc, delta);
+                builder.add_const(temp, row[j])
+            })
+        })
+        .collect::<Vec<_>>();
+    let (input_evals, output_evals) = column_evals.split_at(table.num_inputs());
+    let input_eval = reduce_with_powers_circuit(builder, input_evals, gamma);
+    let output_eval = reduce_with_powers_circuit(builder, output_evals, gamma);
+    builder.mul_add(b, output_eval, input_eval)
+}
+
 /// Evaluate the vanishing polynomial at `x`. In this context, the vanishing polynomial is a random
 /// linear combination of gate constraints, plus some other terms relating to the permutation
 /// argument. All such terms should vanish on `H`.
@@ -818,10 +855,7 @@ pub(crate) fn eval_vanishing_poly_circuit<F: RichField + Extendable<D>, const D:
 
     // The terms checking lookup constraints.
     let mut vanishing_all_lookup_terms = if has_lookup {
-        let num_sldc_polys = common_data.num_lookup_polys - 1;
-        Vec::with_capacity(
-            common_data.config.num_challenges * (4 + common_data.luts.len() + 2 * num_sldc_polys),
-        )
+        Vec::with_capacity(common_data.config.num_challenges * num_lookup_constraints(common_data))
     } else {
         Vec::new()
     };
@@ -927,21 +961,16 @@ pub fn check_lookup_constraints_circuit<F: RichField + Extendable<D>, const D: u
     lookup_selectors: &[ExtensionTarget<D>],
     deltas: &[Target],
 ) -> Vec<ExtensionTarget<D>> {
-    let num_lu_slots = LookupGate::num_slots(&common_data.config);
-    let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
-    let lu_degree = common_data.quotient_degree_factor - 1;
+    let num_fixed_luts = common_data.luts.len() + common_data.tuple_luts.len();
     let num_sldc_polys = local_lookup_zs.len() - 1;
-    let lut_degree = ceil_div_usize(num_lut_slots, num_sldc_polys);
 
-    let mut constraints = Vec::with_capacity(4 + common_data.luts.len() + 2 * num_sldc_polys);
+    let mut constraints = Vec::with_capacity(num_lookup_constraints(common_data));
 
     // RE is the first polynomial stored.
     let z_re = local_lookup_zs[0];
-    let next_z_re = next_lookup_zs[0];
 
     // Partial Sums and LDCs (i.e. the SLDC polynomials) are stored in the remaining polynomials.
     let z_x_lookup_sldcs = &local_lookup_zs[1..num_sldc_polys + 1];
-    let z_gx_lookup_sldcs = &next_lookup_zs[1..num_sldc_polys + 1];
 
     // Convert deltas to ExtensionTargets.
     let ext_deltas = deltas
@@ -949,61 +978,6 @@ pub fn check_lookup_constraints_circuit<F: RichField + Extendable<D>, const D: u
         .map(|d| builder.convert_to_ext(*d))
         .collect::<Vec<_>>();
 
-    // Computing all current looked and looking combos, i.e. the combos we need for the SLDC polynomials.
-    let current_looked_combos = (0..num_lut_slots)
-        .map(|s| {
-            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-            builder.mul_add_extension(
-                ext_deltas[LookupChallenges::ChallengeA as usize],
-                output_wire,
-                input_wire,
-            )
-        })
-        .collect::<Vec<_>>();
-    let current_looking_combos = (0..num_lu_slots)
-        .map(|s| {
-            let input_wire = vars.local_wires[LookupGate::wire_ith_looking_inp(s)];
-            let output_wire = vars.local_wires[LookupGate::wire_ith_looking_out(s)];
-            builder.mul_add_extension(
-                ext_deltas[LookupChallenges::ChallengeA as usize],
-                output_wire,
-                input_wire,
-            )
-        })
-        .collect::<Vec<_>>();
-
-    let current_lut_subs = (0..num_lut_slots)
-        .map(|s| {
-            builder.sub_extension(
-                ext_deltas[LookupChallenges::ChallengeAlpha as usize],
-                current_looked_combos[s],
-            )
-        })
-        .collect::<Vec<_>>();
-
-    let current_lu_subs = (0..num_lu_slots)
-        .map(|s| {
-            builder.sub_extension(
-                ext_deltas[LookupChallenges::ChallengeAlpha as usize],
-                current_looking_combos[s],
-            )
-        })
-        .collect::<Vec<_>>();
-
-    // Computing all current lookup combos, i.e. the combos used to check that the LUT is correct.
-    let current_lookup_combos = (0..num_lut_slots)
-        .map(|s| {
-            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-            builder.mul_add_extension(
-                ext_deltas[LookupChallenges::ChallengeB as usize],
-                output_wire,
-                input_wire,
-            )
-        })
-        .collect::<Vec<_>>();
-
     // Check last LDC constraint.
     constraints.push(builder.mul_extension(
         lookup_selectors[LookupSelectors::LastLdc as usize],
@@ -1020,26 +994,112 @@ pub fn check_lookup_constraints_circuit<F: RichField + Extendable<D>, const D: u
     constraints
         .push(builder.mul_extension(lookup_selectors[LookupSelectors::InitSre as usize], z_re));
 
-    // Check final RE constraints for each different LUT.
-    for r in LookupSelectors::StartEnd as usize..common_data.num_lookup_selectors {
-        let cur_ends_selectors = lookup_selectors[r];
-        let lut_row_number = ceil_div_usize(
-            common_data.luts[r - LookupSelectors::StartEnd as usize].len(),
-            num_lut_slots,
-        );
-        let cur_function_eval = get_lut_poly_circuit(
-            builder,
-            common_data,
-            r - LookupSelectors::StartEnd as usize,
-            deltas,
-            num_lut_slots * lut_row_number,
-        );
+    // Check final RE constraints for each different fixed LUT.
+    for r in 0..num_fixed_luts {
+        let cur_ends_selectors = lookup_selectors[LookupSelectors::StartEnd as usize + r];
+        let cur_function_eval = get_lut_poly_circuit(builder, common_data, r, deltas);
         let cur_function_eval_ext = builder.convert_to_ext(cur_function_eval);
 
         let cur_re = builder.sub_extension(z_re, cur_function_eval_ext);
         constraints.push(builder.mul_extension(cur_ends_selectors, cur_re));
     }
 
+    // Check the transition constraints of the rows with one input and one output.
+    let (lu_gate, lut_gate) = lookup_layout(common_data, false);
+    eval_lookup_transitions_circuit(
+        builder,
+        &mut constraints,
+        vars,
+        (&lu_gate, &lut_gate),
+        common_data.quotient_degree_factor - 1,
+        (local_lookup_zs, next_lookup_zs),
+        &ext_deltas,
+        lookup_selectors,
+    );
+
+    // Check the transition constraints of the rows of tuple LUTs, whose selectors come after the end selectors.
+    if !common_data.tuple_luts.is_empty() {
+        let (lu_gate, lut_gate) = lookup_layout(common_data, true);
+        eval_lookup_transitions_circuit(
+            builder,
+            &mut constraints,
+            vars,
+            (&lu_gate, &lut_gate),
+            common_data.quotient_degree_factor - 1,
+            (local_lookup_zs, next_lookup_zs),
+            &ext_deltas,
+            &lookup_selectors[LookupSelectors::StartEnd as usize + num_fixed_luts..],
+        );
+    }
+
+    constraints
+}
+
+/// Same as `eval_lookup_transitions`, but for the recursive case.
+#[allow(clippy::too_many_arguments)]
+fn eval_lookup_transitions_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    constraints: &mut Vec<ExtensionTarget<D>>,
+    vars: EvaluationTargets<D>,
+    (lu_gate, lut_gate): (&TupleLookupGate, &TupleLookupTableGate),
+    lu_degree: usize,
+    (local_lookup_zs, next_lookup_zs): (&[ExtensionTarget<D>], &[ExtensionTarget<D>]),
+    ext_deltas: &[ExtensionTarget<D>],
+    trans_selectors: &[ExtensionTarget<D>],
+) {
+    let num_lu_slots = lu_gate.num_slots;
+    let num_lut_slots = lut_gate.num_slots;
+    let num_sldc_polys = local_lookup_zs.len() - 1;
+    let lut_degree = ceil_div_usize(num_lut_slots, num_sldc_polys);
+
+    // RE is the first polynomial stored.
+    let z_re = local_lookup_zs[0];
+    let next_z_re = next_lookup_zs[0];
+
+    // Partial Sums and LDCs (i.e. the SLDC polynomials) are stored in the remaining polynomials.
+    let z_x_lookup_sldcs = &local_lookup_zs[1..num_sldc_polys + 1];
+    let z_gx_lookup_sldcs = &next_lookup_zs[1..num_sldc_polys + 1];
+
+    let challenge_a = ext_deltas[LookupChallenges::ChallengeA as usize];
+    let challenge_b = ext_deltas[LookupChallenges::ChallengeB as usize];
+    let alpha = ext_deltas[LookupChallenges::ChallengeAlpha as usize];
+    let gamma = ext_deltas[LookupChallenges::ChallengeTuple as usize];
+
+    // Tuples are compressed with powers of gamma. Single values are left as they are.
+    let compress = |builder: &mut CircuitBuilder<F, D>, wires: Range<usize>| {
+        let mut values = vars.local_wires[wires].iter().rev();
+        match values.next() {
+            Some(&last) => values.fold(last, |acc, &v| builder.mul_add_extension(acc, gamma, v)),
+            None => builder.zero_extension(),
+        }
+    };
+
+    // Computing all current looked and looking combos, i.e. the combos we need for the SLDC polynomials,
+    // and the lookup combos, i.e. the combos used to check that the LUT is correct.
+    let mut current_looked_combos = Vec::with_capacity(num_lut_slots);
+    let mut current_lookup_combos = Vec::with_capacity(num_lut_slots);
+    for s in 0..num_lut_slots {
+        let input = compress(builder, lut_gate.wires_ith_looked_inp(s));
+        let output = compress(builder, lut_gate.wires_ith_looked_out(s));
+        current_looked_combos.push(builder.mul_add_extension(challenge_a, output, input));
+        current_lookup_combos.push(builder.mul_add_extension(challenge_b, output, input));
+    }
+    let current_looking_combos = (0..num_lu_slots)
+        .map(|s| {
+            let input = compress(builder, lu_gate.wires_ith_looking_inp(s));
+            let output = compress(builder, lu_gate.wires_ith_looking_out(s));
+            builder.mul_add_extension(challenge_a, output, input)
+        })
+        .collect::<Vec<_>>();
+
+    let current_lut_subs = (0..num_lut_slots)
+        .map(|s| builder.sub_extension(alpha, current_looked_combos[s]))
+        .collect::<Vec<_>>();
+
+    let current_lu_subs = (0..num_lu_slots)
+        .map(|s| builder.sub_extension(alpha, current_looking_combos[s]))
+        .collect::<Vec<_>>();
+
     // Check RE row transition constraint.
     let mut cur_sum = next_z_re;
     for elt in &current_lookup_combos {
@@ -1052,7 +1112,7 @@ pub fn check_lookup_constraints_circuit<F: RichField + Extendable<D>, const D: u
     let unfiltered_re_line = builder.sub_extension(z_re, cur_sum);
 
     constraints.push(builder.mul_extension(
-        lookup_selectors[LookupSelectors::TransSre as usize],
+        trans_selectors[LookupSelectors::TransSre as usize],
         unfiltered_re_line,
     ));
 
@@ -1097,7 +1157,7 @@ pub fn check_lookup_constraints_circuit<F: RichField + Extendable<D>, const D: u
                 }
                 builder.mul_add_extension(
                     prod_i,
-                    vars.local_wires[LookupTableGate::wire_ith_multiplicity(i)],
+                    vars.local_wires[lut_gate.wire_ith_multiplicity(i)],
                     acc,
                 )
             });
@@ -1115,16 +1175,15 @@ pub fn check_lookup_constraints_circuit<F: RichField + Extendable<D>, const D: u
         let unfiltered_sum_transition =
             builder.mul_sub_extension(lut_prod, cur_sub, lut_sum_prods_mul);
         constraints.push(builder.mul_extension(
-            lookup_selectors[LookupSelectors::TransSre as usize],
+            trans_selectors[LookupSelectors::TransSre as usize],
             unfiltered_sum_transition,
         ));
 
         // Check ldc row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
         let unfiltered_ldc_transition = builder.mul_add_extension(lu_prod, cur_sub, lu_sum_prods);
         constraints.push(builder.mul_extension(
-            lookup_selectors[LookupSelectors::TransLdc as usize],
+            trans_selectors[LookupSelectors::TransLdc as usize],
             unfiltered_ldc_transition,
         ));
     }
-    constraints
 }
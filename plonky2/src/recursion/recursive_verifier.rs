@@ -2,7 +2,7 @@ use crate::field::extension::Extendable;
 use crate::hash::hash_types::{HashOutTarget, RichField};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::{CommonCircuitData, VerifierCircuitTarget};
-use crate::plonk::config::{AlgebraicHasher, GenericConfig};
+use crate::plonk::config::{CircuitHasher, GenericConfig};
 use crate::plonk::plonk_common::salt_size;
 use crate::plonk::proof::{
     OpeningSetTarget, ProofChallengesTarget, ProofTarget, ProofWithPublicInputsTarget,
@@ -20,7 +20,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         inner_verifier_data: &VerifierCircuitTarget,
         inner_common_data: &CommonCircuitData<F, D>,
     ) where
-        C::Hasher: AlgebraicHasher<F>,
+        C::Hasher: CircuitHasher<F>,
     {
         assert_eq!(
             proof_with_pis.public_inputs.len(),
@@ -53,7 +53,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         inner_verifier_data: &VerifierCircuitTarget,
         inner_common_data: &CommonCircuitData<F, D>,
     ) where
-        C::Hasher: AlgebraicHasher<F>,
+        C::Hasher: CircuitHasher<F>,
     {
         let one = self.one_extension();
 
@@ -206,9 +206,12 @@ mod tests {
     use crate::gadgets::lookup::{OTHER_TABLE, TIP5_TABLE};
     use crate::gates::lookup_table::LookupTable;
     use crate::gates::noop::NoopGate;
+    use crate::gates::tuple_lookup::TupleLookupTable;
     use crate::iop::witness::{PartialWitness, WitnessWrite};
     use crate::plonk::circuit_data::{CircuitConfig, VerifierOnlyCircuitData};
-    use crate::plonk::config::{KeccakGoldilocksConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::config::{
+        KeccakGoldilocksConfig, Poseidon2GoldilocksConfig, PoseidonGoldilocksConfig,
+    };
     use crate::plonk::proof::{CompressedProofWithPublicInputs, ProofWithPublicInputs};
     use crate::plonk::prover::prove;
     use crate::util::timing::TimingTree;
@@ -229,6 +232,22 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_recursive_verifier_poseidon2() -> Result<()> {
+        init_logger();
+        const D: usize = 2;
+        type C = Poseidon2GoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_zk_config();
+
+        let (proof, vd, common_data) = dummy_proof::<F, C, D>(&config, 4_000)?;
+        let (proof, vd, common_data) =
+            recursive_proof::<F, C, C, D>(proof, vd, common_data, &config, None, true, true)?;
+        test_serialization(&proof, &vd, &common_data)?;
+
+        Ok(())
+    }
+
     #[test]
     fn test_recursive_verifier_one_lookup() -> Result<()> {
         init_logger();
@@ -277,6 +296,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_recursive_verifier_dynamic_lut() -> Result<()> {
+        init_logger();
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+
+        let (proof, vd, common_data) = dummy_dynamic_lut_proof::<F, C, D>(&config)?;
+        let (proof, vd, common_data) =
+            recursive_proof::<F, C, C, D>(proof, vd, common_data, &config, None, true, true)?;
+        test_serialization(&proof, &vd, &common_data)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recursive_verifier_tuple_lut() -> Result<()> {
+        init_logger();
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+
+        let (proof, vd, common_data) = dummy_tuple_lut_proof::<F, C, D>(&config)?;
+        let (proof, vd, common_data) =
+            recursive_proof::<F, C, C, D>(proof, vd, common_data, &config, None, true, true)?;
+        test_serialization(&proof, &vd, &common_data)?;
+
+        Ok(())
+    }
+
     #[test]
     fn test_recursive_recursive_verifier() -> Result<()> {
         init_logger();
@@ -405,6 +456,64 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_recursive_verifier_keccak_inner() -> Result<()> {
+        init_logger();
+        const D: usize = 2;
+        type PC = PoseidonGoldilocksConfig;
+        type KC = KeccakGoldilocksConfig;
+        type F = <PC as GenericConfig<D>>::F;
+
+        // Keccak is expensive to evaluate in a circuit, so the inner proof is as small as
+        // possible, with a narrow and insecure configuration, a single challenge and a single FRI
+        // query.
+        let standard_config = CircuitConfig::standard_recursion_config();
+        let inner_config = CircuitConfig {
+            num_wires: 4,
+            num_routed_wires: 4,
+            security_bits: 2,
+            num_challenges: 1,
+            max_quotient_degree_factor: 3,
+            fri_config: FriConfig {
+                rate_bits: 2,
+                cap_height: 0,
+                proof_of_work_bits: 0,
+                num_query_rounds: 1,
+                ..standard_config.fri_config.clone()
+            },
+            ..standard_config.clone()
+        };
+        let (proof, vd, common_data) = dummy_proof::<F, KC, D>(&inner_config, 0)?;
+
+        // The Keccak gadget mostly consists of lookups and arithmetic operations, which fit more
+        // per row when all wires are routed. Together with a blowup factor of 4, this keeps the
+        // outer circuit at 2^16 rows and its proof within a few GB of memory.
+        let outer_config = CircuitConfig {
+            num_wires: 160,
+            num_routed_wires: 160,
+            max_quotient_degree_factor: 4,
+            fri_config: FriConfig {
+                rate_bits: 2,
+                num_query_rounds: 50,
+                ..standard_config.fri_config.clone()
+            },
+            ..standard_config
+        };
+        let (proof, vd, common_data) = recursive_proof::<F, PC, KC, D>(
+            proof,
+            vd,
+            common_data,
+            &outer_config,
+            None,
+            false,
+            false,
+        )?;
+        assert_eq!(common_data.degree_bits(), 16);
+        test_serialization(&proof, &vd, &common_data)?;
+
+        Ok(())
+    }
+
     type Proof<F, C, const D: usize> = (
         ProofWithPublicInputs<F, C, D>,
         VerifierOnlyCircuitData<C, D>,
@@ -620,6 +729,121 @@ mod tests {
         Ok((proof, data.verifier_only, data.common))
     }
 
+    /// Creates a dummy proof which does lookups to a dynamic LUT and to a fixed LUT.
+    fn dummy_dynamic_lut_proof<
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        const D: usize,
+    >(
+        config: &CircuitConfig,
+    ) -> Result<Proof<F, C, D>> {
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+
+        let table = (0..30)
+            .map(|_| (builder.add_virtual_target(), builder.add_virtual_target()))
+            .collect::<Vec<_>>();
+        let dynamic_index = builder.add_dynamic_lookup_table(&table);
+        let inputs = builder.add_virtual_targets(50);
+        for &inp in &inputs {
+            let out = builder.add_dynamic_lookup(inp, dynamic_index);
+            builder.register_public_input(out);
+        }
+
+        let tip5_table = TIP5_TABLE.to_vec();
+        let table_pairs: LookupTable = Arc::new((0..256).zip_eq(tip5_table).collect());
+        let tip5_index = builder.add_lookup_table_from_pairs(table_pairs);
+        let tip5_input = builder.add_virtual_target();
+        let tip5_output = builder.add_lookup_from_index(tip5_input, tip5_index);
+        builder.register_public_input(tip5_output);
+
+        let mut pw = PartialWitness::new();
+        for (i, &(inp, out)) in table.iter().enumerate() {
+            pw.set_target(inp, F::from_canonical_usize(3 * i));
+            pw.set_target(out, F::from_canonical_usize(i * i));
+        }
+        for (i, &inp) in inputs.iter().enumerate() {
+            pw.set_target(inp, F::from_canonical_usize(3 * (i % 30)));
+        }
+        pw.set_target(tip5_input, F::ONE);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        for i in 0..50 {
+            assert_eq!(
+                proof.public_inputs[i],
+                F::from_canonical_usize((i % 30) * (i % 30))
+            );
+        }
+        data.verify(proof.clone())?;
+
+        Ok((proof, data.verifier_only, data.common))
+    }
+
+    /// Creates a proof with lookups into two tuple LUTs of different shapes, and a standard LUT.
+    fn dummy_tuple_lut_proof<
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        const D: usize,
+    >(
+        config: &CircuitConfig,
+    ) -> Result<Proof<F, C, D>> {
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+
+        let inputs = (0..16)
+            .cartesian_product(0..16)
+            .map(|(a, b)| vec![F::from_canonical_u64(a), F::from_canonical_u64(b)])
+            .collect::<Vec<_>>();
+        let mul_table = TupleLookupTable::from_fn(2, 2, &inputs, |inp| {
+            let product = inp[0] * inp[1];
+            vec![product, product * F::from_canonical_u64(1 << 40)]
+        });
+        let mul_index = builder.add_tuple_lookup_table(mul_table);
+        let square_rows = (0..10)
+            .map(|i| vec![F::from_canonical_u64(i), F::from_canonical_u64(i * i)])
+            .collect::<Vec<_>>();
+        let square_index =
+            builder.add_tuple_lookup_table(TupleLookupTable::new(1, 1, &square_rows));
+
+        let mul_inputs = (0..30)
+            .map(|_| builder.add_virtual_targets(2))
+            .collect::<Vec<_>>();
+        for inputs in &mul_inputs {
+            let outputs = builder.add_tuple_lookup(inputs, mul_index);
+            builder.register_public_inputs(&outputs);
+        }
+        let square_input = builder.add_virtual_target();
+        let square_output = builder.add_tuple_lookup(&[square_input], square_index);
+        builder.register_public_inputs(&square_output);
+
+        let tip5_table = TIP5_TABLE.to_vec();
+        let table_pairs: LookupTable = Arc::new((0..256).zip_eq(tip5_table).collect());
+        let tip5_index = builder.add_lookup_table_from_pairs(table_pairs);
+        let tip5_input = builder.add_virtual_target();
+        let tip5_output = builder.add_lookup_from_index(tip5_input, tip5_index);
+        builder.register_public_input(tip5_output);
+
+        let mut pw = PartialWitness::new();
+        for (i, inputs) in mul_inputs.iter().enumerate() {
+            pw.set_target(inputs[0], F::from_canonical_usize(i % 16));
+            pw.set_target(inputs[1], F::from_canonical_usize(7 * i % 16));
+        }
+        pw.set_target(square_input, F::from_canonical_u64(9));
+        pw.set_target(tip5_input, F::ONE);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        for i in 0..30 {
+            assert_eq!(
+                proof.public_inputs[2 * i],
+                F::from_canonical_usize((i % 16) * (7 * i % 16))
+            );
+        }
+        assert_eq!(proof.public_inputs[60], F::from_canonical_u64(81));
+        data.verify(proof.clone())?;
+
+        Ok((proof, data.verifier_only, data.common))
+    }
+
     fn recursive_proof<
         F: RichField + Extendable<D>,
         C: GenericConfig<D, F = F>,
@@ -635,7 +859,7 @@ mod tests {
         print_timing: bool,
     ) -> Result<Proof<F, C, D>>
     where
-        InnerC::Hasher: AlgebraicHasher<F>,
+        InnerC::Hasher: CircuitHasher<F>,
     {
         let mut builder = CircuitBuilder::<F, D>::new(config.clone());
         let mut pw = PartialWitness::new();
@@ -643,11 +867,7 @@ mod tests {
         pw.set_proof_with_pis_target(&pt, &inner_proof);
 
         let inner_data = builder.add_virtual_verifier_data(inner_cd.config.fri_config.cap_height);
-        pw.set_cap_target(
-            &inner_data.constants_sigmas_cap,
-            &inner_vd.constants_sigmas_cap,
-        );
-        pw.set_hash_target(inner_data.circuit_digest, inner_vd.circuit_digest);
+        pw.set_verifier_data_target(&inner_data, &inner_vd);
 
         builder.verify_proof::<InnerC>(&pt, &inner_data, &inner_cd);
 
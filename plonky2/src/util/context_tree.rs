@@ -8,7 +8,7 @@ use alloc::{
 use log::{log, Level};
 
 /// The hierarchy of contexts, and the gate count contributed by each one. Useful for debugging.
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub(crate) struct ContextTree {
     /// The name of this scope.
     name: String,
@@ -54,6 +54,30 @@ impl ContextTree {
         }
     }
 
+    /// A description of the stack of scopes which were open when the gate at `gate_index` was added.
+    pub fn gate_stack(&self, gate_index: usize) -> String {
+        let mut stack = Vec::new();
+        self.gate_stack_helper(gate_index, &mut stack);
+        stack.join(" > ")
+    }
+
+    fn gate_stack_helper(&self, gate_index: usize, stack: &mut Vec<String>) {
+        stack.push(self.name.clone());
+        if let Some(child) = self
+            .children
+            .iter()
+            .find(|child| child.contains_gate(gate_index))
+        {
+            child.gate_stack_helper(gate_index, stack);
+        }
+    }
+
+    /// Whether the gate at `gate_index` was added while this scope was open.
+    fn contains_gate(&self, gate_index: usize) -> bool {
+        self.enter_gate_count <= gate_index
+            && !matches!(self.exit_gate_count, Some(exit) if exit <= gate_index)
+    }
+
     pub fn push(&mut self, ctx: &str, mut level: log::Level, current_gate_count: usize) {
         assert!(self.is_open());
 
@@ -98,21 +98,65 @@ pub mod default {
     use crate::gates::arithmetic_base::ArithmeticGate;
     use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
     use crate::gates::base_sum::BaseSumGate;
+    use crate::gates::comparison::ComparisonGate;
     use crate::gates::constant::ConstantGate;
     use crate::gates::coset_interpolation::CosetInterpolationGate;
+    use crate::gates::dynamic_lookup::{DynamicLookupGate, DynamicLookupTableGate};
     use crate::gates::exponentiation::ExponentiationGate;
     use crate::gates::lookup::LookupGate;
     use crate::gates::lookup_table::LookupTableGate;
     use crate::gates::multiplication_extension::MulExtensionGate;
     use crate::gates::noop::NoopGate;
     use crate::gates::poseidon::PoseidonGate;
+    use crate::gates::poseidon2::Poseidon2Gate;
     use crate::gates::poseidon_mds::PoseidonMdsGate;
     use crate::gates::public_input::PublicInputGate;
     use crate::gates::random_access::RandomAccessGate;
     use crate::gates::reducing::ReducingGate;
     use crate::gates::reducing_extension::ReducingExtensionGate;
+    use crate::gates::tuple_lookup::{TupleLookupGate, TupleLookupTableGate};
+    use crate::gates::u32_arithmetic::U32ArithmeticGate;
+    use crate::gates::u32_subtraction::U32SubtractionGate;
     use crate::hash::hash_types::RichField;
+    use crate::hash::poseidon2::Poseidon2;
     use crate::util::serialization::GateSerializer;
+
+    /// Implements [`GateSerializer`] for the given gates followed by all the default gates. The
+    /// given gates come first so that appending a default gate keeps all existing tags.
+    macro_rules! impl_default_gate_serializer {
+        ($target:ty $(, $gate_types:ty)*) => {
+            impl_gate_serializer! {
+                $target,
+                $($gate_types,)*
+                // Tags are positions in this list, so new entries must be appended at the end.
+                ArithmeticGate,
+                ArithmeticExtensionGate<D>,
+                BaseSumGate<2>,
+                ConstantGate,
+                CosetInterpolationGate<F, D>,
+                ExponentiationGate<F, D>,
+                LookupGate,
+                LookupTableGate,
+                MulExtensionGate<D>,
+                NoopGate,
+                PoseidonMdsGate<F, D>,
+                PoseidonGate<F, D>,
+                PublicInputGate,
+                RandomAccessGate<F, D>,
+                ReducingExtensionGate<D>,
+                ReducingGate<D>,
+                BaseSumGate<4>,
+                ComparisonGate<F, D>,
+                U32ArithmeticGate<F, D>,
+                U32SubtractionGate<F, D>,
+                DynamicLookupGate,
+                DynamicLookupTableGate,
+                TupleLookupGate,
+                TupleLookupTableGate
+            }
+        };
+    }
+
     /// A gate serializer that can be used to serialize all default gates supported
     /// by the `plonky2` library.
     /// Being a unit struct, it can be simply called as
@@ -125,24 +169,17 @@ pub mod default {
     #[derive(Debug)]
     pub struct DefaultGateSerializer;
     impl<F: RichField + Extendable<D>, const D: usize> GateSerializer<F, D> for DefaultGateSerializer {
-        impl_gate_serializer! {
-            DefaultGateSerializer,
-            ArithmeticGate,
-            ArithmeticExtensionGate<D>,
-            BaseSumGate<2>,
-            ConstantGate,
-            CosetInterpolationGate<F, D>,
-            ExponentiationGate<F, D>,
-            LookupGate,
-            LookupTableGate,
-            MulExtensionGate<D>,
-            NoopGate,
-            PoseidonMdsGate<F, D>,
-            PoseidonGate<F, D>,
-            PublicInputGate,
-            RandomAccessGate<F, D>,
-            ReducingExtensionGate<D>,
-            ReducingGate<D>
-        }
+        impl_default_gate_serializer!(DefaultGateSerializer);
+    }
+
+    /// A gate serializer supporting the default gates and [`Poseidon2Gate`], for fields
+    /// implementing [`Poseidon2`], e.g. circuits built with
+    /// [`Poseidon2GoldilocksConfig`](crate::plonk::config::Poseidon2GoldilocksConfig).
+    #[derive(Debug)]
+    pub struct Poseidon2GateSerializer;
+    impl<F: RichField + Extendable<D> + Poseidon2, const D: usize> GateSerializer<F, D>
+        for Poseidon2GateSerializer
+    {
+        impl_default_gate_serializer!(Poseidon2GateSerializer, Poseidon2Gate<F, D>);
     }
 }
@@ -13,9 +13,11 @@ use core::ops::Range;
 #[cfg(feature = "std")]
 use std::{collections::BTreeMap, sync::Arc};
 
-pub use gate_serialization::default::DefaultGateSerializer;
+pub use gate_serialization::default::{DefaultGateSerializer, Poseidon2GateSerializer};
 pub use gate_serialization::GateSerializer;
-pub use generator_serialization::default::DefaultGeneratorSerializer;
+pub use generator_serialization::default::{
+    DefaultGeneratorSerializer, Poseidon2GeneratorSerializer,
+};
 pub use generator_serialization::WitnessGeneratorSerializer;
 use hashbrown::HashMap;
 
@@ -33,6 +35,7 @@ use crate::gadgets::polynomial::PolynomialCoeffsExtTarget;
 use crate::gates::gate::GateRef;
 use crate::gates::lookup::Lookup;
 use crate::gates::selectors::SelectorsInfo;
+use crate::gates::tuple_lookup::{TupleLookup, TupleLookupTable};
 use crate::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_proofs::{MerkleProof, MerkleProofTarget};
 use crate::hash::merkle_tree::{MerkleCap, MerkleTree};
@@ -261,7 +264,12 @@ pub trait Read {
     {
         let mut buf = vec![0; H::HASH_SIZE];
         self.read_exact(&mut buf)?;
-        Ok(H::Hash::from_bytes(&buf))
+        let hash = H::Hash::from_bytes(&buf);
+        // Non-canonical encodings would otherwise decode to the same hash as canonical ones.
+        if hash.to_bytes() != buf {
+            return Err(IoError);
+        }
+        Ok(hash)
     }
 
     /// Reads a HashOutTarget value from `self`.
@@ -773,6 +781,12 @@ pub trait Read {
             luts.push(Arc::new(self.read_lut()?));
         }
 
+        let length = self.read_usize()?;
+        let mut tuple_luts = Vec::with_capacity(length);
+        for _ in 0..length {
+            tuple_luts.push(self.read_tuple_lut()?);
+        }
+
         let gates_len = self.read_usize()?;
         let mut gates = Vec::with_capacity(gates_len);
 
@@ -792,6 +806,7 @@ pub trait Read {
             num_lookup_polys,
             num_lookup_selectors,
             luts,
+            tuple_luts,
         };
 
         for _ in 0..gates_len {
@@ -891,6 +906,24 @@ pub trait Read {
             lut_to_lookups.push(self.read_target_lut()?);
         }
 
+        let length = self.read_usize()?;
+        let mut tuple_lut_to_lookups = Vec::with_capacity(length);
+        for _ in 0..length {
+            tuple_lut_to_lookups.push(self.read_target_tuple_lut()?);
+        }
+
+        let length = self.read_usize()?;
+        let mut dynamic_luts = Vec::with_capacity(length);
+        for _ in 0..length {
+            dynamic_luts.push(self.read_target_lut()?);
+        }
+
+        let length = self.read_usize()?;
+        let mut dynamic_lut_to_lookups = Vec::with_capacity(length);
+        for _ in 0..length {
+            dynamic_lut_to_lookups.push(self.read_target_lut()?);
+        }
+
         Ok(ProverOnlyCircuitData {
             generators,
             generator_indices_by_watches,
@@ -903,6 +936,9 @@ pub trait Read {
             circuit_digest,
             lookup_rows,
             lut_to_lookups,
+            tuple_lut_to_lookups,
+            dynamic_luts,
+            dynamic_lut_to_lookups,
         })
     }
 
@@ -1170,6 +1206,32 @@ pub trait Read {
         Ok(lut)
     }
 
+    /// Reads a tuple lookup table from `self`.
+    #[inline]
+    fn read_tuple_lut<F: Field64>(&mut self) -> IoResult<TupleLookupTable<F>> {
+        let num_inputs = self.read_usize()?;
+        let num_outputs = self.read_usize()?;
+        let length = self.read_usize()?;
+        let mut rows = Vec::with_capacity(length);
+        for _ in 0..length {
+            rows.push(self.read_field_vec(num_inputs + num_outputs)?);
+        }
+
+        Ok(TupleLookupTable::new(num_inputs, num_outputs, &rows))
+    }
+
+    /// Reads tuple lookups stored as `TupleLookup` from `self`.
+    #[inline]
+    fn read_target_tuple_lut(&mut self) -> IoResult<TupleLookup> {
+        let length = self.read_usize()?;
+        let mut lut = Vec::with_capacity(length);
+        for _ in 0..length {
+            lut.push((self.read_target_vec()?, self.read_target_vec()?));
+        }
+
+        Ok(lut)
+    }
+
     /// Reads a target lookup table stored as `Lookup` from `self`.
     #[inline]
     fn read_target_lut(&mut self) -> IoResult<Lookup> {
@@ -1781,6 +1843,7 @@ pub trait Write {
             num_lookup_polys,
             num_lookup_selectors,
             luts,
+            tuple_luts,
         } = common_data;
 
         self.write_circuit_config(config)?;
@@ -1803,6 +1866,10 @@ pub trait Write {
         for lut in luts.iter() {
             self.write_lut(lut)?;
         }
+        self.write_usize(tuple_luts.len())?;
+        for tuple_lut in tuple_luts.iter() {
+            self.write_tuple_lut(tuple_lut)?;
+        }
 
         self.write_usize(gates.len())?;
         for gate in gates.iter() {
@@ -1853,6 +1920,9 @@ pub trait Write {
             circuit_digest,
             lookup_rows,
             lut_to_lookups,
+            tuple_lut_to_lookups,
+            dynamic_luts,
+            dynamic_lut_to_lookups,
         } = prover_only_circuit_data;
 
         self.write_usize(generators.len())?;
@@ -1903,6 +1973,21 @@ pub trait Write {
             self.write_target_lut(tlut)?;
         }
 
+        self.write_usize(tuple_lut_to_lookups.len())?;
+        for tlut in tuple_lut_to_lookups.iter() {
+            self.write_target_tuple_lut(tlut)?;
+        }
+
+        self.write_usize(dynamic_luts.len())?;
+        for tlut in dynamic_luts.iter() {
+            self.write_target_lut(tlut)?;
+        }
+
+        self.write_usize(dynamic_lut_to_lookups.len())?;
+        for tlut in dynamic_lut_to_lookups.iter() {
+            self.write_target_lut(tlut)?;
+        }
+
         Ok(())
     }
 
@@ -2122,6 +2207,31 @@ pub trait Write {
         Ok(())
     }
 
+    /// Writes a tuple lookup table to `self`.
+    #[inline]
+    fn write_tuple_lut<F: PrimeField64>(&mut self, lut: &TupleLookupTable<F>) -> IoResult<()> {
+        self.write_usize(lut.num_inputs())?;
+        self.write_usize(lut.num_outputs())?;
+        self.write_usize(lut.len())?;
+        for row in lut.rows() {
+            self.write_field_vec(row)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes tuple lookups to `self`.
+    #[inline]
+    fn write_target_tuple_lut(&mut self, lut: &[(Vec<Target>, Vec<Target>)]) -> IoResult<()> {
+        self.write_usize(lut.len())?;
+        for (inputs, outputs) in lut.iter() {
+            self.write_target_vec(inputs)?;
+            self.write_target_vec(outputs)?;
+        }
+
+        Ok(())
+    }
+
     /// Writes a target lookup table to `self`.
     #[inline]
     fn write_target_lut(&mut self, lut: &[(Target, Target)]) -> IoResult<()> {
@@ -26,6 +26,15 @@ pub struct StarkConfig {
 
     /// The configuration of the FRI sub-protocol.
     pub fri_config: FriConfig,
+
+    /// Whether proofs generated with this configuration should be zero-knowledge.
+    /// When enabled, the trace and auxiliary polynomials are randomized, all
+    /// Merkle tree leaves are salted, the quotient chunks are masked, and the
+    /// polynomial FRI is run on is masked by a random polynomial.
+    ///
+    /// The randomization uses `num_query_rounds + 2 * D` random coefficients per
+    /// polynomial, hence traces must be strictly longer than that.
+    pub zero_knowledge: bool,
 }
 
 impl Default for StarkConfig {
@@ -41,6 +50,7 @@ impl StarkConfig {
             security_bits,
             num_challenges,
             fri_config,
+            zero_knowledge: false,
         }
     }
 
@@ -57,12 +67,39 @@ impl StarkConfig {
                 reduction_strategy: FriReductionStrategy::ConstantArityBits(4, 5),
                 num_query_rounds: 84,
             },
+            zero_knowledge: false,
         }
     }
 
-    /// Outputs the [`FriParams`] used during the FRI sub-protocol by this [`StarkConfig`].
+    /// A zero-knowledge configuration with a rate of 4, to fit the quotient of degree 3
+    /// constraints over randomized polynomials. Targets ~100 bit conjectured security.
+    pub const fn standard_fast_zk_config() -> Self {
+        Self {
+            security_bits: 100,
+            num_challenges: 2,
+            fri_config: FriConfig {
+                rate_bits: 2,
+                cap_height: 4,
+                proof_of_work_bits: 16,
+                reduction_strategy: FriReductionStrategy::ConstantArityBits(4, 5),
+                num_query_rounds: 42,
+            },
+            zero_knowledge: true,
+        }
+    }
+
+    /// Outputs the degree, in bits, of the polynomials committed to by the prover
+    /// for a trace of length `2^degree_bits`. In zero-knowledge mode, the trace and
+    /// auxiliary polynomials are randomized, which doubles their degree.
+    pub const fn committed_degree_bits(&self, degree_bits: usize) -> usize {
+        degree_bits + self.zero_knowledge as usize
+    }
+
+    /// Outputs the [`FriParams`] used during the FRI sub-protocol by this [`StarkConfig`],
+    /// for a trace of length `2^degree_bits`.
     pub fn fri_params(&self, degree_bits: usize) -> FriParams {
-        self.fri_config.fri_params(degree_bits, false)
+        self.fri_config
+            .fri_params(self.committed_degree_bits(degree_bits), self.zero_knowledge)
     }
 
     /// Checks that this STARK configuration is consistent, i.e. that the different
@@ -122,6 +159,12 @@ mod tests {
             },
         );
         assert!(high_rate_config.check_config::<F, D>().is_ok());
+
+        let zk_config = StarkConfig::standard_fast_zk_config();
+        assert!(zk_config.check_config::<F, D>().is_ok());
+        let fri_params = zk_config.fri_params(10);
+        assert!(fri_params.hiding);
+        assert_eq!(fri_params.degree_bits, 11);
     }
 
     #[test]
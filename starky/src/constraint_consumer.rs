@@ -34,6 +34,10 @@ pub struct ConstraintConsumer<P: PackedField> {
     /// The evaluation of the Lagrange basis polynomial which is nonzero at the point associated
     /// with the last trace row, and zero at other points in the subgroup.
     lagrange_basis_last: P,
+
+    /// Individual evaluations of the constraints emitted so far, only recorded when
+    /// debugging a trace. See [`ConstraintConsumer::new_debug`].
+    debug_constraints: Option<Vec<P>>,
 }
 
 impl<P: PackedField> ConstraintConsumer<P> {
@@ -50,6 +54,20 @@ impl<P: PackedField> ConstraintConsumer<P> {
             z_last,
             lagrange_basis_first,
             lagrange_basis_last,
+            debug_constraints: None,
+        }
+    }
+
+    /// Creates a new instance of [`ConstraintConsumer`] which, instead of combining
+    /// constraints with random values, records the evaluation of each emitted constraint.
+    pub fn new_debug(z_last: P, lagrange_basis_first: P, lagrange_basis_last: P) -> Self {
+        Self {
+            constraint_accs: vec![],
+            alphas: vec![],
+            z_last,
+            lagrange_basis_first,
+            lagrange_basis_last,
+            debug_constraints: Some(vec![]),
         }
     }
 
@@ -59,6 +77,14 @@ impl<P: PackedField> ConstraintConsumer<P> {
         self.constraint_accs
     }
 
+    /// Consumes this [`ConstraintConsumer`] and outputs the evaluations of all emitted
+    /// constraints, in order of emission, filters included.
+    ///
+    /// This is empty unless the consumer has been created with [`ConstraintConsumer::new_debug`].
+    pub fn debug_constraints(self) -> Vec<P> {
+        self.debug_constraints.unwrap_or_default()
+    }
+
     /// Add one constraint valid on all rows except the last.
     pub fn constraint_transition(&mut self, constraint: P) {
         self.constraint(constraint * self.z_last);
@@ -70,6 +96,9 @@ impl<P: PackedField> ConstraintConsumer<P> {
             *acc *= alpha;
             *acc += constraint;
         }
+        if let Some(debug_constraints) = &mut self.debug_constraints {
+            debug_constraints.push(constraint);
+        }
     }
 
     /// Add one constraint, but first multiply it by a filter such that it will only apply to the
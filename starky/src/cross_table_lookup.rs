@@ -33,7 +33,7 @@ use core::cmp::min;
 use core::fmt::Debug;
 use core::iter::once;
 
-use anyhow::{ensure, Result};
+use anyhow::{anyhow, ensure, Result};
 use itertools::Itertools;
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::packed::PackedField;
@@ -958,10 +958,6 @@ pub fn verify_cross_table_lookups<F: RichField + Extendable<D>, const D: usize,
         },
     ) in cross_table_lookups.iter().enumerate()
     {
-        // Get elements looking into `looked_table` that are not associated to any STARK.
-        let extra_sum_vec: &[F] = ctl_extra_looking_sums
-            .map(|v| v[looked_table.table].as_ref())
-            .unwrap_or_default();
         // We want to iterate on each looking table only once.
         let mut filtered_looking_tables = vec![];
         for table in looking_tables {
@@ -972,14 +968,31 @@ pub fn verify_cross_table_lookups<F: RichField + Extendable<D>, const D: usize,
         for c in 0..config.num_challenges {
             // Compute the combination of all looking table CTL polynomial openings.
 
+            // Get the sum of the elements looking into `looked_table` that are not associated to any
+            // STARK. When extra sums are provided, one is expected for each table and challenge.
+            let extra_sum = match ctl_extra_looking_sums {
+                Some(sums) => *sums
+                    .get(looked_table.table)
+                    .and_then(|v| v.get(c))
+                    .ok_or_else(|| {
+                        anyhow!("Missing extra looking sum for cross-table lookup {index}.")
+                    })?,
+                None => F::ZERO,
+            };
+            let mut next_opening = |table: TableIdx| {
+                ctl_zs_openings[table]
+                    .next()
+                    .copied()
+                    .ok_or_else(|| anyhow!("Missing CTL opening of table {table}."))
+            };
             let looking_zs_sum = filtered_looking_tables
                 .iter()
-                .map(|&table| *ctl_zs_openings[table].next().unwrap())
-                .sum::<F>()
-                + extra_sum_vec[c];
+                .map(|&table| next_opening(table))
+                .sum::<Result<F>>()?
+                + extra_sum;
 
             // Get the looked table CTL polynomial opening.
-            let looked_z = *ctl_zs_openings[looked_table.table].next().unwrap();
+            let looked_z = next_opening(looked_table.table)?;
             // Ensure that the combination of looking table openings is equal to the looked table opening.
             ensure!(
                 looking_zs_sum == looked_z,
@@ -1012,9 +1025,8 @@ pub fn verify_cross_table_lookups_circuit<
     } in cross_table_lookups.into_iter()
     {
         // Get elements looking into `looked_table` that are not associated to any STARK.
-        let extra_sum_vec: &[Target] = ctl_extra_looking_sums
-            .map(|v| v[looked_table.table].as_ref())
-            .unwrap_or_default();
+        let extra_sum_vec: Option<&[Target]> =
+            ctl_extra_looking_sums.map(|v| v[looked_table.table].as_ref());
         // We want to iterate on each looking table only once.
         let mut filtered_looking_tables = vec![];
         for table in looking_tables {
@@ -1030,7 +1042,9 @@ pub fn verify_cross_table_lookups_circuit<
                     .map(|&table| *ctl_zs_openings[table].next().unwrap()),
             );
 
-            looking_zs_sum = builder.add(looking_zs_sum, extra_sum_vec[c]);
+            if let Some(extra_sum_vec) = extra_sum_vec {
+                looking_zs_sum = builder.add(looking_zs_sum, extra_sum_vec[c]);
+            }
 
             // Get the looked table CTL polynomial opening.
             let looked_z = *ctl_zs_openings[looked_table.table].next().unwrap();
@@ -1044,13 +1058,13 @@ pub fn verify_cross_table_lookups_circuit<
 /// Debugging module, to assert correctness of the different CTLs of a multi-STARK system,
 /// that can be used during the proof generation process.
 ///
-/// **Note**: this is an expensive check, hence is only available when the `debug_assertions`
-/// flag is activated, to not hinder performances with regular `release` build.
-#[cfg(debug_assertions)]
+/// **Note**: these are expensive checks, hence the prover only runs them when the
+/// `debug_assertions` flag is activated, to not hinder performances with regular `release` build.
 pub mod debug_utils {
     #[cfg(not(feature = "std"))]
     use alloc::{vec, vec::Vec};
 
+    use anyhow::{ensure, Result};
     use hashbrown::HashMap;
     use plonky2::field::polynomial::PolynomialValues;
     use plonky2::field::types::Field;
@@ -1064,10 +1078,11 @@ pub mod debug_utils {
         trace_poly_values: &[Vec<PolynomialValues<F>>],
         cross_table_lookups: &[CrossTableLookup<F>],
         extra_looking_values: &HashMap<TableIdx, Vec<Vec<F>>>,
-    ) {
+    ) -> Result<()> {
         for (i, ctl) in cross_table_lookups.iter().enumerate() {
-            check_ctl(trace_poly_values, ctl, i, extra_looking_values.get(&i));
+            check_ctl(trace_poly_values, ctl, i, extra_looking_values.get(&i))?;
         }
+        Ok(())
     }
 
     fn check_ctl<F: Field>(
@@ -1075,7 +1090,7 @@ pub mod debug_utils {
         ctl: &CrossTableLookup<F>,
         ctl_index: usize,
         extra_looking_values: Option<&Vec<Vec<F>>>,
-    ) {
+    ) -> Result<()> {
         let CrossTableLookup {
             looking_tables,
             looked_table,
@@ -1087,9 +1102,9 @@ pub mod debug_utils {
         let mut looked_multiset = MultiSet::<F>::new();
 
         for table in looking_tables {
-            process_table(trace_poly_values, table, &mut looking_multiset);
+            process_table(trace_poly_values, table, &mut looking_multiset)?;
         }
-        process_table(trace_poly_values, looked_table, &mut looked_multiset);
+        process_table(trace_poly_values, looked_table, &mut looked_multiset)?;
 
         // Include extra looking values if any for this `ctl_index`.
         if let Some(values) = extra_looking_values {
@@ -1107,20 +1122,27 @@ pub mod debug_utils {
         // Check that every row in the looking tables appears in the looked table the same number of times.
         for (row, looking_locations) in &looking_multiset {
             let looked_locations = looked_multiset.get(row).unwrap_or(empty);
-            check_locations(looking_locations, looked_locations, ctl_index, row);
+            check_locations(looking_locations, looked_locations, ctl_index, row)?;
         }
         // Check that every row in the looked tables appears in the looked table the same number of times.
         for (row, looked_locations) in &looked_multiset {
             let looking_locations = looking_multiset.get(row).unwrap_or(empty);
-            check_locations(looking_locations, looked_locations, ctl_index, row);
+            check_locations(looking_locations, looked_locations, ctl_index, row)?;
         }
+
+        Ok(())
     }
 
     fn process_table<F: Field>(
         trace_poly_values: &[Vec<PolynomialValues<F>>],
         table: &TableWithColumns<F>,
         multiset: &mut MultiSet<F>,
-    ) {
+    ) -> Result<()> {
+        ensure!(
+            table.table < trace_poly_values.len(),
+            "Table #{} does not have a trace.",
+            table.table
+        );
         let trace = &trace_poly_values[table.table];
         for i in 0..trace[0].len() {
             let filter = if let Some(combin) = &table.filter {
@@ -1136,9 +1158,15 @@ pub mod debug_utils {
                     .collect::<Vec<_>>();
                 multiset.entry(row).or_default().push((table.table, i));
             } else {
-                assert_eq!(filter, F::ZERO, "Non-binary filter?")
+                ensure!(
+                    filter.is_zero(),
+                    "Non-binary filter {filter:?} in table #{} at row {i}.",
+                    table.table
+                );
             }
         }
+
+        Ok(())
     }
 
     fn check_locations<F: Field>(
@@ -1146,16 +1174,16 @@ pub mod debug_utils {
         looked_locations: &[(TableIdx, usize)],
         ctl_index: usize,
         row: &[F],
-    ) {
-        if looking_locations.len() != looked_locations.len() {
-            panic!(
-                "CTL #{ctl_index}:\n\
-                 Row {row:?} is present {l0} times in the looking tables, but {l1} times in the looked table.\n\
-                 Looking locations (Table, Row index): {looking_locations:?}.\n\
-                 Looked locations (Table, Row index): {looked_locations:?}.",
-                l0 = looking_locations.len(),
-                l1 = looked_locations.len(),
-            );
-        }
+    ) -> Result<()> {
+        ensure!(
+            looking_locations.len() == looked_locations.len(),
+            "CTL #{ctl_index}:\n\
+             Row {row:?} is present {l0} times in the looking tables, but {l1} times in the looked table.\n\
+             Looking locations (Table, Row index): {looking_locations:?}.\n\
+             Looked locations (Table, Row index): {looked_locations:?}.",
+            l0 = looking_locations.len(),
+            l1 = looked_locations.len(),
+        );
+        Ok(())
     }
 }
@@ -0,0 +1,563 @@
+//! An example of generating and verifying a multi-STARK proof, with two tables
+//! connected through a cross-table lookup.
+//!
+//! The first table computes a Fibonacci sequence, but delegates the additions
+//! `x0 + x1` to the second table, which only checks that each of its rows is a
+//! valid addition. The cross-table lookup ensures that every addition performed
+//! in the Fibonacci table has been carried out by the addition table.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::cross_table_lookup::{CrossTableLookup, TableWithColumns};
+use crate::evaluation_frame::{StarkEvaluationFrame, StarkFrame};
+use crate::lookup::Column;
+use crate::stark::Stark;
+use crate::util::trace_rows_to_poly_values;
+
+/// Index of the Fibonacci table in the multi-STARK system.
+const FIBONACCI_TABLE: usize = 0;
+/// Index of the addition table in the multi-STARK system.
+const ADDITION_TABLE: usize = 1;
+
+/// Toy STARK table computing a Fibonacci sequence with state `[x0, x1, s]`
+/// using the state transition `x0' <- x1, x1' <- s`, where `s = x0 + x1`
+/// is not constrained here but looked up in the [`AdditionStark`] table.
+#[derive(Copy, Clone)]
+struct FibonacciCtlStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> FibonacciCtlStark<F, D> {
+    // The first public input is `x0`.
+    const PI_INDEX_X0: usize = 0;
+    // The second public input is `x1`.
+    const PI_INDEX_X1: usize = 1;
+    // The third public input is the second element of the last row, which should be equal to the
+    // `num_rows`-th Fibonacci number.
+    const PI_INDEX_RES: usize = 2;
+
+    const fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Generate the trace using `x0, x1` as initial state values.
+    fn generate_trace(&self, x0: F, x1: F) -> Vec<PolynomialValues<F>> {
+        let trace_rows = (0..self.num_rows)
+            .scan([x0, x1, x0 + x1], |acc, _| {
+                let tmp = *acc;
+                acc[0] = tmp[1];
+                acc[1] = tmp[2];
+                acc[2] = tmp[1] + tmp[2];
+                Some(tmp)
+            })
+            .collect::<Vec<_>>();
+        trace_rows_to_poly_values(trace_rows)
+    }
+}
+
+const FIBONACCI_CTL_COLUMNS: usize = 3;
+const FIBONACCI_CTL_PUBLIC_INPUTS: usize = 3;
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FibonacciCtlStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize>
+        = StarkFrame<P, P::Scalar, FIBONACCI_CTL_COLUMNS, FIBONACCI_CTL_PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    type EvaluationFrameTarget = StarkFrame<
+        ExtensionTarget<D>,
+        ExtensionTarget<D>,
+        FIBONACCI_CTL_COLUMNS,
+        FIBONACCI_CTL_PUBLIC_INPUTS,
+    >;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        // Check public inputs.
+        yield_constr.constraint_first_row(local_values[0] - public_inputs[Self::PI_INDEX_X0]);
+        yield_constr.constraint_first_row(local_values[1] - public_inputs[Self::PI_INDEX_X1]);
+        yield_constr.constraint_last_row(local_values[1] - public_inputs[Self::PI_INDEX_RES]);
+
+        // x0' <- x1
+        yield_constr.constraint_transition(next_values[0] - local_values[1]);
+        // x1' <- s
+        yield_constr.constraint_transition(next_values[1] - local_values[2]);
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+        // Check public inputs.
+        let pis_constraints = [
+            builder.sub_extension(local_values[0], public_inputs[Self::PI_INDEX_X0]),
+            builder.sub_extension(local_values[1], public_inputs[Self::PI_INDEX_X1]),
+            builder.sub_extension(local_values[1], public_inputs[Self::PI_INDEX_RES]),
+        ];
+        yield_constr.constraint_first_row(builder, pis_constraints[0]);
+        yield_constr.constraint_first_row(builder, pis_constraints[1]);
+        yield_constr.constraint_last_row(builder, pis_constraints[2]);
+
+        // x0' <- x1
+        let first_col_constraint = builder.sub_extension(next_values[0], local_values[1]);
+        yield_constr.constraint_transition(builder, first_col_constraint);
+        // x1' <- s
+        let second_col_constraint = builder.sub_extension(next_values[1], local_values[2]);
+        yield_constr.constraint_transition(builder, second_col_constraint);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        // The transition constraints on the CTL `Z` polynomial are of degree 3.
+        3
+    }
+
+    fn requires_ctls(&self) -> bool {
+        true
+    }
+}
+
+/// Toy STARK table checking additions, with state `[a, b, c]` and the constraint
+/// `c = a + b` on every row.
+#[derive(Copy, Clone)]
+struct AdditionStark<F: RichField + Extendable<D>, const D: usize> {
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> AdditionStark<F, D> {
+    const fn new() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Generate the trace from the rows of the Fibonacci table. The rows are reversed,
+    /// as the order of the additions does not matter for the cross-table lookup.
+    fn generate_trace(&self, fibonacci_trace: &[PolynomialValues<F>]) -> Vec<PolynomialValues<F>> {
+        let num_rows = fibonacci_trace[0].len();
+        let trace_rows = (0..num_rows)
+            .rev()
+            .map(|i| {
+                let a = fibonacci_trace[0].values[i];
+                let b = fibonacci_trace[1].values[i];
+                [a, b, a + b]
+            })
+            .collect::<Vec<_>>();
+        trace_rows_to_poly_values(trace_rows)
+    }
+}
+
+const ADDITION_COLUMNS: usize = 3;
+const ADDITION_PUBLIC_INPUTS: usize = 0;
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for AdditionStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize>
+        = StarkFrame<P, P::Scalar, ADDITION_COLUMNS, ADDITION_PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    type EvaluationFrameTarget = StarkFrame<
+        ExtensionTarget<D>,
+        ExtensionTarget<D>,
+        ADDITION_COLUMNS,
+        ADDITION_PUBLIC_INPUTS,
+    >;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+
+        // c <- a + b
+        yield_constr.constraint(local_values[2] - local_values[0] - local_values[1]);
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+
+        // c <- a + b
+        let constraint = {
+            let tmp = builder.sub_extension(local_values[2], local_values[0]);
+            builder.sub_extension(tmp, local_values[1])
+        };
+        yield_constr.constraint(builder, constraint);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        // The transition constraints on the CTL `Z` polynomial are of degree 3.
+        3
+    }
+
+    fn requires_ctls(&self) -> bool {
+        true
+    }
+}
+
+/// The cross-table lookup connecting the `[x0, x1, s]` rows of the Fibonacci table
+/// with the `[a, b, c]` rows of the addition table.
+fn ctls<F: Field>() -> Vec<CrossTableLookup<F>> {
+    vec![CrossTableLookup::new(
+        vec![TableWithColumns::new(
+            FIBONACCI_TABLE,
+            Column::singles(0..FIBONACCI_CTL_COLUMNS).collect(),
+            None,
+        )],
+        TableWithColumns::new(
+            ADDITION_TABLE,
+            Column::singles(0..ADDITION_COLUMNS).collect(),
+            None,
+        ),
+    )]
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::field::polynomial::PolynomialValues;
+    use plonky2::field::types::Field;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+
+    use crate::config::StarkConfig;
+    use crate::cross_table_lookup::{verify_cross_table_lookups, CrossTableLookup};
+    use crate::fibonacci_ctl_stark::{ctls, AdditionStark, FibonacciCtlStark};
+    use crate::proof::MultiProof;
+    use crate::prover::prove_multi;
+    use crate::stark::{Stark, StarkTable};
+    use crate::stark_testing::{
+        check_multi_constraints, test_stark_circuit_constraints, test_stark_low_degree,
+    };
+    use crate::verifier::verify_multi;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S1 = FibonacciCtlStark<F, D>;
+    type S2 = AdditionStark<F, D>;
+
+    fn fibonacci<F: Field>(n: usize, x0: F, x1: F) -> F {
+        (0..n).fold((x0, x1), |x, _| (x.1, x.0 + x.1)).1
+    }
+
+    /// The Fibonacci and addition tables, with their traces, public inputs and the
+    /// cross-table lookups connecting them.
+    struct FibonacciCtlSystem {
+        fibonacci_stark: S1,
+        addition_stark: S2,
+        traces: [Vec<PolynomialValues<F>>; 2],
+        public_inputs: [F; 3],
+        cross_table_lookups: Vec<CrossTableLookup<F>>,
+    }
+
+    impl FibonacciCtlSystem {
+        fn new(num_rows: usize) -> Self {
+            let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+            let fibonacci_stark = S1::new(num_rows);
+            let addition_stark = S2::new();
+            let fibonacci_trace =
+                fibonacci_stark.generate_trace(public_inputs[0], public_inputs[1]);
+            let addition_trace = addition_stark.generate_trace(&fibonacci_trace);
+
+            Self {
+                fibonacci_stark,
+                addition_stark,
+                traces: [fibonacci_trace, addition_trace],
+                public_inputs,
+                cross_table_lookups: ctls(),
+            }
+        }
+
+        fn starks(&self) -> [&dyn StarkTable<F, C, D>; 2] {
+            [&self.fibonacci_stark, &self.addition_stark]
+        }
+
+        fn prove_traces(
+            &self,
+            traces: [Vec<PolynomialValues<F>>; 2],
+            config: &StarkConfig,
+        ) -> Result<MultiProof<F, C, D, 2>> {
+            prove_multi::<F, C, D, 2>(
+                self.starks(),
+                &self.cross_table_lookups,
+                config,
+                traces,
+                [&self.public_inputs, &[]],
+                &mut TimingTree::default(),
+            )
+        }
+
+        fn prove(&self, config: &StarkConfig) -> Result<MultiProof<F, C, D, 2>> {
+            self.prove_traces(self.traces.clone(), config)
+        }
+
+        fn verify_with_public_inputs(
+            &self,
+            proof: &MultiProof<F, C, D, 2>,
+            public_inputs: &[F],
+            config: &StarkConfig,
+        ) -> Result<()> {
+            verify_multi(
+                self.starks(),
+                &self.cross_table_lookups,
+                proof,
+                [public_inputs, &[]],
+                config,
+            )
+        }
+
+        fn verify(&self, proof: &MultiProof<F, C, D, 2>, config: &StarkConfig) -> Result<()> {
+            self.verify_with_public_inputs(proof, &self.public_inputs, config)
+        }
+    }
+
+    #[test]
+    fn test_fibonacci_ctl_stark() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let system = FibonacciCtlSystem::new(1 << 5);
+        let proof = system.prove(&config)?;
+        system.verify(&proof, &config)?;
+
+        // The proof should not verify against other public inputs.
+        let wrong_public_inputs = [F::ONE, F::ONE, system.public_inputs[2]];
+        assert!(system
+            .verify_with_public_inputs(&proof, &wrong_public_inputs, &config)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_ctl_stark_zk() -> Result<()> {
+        let config = StarkConfig::standard_fast_zk_config();
+        let system = FibonacciCtlSystem::new(1 << 7);
+
+        // Both tables have constraints of degree 3.
+        assert_eq!(system.fibonacci_stark.num_quotient_chunks(&config), 5);
+        assert_eq!(system.addition_stark.num_quotient_chunks(&config), 5);
+
+        let proof = system.prove(&config)?;
+        system.verify(&proof, &config)?;
+
+        let bytes = proof.to_bytes();
+        let read_proof = MultiProof::<F, C, D, 2>::from_bytes(
+            bytes,
+            system.starks(),
+            &system.cross_table_lookups,
+            &config,
+        )?;
+        system.verify(&read_proof, &config)?;
+
+        // The proof should not verify against other public inputs.
+        let wrong_public_inputs = [F::ONE, F::ONE, system.public_inputs[2]];
+        assert!(system
+            .verify_with_public_inputs(&proof, &wrong_public_inputs, &config)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_ctl_stark_malformed_proof() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let system = FibonacciCtlSystem::new(1 << 5);
+        let proof = system.prove(&config)?;
+
+        // Truncated openings of one table should be rejected before extracting its CTL data.
+        let mut truncated_proof = proof.clone();
+        let openings = &mut truncated_proof.stark_proofs[1].proof.openings;
+        openings.auxiliary_polys.as_mut().unwrap().pop();
+        openings.auxiliary_polys_next.as_mut().unwrap().pop();
+        assert!(system.verify(&truncated_proof, &config).is_err());
+
+        let mut truncated_proof = proof.clone();
+        truncated_proof.stark_proofs[0]
+            .proof
+            .openings
+            .ctl_zs_first
+            .as_mut()
+            .unwrap()
+            .pop();
+        assert!(system.verify(&truncated_proof, &config).is_err());
+
+        // A table requiring CTLs should not be given default CTL openings.
+        let mut missing_ctls_proof = proof;
+        missing_ctls_proof.stark_proofs[0]
+            .proof
+            .openings
+            .ctl_zs_first = None;
+        assert!(system.verify(&missing_ctls_proof, &config).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_ctl_stark_extra_looking_sums() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let system = FibonacciCtlSystem::new(1 << 5);
+        let proof = system.prove(&config)?;
+        let ctl_zs_first = || {
+            proof
+                .stark_proofs
+                .each_ref()
+                .map(|p| p.proof.openings.ctl_zs_first.clone().unwrap())
+        };
+
+        let zero_sums = vec![vec![F::ZERO; config.num_challenges]; 2];
+        verify_cross_table_lookups::<F, D, 2>(
+            &system.cross_table_lookups,
+            ctl_zs_first(),
+            Some(&zero_sums),
+            &config,
+        )?;
+
+        // Provided extra sums must cover all challenges.
+        let missing_sums = vec![vec![]; 2];
+        assert!(verify_cross_table_lookups::<F, D, 2>(
+            &system.cross_table_lookups,
+            ctl_zs_first(),
+            Some(&missing_sums),
+            &config,
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_ctl_stark_malformed_traces() {
+        let config = StarkConfig::standard_fast_config();
+        let system = FibonacciCtlSystem::new(1 << 5);
+        let [fibonacci_trace, addition_trace] = system.traces.clone();
+
+        // A table without columns.
+        assert!(system
+            .prove_traces([fibonacci_trace.clone(), vec![]], &config)
+            .is_err());
+
+        // A column shorter than the others.
+        let mut short_column_trace = addition_trace.clone();
+        short_column_trace[1].values.pop();
+        assert!(system
+            .prove_traces([fibonacci_trace.clone(), short_column_trace], &config)
+            .is_err());
+
+        // A trace whose length is not a power of two.
+        let mut odd_length_trace = addition_trace;
+        for column in &mut odd_length_trace {
+            column.values.pop();
+        }
+        assert!(system
+            .prove_traces([fibonacci_trace, odd_length_trace], &config)
+            .is_err());
+    }
+
+    #[test]
+    fn test_fibonacci_ctl_stark_serialization() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let system = FibonacciCtlSystem::new(1 << 5);
+        let proof = system.prove(&config)?;
+
+        let read = |bytes: Vec<u8>, config: &StarkConfig| {
+            MultiProof::<F, C, D, 2>::from_bytes(
+                bytes,
+                system.starks(),
+                &system.cross_table_lookups,
+                config,
+            )
+        };
+        let bytes = proof.to_bytes();
+        let read_proof = read(bytes.clone(), &config)?;
+        assert_eq!(read_proof.to_bytes(), bytes);
+        system.verify(&read_proof, &config)?;
+
+        // Truncated bytes, or bytes read with another configuration, should be rejected.
+        assert!(read(bytes[..bytes.len() - 1].to_vec(), &config).is_err());
+        assert!(read(bytes, &StarkConfig::standard_fast_zk_config()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_ctl_stark_check_constraints() -> Result<()> {
+        let system = FibonacciCtlSystem::new(1 << 5);
+        let check = |traces: &[Vec<PolynomialValues<F>>; 2]| {
+            check_multi_constraints(
+                system.starks(),
+                &system.cross_table_lookups,
+                traces,
+                [&system.public_inputs, &[]],
+            )
+        };
+        check(&system.traces)?;
+
+        // A wrong sum is caught by the constraints of the addition table.
+        let mut wrong_traces = system.traces.clone();
+        wrong_traces[1][2].values[0] += F::ONE;
+        let err = check(&wrong_traces).unwrap_err();
+        assert!(err.to_string().starts_with("Table #1: "));
+
+        // A valid addition which is not part of the Fibonacci sequence is caught by the CTL.
+        let mut wrong_traces = system.traces.clone();
+        wrong_traces[1][0].values[0] += F::ONE;
+        wrong_traces[1][2].values[0] += F::ONE;
+        let err = check(&wrong_traces).unwrap_err();
+        assert!(err.to_string().starts_with("CTL #0:"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_ctl_stark_degree() -> Result<()> {
+        let num_rows = 1 << 5;
+        test_stark_low_degree(S1::new(num_rows))?;
+        test_stark_low_degree(S2::new())
+    }
+
+    #[test]
+    fn test_fibonacci_ctl_stark_circuit() -> Result<()> {
+        let num_rows = 1 << 5;
+        test_stark_circuit_constraints::<F, C, S1, D>(S1::new(num_rows))?;
+        test_stark_circuit_constraints::<F, C, S2, D>(S2::new())
+    }
+}
@@ -62,7 +62,8 @@ const FIBONACCI_COLUMNS: usize = 2;
 const FIBONACCI_PUBLIC_INPUTS: usize = 3;
 
 impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FibonacciStark<F, D> {
-    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, FIBONACCI_COLUMNS, FIBONACCI_PUBLIC_INPUTS>
+    type EvaluationFrame<FE, P, const D2: usize>
+        = StarkFrame<P, P::Scalar, FIBONACCI_COLUMNS, FIBONACCI_PUBLIC_INPUTS>
     where
         FE: FieldExtension<D2, BaseField = F>,
         P: PackedField<Scalar = FE>;
@@ -182,7 +183,8 @@ const FIBONACCI_PERM_PUBLIC_INPUTS: usize = 3;
 impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D>
     for FibonacciWithPermutationStark<F, D>
 {
-    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, FIBONACCI_PERM_COLUMNS, FIBONACCI_PERM_PUBLIC_INPUTS>
+    type EvaluationFrame<FE, P, const D2: usize>
+        = StarkFrame<P, P::Scalar, FIBONACCI_PERM_COLUMNS, FIBONACCI_PERM_PUBLIC_INPUTS>
     where
         FE: FieldExtension<D2, BaseField = F>,
         P: PackedField<Scalar = FE>;
@@ -261,11 +263,125 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D>
     }
 }
 
+/// Similar system than [`FibonacciStark`], but with a non-linear state transition, to test
+/// constraints of degree 3.
+/// Computes a sequence with state `[x0, x1]` using the state transition
+/// `x0' <- x1, x1' <- x0 + x1^2`.
+#[derive(Copy, Clone)]
+struct SquareFibonacciStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SquareFibonacciStark<F, D> {
+    // The first public input is `x0`.
+    const PI_INDEX_X0: usize = 0;
+    // The second public input is `x1`.
+    const PI_INDEX_X1: usize = 1;
+    // The third public input is the second element of the last row.
+    const PI_INDEX_RES: usize = 2;
+
+    const fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Generate the trace using `x0, x1` as initial state values.
+    fn generate_trace(&self, x0: F, x1: F) -> Vec<PolynomialValues<F>> {
+        let trace_rows = (0..self.num_rows)
+            .scan([x0, x1], |acc, _| {
+                let tmp = *acc;
+                acc[0] = tmp[1];
+                acc[1] = tmp[0] + tmp[1].square();
+                Some(tmp)
+            })
+            .collect::<Vec<_>>();
+        trace_rows_to_poly_values(trace_rows)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for SquareFibonacciStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize>
+        = StarkFrame<P, P::Scalar, FIBONACCI_COLUMNS, FIBONACCI_PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    type EvaluationFrameTarget = StarkFrame<
+        ExtensionTarget<D>,
+        ExtensionTarget<D>,
+        FIBONACCI_COLUMNS,
+        FIBONACCI_PUBLIC_INPUTS,
+    >;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        // Check public inputs.
+        yield_constr.constraint_first_row(local_values[0] - public_inputs[Self::PI_INDEX_X0]);
+        yield_constr.constraint_first_row(local_values[1] - public_inputs[Self::PI_INDEX_X1]);
+        yield_constr.constraint_last_row(local_values[1] - public_inputs[Self::PI_INDEX_RES]);
+
+        // x0' <- x1
+        yield_constr.constraint_transition(next_values[0] - local_values[1]);
+        // x1' <- x0 + x1^2
+        yield_constr
+            .constraint_transition(next_values[1] - local_values[0] - local_values[1].square());
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+        // Check public inputs.
+        let pis_constraints = [
+            builder.sub_extension(local_values[0], public_inputs[Self::PI_INDEX_X0]),
+            builder.sub_extension(local_values[1], public_inputs[Self::PI_INDEX_X1]),
+            builder.sub_extension(local_values[1], public_inputs[Self::PI_INDEX_RES]),
+        ];
+        yield_constr.constraint_first_row(builder, pis_constraints[0]);
+        yield_constr.constraint_first_row(builder, pis_constraints[1]);
+        yield_constr.constraint_last_row(builder, pis_constraints[2]);
+
+        // x0' <- x1
+        let first_col_constraint = builder.sub_extension(next_values[0], local_values[1]);
+        yield_constr.constraint_transition(builder, first_col_constraint);
+        // x1' <- x0 + x1^2
+        let second_col_constraint = {
+            let tmp = builder.sub_extension(next_values[1], local_values[0]);
+            let square = builder.square_extension(local_values[1]);
+            builder.sub_extension(tmp, square)
+        };
+        yield_constr.constraint_transition(builder, second_col_constraint);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
     use plonky2::field::extension::Extendable;
     use plonky2::field::types::Field;
+    use plonky2::fri::proof::CompressedFriQueryRounds;
     use plonky2::hash::hash_types::RichField;
     use plonky2::iop::witness::PartialWitness;
     use plonky2::plonk::circuit_builder::CircuitBuilder;
@@ -274,7 +390,9 @@ mod tests {
     use plonky2::util::timing::TimingTree;
 
     use crate::config::StarkConfig;
-    use crate::fibonacci_stark::{FibonacciStark, FibonacciWithPermutationStark};
+    use crate::fibonacci_stark::{
+        FibonacciStark, FibonacciWithPermutationStark, SquareFibonacciStark,
+    };
     use crate::proof::StarkProofWithPublicInputs;
     use crate::prover::prove;
     use crate::recursive_verifier::{
@@ -282,8 +400,10 @@ mod tests {
         verify_stark_proof_circuit,
     };
     use crate::stark::Stark;
-    use crate::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
-    use crate::verifier::verify_stark_proof;
+    use crate::stark_testing::{
+        check_constraints, test_stark_circuit_constraints, test_stark_low_degree,
+    };
+    use crate::verifier::{verify_compressed_stark_proof, verify_stark_proof};
 
     fn fibonacci<F: Field>(n: usize, x0: F, x1: F) -> F {
         (0..n).fold((x0, x1), |x, _| (x.1, x.0 + x.1)).1
@@ -328,6 +448,211 @@ mod tests {
         verify_stark_proof(stark, proof, &config)
     }
 
+    #[test]
+    fn test_fibonacci_stark_compressed() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciWithPermutationStark<F, D>;
+
+        let num_rows = 1 << 7;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+        let stark = S::new(num_rows);
+
+        for config in [
+            StarkConfig::standard_fast_config(),
+            StarkConfig::standard_fast_zk_config(),
+        ] {
+            let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+            let proof = prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace,
+                &public_inputs,
+                &mut TimingTree::default(),
+            )?;
+
+            let compressed_proof = proof.clone().compress(&config);
+            verify_compressed_stark_proof(stark, compressed_proof.clone(), &config)?;
+
+            let decompressed_proof = compressed_proof.decompress(&stark, &config)?;
+            assert_eq!(
+                decompressed_proof.proof.opening_proof,
+                proof.proof.opening_proof
+            );
+            verify_stark_proof(stark, decompressed_proof, &config)?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_stark_malformed_compressed() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type H = <C as GenericConfig<D>>::Hasher;
+        type S = FibonacciWithPermutationStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        // Enough rows for the FRI proof to have reduction steps.
+        let num_rows = 1 << 10;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        let compressed_proof = proof.compress(&config);
+
+        let malformations: [fn(&mut CompressedFriQueryRounds<F, H, D>); 5] = [
+            |rounds| {
+                let index = *rounds.initial_trees_proofs.keys().next().unwrap();
+                rounds.initial_trees_proofs.remove(&index);
+            },
+            |rounds| {
+                for proof in rounds.initial_trees_proofs.values_mut() {
+                    proof.evals_proofs[0].0.pop();
+                }
+            },
+            |rounds| {
+                for proof in rounds.initial_trees_proofs.values_mut() {
+                    proof.evals_proofs[0].1.siblings.clear();
+                }
+            },
+            |rounds| {
+                rounds.steps.pop();
+            },
+            |rounds| {
+                for step in rounds.steps[0].values_mut() {
+                    step.evals.pop();
+                }
+            },
+        ];
+        for malform in malformations {
+            let mut malformed_proof = compressed_proof.clone();
+            malform(&mut malformed_proof.proof.opening_proof.query_round_proofs);
+            assert!(malformed_proof.clone().decompress(&stark, &config).is_err());
+            assert!(verify_compressed_stark_proof(stark, malformed_proof, &config).is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_stark_serialization() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciWithPermutationStark<F, D>;
+
+        let num_rows = 1 << 7;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+        let stark = S::new(num_rows);
+
+        for config in [
+            StarkConfig::standard_fast_config(),
+            StarkConfig::standard_fast_zk_config(),
+        ] {
+            let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+            let proof = prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace,
+                &public_inputs,
+                &mut TimingTree::default(),
+            )?;
+
+            let bytes = proof.to_bytes();
+            let read_proof =
+                StarkProofWithPublicInputs::<F, C, D>::from_bytes(bytes.clone(), &stark, &config)?;
+            assert_eq!(read_proof.to_bytes(), bytes);
+            verify_stark_proof(stark, read_proof, &config)?;
+
+            // Truncated or padded bytes should be rejected.
+            assert!(StarkProofWithPublicInputs::<F, C, D>::from_bytes(
+                bytes[..bytes.len() - 1].to_vec(),
+                &stark,
+                &config
+            )
+            .is_err());
+            let mut padded_bytes = bytes.clone();
+            padded_bytes.push(0);
+            assert!(StarkProofWithPublicInputs::<F, C, D>::from_bytes(
+                padded_bytes,
+                &stark,
+                &config
+            )
+            .is_err());
+
+            // A well-formed proof for another configuration should be rejected.
+            let other_config = StarkConfig {
+                zero_knowledge: !config.zero_knowledge,
+                ..config.clone()
+            };
+            assert!(StarkProofWithPublicInputs::<F, C, D>::from_bytes(
+                bytes,
+                &stark,
+                &other_config
+            )
+            .is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_stark_check_constraints() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S1 = FibonacciStark<F, D>;
+        type S2 = FibonacciWithPermutationStark<F, D>;
+
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+
+        let stark = S1::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        check_constraints(&stark, &trace, &public_inputs)?;
+
+        // Wrong public inputs only break the constraints of the first row.
+        let wrong_public_inputs = [F::ONE, F::ONE, public_inputs[2]];
+        let err = check_constraints(&stark, &trace, &wrong_public_inputs).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unsatisfied constraints (row: constraint indices):\n  row 0: [0]"
+        );
+
+        // Tampering with `x0` at row 5 breaks the transitions from row 4 and from row 5.
+        let mut wrong_trace = trace;
+        wrong_trace[0].values[5] += F::ONE;
+        let err = check_constraints(&stark, &wrong_trace, &public_inputs).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unsatisfied constraints (row: constraint indices):\n  row 4: [3]\n  row 5: [4]"
+        );
+
+        let stark = S2::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        check_constraints(&stark, &trace, &public_inputs)?;
+
+        // Wrong frequencies break the lookup, while all constraints still hold.
+        let mut wrong_trace = trace;
+        wrong_trace[4].values[0] = F::TWO;
+        let err = check_constraints(&stark, &wrong_trace, &public_inputs).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unsatisfied lookup #0:\n  value 1: looked up 1 times, table frequency 2"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_fibonacci_stark_degree() -> Result<()> {
         const D: usize = 2;
@@ -335,12 +660,16 @@ mod tests {
         type F = <C as GenericConfig<D>>::F;
         type S1 = FibonacciStark<F, D>;
         type S2 = FibonacciWithPermutationStark<F, D>;
+        type S3 = SquareFibonacciStark<F, D>;
 
         let num_rows = 1 << 5;
         let stark = S1::new(num_rows);
         test_stark_low_degree(stark)?;
 
         let stark = S2::new(num_rows);
+        test_stark_low_degree(stark)?;
+
+        let stark = S3::new(num_rows);
         test_stark_low_degree(stark)
     }
 
@@ -351,12 +680,15 @@ mod tests {
         type F = <C as GenericConfig<D>>::F;
         type S1 = FibonacciStark<F, D>;
         type S2 = FibonacciWithPermutationStark<F, D>;
+        type S3 = SquareFibonacciStark<F, D>;
 
         let num_rows = 1 << 5;
         let stark = S1::new(num_rows);
         test_stark_circuit_constraints::<F, C, S1, D>(stark)?;
         let stark = S2::new(num_rows);
-        test_stark_circuit_constraints::<F, C, S2, D>(stark)
+        test_stark_circuit_constraints::<F, C, S2, D>(stark)?;
+        let stark = S3::new(num_rows);
+        test_stark_circuit_constraints::<F, C, S3, D>(stark)
     }
 
     #[test]
@@ -401,6 +733,90 @@ mod tests {
         recursive_proof::<F, C, S2, C, D>(stark, proof, &config, true)
     }
 
+    #[test]
+    fn test_fibonacci_stark_zk() -> Result<()> {
+        init_logger();
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S1 = FibonacciStark<F, D>;
+        type S2 = FibonacciWithPermutationStark<F, D>;
+        type S3 = SquareFibonacciStark<F, D>;
+
+        let config = StarkConfig::standard_fast_zk_config();
+        let num_rows = 1 << 7;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+
+        // Test first STARK
+        let stark = S1::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let proof = prove::<F, C, S1, D>(
+            stark,
+            &config,
+            trace,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        assert_eq!(proof.proof.recover_degree_bits(&config), 7);
+        verify_stark_proof(stark, proof.clone(), &config)?;
+
+        let mut wrong_proof = proof.clone();
+        wrong_proof.public_inputs[2] += F::ONE;
+        assert!(verify_stark_proof(stark, wrong_proof, &config).is_err());
+
+        recursive_proof::<F, C, S1, C, D>(stark, proof, &config, false)?;
+
+        // Test second STARK
+        let stark = S2::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let proof = prove::<F, C, S2, D>(
+            stark,
+            &config,
+            trace,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof.clone(), &config)?;
+
+        recursive_proof::<F, C, S2, C, D>(stark, proof, &config, false)?;
+
+        // Test a STARK with constraints of degree 3
+        let stark = S3::new(num_rows);
+        assert_eq!(stark.num_quotient_chunks(&config), 5);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let public_inputs = [
+            public_inputs[0],
+            public_inputs[1],
+            trace[1].values[num_rows - 1],
+        ];
+        let proof = prove::<F, C, S3, D>(
+            stark,
+            &config,
+            trace,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof.clone(), &config)?;
+
+        recursive_proof::<F, C, S3, C, D>(stark, proof, &config, false)?;
+
+        // Traces too short to be randomized should be rejected.
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+        let stark = S1::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        assert!(prove::<F, C, S1, D>(
+            stark,
+            &config,
+            trace,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )
+        .is_err());
+
+        Ok(())
+    }
+
     fn recursive_proof<
         F: RichField + Extendable<D>,
         C: GenericConfig<D, F = F>,
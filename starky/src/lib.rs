@@ -18,6 +18,12 @@
 //! [`StarkEvaluationFrame`][crate::evaluation_frame::StarkEvaluationFrame] of
 //! two consecutive rows and a list of public inputs.
 //!
+//! Proofs are not zero-knowledge by default. Setting the `zero_knowledge` field of
+//! the [`StarkConfig`][crate::config::StarkConfig], or using
+//! [`StarkConfig::standard_fast_zk_config`][crate::config::StarkConfig::standard_fast_zk_config],
+//! makes the prover randomize its committed polynomials so that proofs do not leak
+//! information about the trace.
+//!
 //! ### Example: Fibonacci sequence
 //!
 //! To build a STARK for the modified Fibonacci sequence starting with two
@@ -310,6 +316,21 @@
 //! }
 //! ```
 //!
+//!
+//! # Defining a multi-STARK system
+//!
+//! Several STARK tables can be proven together, with values shared across tables
+//! being connected through [`CrossTableLookup`][crate::cross_table_lookup::CrossTableLookup]s.
+//! Each table involved in a cross-table lookup should override
+//! [`Stark::requires_ctls`][crate::stark::Stark::requires_ctls] to return `true`, and all
+//! tables should share the same constraint degree, of at least 3 to accommodate the
+//! cross-table lookup constraints.
+//!
+//! Tables of different types are gathered as [`StarkTable`][crate::stark::StarkTable] trait
+//! objects, and proven with [`prove_multi`][crate::prover::prove_multi], which outputs a
+//! [`MultiProof`][crate::proof::MultiProof] that can be checked with
+//! [`verify_multi`][crate::verifier::verify_multi].
+//!
 
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::needless_range_loop)]
@@ -338,5 +359,7 @@ pub mod util;
 mod vanishing_poly;
 pub mod verifier;
 
+#[cfg(test)]
+pub mod fibonacci_ctl_stark;
 #[cfg(test)]
 pub mod fibonacci_stark;
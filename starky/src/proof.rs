@@ -5,25 +5,33 @@
 #[cfg(not(feature = "std"))]
 use alloc::{vec, vec::Vec};
 
+use anyhow::{ensure, Result};
 use itertools::Itertools;
 use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::polynomial::PolynomialCoeffs;
 use plonky2::fri::oracle::PolynomialBatch;
 use plonky2::fri::proof::{
-    CompressedFriProof, FriChallenges, FriChallengesTarget, FriProof, FriProofTarget,
+    CompressedFriProof, FriChallenges, FriChallengesTarget, FriInitialTreeProof, FriProof,
+    FriProofTarget, FriQueryRound, FriQueryStep,
 };
 use plonky2::fri::structure::{
     FriOpeningBatch, FriOpeningBatchTarget, FriOpenings, FriOpeningsTarget,
 };
 use plonky2::hash::hash_types::{MerkleCapTarget, RichField};
+use plonky2::hash::hashing::PlonkyPermutation;
 use plonky2::hash::merkle_tree::MerkleCap;
+use plonky2::iop::challenger::Challenger;
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::iop::target::Target;
 use plonky2::plonk::config::{GenericConfig, Hasher};
-use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use plonky2::util::serialization::{Buffer, IoResult, Read, Remaining, Write};
 use plonky2_maybe_rayon::*;
 
 use crate::config::StarkConfig;
-use crate::lookup::GrandProductChallengeSet;
+use crate::cross_table_lookup::CrossTableLookup;
+use crate::lookup::{GrandProductChallenge, GrandProductChallengeSet};
+use crate::stark::{multi_stark_constraint_degree, Stark, StarkTable};
+use crate::verifier::validate_proof_shape_with_fri;
 
 /// Merkle caps and openings that form the proof of a single STARK.
 #[derive(Debug, Clone)]
@@ -48,7 +56,91 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> S
             .evals_proofs[0]
             .1;
         let lde_bits = config.fri_config.cap_height + initial_merkle_proof.siblings.len();
-        lde_bits - config.fri_config.rate_bits
+        lde_bits - config.fri_config.rate_bits - config.zero_knowledge as usize
+    }
+
+    /// Compresses the proof, by removing the redundancy in the Merkle paths
+    /// of the FRI query rounds at the provided `indices`.
+    pub fn compress(
+        self,
+        indices: &[usize],
+        config: &StarkConfig,
+    ) -> CompressedStarkProof<F, C, D> {
+        let degree_bits = self.recover_degree_bits(config);
+        let StarkProof {
+            trace_cap,
+            auxiliary_polys_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        } = self;
+
+        CompressedStarkProof {
+            degree_bits,
+            trace_cap,
+            auxiliary_polys_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof: opening_proof.compress(indices, &config.fri_params(degree_bits)),
+        }
+    }
+
+    /// Serializes a STARK proof.
+    pub fn to_buffer(&self, buffer: &mut Vec<u8>) -> IoResult<()> {
+        write_merkle_cap(buffer, &self.trace_cap)?;
+        buffer.write_bool(self.auxiliary_polys_cap.is_some())?;
+        if let Some(poly) = &self.auxiliary_polys_cap {
+            write_merkle_cap(buffer, poly)?;
+        }
+        write_merkle_cap(buffer, &self.quotient_polys_cap)?;
+        write_fri_proof(buffer, &self.opening_proof)?;
+        self.openings.to_buffer(buffer)?;
+        Ok(())
+    }
+
+    /// Deserializes a STARK proof.
+    ///
+    /// **Note**: the shape of the proof is not validated, see [`StarkProof::from_bytes`].
+    pub fn from_buffer(buffer: &mut Buffer) -> IoResult<Self> {
+        let trace_cap = read_merkle_cap(buffer)?;
+        let auxiliary_polys_cap = if buffer.read_bool()? {
+            Some(read_merkle_cap(buffer)?)
+        } else {
+            None
+        };
+        let quotient_polys_cap = read_merkle_cap(buffer)?;
+        let opening_proof = read_fri_proof(buffer)?;
+        let openings = StarkOpeningSet::from_buffer(buffer)?;
+
+        Ok(Self {
+            trace_cap,
+            auxiliary_polys_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        })
+    }
+
+    /// Serializes a STARK proof to bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        self.to_buffer(&mut buffer)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    /// Deserializes a proof of a single STARK from bytes, and checks that its shape
+    /// matches the one expected by `stark` with the provided [`StarkConfig`].
+    pub fn from_bytes<S: Stark<F, D>>(
+        bytes: Vec<u8>,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> Result<Self> {
+        let mut buffer = Buffer::new(&bytes);
+        let proof = Self::from_buffer(&mut buffer).map_err(anyhow::Error::msg)?;
+        ensure!(buffer.is_empty(), "Trailing bytes after STARK proof.");
+        validate_proof_shape_with_fri(stark, &proof, config, 0, 0)?;
+        Ok(proof)
     }
 }
 
@@ -110,7 +202,7 @@ impl<const D: usize> StarkProofTarget<D> {
             .evals_proofs[0]
             .1;
         let lde_bits = config.fri_config.cap_height + initial_merkle_proof.siblings.len();
-        lde_bits - config.fri_config.rate_bits
+        lde_bits - config.fri_config.rate_bits - config.zero_knowledge as usize
     }
 }
 
@@ -128,6 +220,71 @@ pub struct StarkProofWithPublicInputs<
     pub public_inputs: Vec<F>,
 }
 
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    StarkProofWithPublicInputs<F, C, D>
+{
+    /// Compresses the proof of a single STARK. The FRI query indices are recomputed
+    /// from the proof, assuming it has been generated by [`prove`][crate::prover::prove].
+    pub fn compress(self, config: &StarkConfig) -> CompressedStarkProofWithPublicInputs<F, C, D> {
+        let mut challenger = Challenger::<F, C::Hasher>::new();
+        let challenges = self.get_challenges(&mut challenger, None, false, config);
+        let compressed_proof = self
+            .proof
+            .compress(&challenges.fri_challenges.fri_query_indices, config);
+
+        CompressedStarkProofWithPublicInputs {
+            proof: compressed_proof,
+            public_inputs: self.public_inputs,
+        }
+    }
+
+    /// Serializes a STARK proof along with its public inputs.
+    pub fn to_buffer(&self, buffer: &mut Vec<u8>) -> IoResult<()> {
+        self.proof.to_buffer(buffer)?;
+        buffer.write_usize(self.public_inputs.len())?;
+        buffer.write_field_vec(&self.public_inputs)
+    }
+
+    /// Deserializes a STARK proof along with its public inputs.
+    ///
+    /// **Note**: the shape of the proof is not validated, see
+    /// [`StarkProofWithPublicInputs::from_bytes`].
+    pub fn from_buffer(buffer: &mut Buffer) -> IoResult<Self> {
+        let proof = StarkProof::from_buffer(buffer)?;
+        let pi_len = buffer.read_usize()?;
+        let public_inputs = buffer.read_field_vec(pi_len)?;
+
+        Ok(Self {
+            proof,
+            public_inputs,
+        })
+    }
+
+    /// Serializes a STARK proof along with its public inputs to bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        self.to_buffer(&mut buffer)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    /// Deserializes a proof of a single STARK along with its public inputs from bytes,
+    /// and checks that its shape matches the one expected by `stark` with the provided
+    /// [`StarkConfig`].
+    pub fn from_bytes<S: Stark<F, D>>(
+        bytes: Vec<u8>,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> Result<Self> {
+        let mut buffer = Buffer::new(&bytes);
+        let proof_with_pis = Self::from_buffer(&mut buffer).map_err(anyhow::Error::msg)?;
+        ensure!(buffer.is_empty(), "Trailing bytes after STARK proof.");
+        ensure!(proof_with_pis.public_inputs.len() == S::PUBLIC_INPUTS);
+        validate_proof_shape_with_fri(stark, &proof_with_pis.proof, config, 0, 0)?;
+        Ok(proof_with_pis)
+    }
+}
+
 /// Circuit version of [`StarkProofWithPublicInputs`].
 #[derive(Debug, Clone)]
 pub struct StarkProofWithPublicInputsTarget<const D: usize> {
@@ -144,14 +301,66 @@ pub struct CompressedStarkProof<
     C: GenericConfig<D, F = F>,
     const D: usize,
 > {
+    /// Length of the trace, in bits. Unlike for a [`StarkProof`], it cannot be
+    /// recovered from the compressed Merkle paths.
+    pub degree_bits: usize,
     /// Merkle cap of LDEs of trace values.
     pub trace_cap: MerkleCap<F, C::Hasher>,
+    /// Optional merkle cap of LDEs of permutation Z values, if any.
+    pub auxiliary_polys_cap: Option<MerkleCap<F, C::Hasher>>,
+    /// Merkle cap of LDEs of quotient values.
+    pub quotient_polys_cap: MerkleCap<F, C::Hasher>,
     /// Purported values of each polynomial at the challenge point.
     pub openings: StarkOpeningSet<F, D>,
-    /// A batch FRI argument for all openings.
+    /// A compressed batch FRI argument for all openings.
     pub opening_proof: CompressedFriProof<F, C::Hasher, D>,
 }
 
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    CompressedStarkProof<F, C, D>
+{
+    /// Decompresses the proof of `stark` from its challenges, inferring the coset elements
+    /// which were removed by the compression.
+    ///
+    /// Returns an error if the proof doesn't have the shape expected for `stark` and `config`.
+    pub fn decompress<S: Stark<F, D>>(
+        self,
+        stark: &S,
+        challenges: &StarkProofChallenges<F, D>,
+        config: &StarkConfig,
+    ) -> Result<StarkProof<F, C, D>> {
+        let CompressedStarkProof {
+            degree_bits,
+            trace_cap,
+            auxiliary_polys_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        } = self;
+        let fri_instance = stark.fri_instance(
+            challenges.stark_zeta,
+            F::primitive_root_of_unity(degree_bits),
+            0,
+            vec![],
+            config,
+        );
+        let opening_proof = opening_proof.decompress_for_instance::<C>(
+            &challenges.fri_challenges,
+            &fri_instance,
+            &openings.to_fri_openings(),
+            &config.fri_params(degree_bits),
+        )?;
+
+        Ok(StarkProof {
+            trace_cap,
+            auxiliary_polys_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        })
+    }
+}
+
 /// A compressed [`StarkProof`] format of a single STARK with its public inputs.
 #[derive(Debug, Clone)]
 pub struct CompressedStarkProofWithPublicInputs<
@@ -165,6 +374,51 @@ pub struct CompressedStarkProofWithPublicInputs<
     pub public_inputs: Vec<F>,
 }
 
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    CompressedStarkProofWithPublicInputs<F, C, D>
+{
+    /// Decompresses the proof of a single STARK.
+    pub fn decompress<S: Stark<F, D>>(
+        self,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> Result<StarkProofWithPublicInputs<F, C, D>> {
+        self.decompress_with_challenges(stark, config)
+            .map(|(proof, _)| proof)
+    }
+
+    /// Decompresses the proof of a single STARK, and also returns its challenges
+    /// so that they do not need to be recomputed during verification.
+    pub(crate) fn decompress_with_challenges<S: Stark<F, D>>(
+        self,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> Result<(
+        StarkProofWithPublicInputs<F, C, D>,
+        StarkProofChallenges<F, D>,
+    )> {
+        let degree_bits = self.proof.degree_bits;
+        ensure!(
+            config.fri_params(degree_bits).lde_bits() <= F::TWO_ADICITY,
+            "Invalid trace length in compressed proof."
+        );
+
+        let mut challenger = Challenger::<F, C::Hasher>::new();
+        let challenges = self
+            .proof
+            .get_challenges(&mut challenger, None, false, config);
+        let proof = self.proof.decompress(stark, &challenges, config)?;
+
+        Ok((
+            StarkProofWithPublicInputs {
+                proof,
+                public_inputs: self.public_inputs,
+            },
+            challenges,
+        ))
+    }
+}
+
 /// A [`StarkProof`] along with metadata about the initial Fiat-Shamir state, which is used when
 /// creating a recursive wrapper proof around a STARK proof.
 #[derive(Debug, Clone)]
@@ -202,6 +456,91 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize, c
     pub fn recover_degree_bits(&self, config: &StarkConfig) -> [usize; N] {
         core::array::from_fn(|i| self.stark_proofs[i].proof.recover_degree_bits(config))
     }
+
+    /// Serializes a multi-STARK proof.
+    pub fn to_buffer(&self, buffer: &mut Vec<u8>) -> IoResult<()> {
+        for stark_proof in &self.stark_proofs {
+            buffer.write_field_vec(stark_proof.init_challenger_state.as_ref())?;
+            stark_proof.proof.to_buffer(buffer)?;
+        }
+        buffer.write_usize(self.ctl_challenges.challenges.len())?;
+        for challenge in &self.ctl_challenges.challenges {
+            buffer.write_field(challenge.beta)?;
+            buffer.write_field(challenge.gamma)?;
+        }
+        Ok(())
+    }
+
+    /// Deserializes a multi-STARK proof.
+    ///
+    /// **Note**: the shape of the proof is not validated, see [`MultiProof::from_bytes`].
+    pub fn from_buffer(buffer: &mut Buffer) -> IoResult<Self> {
+        let mut stark_proofs = Vec::with_capacity(N);
+        for _ in 0..N {
+            let state = buffer.read_field_vec(<C::Hasher as Hasher<F>>::Permutation::WIDTH)?;
+            stark_proofs.push(StarkProofWithMetadata {
+                init_challenger_state: <C::Hasher as Hasher<F>>::Permutation::new(state),
+                proof: StarkProof::from_buffer(buffer)?,
+            });
+        }
+        let num_challenges = buffer.read_usize()?;
+        let challenges = (0..num_challenges)
+            .map(|_| {
+                Ok(GrandProductChallenge {
+                    beta: buffer.read_field()?,
+                    gamma: buffer.read_field()?,
+                })
+            })
+            .collect::<IoResult<Vec<_>>>()?;
+
+        Ok(Self {
+            stark_proofs: stark_proofs
+                .try_into()
+                .expect("There should be exactly one proof per table."),
+            ctl_challenges: GrandProductChallengeSet { challenges },
+        })
+    }
+
+    /// Serializes a multi-STARK proof to bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        self.to_buffer(&mut buffer)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    /// Deserializes a multi-STARK proof from bytes, and checks that the shape of each
+    /// table's proof matches the one expected by the corresponding STARK, with the
+    /// provided [`CrossTableLookup`]s and [`StarkConfig`].
+    pub fn from_bytes(
+        bytes: Vec<u8>,
+        starks: [&dyn StarkTable<F, C, D>; N],
+        cross_table_lookups: &[CrossTableLookup<F>],
+        config: &StarkConfig,
+    ) -> Result<Self> {
+        let mut buffer = Buffer::new(&bytes);
+        let multi_proof = Self::from_buffer(&mut buffer).map_err(anyhow::Error::msg)?;
+        ensure!(buffer.is_empty(), "Trailing bytes after multi-STARK proof.");
+        ensure!(multi_proof.ctl_challenges.challenges.len() == config.num_challenges);
+
+        let constraint_degree = multi_stark_constraint_degree(&starks)?;
+        for (i, stark) in starks.iter().enumerate() {
+            let (num_ctl_helpers, num_ctl_zs, _) = CrossTableLookup::num_ctl_helpers_zs_all(
+                cross_table_lookups,
+                i,
+                config.num_challenges,
+                constraint_degree,
+            );
+            stark.validate_table_proof_shape(
+                &multi_proof.stark_proofs[i].proof,
+                num_ctl_helpers,
+                num_ctl_zs,
+                config,
+            )?;
+        }
+
+        Ok(multi_proof)
+    }
 }
 
 /// Randomness used for a STARK proof.
@@ -343,6 +682,65 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
 
         FriOpenings { batches }
     }
+
+    /// Serializes a STARK's opening set.
+    pub(crate) fn to_buffer(&self, buffer: &mut Vec<u8>) -> IoResult<()> {
+        write_field_ext_vec::<F, D>(buffer, &self.local_values)?;
+        write_field_ext_vec::<F, D>(buffer, &self.next_values)?;
+        if let Some(poly) = &self.auxiliary_polys {
+            buffer.write_bool(true)?;
+            write_field_ext_vec::<F, D>(buffer, poly)?;
+        } else {
+            buffer.write_bool(false)?;
+        }
+        if let Some(poly_next) = &self.auxiliary_polys_next {
+            buffer.write_bool(true)?;
+            write_field_ext_vec::<F, D>(buffer, poly_next)?;
+        } else {
+            buffer.write_bool(false)?;
+        }
+        if let Some(ctl_zs_first) = &self.ctl_zs_first {
+            buffer.write_bool(true)?;
+            buffer.write_usize(ctl_zs_first.len())?;
+            buffer.write_field_vec(ctl_zs_first)?;
+        } else {
+            buffer.write_bool(false)?;
+        }
+        write_field_ext_vec::<F, D>(buffer, &self.quotient_polys)?;
+        Ok(())
+    }
+
+    /// Deserializes a STARK's opening set.
+    pub(crate) fn from_buffer(buffer: &mut Buffer) -> IoResult<Self> {
+        let local_values = read_field_ext_vec::<F, D>(buffer)?;
+        let next_values = read_field_ext_vec::<F, D>(buffer)?;
+        let auxiliary_polys = if buffer.read_bool()? {
+            Some(read_field_ext_vec::<F, D>(buffer)?)
+        } else {
+            None
+        };
+        let auxiliary_polys_next = if buffer.read_bool()? {
+            Some(read_field_ext_vec::<F, D>(buffer)?)
+        } else {
+            None
+        };
+        let ctl_zs_first = if buffer.read_bool()? {
+            let length = buffer.read_usize()?;
+            Some(buffer.read_field_vec(length)?)
+        } else {
+            None
+        };
+        let quotient_polys = read_field_ext_vec::<F, D>(buffer)?;
+
+        Ok(Self {
+            local_values,
+            next_values,
+            auxiliary_polys,
+            auxiliary_polys_next,
+            ctl_zs_first,
+            quotient_polys,
+        })
+    }
 }
 
 /// Circuit version of [`StarkOpeningSet`].
@@ -461,3 +859,104 @@ impl<const D: usize> StarkOpeningSetTarget<D> {
         FriOpeningsTarget { batches }
     }
 }
+
+// Contrary to the serialization of plonky2 proofs, the shape of native STARK proofs
+// cannot be inferred ahead of reading them, hence the following helpers also write
+// the length of each sequence of elements.
+
+fn write_field_ext_vec<F: RichField + Extendable<D>, const D: usize>(
+    buffer: &mut Vec<u8>,
+    v: &[F::Extension],
+) -> IoResult<()> {
+    buffer.write_usize(v.len())?;
+    buffer.write_field_ext_vec::<F, D>(v)
+}
+
+fn read_field_ext_vec<F: RichField + Extendable<D>, const D: usize>(
+    buffer: &mut Buffer,
+) -> IoResult<Vec<F::Extension>> {
+    let length = buffer.read_usize()?;
+    buffer.read_field_ext_vec::<F, D>(length)
+}
+
+fn write_merkle_cap<F: RichField, H: Hasher<F>>(
+    buffer: &mut Vec<u8>,
+    cap: &MerkleCap<F, H>,
+) -> IoResult<()> {
+    buffer.write_usize(cap.0.len())?;
+    buffer.write_merkle_cap(cap)
+}
+
+fn read_merkle_cap<F: RichField, H: Hasher<F>>(buffer: &mut Buffer) -> IoResult<MerkleCap<F, H>> {
+    let length = buffer.read_usize()?;
+    Ok(MerkleCap(buffer.read_hash_vec::<F, H>(length)?))
+}
+
+fn write_fri_proof<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize>(
+    buffer: &mut Vec<u8>,
+    proof: &FriProof<F, H, D>,
+) -> IoResult<()> {
+    buffer.write_usize(proof.commit_phase_merkle_caps.len())?;
+    for cap in &proof.commit_phase_merkle_caps {
+        write_merkle_cap(buffer, cap)?;
+    }
+    buffer.write_usize(proof.query_round_proofs.len())?;
+    for round in &proof.query_round_proofs {
+        buffer.write_usize(round.initial_trees_proof.evals_proofs.len())?;
+        for (evals, merkle_proof) in &round.initial_trees_proof.evals_proofs {
+            buffer.write_usize(evals.len())?;
+            buffer.write_field_vec(evals)?;
+            buffer.write_merkle_proof(merkle_proof)?;
+        }
+        buffer.write_usize(round.steps.len())?;
+        for step in &round.steps {
+            write_field_ext_vec::<F, D>(buffer, &step.evals)?;
+            buffer.write_merkle_proof(&step.merkle_proof)?;
+        }
+    }
+    write_field_ext_vec::<F, D>(buffer, &proof.final_poly.coeffs)?;
+    buffer.write_field(proof.pow_witness)
+}
+
+fn read_fri_proof<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize>(
+    buffer: &mut Buffer,
+) -> IoResult<FriProof<F, H, D>> {
+    let num_caps = buffer.read_usize()?;
+    let commit_phase_merkle_caps = (0..num_caps)
+        .map(|_| read_merkle_cap(buffer))
+        .collect::<IoResult<Vec<_>>>()?;
+    let num_query_rounds = buffer.read_usize()?;
+    let query_round_proofs = (0..num_query_rounds)
+        .map(|_| {
+            let num_trees = buffer.read_usize()?;
+            let evals_proofs = (0..num_trees)
+                .map(|_| {
+                    let length = buffer.read_usize()?;
+                    Ok((buffer.read_field_vec(length)?, buffer.read_merkle_proof()?))
+                })
+                .collect::<IoResult<Vec<_>>>()?;
+            let num_steps = buffer.read_usize()?;
+            let steps = (0..num_steps)
+                .map(|_| {
+                    Ok(FriQueryStep {
+                        evals: read_field_ext_vec::<F, D>(buffer)?,
+                        merkle_proof: buffer.read_merkle_proof()?,
+                    })
+                })
+                .collect::<IoResult<Vec<_>>>()?;
+            Ok(FriQueryRound {
+                initial_trees_proof: FriInitialTreeProof { evals_proofs },
+                steps,
+            })
+        })
+        .collect::<IoResult<Vec<_>>>()?;
+    let final_poly = PolynomialCoeffs::new(read_field_ext_vec::<F, D>(buffer)?);
+    let pow_witness = buffer.read_field()?;
+
+    Ok(FriProof {
+        commit_phase_merkle_caps,
+        query_round_proofs,
+        final_poly,
+        pow_witness,
+    })
+}
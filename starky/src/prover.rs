@@ -23,14 +23,18 @@ use plonky2_maybe_rayon::*;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::ConstraintConsumer;
-use crate::cross_table_lookup::{get_ctl_auxiliary_polys, CtlCheckVars, CtlData};
+use crate::cross_table_lookup::{
+    get_ctl_auxiliary_polys, get_ctl_data, CrossTableLookup, CtlCheckVars, CtlData,
+};
 use crate::evaluation_frame::StarkEvaluationFrame;
 use crate::lookup::{
     get_grand_product_challenge_set, lookup_helper_columns, GrandProductChallengeSet, Lookup,
     LookupCheckVars,
 };
-use crate::proof::{StarkOpeningSet, StarkProof, StarkProofWithPublicInputs};
-use crate::stark::Stark;
+use crate::proof::{
+    MultiProof, StarkOpeningSet, StarkProof, StarkProofWithMetadata, StarkProofWithPublicInputs,
+};
+use crate::stark::{multi_stark_constraint_degree, Stark, StarkTable};
 use crate::vanishing_poly::eval_vanishing_poly;
 
 /// From a STARK trace, computes a STARK proof to attest its correctness.
@@ -49,24 +53,17 @@ where
     let degree = trace_poly_values[0].len();
     let degree_bits = log2_strict(degree);
     let fri_params = config.fri_params(degree_bits);
-    let rate_bits = config.fri_config.rate_bits;
     let cap_height = config.fri_config.cap_height;
     assert!(
-        fri_params.total_arities() <= degree_bits + rate_bits - cap_height,
+        fri_params.total_arities() <= fri_params.lde_bits() - cap_height,
         "FRI total reduction arity is too large.",
     );
+    check_zk_trace_length::<D>(config, degree)?;
 
     let trace_commitment = timed!(
         timing,
         "compute trace commitment",
-        PolynomialBatch::<F, C, D>::from_values(
-            trace_poly_values.clone(),
-            rate_bits,
-            false,
-            cap_height,
-            timing,
-            None,
-        )
+        commit_values::<F, C, D>(trace_poly_values.clone(), config, timing)
     );
 
     let trace_cap = trace_commitment.merkle_tree.cap.clone();
@@ -86,6 +83,119 @@ where
     )
 }
 
+/// From the traces of all tables of a multi-STARK system, computes a [`MultiProof`]
+/// attesting their correctness, as well as the consistency of the values shared
+/// across tables through the provided [`CrossTableLookup`]s.
+///
+/// All tables are expected to have the same constraint degree, and to take part in
+/// at least one cross-table lookup.
+pub fn prove_multi<F, C, const D: usize, const N: usize>(
+    starks: [&dyn StarkTable<F, C, D>; N],
+    cross_table_lookups: &[CrossTableLookup<F>],
+    config: &StarkConfig,
+    trace_poly_values: [Vec<PolynomialValues<F>>; N],
+    public_inputs: [&[F]; N],
+    timing: &mut TimingTree,
+) -> Result<MultiProof<F, C, D, N>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let constraint_degree = multi_stark_constraint_degree(&starks)?;
+    ensure!(
+        starks.iter().all(|stark| stark.table_requires_ctls()),
+        "All tables of a multi-STARK system should require cross-table lookups."
+    );
+    let cap_height = config.fri_config.cap_height;
+    for (i, trace) in trace_poly_values.iter().enumerate() {
+        ensure!(!trace.is_empty(), "The trace of table {i} has no columns.");
+        let degree = trace[0].len();
+        ensure!(
+            degree.is_power_of_two() && trace.iter().all(|column| column.len() == degree),
+            "The columns of the trace of table {i} should all have the same power of two length."
+        );
+        let fri_params = config.fri_params(log2_strict(degree));
+        ensure!(
+            fri_params.lde_bits() <= F::TWO_ADICITY,
+            "The trace of table {i} is too long."
+        );
+        ensure!(
+            fri_params.total_arities() + cap_height <= fri_params.lde_bits(),
+            "FRI total reduction arity is too large for table {i}."
+        );
+        check_zk_trace_length::<D>(config, degree)?;
+    }
+
+    let trace_commitments = timed!(
+        timing,
+        "compute all trace commitments",
+        trace_poly_values
+            .iter()
+            .map(|trace| {
+                timed!(
+                    timing,
+                    "compute trace commitment",
+                    commit_values::<F, C, D>(trace.clone(), config, timing)
+                )
+            })
+            .collect::<Vec<_>>()
+    );
+
+    // Observe all trace caps before sampling the CTL challenges shared by all tables.
+    let mut challenger = Challenger::<F, C::Hasher>::new();
+    for commitment in &trace_commitments {
+        challenger.observe_cap(&commitment.merkle_tree.cap);
+    }
+
+    // This is an expensive check, hence is only run when `debug_assertions` are enabled.
+    #[cfg(debug_assertions)]
+    {
+        crate::cross_table_lookup::debug_utils::check_ctls(
+            &trace_poly_values,
+            cross_table_lookups,
+            &Default::default(),
+        )?;
+    }
+
+    let (ctl_challenges, ctl_data_per_table) = timed!(
+        timing,
+        "compute CTL data",
+        get_ctl_data::<F, C, D, N>(
+            config,
+            &trace_poly_values,
+            cross_table_lookups,
+            &mut challenger,
+            constraint_degree,
+        )
+    );
+
+    let mut stark_proofs = Vec::with_capacity(N);
+    for (i, stark) in starks.iter().enumerate() {
+        let init_challenger_state = challenger.compact();
+        let proof = stark.prove_table(
+            config,
+            &trace_poly_values[i],
+            &trace_commitments[i],
+            Some(&ctl_data_per_table[i]),
+            Some(&ctl_challenges),
+            &mut challenger,
+            public_inputs[i],
+            timing,
+        )?;
+        stark_proofs.push(StarkProofWithMetadata {
+            init_challenger_state,
+            proof: proof.proof,
+        });
+    }
+
+    Ok(MultiProof {
+        stark_proofs: stark_proofs
+            .try_into()
+            .expect("There should be exactly one proof per table."),
+        ctl_challenges,
+    })
+}
+
 /// Generates a proof for a single STARK table, including:
 ///
 /// - the initial state of the challenger,
@@ -115,9 +225,10 @@ where
     let rate_bits = config.fri_config.rate_bits;
     let cap_height = config.fri_config.cap_height;
     assert!(
-        fri_params.total_arities() <= degree_bits + rate_bits - cap_height,
+        fri_params.total_arities() <= fri_params.lde_bits() - cap_height,
         "FRI total reduction arity is too large.",
     );
+    check_zk_trace_length::<D>(config, degree)?;
 
     // Permutation arguments.
 
@@ -178,14 +289,7 @@ where
         timed!(
             timing,
             "compute auxiliary polynomials commitment",
-            PolynomialBatch::from_values(
-                aux_polys,
-                rate_bits,
-                false,
-                config.fri_config.cap_height,
-                timing,
-                None,
-            )
+            commit_values(aux_polys, config, timing)
         )
     });
 
@@ -238,22 +342,33 @@ where
             config,
         )
     );
-    let all_quotient_chunks = timed!(
+    let num_quotient_chunks = stark.num_quotient_chunks(config);
+    let num_blinding_coeffs = num_blinding_coeffs::<D>(config);
+    let mut all_quotient_chunks: Vec<_> = timed!(
         timing,
         "split quotient polys",
         quotient_polys
             .into_par_iter()
             .flat_map(|mut quotient_poly| {
                 quotient_poly
-                    .trim_to_len(degree * stark.quotient_degree_factor())
+                    .trim_to_len(degree * num_quotient_chunks)
                     .expect(
                         "Quotient has failed, the vanishing polynomial is not divisible by Z_H",
                     );
-                // Split quotient into degree-n chunks.
-                quotient_poly.chunks(degree)
+                // Split quotient into chunks of the same degree as the trace polynomials.
+                let chunks = quotient_poly.chunks(degree);
+                if config.zero_knowledge {
+                    mask_quotient_chunks(chunks, num_blinding_coeffs)
+                } else {
+                    chunks
+                }
             })
             .collect()
     );
+    if config.zero_knowledge {
+        // Add a random polynomial masking the polynomial FRI is run on.
+        all_quotient_chunks.push(PolynomialCoeffs::new(F::rand_vec(2 * degree)));
+    }
     // Commit to the quotient polynomials.
     let quotient_commitment = timed!(
         timing,
@@ -261,7 +376,7 @@ where
         PolynomialBatch::from_coeffs(
             all_quotient_chunks,
             rate_bits,
-            false,
+            config.zero_knowledge,
             config.fri_config.cap_height,
             timing,
             None,
@@ -327,6 +442,97 @@ where
     })
 }
 
+/// Outputs the number of random coefficients used to randomize each committed polynomial
+/// in zero-knowledge mode: one per FRI query, and one per base field coordinate of the
+/// openings at `zeta` and `g * zeta`.
+const fn num_blinding_coeffs<const D: usize>(config: &StarkConfig) -> usize {
+    config.fri_config.num_query_rounds + 2 * D
+}
+
+/// Checks that a trace of length `degree` is long enough to be randomized in
+/// zero-knowledge mode.
+fn check_zk_trace_length<const D: usize>(config: &StarkConfig, degree: usize) -> Result<()> {
+    let num_blinding_coeffs = num_blinding_coeffs::<D>(config);
+    ensure!(
+        !config.zero_knowledge || degree > num_blinding_coeffs,
+        "In zero-knowledge mode, traces should have more than {num_blinding_coeffs} rows."
+    );
+    Ok(())
+}
+
+/// Computes a commitment to the provided polynomial values.
+///
+/// In zero-knowledge mode, each polynomial `f` is first randomized into `f + Z_H * r`,
+/// for a random polynomial `r` with as many coefficients as values of `f` revealed
+/// outside of `H`. This leaves the values over `H` unchanged, while hiding them at
+/// the opened points. The leaves of the resulting Merkle tree are salted as well.
+fn commit_values<F, C, const D: usize>(
+    values: Vec<PolynomialValues<F>>,
+    config: &StarkConfig,
+    timing: &mut TimingTree,
+) -> PolynomialBatch<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let rate_bits = config.fri_config.rate_bits;
+    let cap_height = config.fri_config.cap_height;
+    if !config.zero_knowledge {
+        return PolynomialBatch::from_values(values, rate_bits, false, cap_height, timing, None);
+    }
+
+    let num_blinding_coeffs = num_blinding_coeffs::<D>(config);
+    let randomized_coeffs = values
+        .into_par_iter()
+        .map(|poly| {
+            let mut coeffs = poly.ifft().coeffs;
+            let degree = coeffs.len();
+            let r = F::rand_vec(num_blinding_coeffs);
+            // Since `Z_H(x) = x^n - 1`, the coefficients of `f + Z_H * r` are those
+            // of `f - r` followed by those of `r`.
+            for (c, &r_i) in coeffs.iter_mut().zip(&r) {
+                *c -= r_i;
+            }
+            coeffs.extend(r);
+            coeffs.resize(2 * degree, F::ZERO);
+            PolynomialCoeffs::new(coeffs)
+        })
+        .collect();
+
+    PolynomialBatch::from_coeffs(randomized_coeffs, rate_bits, true, cap_height, timing, None)
+}
+
+/// Masks the chunks `t_i` of a quotient polynomial `t = sum_i X^(i * n) t_i`, where `n`
+/// is the trace length, into `t_i + X^n * b_i - b_(i-1)`, for random polynomials `b_i`
+/// with `num_blinding_coeffs` coefficients, and `b_(-1) = b_(k-1) = 0` for `k` chunks.
+/// The masked chunks have twice the trace degree, and still recombine into `t`.
+fn mask_quotient_chunks<F: Field>(
+    chunks: Vec<PolynomialCoeffs<F>>,
+    num_blinding_coeffs: usize,
+) -> Vec<PolynomialCoeffs<F>> {
+    let num_chunks = chunks.len();
+    let mut prev_blinding = Vec::new();
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut coeffs = chunk.coeffs;
+            let degree = coeffs.len();
+            for (c, &b) in coeffs.iter_mut().zip(&prev_blinding) {
+                *c -= b;
+            }
+            prev_blinding = if i + 1 < num_chunks {
+                F::rand_vec(num_blinding_coeffs)
+            } else {
+                Vec::new()
+            };
+            coeffs.extend(&prev_blinding);
+            coeffs.resize(2 * degree, F::ZERO);
+            PolynomialCoeffs::new(coeffs)
+        })
+        .collect()
+}
+
 /// Computes the quotient polynomials `(sum alpha^i C_i(x)) / Z_H(x)` for `alpha` in `alphas`,
 /// where the `C_i`s are the STARK constraints.
 fn compute_quotient_polys<'a, F, P, C, S, const D: usize>(
@@ -353,12 +559,15 @@ where
     let rate_bits = config.fri_config.rate_bits;
     let total_num_helper_cols: usize = num_ctl_columns.iter().sum();
 
-    let quotient_degree_bits = log2_ceil(stark.quotient_degree_factor());
+    // In zero-knowledge mode, the committed polynomials have twice the trace degree,
+    // hence their LDE has twice as many points per trace row.
+    let lde_rate_bits = rate_bits + config.zero_knowledge as usize;
+    let quotient_degree_bits = log2_ceil(stark.num_quotient_chunks(config));
     assert!(
-        quotient_degree_bits <= rate_bits,
+        quotient_degree_bits <= lde_rate_bits,
         "Having constraints of degree higher than the rate is not supported yet."
     );
-    let step = 1 << (rate_bits - quotient_degree_bits);
+    let step = 1 << (lde_rate_bits - quotient_degree_bits);
     // When opening the `Z`s polys at the "next" point, need to look at the point `next_step` steps away.
     let next_step = 1 << quotient_degree_bits;
 
@@ -549,11 +758,22 @@ fn check_constraints<'a, F, C, S, const D: usize>(
     let subgroup = F::two_adic_subgroup(degree_bits + rate_bits);
 
     // Get the evaluations of a batch of polynomials over our subgroup.
+    // In zero-knowledge mode, committed polynomials have a larger degree than the trace,
+    // so we only keep the evaluations over `H`.
     let get_subgroup_evals = |comm: &PolynomialBatch<F, C, D>| -> Vec<Vec<F>> {
         let values = comm
             .polynomials
             .par_iter()
-            .map(|coeffs| coeffs.clone().fft().values)
+            .map(|coeffs| {
+                let stride = coeffs.len() >> degree_bits;
+                coeffs
+                    .clone()
+                    .fft()
+                    .values
+                    .into_iter()
+                    .step_by(stride)
+                    .collect()
+            })
             .collect::<Vec<_>>();
         transpose(&values)
     };
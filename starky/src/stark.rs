@@ -4,21 +4,32 @@
 #[cfg(not(feature = "std"))]
 use alloc::{vec, vec::Vec};
 
+use anyhow::{ensure, Result};
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
 use plonky2::field::types::Field;
+use plonky2::fri::oracle::PolynomialBatch;
 use plonky2::fri::structure::{
     FriBatchInfo, FriBatchInfoTarget, FriInstanceInfo, FriInstanceInfoTarget, FriOracleInfo,
     FriPolynomialInfo,
 };
 use plonky2::hash::hash_types::RichField;
+use plonky2::iop::challenger::Challenger;
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::config::GenericConfig;
+use plonky2::util::timing::TimingTree;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::cross_table_lookup::{CtlCheckVars, CtlData};
 use crate::evaluation_frame::StarkEvaluationFrame;
-use crate::lookup::Lookup;
+use crate::lookup::{GrandProductChallengeSet, Lookup};
+use crate::proof::{StarkProof, StarkProofChallenges, StarkProofWithPublicInputs};
+use crate::prover::prove_with_commitment;
+use crate::stark_testing::check_constraints;
+use crate::verifier::{validate_proof_shape_with_fri, verify_stark_proof_with_challenges};
 
 /// Represents a STARK system.
 pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
@@ -87,10 +98,23 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         1.max(self.constraint_degree() - 1)
     }
 
+    /// Outputs the number of chunks, of the trace degree, each quotient polynomial of this
+    /// [`Stark`] is split into with the provided [`StarkConfig`]. In zero-knowledge mode,
+    /// the randomized polynomials have up to twice the trace degree, so that the quotient
+    /// requires `2 * constraint_degree - 1` chunks.
+    fn num_quotient_chunks(&self, config: &StarkConfig) -> usize {
+        if config.zero_knowledge {
+            (2 * self.constraint_degree()).max(2) - 1
+        } else {
+            self.quotient_degree_factor()
+        }
+    }
+
     /// Outputs the number of quotient polynomials this [`Stark`] would require with
-    /// the provided [`StarkConfig`]
+    /// the provided [`StarkConfig`]. In zero-knowledge mode, this includes a random
+    /// polynomial masking the polynomial FRI is run on.
     fn num_quotient_polys(&self, config: &StarkConfig) -> usize {
-        self.quotient_degree_factor() * config.num_challenges
+        self.num_quotient_chunks(config) * config.num_challenges + config.zero_knowledge as usize
     }
 
     /// Computes the FRI instance used to prove this Stark.
@@ -106,7 +130,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         let trace_info = FriPolynomialInfo::from_range(oracles.len(), 0..Self::COLUMNS);
         oracles.push(FriOracleInfo {
             num_polys: Self::COLUMNS,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
         let num_lookup_columns = self.num_lookup_helper_columns(config);
@@ -115,7 +139,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
             let aux_polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_auxiliary_polys);
             oracles.push(FriOracleInfo {
                 num_polys: num_auxiliary_polys,
-                blinding: false,
+                blinding: config.zero_knowledge,
             });
             aux_polys
         } else {
@@ -126,7 +150,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         let quotient_info = FriPolynomialInfo::from_range(oracles.len(), 0..num_quotient_polys);
         oracles.push(FriOracleInfo {
             num_polys: num_quotient_polys,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
         let zeta_batch = FriBatchInfo {
@@ -175,7 +199,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         let trace_info = FriPolynomialInfo::from_range(oracles.len(), 0..Self::COLUMNS);
         oracles.push(FriOracleInfo {
             num_polys: Self::COLUMNS,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
         let num_lookup_columns = self.num_lookup_helper_columns(config);
@@ -184,7 +208,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
             let aux_polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_auxiliary_polys);
             oracles.push(FriOracleInfo {
                 num_polys: num_auxiliary_polys,
-                blinding: false,
+                blinding: config.zero_knowledge,
             });
             aux_polys
         } else {
@@ -195,7 +219,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         let quotient_info = FriPolynomialInfo::from_range(oracles.len(), 0..num_quotient_polys);
         oracles.push(FriOracleInfo {
             num_polys: num_quotient_polys,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
         let zeta_batch = FriBatchInfoTarget {
@@ -260,3 +284,163 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         false
     }
 }
+
+/// An object-safe view of a [`Stark`], allowing to gather STARK tables of
+/// different types into a single multi-STARK system.
+///
+/// This trait is automatically implemented for any type implementing [`Stark`].
+pub trait StarkTable<F, C, const D: usize>: Sync
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    /// Outputs the maximum constraint degree of this table.
+    fn table_constraint_degree(&self) -> usize;
+
+    /// Outputs the number of total lookup helper columns of this table.
+    fn table_num_lookup_helper_columns(&self, config: &StarkConfig) -> usize;
+
+    /// Indicates whether this table requires cross-table lookups.
+    fn table_requires_ctls(&self) -> bool;
+
+    /// Generates a proof for this table, given its trace and the commitment to it.
+    /// See [`prove_with_commitment`].
+    fn prove_table(
+        &self,
+        config: &StarkConfig,
+        trace_poly_values: &[PolynomialValues<F>],
+        trace_commitment: &PolynomialBatch<F, C, D>,
+        ctl_data: Option<&CtlData<F>>,
+        ctl_challenges: Option<&GrandProductChallengeSet<F>>,
+        challenger: &mut Challenger<F, C::Hasher>,
+        public_inputs: &[F],
+        timing: &mut TimingTree,
+    ) -> Result<StarkProofWithPublicInputs<F, C, D>>;
+
+    /// Verifies a proof for this table, with the provided challenges.
+    /// See [`verify_stark_proof_with_challenges`].
+    fn verify_table(
+        &self,
+        proof: &StarkProof<F, C, D>,
+        challenges: &StarkProofChallenges<F, D>,
+        ctl_vars: Option<&[CtlCheckVars<F, F::Extension, F::Extension, D>]>,
+        public_inputs: &[F],
+        config: &StarkConfig,
+    ) -> Result<()>;
+
+    /// Checks that a proof for this table has the expected shape, given the number of
+    /// cross-table lookup helper columns and `Z` polynomials of this table.
+    fn validate_table_proof_shape(
+        &self,
+        proof: &StarkProof<F, C, D>,
+        num_ctl_helpers: usize,
+        num_ctl_zs: usize,
+        config: &StarkConfig,
+    ) -> Result<()>;
+
+    /// Checks that the given trace satisfies the constraints and lookups of this table.
+    /// See [`check_constraints`].
+    fn check_table_constraints(
+        &self,
+        trace_poly_values: &[PolynomialValues<F>],
+        public_inputs: &[F],
+    ) -> Result<()>;
+}
+
+impl<F, C, S, const D: usize> StarkTable<F, C, D> for S
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+{
+    fn table_constraint_degree(&self) -> usize {
+        self.constraint_degree()
+    }
+
+    fn table_num_lookup_helper_columns(&self, config: &StarkConfig) -> usize {
+        self.num_lookup_helper_columns(config)
+    }
+
+    fn table_requires_ctls(&self) -> bool {
+        self.requires_ctls()
+    }
+
+    fn prove_table(
+        &self,
+        config: &StarkConfig,
+        trace_poly_values: &[PolynomialValues<F>],
+        trace_commitment: &PolynomialBatch<F, C, D>,
+        ctl_data: Option<&CtlData<F>>,
+        ctl_challenges: Option<&GrandProductChallengeSet<F>>,
+        challenger: &mut Challenger<F, C::Hasher>,
+        public_inputs: &[F],
+        timing: &mut TimingTree,
+    ) -> Result<StarkProofWithPublicInputs<F, C, D>> {
+        prove_with_commitment(
+            self,
+            config,
+            trace_poly_values,
+            trace_commitment,
+            ctl_data,
+            ctl_challenges,
+            challenger,
+            public_inputs,
+            timing,
+        )
+    }
+
+    fn verify_table(
+        &self,
+        proof: &StarkProof<F, C, D>,
+        challenges: &StarkProofChallenges<F, D>,
+        ctl_vars: Option<&[CtlCheckVars<F, F::Extension, F::Extension, D>]>,
+        public_inputs: &[F],
+        config: &StarkConfig,
+    ) -> Result<()> {
+        verify_stark_proof_with_challenges(self, proof, challenges, ctl_vars, public_inputs, config)
+    }
+
+    fn validate_table_proof_shape(
+        &self,
+        proof: &StarkProof<F, C, D>,
+        num_ctl_helpers: usize,
+        num_ctl_zs: usize,
+        config: &StarkConfig,
+    ) -> Result<()> {
+        validate_proof_shape_with_fri(self, proof, config, num_ctl_helpers, num_ctl_zs)
+    }
+
+    fn check_table_constraints(
+        &self,
+        trace_poly_values: &[PolynomialValues<F>],
+        public_inputs: &[F],
+    ) -> Result<()> {
+        check_constraints(self, trace_poly_values, public_inputs)
+    }
+}
+
+/// Outputs the constraint degree shared by all tables of a multi-STARK system.
+///
+/// Cross-table lookup helper columns are computed with a single constraint degree,
+/// hence all tables are required to have the same one.
+pub(crate) fn multi_stark_constraint_degree<F, C, const D: usize>(
+    starks: &[&dyn StarkTable<F, C, D>],
+) -> Result<usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    ensure!(
+        !starks.is_empty(),
+        "A multi-STARK system needs at least one table."
+    );
+    let constraint_degree = starks[0].table_constraint_degree();
+    ensure!(
+        starks
+            .iter()
+            .all(|stark| stark.table_constraint_degree() == constraint_degree),
+        "All tables of a multi-STARK system should have the same constraint degree."
+    );
+
+    Ok(constraint_degree)
+}
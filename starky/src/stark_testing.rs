@@ -1,9 +1,11 @@
 //! Utility module for testing [`Stark`] implementation.
 
 #[cfg(not(feature = "std"))]
-use alloc::{vec, vec::Vec};
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::Write;
 
-use anyhow::{ensure, Result};
+use anyhow::{anyhow, ensure, Result};
+use hashbrown::HashMap;
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::polynomial::{PolynomialCoeffs, PolynomialValues};
 use plonky2::field::types::{Field, Sample};
@@ -15,11 +17,16 @@ use plonky2::plonk::config::GenericConfig;
 use plonky2::util::{log2_ceil, log2_strict, transpose};
 
 use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::cross_table_lookup::debug_utils::check_ctls;
+use crate::cross_table_lookup::CrossTableLookup;
 use crate::evaluation_frame::StarkEvaluationFrame;
-use crate::stark::Stark;
+use crate::stark::{Stark, StarkTable};
 
 const WITNESS_SIZE: usize = 1 << 5;
 
+/// Maximum number of failures listed in the errors returned by [`check_constraints`].
+const MAX_REPORTED_FAILURES: usize = 16;
+
 /// Tests that the constraints imposed by the given STARK are low-degree by applying them to random
 /// low-degree witness polynomials.
 pub fn test_stark_low_degree<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
@@ -141,6 +148,178 @@ pub fn test_stark_circuit_constraints<
     data.verify(proof)
 }
 
+/// Checks that the given trace satisfies the constraints imposed by the given STARK, by evaluating
+/// them row by row with the actual first row, last row and transition filters. It also checks that
+/// the lookups of the STARK hold over the trace.
+///
+/// Contrary to proof generation, which only fails with an opaque error when the trace is invalid,
+/// the returned error lists the failing rows along with the indices of the failing constraints,
+/// in their order of emission through the [`ConstraintConsumer`].
+pub fn check_constraints<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
+    stark: &S,
+    trace_poly_values: &[PolynomialValues<F>],
+    public_inputs: &[F],
+) -> Result<()> {
+    ensure!(
+        trace_poly_values.len() == S::COLUMNS,
+        "Expected {} trace columns, got {}.",
+        S::COLUMNS,
+        trace_poly_values.len()
+    );
+    ensure!(
+        public_inputs.len() == S::PUBLIC_INPUTS,
+        "Expected {} public inputs, got {}.",
+        S::PUBLIC_INPUTS,
+        public_inputs.len()
+    );
+    let degree = trace_poly_values.first().map_or(0, |column| column.len());
+    ensure!(
+        degree.is_power_of_two(),
+        "The trace length should be a non-zero power of two, got {degree}."
+    );
+    ensure!(
+        trace_poly_values
+            .iter()
+            .all(|column| column.len() == degree),
+        "All trace columns should have the same length."
+    );
+    let degree_bits = log2_strict(degree);
+
+    let trace_rows = transpose(
+        &trace_poly_values
+            .iter()
+            .map(|column| column.values.clone())
+            .collect::<Vec<_>>(),
+    );
+    let subgroup = F::two_adic_subgroup(degree_bits);
+    // Last element of the subgroup.
+    let last = F::primitive_root_of_unity(degree_bits).inverse();
+
+    let failures = (0..degree)
+        .filter_map(|i| {
+            let vars = S::EvaluationFrame::from_values(
+                &trace_rows[i],
+                &trace_rows[(i + 1) % degree],
+                public_inputs,
+            );
+            let mut consumer = ConstraintConsumer::<F>::new_debug(
+                subgroup[i] - last,
+                F::from_bool(i == 0),
+                F::from_bool(i == degree - 1),
+            );
+            stark.eval_packed_base(&vars, &mut consumer);
+
+            let failing_constraints = consumer
+                .debug_constraints()
+                .into_iter()
+                .enumerate()
+                .filter_map(|(index, constraint)| (!constraint.is_zero()).then_some(index))
+                .collect::<Vec<_>>();
+            (!failing_constraints.is_empty()).then(|| format!("row {i}: {failing_constraints:?}"))
+        })
+        .collect::<Vec<_>>();
+    ensure!(
+        failures.is_empty(),
+        "{}",
+        failure_report(
+            "Unsatisfied constraints (row: constraint indices)",
+            &failures
+        )
+    );
+
+    check_lookups(stark, trace_poly_values)
+}
+
+/// Checks that the given traces satisfy the constraints imposed by the tables of a multi-STARK
+/// system, as with [`check_constraints`], as well as the provided [`CrossTableLookup`]s.
+pub fn check_multi_constraints<F, C, const D: usize, const N: usize>(
+    starks: [&dyn StarkTable<F, C, D>; N],
+    cross_table_lookups: &[CrossTableLookup<F>],
+    trace_poly_values: &[Vec<PolynomialValues<F>>; N],
+    public_inputs: [&[F]; N],
+) -> Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    for (i, stark) in starks.iter().enumerate() {
+        stark
+            .check_table_constraints(&trace_poly_values[i], public_inputs[i])
+            .map_err(|e| anyhow!("Table #{i}: {e}"))?;
+    }
+
+    check_ctls(trace_poly_values, cross_table_lookups, &HashMap::new())
+}
+
+/// Checks that, for each lookup of the given STARK, every looked up value appears in the lookup
+/// table with a frequency matching its number of occurrences in the looking columns.
+fn check_lookups<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
+    stark: &S,
+    trace_poly_values: &[PolynomialValues<F>],
+) -> Result<()> {
+    let degree = trace_poly_values[0].len();
+
+    for (lookup_index, lookup) in stark.lookups().iter().enumerate() {
+        // Maps each value to its number of occurrences in the looking columns, and
+        // to its frequency in the lookup table.
+        let mut counts = HashMap::<F, (F, F)>::new();
+        for row in 0..degree {
+            for (i, column) in lookup.columns.iter().enumerate() {
+                let filter = lookup
+                    .filter_columns
+                    .get(i)
+                    .and_then(Option::as_ref)
+                    .map_or(F::ONE, |filter| filter.eval_table(trace_poly_values, row));
+                if !filter.is_zero() {
+                    let value = column.eval_table(trace_poly_values, row);
+                    counts.entry(value).or_default().0 += filter;
+                }
+            }
+
+            let frequency = lookup.frequencies_column.eval_table(trace_poly_values, row);
+            if !frequency.is_zero() {
+                let value = lookup.table_column.eval_table(trace_poly_values, row);
+                counts.entry(value).or_default().1 += frequency;
+            }
+        }
+
+        let mut failures = counts
+            .into_iter()
+            .filter(|(_, (looking, looked))| looking != looked)
+            .collect::<Vec<_>>();
+        failures.sort_unstable_by_key(|(value, _)| value.to_canonical_u64());
+        let failures = failures
+            .into_iter()
+            .map(|(value, (looking, looked))| {
+                format!("value {value}: looked up {looking} times, table frequency {looked}")
+            })
+            .collect::<Vec<_>>();
+        ensure!(
+            failures.is_empty(),
+            "{}",
+            failure_report(&format!("Unsatisfied lookup #{lookup_index}"), &failures)
+        );
+    }
+
+    Ok(())
+}
+
+/// Formats a list of failures, truncated to [`MAX_REPORTED_FAILURES`] entries.
+fn failure_report(title: &str, failures: &[String]) -> String {
+    let mut report = format!("{title}:");
+    for failure in failures.iter().take(MAX_REPORTED_FAILURES) {
+        let _ = write!(report, "\n  {failure}");
+    }
+    if failures.len() > MAX_REPORTED_FAILURES {
+        let _ = write!(
+            report,
+            "\n  ... and {} more.",
+            failures.len() - MAX_REPORTED_FAILURES
+        );
+    }
+    report
+}
+
 fn random_low_degree_matrix<F: Field>(num_polys: usize, rate_bits: usize) -> Vec<Vec<F>> {
     let polys = (0..num_polys)
         .map(|_| random_low_degree_values(rate_bits))
@@ -1,7 +1,7 @@
 //! Implementation of the STARK verifier.
 
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{vec, vec::Vec};
 use core::any::type_name;
 use core::iter::once;
 
@@ -9,6 +9,7 @@ use anyhow::{anyhow, ensure, Result};
 use itertools::Itertools;
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::types::Field;
+use plonky2::fri::validate_shape::validate_fri_proof_shape;
 use plonky2::fri::verifier::verify_fri_proof;
 use plonky2::hash::hash_types::RichField;
 use plonky2::hash::merkle_tree::MerkleCap;
@@ -18,11 +19,16 @@ use plonky2::plonk::plonk_common::reduce_with_powers;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::ConstraintConsumer;
-use crate::cross_table_lookup::CtlCheckVars;
+use crate::cross_table_lookup::{
+    get_ctl_vars_from_proofs, verify_cross_table_lookups, CrossTableLookup, CtlCheckVars,
+};
 use crate::evaluation_frame::StarkEvaluationFrame;
-use crate::lookup::LookupCheckVars;
-use crate::proof::{StarkOpeningSet, StarkProof, StarkProofChallenges, StarkProofWithPublicInputs};
-use crate::stark::Stark;
+use crate::lookup::{get_grand_product_challenge_set, LookupCheckVars};
+use crate::proof::{
+    CompressedStarkProofWithPublicInputs, MultiProof, StarkOpeningSet, StarkProof,
+    StarkProofChallenges, StarkProofWithPublicInputs,
+};
+use crate::stark::{multi_stark_constraint_degree, Stark, StarkTable};
 use crate::vanishing_poly::eval_vanishing_poly;
 
 /// Verifies a [`StarkProofWithPublicInputs`] against a STARK statement.
@@ -51,6 +57,124 @@ pub fn verify_stark_proof<
     )
 }
 
+/// Verifies a [`CompressedStarkProofWithPublicInputs`] against a STARK statement.
+pub fn verify_compressed_stark_proof<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+    const D: usize,
+>(
+    stark: S,
+    compressed_proof_with_pis: CompressedStarkProofWithPublicInputs<F, C, D>,
+    config: &StarkConfig,
+) -> Result<()> {
+    ensure!(compressed_proof_with_pis.public_inputs.len() == S::PUBLIC_INPUTS);
+    let (proof_with_pis, challenges) =
+        compressed_proof_with_pis.decompress_with_challenges(&stark, config)?;
+
+    verify_stark_proof_with_challenges(
+        &stark,
+        &proof_with_pis.proof,
+        &challenges,
+        None,
+        &proof_with_pis.public_inputs,
+        config,
+    )
+}
+
+/// Verifies a [`MultiProof`] against the tables of a multi-STARK system, along with
+/// the [`CrossTableLookup`]s connecting them.
+///
+/// The public inputs of each table are provided separately, in the same order as `starks`.
+pub fn verify_multi<F, C, const D: usize, const N: usize>(
+    starks: [&dyn StarkTable<F, C, D>; N],
+    cross_table_lookups: &[CrossTableLookup<F>],
+    multi_proof: &MultiProof<F, C, D, N>,
+    public_inputs: [&[F]; N],
+    config: &StarkConfig,
+) -> Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let constraint_degree = multi_stark_constraint_degree(&starks)?;
+
+    // Observe all trace caps before sampling the CTL challenges shared by all tables.
+    let mut challenger = Challenger::<F, C::Hasher>::new();
+    for stark_proof in &multi_proof.stark_proofs {
+        challenger.observe_cap(&stark_proof.proof.trace_cap);
+    }
+    let ctl_challenges = get_grand_product_challenge_set(&mut challenger, config.num_challenges);
+    ensure!(
+        ctl_challenges == multi_proof.ctl_challenges,
+        "Mismatch between the computed and the provided CTL challenges."
+    );
+
+    // Check the shape of each table's proof before extracting its CTL openings.
+    for (i, stark) in starks.iter().enumerate() {
+        let (num_ctl_helpers, num_ctl_zs, _) = CrossTableLookup::num_ctl_helpers_zs_all(
+            cross_table_lookups,
+            i,
+            config.num_challenges,
+            constraint_degree,
+        );
+        stark.validate_table_proof_shape(
+            &multi_proof.stark_proofs[i].proof,
+            num_ctl_helpers,
+            num_ctl_zs,
+            config,
+        )?;
+    }
+
+    let num_lookup_columns =
+        core::array::from_fn(|i| starks[i].table_num_lookup_helper_columns(config));
+    let ctl_vars_per_table = get_ctl_vars_from_proofs(
+        multi_proof,
+        cross_table_lookups,
+        &ctl_challenges,
+        &num_lookup_columns,
+        constraint_degree,
+    );
+
+    for (i, stark) in starks.iter().enumerate() {
+        let stark_proof = &multi_proof.stark_proofs[i];
+        ensure!(
+            challenger.compact() == stark_proof.init_challenger_state,
+            "Mismatch in the initial challenger state of table {}.",
+            i
+        );
+        let challenges =
+            stark_proof
+                .proof
+                .get_challenges(&mut challenger, Some(&ctl_challenges), true, config);
+        stark.verify_table(
+            &stark_proof.proof,
+            &challenges,
+            Some(&ctl_vars_per_table[i]),
+            public_inputs[i],
+            config,
+        )?;
+    }
+
+    let ctl_zs_first = multi_proof
+        .stark_proofs
+        .iter()
+        .zip(starks)
+        .enumerate()
+        .map(
+            |(i, (stark_proof, stark))| match &stark_proof.proof.openings.ctl_zs_first {
+                Some(ctl_zs_first) => Ok(ctl_zs_first.clone()),
+                None if !stark.table_requires_ctls() => Ok(vec![]),
+                None => Err(anyhow!("Missing CTL openings of table {}.", i)),
+            },
+        )
+        .collect::<Result<Vec<_>>>()?
+        .try_into()
+        .expect("There should be exactly one proof per table.");
+
+    verify_cross_table_lookups::<F, D, N>(cross_table_lookups, ctl_zs_first, None, config)
+}
+
 /// Verifies a [`StarkProofWithPublicInputs`] against a STARK statement,
 /// with the provided [`StarkProofChallenges`].
 /// It also supports optional cross-table lookups data and challenges,
@@ -70,23 +194,22 @@ where
 {
     log::debug!("Checking proof: {}", type_name::<S>());
 
-    let (num_ctl_z_polys, num_ctl_polys) = ctl_vars
-        .map(|ctls| {
-            (
-                ctls.len(),
-                ctls.iter().map(|ctl| ctl.helper_columns.len()).sum(),
-            )
-        })
-        .unwrap_or_default();
+    let (num_ctl_z_polys, num_ctl_polys) = match ctl_vars {
+        Some(ctls) => (
+            ctls.len(),
+            ctls.iter().map(|ctl| ctl.helper_columns.len()).sum(),
+        ),
+        None if !stark.requires_ctls() => (0, 0),
+        None => {
+            return Err(anyhow!(
+                "Missing cross-table lookup data for {}.",
+                type_name::<S>()
+            ))
+        }
+    };
 
-    validate_proof_shape(
-        stark,
-        proof,
-        public_inputs,
-        config,
-        num_ctl_polys,
-        num_ctl_z_polys,
-    )?;
+    ensure!(public_inputs.len() == S::PUBLIC_INPUTS);
+    validate_proof_shape(stark, proof, config, num_ctl_polys, num_ctl_z_polys)?;
 
     let StarkOpeningSet {
         local_values,
@@ -159,13 +282,15 @@ where
     // Check each polynomial identity, of the form `vanishing(x) = Z_H(x) quotient(x)`, at zeta.
     let zeta_pow_deg = challenges.stark_zeta.exp_power_of_2(degree_bits);
     let z_h_zeta = zeta_pow_deg - F::Extension::ONE;
-    // `quotient_polys_zeta` holds `num_challenges * quotient_degree_factor` evaluations.
-    // Each chunk of `quotient_degree_factor` holds the evaluations of `t_0(zeta),...,t_{quotient_degree_factor-1}(zeta)`
+    // `quotient_polys_zeta` holds `num_challenges * num_quotient_chunks` evaluations, followed
+    // by the evaluation of the masking polynomial in zero-knowledge mode.
+    // Each chunk of `num_quotient_chunks` holds the evaluations of `t_0(zeta),...,t_{num_quotient_chunks-1}(zeta)`
     // where the "real" quotient polynomial is `t(X) = t_0(X) + t_1(X)*X^n + t_2(X)*X^{2n} + ...`.
     // So to reconstruct `t(zeta)` we can compute `reduce_with_powers(chunk, zeta^n)` for each
-    // `quotient_degree_factor`-sized chunk of the original evaluations.
+    // `num_quotient_chunks`-sized chunk of the original evaluations.
     for (i, chunk) in quotient_polys
-        .chunks(stark.quotient_degree_factor())
+        .chunks(stark.num_quotient_chunks(config))
+        .take(config.num_challenges)
         .enumerate()
     {
         ensure!(
@@ -205,10 +330,59 @@ where
     Ok(())
 }
 
+/// Checks that a [`StarkProof`] has the shape expected by `stark` with the provided
+/// [`StarkConfig`], including its FRI opening proof.
+///
+/// Contrary to `validate_proof_shape`, this does not assume that the trace length can
+/// be safely recovered from the proof, and hence can be used on untrusted inputs, e.g.
+/// proofs read from bytes.
+pub(crate) fn validate_proof_shape_with_fri<F, C, S, const D: usize>(
+    stark: &S,
+    proof: &StarkProof<F, C, D>,
+    config: &StarkConfig,
+    num_ctl_helpers: usize,
+    num_ctl_zs: usize,
+) -> Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+{
+    let query_round_proofs = &proof.opening_proof.query_round_proofs;
+    ensure!(query_round_proofs.len() == config.fri_config.num_query_rounds);
+    let (_, initial_merkle_proof) = query_round_proofs
+        .first()
+        .and_then(|round| round.initial_trees_proof.evals_proofs.first())
+        .ok_or_else(|| anyhow!("Missing initial Merkle proof"))?;
+    let lde_bits = config.fri_config.cap_height + initial_merkle_proof.siblings.len();
+    ensure!(
+        lde_bits >= config.fri_config.rate_bits + config.zero_knowledge as usize
+            && lde_bits <= F::TWO_ADICITY,
+        "Invalid trace length"
+    );
+
+    validate_proof_shape(stark, proof, config, num_ctl_helpers, num_ctl_zs)?;
+    ensure!(proof.openings.ctl_zs_first.is_some() == stark.requires_ctls());
+
+    let degree_bits = proof.recover_degree_bits(config);
+    // The opening points do not affect the shape of the FRI proof.
+    let fri_instance = stark.fri_instance(
+        F::Extension::ZERO,
+        F::primitive_root_of_unity(degree_bits),
+        num_ctl_helpers,
+        vec![0; num_ctl_zs],
+        config,
+    );
+    validate_fri_proof_shape::<F, C, D>(
+        &proof.opening_proof,
+        &fri_instance,
+        &config.fri_params(degree_bits),
+    )
+}
+
 fn validate_proof_shape<F, C, S, const D: usize>(
     stark: &S,
     proof: &StarkProof<F, C, D>,
-    public_inputs: &[F],
     config: &StarkConfig,
     num_ctl_helpers: usize,
     num_ctl_zs: usize,
@@ -239,8 +413,6 @@ where
         quotient_polys,
     } = openings;
 
-    ensure!(public_inputs.len() == S::PUBLIC_INPUTS);
-
     let fri_params = config.fri_params(degree_bits);
     let cap_height = fri_params.config.cap_height;
 